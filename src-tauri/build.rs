@@ -1,6 +1,8 @@
 /// Tauri 构建脚本
 ///
-/// 在编译前自动运行，用于生成 Tauri 所需的绑定代码
+/// 在编译前自动运行，用于生成 Tauri 所需的绑定代码；
+/// 不含 desktop feature 的构建（仅 clipforge-cli）无需生成
 fn main() {
+    #[cfg(feature = "desktop")]
     tauri_build::build()
 }
@@ -0,0 +1,127 @@
+// ClipForge 命令行入口
+//
+// 无界面执行 ClipForge 的处理任务（如在 CI 中批量转换），参数 JSON 与前端调用
+// 对应 command 时的参数结构完全一致：
+//
+//     clipforge-cli convert params.json [--ffmpeg <path>] [--ffprobe <path>]
+//
+// 进度以 JSON Lines 输出到 stdout，每行一个与 GUI 相同格式的 TaskEvent
+// （{"event":"progress","data":{...}}）；任务失败时退出码为 1
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clipforge_lib::engine::builder::build_convert_command_for_source;
+use clipforge_lib::engine::headless::HeadlessTools;
+use clipforge_lib::models::preset::ConvertParams;
+use clipforge_lib::models::task::TaskEvent;
+
+/// 用法说明
+const USAGE: &str = "Usage: clipforge-cli <operation> <params.json> [--ffmpeg <path>] [--ffprobe <path>]
+
+Operations:
+  convert    Format conversion (params: ConvertParams)
+
+ffmpeg and ffprobe are looked up in PATH unless given explicitly.
+Progress is printed to stdout as JSON lines, one TaskEvent per line.";
+
+/// 解析后的命令行参数
+struct CliArgs {
+    /// 操作名称（如 "convert"）
+    operation: String,
+    /// 参数 JSON 文件路径（"-" 表示从 stdin 读取）
+    params_path: String,
+    /// 指定的 ffmpeg 路径
+    ffmpeg: Option<PathBuf>,
+    /// 指定的 ffprobe 路径
+    ffprobe: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("clipforge-cli: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// 执行一次命令行任务
+///
+/// # 返回
+/// - `Ok(true)` - 任务成功
+/// - `Ok(false)` - 任务已执行但失败（Failed 事件已输出）
+/// - `Err(String)` - 参数错误或无法启动
+fn run() -> Result<bool, String> {
+    let args = parse_args(std::env::args().skip(1).collect())?;
+    let tools = HeadlessTools::locate(args.ffmpeg, args.ffprobe)?;
+    let params_json = read_params(&args.params_path)?;
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    let result = match args.operation.as_str() {
+        "convert" => {
+            let params: ConvertParams = serde_json::from_str(&params_json)
+                .map_err(|e| format!("Invalid convert params: {}", e))?;
+            let probe = tools.probe(&params.input_path)?;
+            let command = build_convert_command_for_source(&params, &probe, None);
+            tools.run_ffmpeg(&task_id, command, probe.duration(), &params.output_path, print_event)?
+        }
+        other => return Err(format!("Unknown operation: {}\n\n{}", other, USAGE)),
+    };
+    Ok(result.error.is_none())
+}
+
+/// 以 JSON Lines 输出一个任务事件
+fn print_event(event: TaskEvent) {
+    match serde_json::to_string(&event) {
+        Ok(line) => println!("{}", line),
+        Err(e) => eprintln!("clipforge-cli: 序列化事件失败: {}", e),
+    }
+}
+
+/// 读取参数 JSON（"-" 表示 stdin）
+fn read_params(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)
+            .map_err(|e| format!("Failed to read params from stdin: {}", e))?;
+        return Ok(content);
+    }
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+/// 解析命令行参数
+fn parse_args(raw: Vec<String>) -> Result<CliArgs, String> {
+    let mut positional = Vec::new();
+    let mut ffmpeg = None;
+    let mut ffprobe = None;
+    let mut iter = raw.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            "--ffmpeg" | "--ffprobe" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{} requires a path", arg))?;
+                if arg == "--ffmpeg" {
+                    ffmpeg = Some(PathBuf::from(value));
+                } else {
+                    ffprobe = Some(PathBuf::from(value));
+                }
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let [operation, params_path]: [String; 2] = positional
+        .try_into()
+        .map_err(|_| USAGE.to_string())?;
+    Ok(CliArgs {
+        operation,
+        params_path,
+        ffmpeg,
+        ffprobe,
+    })
+}
@@ -0,0 +1,164 @@
+/// 画面缺陷检查 command
+///
+/// 用 blackdetect / freezedetect 扫描整段视频，报告黑场和静止画面（冻帧），
+/// 常用于检查合并结果或源文件是否损坏
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::analysis::{parse_blackdetect, parse_freezedetect};
+use crate::engine::builder::{build_defect_detect_command, build_scrub_thumbnails_command};
+use crate::engine::preview::register_previews;
+use crate::engine::process::{
+    probe_duration, run_ffmpeg_analysis, run_ffmpeg_quiet, send_phase, ANALYZING_INPUT_PHASE,
+};
+use crate::models::preset::{DefectAnalysisParams, DefectKind, DefectRegion, DefectReport};
+use crate::models::task::{TaskEvent, TaskStatus};
+use crate::utils::path::{cleanup_temp_file, temp_file_path};
+
+/// 默认黑场像素亮度阈值
+const DEFAULT_BLACK_THRESHOLD: f64 = 0.10;
+/// 默认静止画面噪声容差（约 -60dB）
+const DEFAULT_FREEZE_NOISE: f64 = 0.001;
+/// 默认最短上报时长（秒）
+const DEFAULT_MIN_DURATION: f64 = 2.0;
+/// 缺陷截图宽度（像素）
+const SCREENSHOT_WIDTH: u32 = 320;
+/// 单次 ffmpeg 调用截取的最大帧数（每帧对应一路解码输入）
+const SCREENSHOTS_PER_RUN: usize = 16;
+
+/// 检查视频中的黑场和静止画面
+///
+/// 整段解码一次，进度按探测到的时长计算；
+/// screenshots 为 true 时在分析完成后截取每段缺陷的起始帧，登记为预览
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 检查参数（阈值、最短时长、是否截图）
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(DefectReport)` - 任务 ID 和缺陷列表（可能为空）
+/// - `Err(String)` - 参数非法、分析失败或被取消
+#[tauri::command]
+pub async fn analyze_defects(
+    app: tauri::AppHandle,
+    params: DefectAnalysisParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<DefectReport, String> {
+    let (threshold, noise, min_duration) = prepare_defect_analysis(&params)?;
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let settings = load_settings(&app).await;
+    let duration = probe_duration(&app, &params.input_path, settings.probe_timeout_ms).await?;
+
+    let args = build_defect_detect_command(&params.input_path, threshold, noise, min_duration);
+    let result = run_ffmpeg_analysis(&app, &task_id, args, duration, &on_progress).await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let (result, log) = result?;
+    if let Some(error) = result.error {
+        return Err(error);
+    }
+    if result.status == TaskStatus::Cancelled {
+        return Err("Analysis cancelled".to_string());
+    }
+
+    let mut defects: Vec<DefectRegion> = parse_blackdetect(&log)
+        .into_iter()
+        .map(|b| (b.start, b.end, DefectKind::Black))
+        .chain(
+            parse_freezedetect(&log, duration)
+                .into_iter()
+                .map(|f| (f.start, f.end, DefectKind::Freeze)),
+        )
+        .map(|(start, end, kind)| DefectRegion {
+            start,
+            end,
+            kind,
+            screenshot: None,
+        })
+        .collect();
+    defects.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    if params.screenshots && !defects.is_empty() {
+        let starts: Vec<f64> = defects.iter().map(|d| d.start).collect();
+        match capture_screenshots(&app, &params.input_path, &starts).await {
+            Ok(tokens) => {
+                for (defect, token) in defects.iter_mut().zip(tokens) {
+                    defect.screenshot = Some(token);
+                }
+            }
+            Err(e) => {
+                let _ = on_progress.send(TaskEvent::Notice {
+                    task_id: task_id.clone(),
+                    message: format!("Could not capture defect screenshots: {}", e),
+                });
+            }
+        }
+    }
+
+    Ok(DefectReport { task_id, defects })
+}
+
+/// 准备画面缺陷检查：填充默认阈值并校验范围
+///
+/// 实际执行（analyze_defects）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `params` - 检查参数
+///
+/// # 返回
+/// - `Ok((f64, f64, f64))` - 黑场亮度阈值、静止画面噪声容差和最短上报时长（秒）
+/// - `Err(String)` - 参数超出范围
+pub(crate) fn prepare_defect_analysis(
+    params: &DefectAnalysisParams,
+) -> Result<(f64, f64, f64), String> {
+    let threshold = params.black_threshold.unwrap_or(DEFAULT_BLACK_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err("Black threshold must be between 0 and 1".to_string());
+    }
+    let noise = params.freeze_noise.unwrap_or(DEFAULT_FREEZE_NOISE);
+    if !(noise > 0.0 && noise <= 1.0) {
+        return Err("Freeze noise tolerance must be between 0 and 1".to_string());
+    }
+    let min_duration = params.min_duration.unwrap_or(DEFAULT_MIN_DURATION);
+    if !min_duration.is_finite() || min_duration <= 0.0 {
+        return Err("Minimum duration must be greater than 0".to_string());
+    }
+    Ok((threshold, noise, min_duration))
+}
+
+/// 截取各时间点的画面并登记为预览
+///
+/// 分批执行以限制单个 ffmpeg 进程打开的输入数量，任一批失败时清理全部已生成的文件
+async fn capture_screenshots(
+    app: &tauri::AppHandle,
+    input_path: &str,
+    timestamps: &[f64],
+) -> Result<Vec<String>, String> {
+    let output_paths = timestamps
+        .iter()
+        .map(|_| temp_file_path("defect", "png"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (times, paths) in timestamps
+        .chunks(SCREENSHOTS_PER_RUN)
+        .zip(output_paths.chunks(SCREENSHOTS_PER_RUN))
+    {
+        let args = build_scrub_thumbnails_command(input_path, times, SCREENSHOT_WIDTH, paths);
+        if let Err(e) = run_ffmpeg_quiet(app, args).await {
+            for path in &output_paths {
+                cleanup_temp_file(path);
+            }
+            return Err(e);
+        }
+    }
+
+    Ok(register_previews("defects", output_paths).await)
+}
@@ -0,0 +1,129 @@
+/// 附件导出 command
+///
+/// 导出 MKV 等容器内嵌的附件流（字体、封面图等），
+/// 字体可供字幕烧录时作为 fontsdir 使用，封面图可用于缩略图
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_extract_attachments_command;
+use crate::engine::process::{probe_media, run_ffmpeg_quiet};
+use crate::models::media::AttachmentStream;
+
+/// 导出附件流
+///
+/// 先通过 ffprobe 列出所有附件流，再按 indices 过滤（为空则导出全部），
+/// 以附件自带的文件名写入目标目录
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `input_path` - 输入文件路径
+/// - `output_dir` - 导出目录（不存在时自动创建）
+/// - `indices` - 要导出的附件流索引，None 或空表示全部
+///
+/// # 返回
+/// - `Ok(Vec<String>)` - 导出的文件路径列表（无附件时为空）
+/// - `Err(String)` - 探测或导出失败
+#[tauri::command]
+pub async fn extract_attachments(
+    app: tauri::AppHandle,
+    input_path: String,
+    output_dir: String,
+    indices: Option<Vec<u32>>,
+) -> Result<Vec<String>, String> {
+    let targets = prepare_extract_attachments(&app, &input_path, &output_dir, indices).await?;
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("创建输出目录失败: {}", e))?;
+
+    let args = build_extract_attachments_command(&input_path, &targets);
+    run_ffmpeg_quiet(&app, args).await?;
+
+    Ok(targets.into_iter().map(|(_, path)| path).collect())
+}
+
+/// 准备附件导出：列出附件流、按 indices 过滤并确定各附件的导出路径
+///
+/// 实际执行（extract_attachments）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `input_path` - 输入文件路径
+/// - `output_dir` - 导出目录
+/// - `indices` - 要导出的附件流索引，None 或空表示全部
+///
+/// # 返回
+/// - `Ok(Vec<(u32, String)>)` - 附件流索引与导出路径（无附件时为空）
+/// - `Err(String)` - 探测失败或指定的流不是附件
+pub(crate) async fn prepare_extract_attachments(
+    app: &tauri::AppHandle,
+    input_path: &str,
+    output_dir: &str,
+    indices: Option<Vec<u32>>,
+) -> Result<Vec<(u32, String)>, String> {
+    let settings = load_settings(app).await;
+    let attachments = probe_media(app, input_path, settings.probe_timeout_ms)
+        .await?
+        .attachment_streams;
+
+    let selected: Vec<&AttachmentStream> = match indices.as_deref() {
+        Some(wanted) if !wanted.is_empty() => {
+            if let Some(missing) = wanted
+                .iter()
+                .find(|i| !attachments.iter().any(|a| a.index == **i))
+            {
+                return Err(format!("Stream {} is not an attachment", missing));
+            }
+            attachments
+                .iter()
+                .filter(|a| wanted.contains(&a.index))
+                .collect()
+        }
+        _ => attachments.iter().collect(),
+    };
+
+    // 同名附件加流索引前缀，避免相互覆盖
+    let mut used_names = HashSet::new();
+    Ok(selected
+        .iter()
+        .map(|a| {
+            let mut name = attachment_file_name(a);
+            if !used_names.insert(name.clone()) {
+                name = format!("{}_{}", a.index, name);
+                used_names.insert(name.clone());
+            }
+            let path = Path::new(output_dir).join(name);
+            (a.index, path.to_string_lossy().to_string())
+        })
+        .collect())
+}
+
+/// 确定附件的导出文件名
+///
+/// 优先使用附件自带的 filename 标签（去掉路径部分，防止写出目标目录），
+/// 缺失时按 MIME 类型推断扩展名
+pub(crate) fn attachment_file_name(attachment: &AttachmentStream) -> String {
+    let tagged = attachment
+        .filename
+        .as_deref()
+        .and_then(|f| Path::new(f).file_name())
+        .map(|f| f.to_string_lossy().to_string())
+        .filter(|f| !f.is_empty());
+    if let Some(name) = tagged {
+        return name;
+    }
+
+    let ext = match attachment.mimetype.as_deref().unwrap_or("") {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "application/x-truetype-font" | "font/ttf" => "ttf",
+        "application/vnd.ms-opentype" | "font/otf" => "otf",
+        _ if !attachment.codec_name.is_empty() => attachment.codec_name.as_str(),
+        _ => "bin",
+    };
+    format!("attachment_{}.{}", attachment.index, ext)
+}
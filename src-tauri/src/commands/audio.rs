@@ -5,11 +5,18 @@
 
 use tauri::ipc::Channel;
 
-use crate::engine::builder::build_audio_command;
-use crate::engine::process::{run_ffmpeg, run_ffprobe};
-use crate::models::media::FfprobeOutput;
-use crate::models::preset::AudioParams;
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{build_audio_command, build_extract_multi_format_command};
+use crate::engine::presets::DEFAULT_LOUDNESS_TARGET;
+use crate::engine::process::{probe_output, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::{
+    AudioFormatOutput, AudioMode, AudioParams, MultiFormatAudioParams, MultiFormatAudioResult,
+};
 use crate::models::task::TaskEvent;
+use crate::utils::path::{generate_output_path, get_file_size};
+
+/// 多格式提取支持的输出格式
+const MULTI_FORMAT_EXTENSIONS: [&str; 5] = ["mp3", "aac", "m4a", "wav", "flac"];
 
 /// 执行音频处理
 ///
@@ -35,18 +42,30 @@ pub async fn process_audio(
 ) -> Result<String, String> {
     let task_id = uuid::Uuid::new_v4().to_string();
 
-    // 获取输入文件时长
-    let duration = get_duration(&app, &params.input_path).await?;
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let PreparedAudio {
+        params,
+        duration,
+        progress_duration,
+        source_tags,
+    } = prepare_audio(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
 
     // 构建音频处理命令
-    let args = build_audio_command(&params, duration);
+    let args = build_audio_command(&params, duration, &source_tags, thread_count);
 
     // 执行 ffmpeg
     let result = run_ffmpeg(
         &app,
         &task_id,
         args,
-        duration,
+        progress_duration,
         &params.output_path,
         &on_progress,
     )
@@ -64,15 +83,310 @@ pub async fn process_audio(
     }
 }
 
-/// 从 ffprobe 获取视频时长
-async fn get_duration(app: &tauri::AppHandle, file_path: &str) -> Result<f64, String> {
-    let json_str = run_ffprobe(app, file_path).await?;
-    let output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("解析 ffprobe 输出失败: {}", e))?;
-    Ok(output
-        .format
+/// 准备好、可直接构建命令的音频处理任务
+pub(crate) struct PreparedAudio {
+    /// 填充默认值后的参数
+    pub params: AudioParams,
+    /// 源文件容器时长（秒）
+    pub duration: f64,
+    /// 进度计算使用的时长（秒）
+    pub progress_duration: f64,
+    /// 需要写入输出的描述性元数据标签（仅提取模式）
+    pub source_tags: Vec<(String, String)>,
+}
+
+/// 准备音频处理任务：填充默认码率、校验响度目标、探测时长并提示替换音频的补齐/截断
+///
+/// 实际执行（process_audio）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 音频处理参数
+/// - `notify` - 提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok(PreparedAudio)` - 调整后的参数、时长和元数据标签
+/// - `Err(String)` - 响度目标非法或探测失败
+pub(crate) async fn prepare_audio(
+    app: &tauri::AppHandle,
+    mut params: AudioParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<PreparedAudio, String> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+
+    // 校验响度标准化目标
+    if matches!(params.mode, AudioMode::Adjust) && params.normalize.unwrap_or(false) {
+        DEFAULT_LOUDNESS_TARGET
+            .with_overrides(params.loudness_target_lufs, params.loudness_range, params.true_peak)
+            .validate()?;
+    }
+
+    // 获取输入文件时长（容器时长 + 首条音频流时长）和描述性元数据标签
+    let (duration, audio_stream_duration, source_tags) =
+        get_durations(app, &params.input_path, settings.probe_timeout_ms).await?;
+
+    // Replace 模式下比较新音频与视频时长，提示将发生的补齐/截断
+    let mut replace_duration = None;
+    if matches!(params.mode, AudioMode::Replace) {
+        if let Some(replace_path) = params.replace_audio_path.as_deref() {
+            let (audio_duration, _, _) =
+                get_durations(app, replace_path, settings.probe_timeout_ms).await?;
+            let fit_mode = params.fit_mode.as_deref().unwrap_or("pad_silence");
+            if let Some(message) = replace_fit_notice(fit_mode, duration, audio_duration) {
+                notify(message);
+            }
+            replace_duration = Some(audio_duration);
+        }
+    }
+
+    let source_tags = match params.mode {
+        AudioMode::Extract => source_tags,
+        _ => Vec::new(),
+    };
+    let progress_duration =
+        progress_duration(&params, duration, audio_stream_duration, replace_duration);
+
+    Ok(PreparedAudio {
+        params,
+        duration,
+        progress_duration,
+        source_tags,
+    })
+}
+
+/// 从同一条音轨一次提取多个格式
+///
+/// 单次 ffmpeg 调用解码一次、按格式分别编码输出，比逐个格式调用 process_audio 少 N-1 次解码。
+/// 完成后逐个推送提示并在结果中返回各文件大小
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 多格式音频提取参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(MultiFormatAudioResult)` - 任务 ID 和各格式输出
+/// - `Err(String)` - 格式无效、没有对应音轨或执行失败
+#[tauri::command]
+pub async fn extract_multi_format(
+    app: tauri::AppHandle,
+    params: MultiFormatAudioParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<MultiFormatAudioResult, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+
+    let (track, outputs, duration) = prepare_extract_multi_format(&app, &params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_extract_multi_format_command(
+        &params.input_path,
+        track,
+        &outputs,
+        thread_count,
+    );
+
+    // run_ffmpeg 以第一个格式为主输出路径（Completed 事件中的 outputPath）
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &outputs[0].1,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        return Err(error);
+    }
+
+    let outputs: Vec<AudioFormatOutput> = outputs
+        .into_iter()
+        .map(|(format, output_path)| AudioFormatOutput {
+            format,
+            output_size: get_file_size(&output_path),
+            output_path,
+        })
+        .collect();
+    for output in &outputs {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message: format!("{} written to {}", output.format, output.output_path),
+        });
+    }
+
+    Ok(MultiFormatAudioResult { task_id, outputs })
+}
+
+/// 准备多格式音频提取：校验格式列表和音轨，确定各格式的输出路径
+///
+/// 实际执行（extract_multi_format）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 多格式音频提取参数
+///
+/// # 返回
+/// - `Ok((u32, Vec<(String, String)>, f64))` - 音轨序号、各格式及其输出路径、音轨时长（秒）
+/// - `Err(String)` - 格式无效、没有对应音轨或输出会覆盖输入
+pub(crate) async fn prepare_extract_multi_format(
+    app: &tauri::AppHandle,
+    params: &MultiFormatAudioParams,
+) -> Result<(u32, Vec<(String, String)>, f64), String> {
+    if params.formats.is_empty() {
+        return Err("At least one output format is required".to_string());
+    }
+    let formats: Vec<String> = params.formats.iter().map(|f| f.to_lowercase()).collect();
+    for (i, format) in formats.iter().enumerate() {
+        if !MULTI_FORMAT_EXTENSIONS.contains(&format.as_str()) {
+            return Err(format!(
+                "Unsupported audio format {}; use one of {}",
+                format,
+                MULTI_FORMAT_EXTENSIONS.join(", ")
+            ));
+        }
+        if formats[..i].contains(format) {
+            return Err(format!("Format {} is listed more than once", format));
+        }
+    }
+
+    // 校验音轨存在，进度按该音轨时长计算（缺失时用容器时长）
+    let track = params.audio_track.unwrap_or(0);
+    let settings = load_settings(app).await;
+    let output = probe_output(app, &params.input_path, settings.probe_timeout_ms).await?;
+    let audio_streams: Vec<_> = output
+        .streams
+        .iter()
+        .flatten()
+        .filter(|s| s.codec_type.as_deref() == Some("audio"))
+        .collect();
+    if audio_streams.is_empty() {
+        return Err("The input file has no audio stream".to_string());
+    }
+    let stream = audio_streams.get(track as usize).ok_or_else(|| {
+        format!(
+            "Audio track {} out of range; this file has {} audio track(s)",
+            track,
+            audio_streams.len()
+        )
+    })?;
+    let duration = stream
+        .duration
         .as_ref()
-        .and_then(|f| f.duration.as_ref())
         .and_then(|d| d.parse::<f64>().ok())
-        .unwrap_or(0.0))
+        .filter(|d| *d > 0.0)
+        .unwrap_or_else(|| output.duration());
+
+    let suffix = params.suffix.as_deref().unwrap_or("_audio");
+    let mut outputs: Vec<(String, String)> = Vec::with_capacity(formats.len());
+    for format in formats {
+        let path = generate_output_path(
+            &params.input_path,
+            &params.output_dir,
+            suffix,
+            &format,
+            !settings.overwrite_existing,
+        );
+        if path == params.input_path {
+            return Err(format!("Output {} would overwrite the input file", path));
+        }
+        outputs.push((format, path));
+    }
+
+    Ok((track, outputs, duration))
+}
+
+/// 从 ffprobe 获取容器时长、第一条音频流的时长和描述性元数据标签
+///
+/// 音频流时长缺失或无法解析时为 None（部分容器只在 format 层记录时长）
+async fn get_durations(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<(f64, Option<f64>, Vec<(String, String)>), String> {
+    let output = probe_output(app, file_path, timeout_ms).await?;
+    let duration = output.duration();
+    let audio_duration = output
+        .streams
+        .iter()
+        .flatten()
+        .find(|s| s.codec_type.as_deref() == Some("audio"))
+        .and_then(|s| s.duration.as_ref())
+        .and_then(|d| d.parse::<f64>().ok())
+        .filter(|d| *d > 0.0);
+    Ok((duration, audio_duration, output.descriptive_tags()))
+}
+
+/// 选择进度计算的总时长
+///
+/// ffmpeg 的 out_time 跟随输出中最长的流：
+/// - Extract 输出只有音频，按音频流时长计算（音轨比视频短时容器时长会让进度停在 100% 之前）
+/// - Replace 的 "shortest" 模式输出被截到较短者
+/// - 其余模式输出与输入视频等长，使用容器时长
+fn progress_duration(
+    params: &AudioParams,
+    duration: f64,
+    audio_stream_duration: Option<f64>,
+    replace_duration: Option<f64>,
+) -> f64 {
+    match params.mode {
+        AudioMode::Extract => audio_stream_duration.unwrap_or(duration),
+        AudioMode::Replace if params.fit_mode.as_deref() == Some("shortest") => {
+            match replace_duration.filter(|d| *d > 0.0) {
+                Some(audio) if duration > 0.0 => duration.min(audio),
+                _ => duration,
+            }
+        }
+        _ => duration,
+    }
+}
+
+/// 根据时长差异生成 Replace 模式的提示信息
+///
+/// 时长未知或差异小于 0.1 秒时不提示
+fn replace_fit_notice(fit_mode: &str, video_duration: f64, audio_duration: f64) -> Option<String> {
+    if video_duration <= 0.0 || audio_duration <= 0.0 {
+        return None;
+    }
+    let diff = video_duration - audio_duration;
+    if diff.abs() < 0.1 {
+        return None;
+    }
+
+    let message = match (fit_mode, diff > 0.0) {
+        ("shortest", true) => format!(
+            "Replacement audio is {:.1}s shorter than the video; output will be trimmed to {:.1}s",
+            diff, audio_duration
+        ),
+        ("shortest", false) => format!(
+            "Replacement audio is {:.1}s longer than the video; audio will be trimmed",
+            -diff
+        ),
+        ("loop", true) => format!(
+            "Replacement audio is {:.1}s shorter than the video; audio will loop to fill {:.1}s",
+            diff, video_duration
+        ),
+        ("loop", false) => format!(
+            "Replacement audio is {:.1}s longer than the video; audio will be trimmed",
+            -diff
+        ),
+        (_, true) => format!(
+            "Replacement audio is {:.1}s shorter than the video; padding with {:.1}s of silence",
+            diff, diff
+        ),
+        (_, false) => format!(
+            "Replacement audio is {:.1}s longer than the video; audio will be trimmed to {:.1}s",
+            -diff, video_duration
+        ),
+    };
+    Some(message)
 }
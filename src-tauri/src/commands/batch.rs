@@ -0,0 +1,53 @@
+/// 批量处理 command
+///
+/// 对一组文件应用同一类操作（目前为格式转换），全部子任务共用一个进度 Channel：
+/// 各子任务按自己的 task_id 推送事件，另外推送汇总的 BatchProgress
+
+use tauri::ipc::Channel;
+
+use crate::commands::convert::run_convert;
+use crate::engine::batch::run_batch_jobs;
+use crate::models::preset::ConvertParams;
+use crate::models::task::TaskEvent;
+
+/// 批量执行格式转换
+///
+/// 子任务同时提交，由任务队列按 max_concurrent 放行，超出的子任务先收到 Queued 事件。
+/// 每个子任务可以用各自的 task_id 单独取消；单个子任务失败不影响其余任务
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `jobs` - 每个文件的转换参数
+/// - `on_progress` - 进度推送 Channel（全部子任务共用）
+///
+/// # 返回
+/// - `Ok(Vec<String>)` - 与 jobs 顺序一一对应的子任务 ID
+/// - `Err(String)` - 任务列表为空
+#[tauri::command]
+pub async fn run_batch(
+    app: tauri::AppHandle,
+    jobs: Vec<ConvertParams>,
+    on_progress: Channel<TaskEvent>,
+) -> Result<Vec<String>, String> {
+    if jobs.is_empty() {
+        return Err("The batch has no jobs".to_string());
+    }
+
+    let events = on_progress.clone();
+    let task_ids = run_batch_jobs(
+        jobs,
+        |params, task_id| {
+            let app = app.clone();
+            let on_progress = on_progress.clone();
+            async move {
+                run_convert(&app, params, &task_id, &on_progress).await.map(|_| ())
+            }
+        },
+        move |event| {
+            let _ = events.send(event);
+        },
+    )
+    .await;
+
+    Ok(task_ids)
+}
@@ -0,0 +1,125 @@
+/// 章节导出与写入 command
+///
+/// 把文件中的章节导出为 CUE 表单，或把编辑后的章节列表写回 MP4/M4A/M4B/MOV/MKV。
+/// 写回通过 ffmetadata 临时文件 + 流复制重封装完成，不重新编码
+
+use std::path::Path;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_embed_chapters_command;
+use crate::engine::process::{probe_media, run_ffmpeg_quiet};
+use crate::models::media::Chapter;
+use crate::utils::chapters::{build_cue_sheet, build_ffmetadata, validate_chapters};
+use crate::utils::path::{cleanup_temp_file, file_extension, file_stem, temp_file_path};
+
+/// 支持写入章节的容器格式
+const CHAPTER_CONTAINERS: &[&str] = &["mp4", "m4a", "m4b", "mov", "mkv"];
+
+/// 把文件中的章节导出为 CUE 表单
+///
+/// CUE 文件与媒体文件同目录同名（扩展名为 .cue），已存在时覆盖
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `path` - 媒体文件路径
+///
+/// # 返回
+/// - `Ok(String)` - CUE 文件路径
+/// - `Err(String)` - 文件没有章节或写入失败
+#[tauri::command]
+pub async fn export_cue_sheet(app: tauri::AppHandle, path: String) -> Result<String, String> {
+    let settings = load_settings(&app).await;
+    let media_info = probe_media(&app, &path, settings.probe_timeout_ms).await?;
+    if media_info.chapters.is_empty() {
+        return Err("The file has no chapters".to_string());
+    }
+
+    let cue = build_cue_sheet(&media_info.file_name, &file_stem(&path), &media_info.chapters);
+    let cue_path = Path::new(&path).with_extension("cue");
+    std::fs::write(&cue_path, cue).map_err(|e| format!("写入 CUE 文件失败: {}", e))?;
+    Ok(cue_path.to_string_lossy().to_string())
+}
+
+/// 把章节列表写回媒体文件
+///
+/// 先重封装到同目录的临时文件，成功后替换原文件；空列表表示清除全部章节
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `path` - 媒体文件路径（MP4/M4A/M4B/MOV/MKV）
+/// - `chapters` - 新的章节列表（按开始时间排序，互不重叠）
+///
+/// # 返回
+/// - `Ok(())` - 写入成功
+/// - `Err(String)` - 格式不支持、章节无效或重封装失败
+#[tauri::command]
+pub async fn embed_chapters(
+    app: tauri::AppHandle,
+    path: String,
+    chapters: Vec<Chapter>,
+) -> Result<(), String> {
+    let temp_output = prepare_embed_chapters(&app, &path, &chapters).await?;
+
+    let metadata_path = if chapters.is_empty() {
+        None
+    } else {
+        let metadata_path = temp_file_path("chapters", "txt")?;
+        std::fs::write(&metadata_path, build_ffmetadata(&chapters))
+            .map_err(|e| format!("写入章节元数据失败: {}", e))?;
+        Some(metadata_path)
+    };
+
+    let source = Path::new(&path);
+    let args = build_embed_chapters_command(&path, metadata_path.as_deref(), &temp_output);
+    let result = run_ffmpeg_quiet(&app, args).await;
+    if let Some(ref metadata_path) = metadata_path {
+        cleanup_temp_file(metadata_path);
+    }
+    if let Err(error) = result {
+        cleanup_temp_file(&temp_output);
+        return Err(error);
+    }
+
+    std::fs::rename(&temp_output, source).map_err(|e| {
+        cleanup_temp_file(&temp_output);
+        format!("替换原文件失败: {}", e)
+    })
+}
+
+/// 准备章节写入：校验容器格式和章节列表，返回重封装使用的临时输出路径
+///
+/// 实际执行（embed_chapters）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `path` - 媒体文件路径
+/// - `chapters` - 新的章节列表
+///
+/// # 返回
+/// - `Ok(String)` - 与原文件同目录的临时输出路径
+/// - `Err(String)` - 格式不支持、探测失败或章节无效
+pub(crate) async fn prepare_embed_chapters(
+    app: &tauri::AppHandle,
+    path: &str,
+    chapters: &[Chapter],
+) -> Result<String, String> {
+    let extension = file_extension(path).to_lowercase();
+    if !CHAPTER_CONTAINERS.contains(&extension.as_str()) {
+        return Err(format!(
+            "Chapters can only be written to {} files",
+            CHAPTER_CONTAINERS.join("/").to_uppercase()
+        ));
+    }
+
+    let settings = load_settings(app).await;
+    let media_info = probe_media(app, path, settings.probe_timeout_ms).await?;
+    validate_chapters(chapters, media_info.duration)?;
+
+    // 临时输出与原文件同目录，保证最后的重命名不跨文件系统
+    let temp_output = Path::new(path).with_file_name(format!(
+        ".{}.chapters.{}",
+        file_stem(path),
+        extension
+    ));
+    Ok(temp_output.to_string_lossy().to_string())
+}
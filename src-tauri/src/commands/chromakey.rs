@@ -0,0 +1,126 @@
+/// 绿幕抠像 command
+///
+/// 用 chromakey 滤镜抠除纯色背景，合成到新背景上或输出带透明通道的视频/图片序列
+
+use std::path::Path;
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_chromakey_command;
+use crate::engine::process::{probe_duration, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::ChromakeyParams;
+use crate::models::task::TaskEvent;
+use crate::utils::path::file_extension;
+
+/// 执行绿幕抠像
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 抠像参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 颜色格式错误、参数超出范围、背景不存在、透明输出格式不支持或执行失败
+#[tauri::command]
+pub async fn apply_chromakey(
+    app: tauri::AppHandle,
+    params: ChromakeyParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_chromakey(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+    let args = build_chromakey_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备绿幕抠像任务：校验参数、填充默认参数并探测时长
+///
+/// 实际执行（apply_chromakey）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 抠像参数
+///
+/// # 返回
+/// - `Ok((ChromakeyParams, f64))` - 填充默认值后的参数和源文件时长（秒）
+/// - `Err(String)` - 参数非法或探测失败
+pub(crate) async fn prepare_chromakey(
+    app: &tauri::AppHandle,
+    mut params: ChromakeyParams,
+) -> Result<(ChromakeyParams, f64), String> {
+    validate_chromakey(&params)?;
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let duration = probe_duration(app, &params.input_path, settings.probe_timeout_ms).await?;
+    Ok((params, duration))
+}
+
+/// 校验抠像颜色、阈值、背景文件和透明输出格式
+fn validate_chromakey(params: &ChromakeyParams) -> Result<(), String> {
+    let hex = params.key_color.strip_prefix('#').unwrap_or("");
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "Key color must be a hex color like #00FF00, got {}",
+            params.key_color
+        ));
+    }
+    if !(0.01..=1.0).contains(&params.similarity) {
+        return Err(format!(
+            "Similarity must be between 0.01 and 1.0, got {}",
+            params.similarity
+        ));
+    }
+    if !(0.0..=1.0).contains(&params.blend) {
+        return Err(format!(
+            "Blend must be between 0.0 and 1.0, got {}",
+            params.blend
+        ));
+    }
+
+    match params.background_path.as_deref() {
+        Some(background) if !Path::new(background).is_file() => {
+            Err(format!("Background file not found: {}", background))
+        }
+        Some(_) => Ok(()),
+        None => match file_extension(&params.output_path).as_str() {
+            "webm" => Ok(()),
+            // 视频逐帧输出为图片，文件名需要帧序号占位符
+            "png" if params.output_path.contains('%') => Ok(()),
+            "png" => Err(
+                "PNG output needs a frame number pattern in the file name, e.g. frame_%04d.png"
+                    .to_string(),
+            ),
+            _ => Err("Transparent output must be a .webm or .png file".to_string()),
+        },
+    }
+}
@@ -0,0 +1,115 @@
+/// 片头片尾拼接 command
+///
+/// 面向品牌包装的固定流程：在正片前后拼接复用的片头/片尾，
+/// 以正片的分辨率和帧率为归一化目标，复用合并的归一化路径
+
+use tauri::ipc::Channel;
+
+use crate::commands::merge::run_merge;
+use crate::commands::settings::load_settings;
+use crate::engine::process::{probe_media, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::{IntroOutroParams, MergeParams};
+use crate::models::task::TaskEvent;
+
+/// 为正片添加片头和/或片尾
+///
+/// 读取正片的显示分辨率（按旋转角度交换宽高、按像素宽高比换算宽度）和帧率，
+/// 把全部片段缩放补边到该尺寸后拼接
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 片头片尾拼接参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 未提供片头片尾、正片无视频流或执行失败
+#[tauri::command]
+pub async fn add_intro_outro(
+    app: tauri::AppHandle,
+    params: IntroOutroParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let merge_params = prepare_intro_outro(&app, params).await?;
+
+    let result = run_merge(&app, merge_params, &task_id, &on_progress).await?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 把片头片尾拼接参数转换为按正片格式归一化的合并参数
+///
+/// 实际执行（add_intro_outro）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 片头片尾拼接参数
+///
+/// # 返回
+/// - `Ok(MergeParams)` - 片头、正片、片尾依次拼接的合并参数
+/// - `Err(String)` - 未提供片头片尾或正片无视频流
+pub(crate) async fn prepare_intro_outro(
+    app: &tauri::AppHandle,
+    params: IntroOutroParams,
+) -> Result<MergeParams, String> {
+    if params.intro.is_none() && params.outro.is_none() {
+        return Err("Add an intro or an outro clip".to_string());
+    }
+
+    let settings = load_settings(app).await;
+    let (width, height, fps) =
+        probe_main_format(app, &params.main, settings.probe_timeout_ms).await?;
+
+    let input_paths: Vec<String> = params
+        .intro
+        .into_iter()
+        .chain(std::iter::once(params.main))
+        .chain(params.outro)
+        .collect();
+    Ok(MergeParams {
+        input_paths,
+        output_path: params.output_path,
+        transition: None,
+        normalize: true,
+        target_resolution: Some(format!("{}x{}", width, height)),
+        target_fps: Some(fps),
+        audio_bitrate: params.audio_bitrate,
+        quality: params.quality,
+        preset: params.preset,
+        audio_only_crossfade: None,
+        min_clip_duration: None,
+        normalize_loudness: None,
+        loudness_target_lufs: None,
+        loudness_range: None,
+        true_peak: None,
+        target_channels: None,
+    })
+}
+
+/// 获取正片的显示分辨率（偶数宽高）和帧率
+async fn probe_main_format(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<(u32, u32, f64), String> {
+    let info = probe_media(app, file_path, timeout_ms).await?;
+    let video = info
+        .video_streams
+        .first()
+        .ok_or_else(|| "The main clip has no video stream".to_string())?;
+
+    // 归一化滤镜先按 SAR 把宽度换算成方形像素，目标尺寸也按换算后的宽度计算
+    let width = (video.width as f64 * video.sar()).round() as u32;
+    let (width, height) = if video.rotation == 90 || video.rotation == 270 {
+        (video.height, width)
+    } else {
+        (width, video.height)
+    };
+    let fps = if video.frame_rate > 0.0 { video.frame_rate } else { 30.0 };
+    Ok((width / 2 * 2, height / 2 * 2, fps))
+}
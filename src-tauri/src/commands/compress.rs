@@ -3,13 +3,25 @@
 /// 支持三种压缩模式：按目标大小、按压缩比例、按质量等级。
 /// 根据模式选择不同的码率/CRF 策略实现视频体积缩减
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use tauri::ipc::Channel;
 
+use crate::commands::deinterlace::apply_auto_deinterlace;
+use crate::commands::settings::load_settings;
 use crate::engine::builder::build_compress_command;
-use crate::engine::process::{run_ffmpeg, run_ffprobe};
-use crate::models::media::FfprobeOutput;
-use crate::models::preset::CompressParams;
-use crate::models::task::TaskEvent;
+use crate::engine::pass_cache::{
+    first_pass_signature, invalidate_stats, lookup_stats, store_stats,
+};
+use crate::engine::presets::{get_platform_constraints, plan_platform_constraints};
+use crate::engine::process::{
+    probe_media, run_ffmpeg, run_ffmpeg_two_pass, run_ffmpeg_two_pass_cached, send_phase,
+    CachedTwoPass, ANALYZING_INPUT_PHASE,
+};
+use crate::models::media::VideoStream;
+use crate::models::preset::{CompressMode, CompressParams};
+use crate::models::task::{TaskEvent, TaskResult, TaskStatus};
+use crate::utils::path::{cleanup_passlog_files, temp_file_path};
 
 /// 执行视频压缩
 ///
@@ -31,23 +43,46 @@ pub async fn compress_video(
     on_progress: Channel<TaskEvent>,
 ) -> Result<String, String> {
     let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
 
-    // 获取输入文件的时长和码率（用于压缩计算）
-    let (duration, bitrate) = get_media_stats(&app, &params.input_path).await?;
+    let prepared = prepare_compress(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
 
-    // 构建压缩命令
-    let args = build_compress_command(&params, duration, bitrate);
+    let result = if prepared.two_pass {
+        run_two_pass(&app, &task_id, &prepared, &on_progress).await?
+    } else {
+        let PreparedCompress {
+            params,
+            duration,
+            bitrate,
+            video_stream,
+            thread_count,
+            ..
+        } = &prepared;
 
-    // 执行 ffmpeg
-    let result = run_ffmpeg(
-        &app,
-        &task_id,
-        args,
-        duration,
-        &params.output_path,
-        &on_progress,
-    )
-    .await?;
+        // 构建压缩命令（单遍编码只有一组参数）
+        let stream = video_stream.as_ref();
+        let args = build_compress_command(params, *duration, *bitrate, stream, None, *thread_count)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        // 执行 ffmpeg
+        run_ffmpeg(
+            &app,
+            &task_id,
+            args,
+            *duration,
+            &params.output_path,
+            &on_progress,
+        )
+        .await?
+    };
 
     {
         let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
@@ -61,24 +96,193 @@ pub async fn compress_video(
     }
 }
 
-/// 获取视频的时长和总码率
+/// 准备好、可直接构建命令的压缩任务
+pub(crate) struct PreparedCompress {
+    /// 填充默认值并按源文件自动调整后的参数
+    pub params: CompressParams,
+    /// 源文件时长（秒）
+    pub duration: f64,
+    /// 源视频码率（bps）
+    pub bitrate: u64,
+    /// 第一条视频流信息
+    pub video_stream: Option<VideoStream>,
+    /// 是否执行两遍编码
+    pub two_pass: bool,
+    /// 编码线程数上限（设置中的 ffmpeg_threads）
+    pub thread_count: Option<u32>,
+}
+
+/// 准备压缩任务：填充默认参数、探测输入并决定平台约束、去隔行和两遍编码
+///
+/// 实际执行（compress_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 视频压缩参数
+/// - `notify` - 自动调整参数时的提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok(PreparedCompress)` - 调整后的参数和构建命令所需的源文件信息
+/// - `Err(String)` - 探测失败
+pub(crate) async fn prepare_compress(
+    app: &tauri::AppHandle,
+    mut params: CompressParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<PreparedCompress, String> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    // 获取输入文件的时长、码率和视频流信息（用于压缩计算和平台约束判断）
+    let (duration, bitrate, video_stream) =
+        get_media_stats(app, &params.input_path, settings.probe_timeout_ms).await?;
+
+    // 按发布平台约束调整时，告知前端具体做了哪些改动
+    if let (Some(platform), Some(stream)) = (params.platform.as_deref(), video_stream.as_ref()) {
+        if let Some(constraints) = get_platform_constraints(platform) {
+            for message in plan_platform_constraints(constraints, stream).notices {
+                notify(message);
+            }
+        }
+    }
+
+    apply_auto_deinterlace(&mut params.deinterlace, video_stream.as_ref(), notify);
+
+    // 两遍编码仅对按目标大小的软件编码生效
+    let requested_two_pass = params.two_pass_options.two_pass;
+    let two_pass = requested_two_pass
+        && !params.hardware_accel.unwrap_or(false)
+        && matches!(params.mode, CompressMode::BySize);
+    if requested_two_pass && !two_pass {
+        notify(
+            "Two-pass encoding only applies to target-size compression with software \
+             encoding; using a single pass"
+                .to_string(),
+        );
+    }
+
+    Ok(PreparedCompress {
+        params,
+        duration,
+        bitrate,
+        video_stream,
+        two_pass,
+        thread_count: settings.ffmpeg_threads,
+    })
+}
+
+/// 两遍编码压缩
+///
+/// 同一源文件以相同第一遍参数压缩过、已有缓存的统计时跳过第一遍，直接用缓存执行第二遍；
+/// 缓存统计导致第二遍失败时删除该条目并退回完整两遍；否则完整执行两遍并缓存统计。平台约束会缩放或改帧率，分析的画面与源文件不同，不读写缓存。
+/// 临时统计文件无论成功、失败还是取消都会清理
+async fn run_two_pass(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    prepared: &PreparedCompress,
+    on_progress: &Channel<TaskEvent>,
+) -> Result<TaskResult, String> {
+    let PreparedCompress {
+        params,
+        duration,
+        bitrate,
+        video_stream,
+        thread_count,
+        ..
+    } = prepared;
+    let (duration, bitrate, threads) = (*duration, *bitrate, *thread_count);
+    let video_stream = video_stream.as_ref();
+
+    let passlog_prefix = temp_file_path("passlog", "stats")?;
+    let passlog = Some(passlog_prefix.as_str());
+    let mut passes =
+        build_compress_command(params, duration, bitrate, video_stream, passlog, threads)
+            .into_iter();
+    let pass1 = passes.next().unwrap_or_default();
+    let pass2 = passes.next().unwrap_or_default();
+
+    // x264 第二遍要求帧类型决策与第一遍一致，第一遍参数有任何不同的统计都不能复用
+    let signature = first_pass_signature(&pass1, &params.input_path, &passlog_prefix);
+    let cacheable = params.platform.is_none();
+
+    let cached = cacheable
+        .then(|| lookup_stats(app, &params.input_path, &signature))
+        .flatten();
+    if let Some(cached) = cached {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.to_string(),
+            message: "Reusing first-pass statistics from a previous encode of this file; \
+                      skipping the first pass"
+                .to_string(),
+        });
+        let cached = Some(cached.as_str());
+        let cached_pass =
+            build_compress_command(params, duration, bitrate, video_stream, cached, threads)
+                .pop()
+                .unwrap_or_default();
+        let passes = CachedTwoPass {
+            cached_pass,
+            pass1,
+            pass2,
+        };
+        // 缓存统计无法使用时删除条目，本次退回完整两遍编码并重新缓存
+        let fell_back = AtomicBool::new(false);
+        let result = run_ffmpeg_two_pass_cached(
+            app,
+            task_id,
+            passes,
+            duration,
+            &params.output_path,
+            on_progress,
+            |_| {
+                invalidate_stats(app, &params.input_path, &signature);
+                fell_back.store(true, Ordering::SeqCst);
+            },
+        )
+        .await;
+        let completed = result.as_ref().is_ok_and(|r| r.status == TaskStatus::Completed);
+        if fell_back.load(Ordering::SeqCst) && completed {
+            let _ = store_stats(app, &params.input_path, &signature, &passlog_prefix);
+        }
+        cleanup_passlog_files(&passlog_prefix);
+        return result;
+    }
+
+    let result = run_ffmpeg_two_pass(
+        app,
+        task_id,
+        pass1,
+        pass2,
+        duration,
+        &params.output_path,
+        on_progress,
+    )
+    .await;
+
+    if cacheable && result.as_ref().is_ok_and(|r| r.status == TaskStatus::Completed) {
+        // 缓存失败不影响本次压缩结果
+        let _ = store_stats(app, &params.input_path, &signature, &passlog_prefix);
+    }
+    cleanup_passlog_files(&passlog_prefix);
+    result
+}
+
+/// 获取视频的时长、视频码率（缺失时为容器总码率）和第一条视频流信息
 async fn get_media_stats(
     app: &tauri::AppHandle,
     file_path: &str,
-) -> Result<(f64, u64), String> {
-    let json_str = run_ffprobe(app, file_path).await?;
-    let output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("解析 ffprobe 输出失败: {}", e))?;
-
-    let format = output.format.as_ref();
-    let duration = format
-        .and_then(|f| f.duration.as_ref())
-        .and_then(|d| d.parse::<f64>().ok())
-        .unwrap_or(0.0);
-    let bitrate = format
-        .and_then(|f| f.bit_rate.as_ref())
-        .and_then(|b| b.parse::<u64>().ok())
-        .unwrap_or(0);
-
-    Ok((duration, bitrate))
+    timeout_ms: u64,
+) -> Result<(f64, u64, Option<VideoStream>), String> {
+    let info = probe_media(app, file_path, timeout_ms).await?;
+    let duration = info.duration;
+    let video_stream = info.video_streams.into_iter().next();
+    // 优先使用视频流自身（或 MKV 统计标签）的码率，估算值和缺失时退回容器总码率
+    let bitrate = video_stream
+        .as_ref()
+        .filter(|v| !v.bitrate_estimated)
+        .and_then(|v| v.bitrate)
+        .unwrap_or(info.bitrate);
+
+    Ok((duration, bitrate, video_stream))
 }
@@ -5,11 +5,27 @@
 
 use tauri::ipc::Channel;
 
-use crate::engine::builder::build_convert_command;
-use crate::engine::process::{run_ffmpeg, run_ffprobe};
-use crate::models::media::FfprobeOutput;
-use crate::models::preset::ConvertParams;
-use crate::models::task::TaskEvent;
+use crate::commands::deinterlace::apply_auto_deinterlace;
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{
+    build_convert_audio_only_command, build_convert_command, build_convert_two_pass_commands,
+    build_multi_resolution_command, videotoolbox_encoder,
+};
+use crate::engine::compatibility::{container_compatibility, encoder_output_codec, Compatibility};
+use crate::engine::presets::{streaming_gop_size, STREAMING_FORMATS, STREAMING_PIXEL_FORMAT};
+use crate::engine::process::{
+    hardware_encoder_available, probe_output, run_ffmpeg, run_ffmpeg_two_pass_verified,
+    run_ffmpeg_verified, send_phase, ANALYZING_INPUT_PHASE,
+};
+use crate::engine::verify::ExpectedOutput;
+use crate::models::media::{parse_frame_rate, MediaInfo};
+use crate::models::preset::{
+    ConvertParams, MultiResolutionParams, MultiResolutionResult, VariantOutput,
+};
+use crate::models::task::{TaskEvent, TaskResult};
+use crate::utils::path::{
+    cleanup_passlog_files, generate_output_path, get_file_size, temp_file_path,
+};
 
 /// 执行视频格式转换
 ///
@@ -33,46 +49,634 @@ pub async fn convert_video(
     // 生成唯一任务 ID
     let task_id = uuid::Uuid::new_v4().to_string();
 
-    // 获取输入文件时长（用于进度计算）
-    let total_duration = get_duration(&app, &params.input_path).await?;
+    let result = run_convert(&app, params, &task_id, &on_progress).await?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 执行单个格式转换任务
+///
+/// convert_video 和批量处理共用：填充默认参数、探测输入、执行（两遍）编码并核对输出
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 格式转换参数
+/// - `task_id` - 任务 ID
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(TaskResult)` - 任务已执行（成功、失败或取消，对应事件已推送）
+/// - `Err(String)` - 启动前失败（参数非法、探测失败），未推送任何事件
+pub(crate) async fn run_convert(
+    app: &tauri::AppHandle,
+    params: ConvertParams,
+    task_id: &str,
+    on_progress: &Channel<TaskEvent>,
+) -> Result<TaskResult, String> {
+    send_phase(on_progress, task_id, ANALYZING_INPUT_PHASE);
+    let prepared = prepare_convert(app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.to_string(),
+            message,
+        });
+    })
+    .await?;
+    let PreparedConvert {
+        params,
+        total_duration,
+        rotation,
+        expected,
+        audio_only,
+        thread_count,
+        ..
+    } = prepared;
+
+    let result = if params.two_pass.unwrap_or(false) {
+        // 统计文件写入临时目录，无论成功、失败还是取消都要清理
+        let passlog_prefix = temp_file_path("passlog", "stats")?;
+        let passes =
+            build_convert_two_pass_commands(&params, rotation, &passlog_prefix, thread_count);
+        let result = run_ffmpeg_two_pass_verified(
+            app,
+            task_id,
+            passes,
+            total_duration,
+            &params.output_path,
+            on_progress,
+            &expected,
+        )
+        .await;
+        cleanup_passlog_files(&passlog_prefix);
+        result
+    } else {
+        // 构建 ffmpeg 命令参数（纯音频输入不设置视频编码）
+        let args = if audio_only {
+            build_convert_audio_only_command(&params, thread_count)
+        } else {
+            build_convert_command(&params, rotation, thread_count)
+        };
+
+        // 启动 ffmpeg 进程并等待完成，完成后核对输出文件的编码属性
+        run_ffmpeg_verified(
+            app,
+            task_id,
+            args,
+            total_duration,
+            &params.output_path,
+            on_progress,
+            &expected,
+        )
+        .await
+    };
+
+    // 清理任务队列记录
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(task_id);
+    }
+
+    result
+}
+
+/// 准备好、可直接构建命令的格式转换任务
+pub(crate) struct PreparedConvert {
+    /// 填充默认值并按源文件自动调整后的参数
+    pub params: ConvertParams,
+    /// 源文件时长（秒），用于进度计算
+    pub total_duration: f64,
+    /// 源视频旋转角度（0/90/180/270）
+    pub rotation: u32,
+    /// 输出核对项
+    pub expected: ExpectedOutput,
+    /// 源文件媒体信息
+    pub media_info: MediaInfo,
+    /// 输入是否为纯音频（没有视频流），需用 build_convert_audio_only_command 构建命令
+    pub audio_only: bool,
+    /// 编码线程数上限（设置中的 ffmpeg_threads）
+    pub thread_count: Option<u32>,
+}
+
+/// 准备格式转换任务：填充默认参数、校验、探测输入并按源文件自动调整参数
+///
+/// 实际执行（run_convert）和命令预览（preview_command）共用，保证预览的命令与实际执行一致
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 格式转换参数
+/// - `notify` - 自动调整参数时的提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok(PreparedConvert)` - 调整后的参数和构建命令所需的源文件信息
+/// - `Err(String)` - 参数非法或探测失败
+pub(crate) async fn prepare_convert(
+    app: &tauri::AppHandle,
+    mut params: ConvertParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<PreparedConvert, String> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    validate_audio_sample_rate(&params)?;
+
+    // "hevc" 是 libx265 的别名
+    if params.video_codec == "hevc" {
+        params.video_codec = "libx265".to_string();
+    }
+
+    // 硬件编码器不可用时回退到软件编码，而不是让任务失败
+    let hardware = params.hardware_accel.unwrap_or(false)
+        && params.video_codec != "copy"
+        && !params.output_format.eq_ignore_ascii_case("webm");
+    if hardware {
+        let encoder = videotoolbox_encoder(&params.video_codec);
+        if !hardware_encoder_available(app, encoder).await {
+            log::warn!("{} 不可用，回退到软件编码 {}", encoder, params.video_codec);
+            params.hardware_accel = Some(false);
+            notify(format!(
+                "{} is not available on this Mac; encoding with {} instead",
+                encoder, params.video_codec
+            ));
+        }
+    }
+
+    validate_streaming(&params)?;
+    if params.streaming == Some(true) {
+        params.pixel_format.get_or_insert_with(|| STREAMING_PIXEL_FORMAT.to_string());
+    }
+    validate_pixel_format(&params)?;
+
+    // 探测输入文件：时长用于进度计算，主视频流用于推导输出核对项
+    let probe = probe_output(app, &params.input_path, settings.probe_timeout_ms).await?;
+    let total_duration = probe.duration();
+    let source_video = probe.main_video_stream();
+    let audio_only = probe.is_audio_only();
+    if source_video.is_none() && !audio_only {
+        return Err("The input has no video or audio stream".to_string());
+    }
+    let mut expected = ExpectedOutput::from_convert(&params, source_video, probe.chapters.len());
+    if audio_only {
+        // 纯音频输入只转换音频，视频编码参数和两遍编码都不适用
+        expected = expected.audio_only();
+        notify("The input has no video stream; converting the audio only".to_string());
+        if params.two_pass == Some(true) {
+            params.two_pass = Some(false);
+            notify("Two-pass encoding applies to video only; using a single pass".to_string());
+        }
+    }
+    let rotation = source_video.map(|s| s.rotation()).unwrap_or(0);
+
+    // 启动 ffmpeg 前拦截容器不支持的编码组合，避免编码数分钟后才在封装阶段失败
+    let media_info = probe.to_media_info(&params.input_path);
+    for warning in validate_convert_params(&params, &media_info)? {
+        notify(warning);
+    }
+
+    // 流媒体输出未指定 GOP 时按源帧率计算，使关键帧间隔固定为 2 秒
+    if params.streaming == Some(true) && params.gop_size.is_none() {
+        let frame_rate = source_video
+            .and_then(|s| s.r_frame_rate.as_deref())
+            .map(parse_frame_rate)
+            .unwrap_or(0.0);
+        params.gop_size = Some(streaming_gop_size(frame_rate));
+    }
+
+    // H.264 VideoToolbox 不接受 10-bit 输入，源为 10-bit 且未指定像素格式时降为 8-bit
+    let source_high_bit_depth = source_video
+        .and_then(|s| s.pix_fmt.as_deref())
+        .is_some_and(is_high_bit_depth);
+    if params.pixel_format.is_none() && uses_h264_videotoolbox(&params) && source_high_bit_depth {
+        params.pixel_format = Some("yuv420p".to_string());
+        notify("H.264 hardware encoding does not support 10-bit; converting to yuv420p".to_string());
+    }
+
+    // 未指定是否去隔行时按源视频场序自动判断（流复制时无法应用滤镜）
+    if params.video_codec != "copy" {
+        apply_auto_deinterlace(&mut params.deinterlace, media_info.video_streams.first(), notify);
+    }
+
+    if params.two_pass.unwrap_or(false) {
+        validate_two_pass(&params)?;
+    }
+
+    Ok(PreparedConvert {
+        params,
+        total_duration,
+        rotation,
+        expected,
+        media_info,
+        audio_only,
+        thread_count: settings.ffmpeg_threads,
+    })
+}
+
+/// 校验输出容器与视频/音频编码的组合
+///
+/// 重编码时检查所选编码器的输出编码，流复制时检查源文件对应流的编码。
+/// 容器不支持的组合直接报错；可以封装但兼容性差的组合（如 MP4 中的 FLAC）返回提示，照常转换
+///
+/// # 参数
+/// - `params` - 格式转换参数（"hevc" 别名已替换为 libx265）
+/// - `source` - 源文件的媒体信息
+///
+/// # 返回
+/// - `Ok(Vec<String>)` - 兼容性提示（可能为空）
+/// - `Err(String)` - 不支持的组合
+fn validate_convert_params(
+    params: &ConvertParams,
+    source: &MediaInfo,
+) -> Result<Vec<String>, String> {
+    let format = params.output_format.to_lowercase();
+    let is_webm = format == "webm";
+    let mut warnings = Vec::new();
+
+    // 纯音频输入不输出视频，不检查视频编码
+    let video = if source.video_streams.is_empty() {
+        None
+    } else if params.video_codec == "copy" {
+        source.video_streams.first().map(|s| (s.codec_name.as_str(), true))
+    } else if is_webm {
+        None
+    } else {
+        Some((encoder_output_codec(&params.video_codec), false))
+    };
+    validate_stream_selection(params, source)?;
+
+    // 流复制时逐条检查实际输出的音轨
+    let audio: Vec<(&str, bool)> = if params.audio_codec == "copy" {
+        let selected = params.audio_stream_indexes.as_ref().filter(|i| !i.is_empty());
+        match selected {
+            Some(indexes) => indexes
+                .iter()
+                .filter_map(|&i| source.audio_streams.get(i as usize))
+                .map(|s| (s.codec_name.as_str(), true))
+                .collect(),
+            None if params.keep_all_streams == Some(true) => source
+                .audio_streams
+                .iter()
+                .map(|s| (s.codec_name.as_str(), true))
+                .collect(),
+            None => source
+                .audio_streams
+                .first()
+                .map(|s| (s.codec_name.as_str(), true))
+                .into_iter()
+                .collect(),
+        }
+    } else if is_webm {
+        Vec::new()
+    } else {
+        vec![(encoder_output_codec(&params.audio_codec), false)]
+    };
+
+    for (codec, copied) in video.into_iter().chain(audio) {
+        match container_compatibility(&format, codec) {
+            Compatibility::Supported => {}
+            Compatibility::Limited(reason) => warnings.push(reason.to_string()),
+            Compatibility::Unsupported if copied => {
+                return Err(format!(
+                    "Cannot copy the source {} stream into {}; \
+                     re-encode it or choose another format",
+                    codec.to_uppercase(),
+                    format.to_uppercase()
+                ))
+            }
+            Compatibility::Unsupported => {
+                return Err(format!(
+                    "{} does not support {}; choose another codec or output format",
+                    format.to_uppercase(),
+                    codec.to_uppercase()
+                ))
+            }
+        }
+    }
+    Ok(warnings)
+}
+
+/// 图形字幕编码（无法转换为 mov_text / WebVTT 等文本字幕）
+const BITMAP_SUBTITLE_CODECS: &[&str] = &["hdmv_pgs_subtitle", "dvd_subtitle", "dvb_subtitle"];
 
-    // 构建 ffmpeg 命令参数
-    let args = build_convert_command(&params);
+/// 校验多轨选择
+///
+/// 所选音轨必须存在且不重复；保留全部字幕输出到 MP4/MOV/WebM 时，
+/// 源中的图形字幕（PGS/VobSub）无法转为文本字幕，需改用 MKV
+fn validate_stream_selection(params: &ConvertParams, source: &MediaInfo) -> Result<(), String> {
+    if let Some(indexes) = &params.audio_stream_indexes {
+        let count = source.audio_streams.len();
+        if let Some(&index) = indexes.iter().find(|&&i| i as usize >= count) {
+            return Err(format!(
+                "Audio track {} does not exist; the input has {} audio track(s)",
+                index + 1,
+                count
+            ));
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(&index) = indexes.iter().find(|&&i| !seen.insert(i)) {
+            return Err(format!("Audio track {} is selected more than once", index + 1));
+        }
+    }
+
+    let format = params.output_format.to_lowercase();
+    let text_subtitles_only = matches!(format.as_str(), "mp4" | "mov" | "m4v" | "webm");
+    if params.keep_all_streams == Some(true) && text_subtitles_only {
+        if let Some(stream) = source
+            .subtitle_streams
+            .iter()
+            .find(|s| BITMAP_SUBTITLE_CODECS.contains(&s.codec_name.as_str()))
+        {
+            return Err(format!(
+                "The {} subtitle track is image-based and cannot be stored in {}; \
+                 choose MKV to keep all streams",
+                stream.codec_name,
+                format.to_uppercase()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 校验流媒体输出参数
+///
+/// 固定 GOP 等设置只能在重编码时生效，faststart 只适用于 MP4/MOV；
+/// 8-bit 4:2:0 以外的像素格式会让部分浏览器和移动设备无法硬解
+fn validate_streaming(params: &ConvertParams) -> Result<(), String> {
+    if params.streaming != Some(true) {
+        return Ok(());
+    }
+    if params.video_codec == "copy" {
+        return Err("Streaming output requires re-encoding the video".to_string());
+    }
+    let format = params.output_format.to_lowercase();
+    if !STREAMING_FORMATS.contains(&format.as_str()) {
+        return Err(format!(
+            "Streaming output must be {}, got {}",
+            STREAMING_FORMATS.join(" or "),
+            params.output_format
+        ));
+    }
+    if params
+        .pixel_format
+        .as_deref()
+        .is_some_and(|f| f != STREAMING_PIXEL_FORMAT)
+    {
+        return Err(format!(
+            "Streaming output must use the {} pixel format",
+            STREAMING_PIXEL_FORMAT
+        ));
+    }
+    Ok(())
+}
+
+/// 校验音频采样率
+///
+/// Opus 编码器只接受 8/12/16/24/48 kHz；WebM 输出未选 Vorbis 时会被替换为 Opus，一并检查
+fn validate_audio_sample_rate(params: &ConvertParams) -> Result<(), String> {
+    let Some(rate) = params.audio_sample_rate else {
+        return Ok(());
+    };
+    if !(8000..=192_000).contains(&rate) {
+        return Err(format!(
+            "Audio sample rate must be between 8000 and 192000 Hz, got {}",
+            rate
+        ));
+    }
+    let is_webm = params.output_format.eq_ignore_ascii_case("webm");
+    let is_opus = params.audio_codec == "libopus"
+        || (is_webm && !matches!(params.audio_codec.as_str(), "libvorbis" | "copy"));
+    if is_opus && ![8000, 12000, 16000, 24000, 48000].contains(&rate) {
+        return Err(format!(
+            "Opus only supports 8000, 12000, 16000, 24000 or 48000 Hz, got {}",
+            rate
+        ));
+    }
+    Ok(())
+}
 
-    // 启动 ffmpeg 进程并等待完成
+/// 转换时允许指定的输出像素格式
+const ALLOWED_PIXEL_FORMATS: [&str; 4] = ["yuv420p", "yuv420p10le", "yuv422p10le", "yuva420p"];
+
+/// 校验输出像素格式
+///
+/// 只接受白名单内的格式；H.264 VideoToolbox 无法编码 10-bit
+fn validate_pixel_format(params: &ConvertParams) -> Result<(), String> {
+    let Some(pix_fmt) = params.pixel_format.as_deref() else {
+        return Ok(());
+    };
+    if !ALLOWED_PIXEL_FORMATS.contains(&pix_fmt) {
+        return Err(format!(
+            "Unsupported pixel format {}; use one of {}",
+            pix_fmt,
+            ALLOWED_PIXEL_FORMATS.join(", ")
+        ));
+    }
+    if is_high_bit_depth(pix_fmt) && uses_h264_videotoolbox(params) {
+        return Err(format!(
+            "H.264 hardware encoding does not support {}; use HEVC or software encoding",
+            pix_fmt
+        ));
+    }
+    Ok(())
+}
+
+/// 是否使用 H.264 VideoToolbox 编码（与构建命令时的编码器选择一致）
+fn uses_h264_videotoolbox(params: &ConvertParams) -> bool {
+    params.hardware_accel.unwrap_or(false)
+        && params.video_codec != "copy"
+        && !params.output_format.eq_ignore_ascii_case("webm")
+        && videotoolbox_encoder(&params.video_codec) == "h264_videotoolbox"
+}
+
+/// 像素格式是否为 10-bit 及以上（如 yuv420p10le、p010le、yuv444p12le）
+fn is_high_bit_depth(pix_fmt: &str) -> bool {
+    ["10", "12", "16"].iter().any(|depth| pix_fmt.contains(depth))
+}
+
+/// 校验两遍编码参数
+///
+/// 两遍编码按目标码率分配，必须指定码率；硬件编码和流复制没有第一遍统计可用。
+/// WebM 输出会被替换为 VP9，因此不检查所选编码器
+fn validate_two_pass(params: &ConvertParams) -> Result<(), String> {
+    if params.video_bitrate.as_deref().is_none_or(|b| b.trim().is_empty()) {
+        return Err("Two-pass encoding requires a target video bitrate".to_string());
+    }
+    if params.video_codec == "copy" {
+        return Err(
+            "Two-pass encoding is not available when copying the video stream".to_string(),
+        );
+    }
+    let is_webm = params.output_format.eq_ignore_ascii_case("webm");
+    if params.hardware_accel.unwrap_or(false) && !is_webm {
+        return Err("Two-pass encoding is not available with hardware acceleration".to_string());
+    }
+    if !is_webm && !matches!(params.video_codec.as_str(), "libx264" | "libx265" | "libvpx-vp9") {
+        return Err(format!(
+            "Two-pass encoding is not supported for encoder {}",
+            params.video_codec
+        ));
+    }
+    Ok(())
+}
+
+/// 一次解码输出多个分辨率
+///
+/// 单次 ffmpeg 调用用 split + scale 生成全部分辨率，比逐个调用 convert 少 N-1 次解码。
+/// 所有输出同时完成，完成后逐档推送提示并在结果中返回各档文件大小。
+/// 不做放大：高于源视频的档位直接报错
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 多分辨率输出参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(MultiResolutionResult)` - 任务 ID 和各档输出
+/// - `Err(String)` - 参数无效或执行失败
+#[tauri::command]
+pub async fn multi_resolution(
+    app: tauri::AppHandle,
+    params: MultiResolutionParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<MultiResolutionResult, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let PreparedMultiResolution {
+        params,
+        outputs,
+        has_audio,
+        duration,
+    } = prepare_multi_resolution(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_multi_resolution_command(&params, &outputs, has_audio, thread_count);
+
+    // run_ffmpeg 以第一档为主输出路径（Completed 事件中的 outputPath）
     let result = run_ffmpeg(
         &app,
         &task_id,
         args,
-        total_duration,
-        &params.output_path,
+        duration,
+        &outputs[0].1,
         &on_progress,
     )
-    .await?;
+    .await;
 
-    // 清理任务队列记录
     {
         let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
         queue.cleanup(&task_id);
     }
 
-    if result.error.is_some() {
-        Err(result.error.unwrap())
-    } else {
-        Ok(task_id)
+    let result = result?;
+    if let Some(error) = result.error {
+        return Err(error);
     }
+
+    let outputs: Vec<VariantOutput> = outputs
+        .into_iter()
+        .map(|(height, output_path)| VariantOutput {
+            height,
+            output_size: get_file_size(&output_path),
+            output_path,
+        })
+        .collect();
+    for output in &outputs {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message: format!("{}p variant written to {}", output.height, output.output_path),
+        });
+    }
+
+    Ok(MultiResolutionResult { task_id, outputs })
 }
 
-/// 从 ffprobe 输出中获取视频时长
-async fn get_duration(app: &tauri::AppHandle, file_path: &str) -> Result<f64, String> {
-    let json_str = run_ffprobe(app, file_path).await?;
-    let output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("解析 ffprobe 输出失败: {}", e))?;
-    let duration = output
-        .format
-        .as_ref()
-        .and_then(|f| f.duration.as_ref())
-        .and_then(|d| d.parse::<f64>().ok())
-        .unwrap_or(0.0);
-    Ok(duration)
+/// 准备好、可直接构建命令的多分辨率输出任务
+pub(crate) struct PreparedMultiResolution {
+    /// 填充默认值后的参数
+    pub params: MultiResolutionParams,
+    /// 各档的输出高度和输出路径
+    pub outputs: Vec<(u32, String)>,
+    /// 源文件是否有音频流
+    pub has_audio: bool,
+    /// 源文件时长（秒）
+    pub duration: f64,
+}
+
+/// 准备多分辨率输出任务：填充默认参数、校验格式和各档高度并确定输出路径
+///
+/// 实际执行（multi_resolution）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 多分辨率输出参数
+///
+/// # 返回
+/// - `Ok(PreparedMultiResolution)` - 各档输出路径和源文件信息
+/// - `Err(String)` - 格式不支持、档位非法、超过源分辨率或输出路径冲突
+pub(crate) async fn prepare_multi_resolution(
+    app: &tauri::AppHandle,
+    mut params: MultiResolutionParams,
+) -> Result<PreparedMultiResolution, String> {
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let format = params.output_format.as_deref().unwrap_or("mp4").to_lowercase();
+    if !matches!(format.as_str(), "mp4" | "mov" | "mkv") {
+        return Err(format!("Unsupported output format for multiple resolutions: {}", format));
+    }
+    if params.variants.is_empty() {
+        return Err("At least one resolution variant is required".to_string());
+    }
+
+    let probe = probe_output(app, &params.input_path, settings.probe_timeout_ms).await?;
+    let media_info = probe.to_media_info(&params.input_path);
+    let source = media_info
+        .video_streams
+        .first()
+        .ok_or_else(|| "The input file has no video stream".to_string())?;
+    let has_audio = !media_info.audio_streams.is_empty();
+
+    let mut outputs: Vec<(u32, String)> = Vec::with_capacity(params.variants.len());
+    for variant in &params.variants {
+        if variant.height == 0 || variant.height % 2 != 0 {
+            return Err(format!(
+                "Variant height must be a positive even number, got {}",
+                variant.height
+            ));
+        }
+        if variant.height > source.height {
+            return Err(format!(
+                "Variant {}p exceeds the {}p source; upscaling is not supported",
+                variant.height, source.height
+            ));
+        }
+        let suffix = variant
+            .suffix
+            .clone()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| format!("_{}p", variant.height));
+        let path = generate_output_path(
+            &params.input_path,
+            &params.output_dir,
+            &suffix,
+            &format,
+            !settings.overwrite_existing,
+        );
+        if outputs.iter().any(|(_, existing)| *existing == path) {
+            return Err(format!("Two variants would write the same file: {}", path));
+        }
+        outputs.push((variant.height, path));
+    }
+
+    Ok(PreparedMultiResolution {
+        params,
+        outputs,
+        has_audio,
+        duration: media_info.duration,
+    })
 }
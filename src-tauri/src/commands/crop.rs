@@ -0,0 +1,132 @@
+/// 画面裁剪 command
+///
+/// 截取视频画面中的任意矩形区域（去除黑边、截取画面一角等）
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_crop_command;
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::CropParams;
+use crate::models::task::TaskEvent;
+
+/// 执行画面裁剪
+///
+/// 裁剪区域基于显示方向的画面（ffmpeg 滤镜前会按旋转元数据自动转正），
+/// 宽高为奇数时向下取偶（libx264 的 4:2:0 输出要求偶数尺寸）
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 画面裁剪参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 输入没有视频流、裁剪区域超出画面或执行失败
+#[tauri::command]
+pub async fn crop_video(
+    app: tauri::AppHandle,
+    params: CropParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_crop(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_crop_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备画面裁剪任务：填充默认参数、按显示方向的画面尺寸校验裁剪区域并把宽高取偶
+///
+/// 实际执行（crop_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 画面裁剪参数
+/// - `notify` - 自动调整参数时的提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok((CropParams, f64))` - 调整后的参数和源文件时长（秒）
+/// - `Err(String)` - 输入没有视频流或裁剪区域超出画面
+pub(crate) async fn prepare_crop(
+    app: &tauri::AppHandle,
+    mut params: CropParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<(CropParams, f64), String> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    let stream = media_info
+        .video_streams
+        .first()
+        .ok_or_else(|| "The input file has no video stream".to_string())?;
+    let (frame_width, frame_height) = if stream.rotation % 180 == 90 {
+        (stream.height, stream.width)
+    } else {
+        (stream.width, stream.height)
+    };
+    validate_crop_region(&params, frame_width, frame_height)?;
+
+    if params.width % 2 == 1 || params.height % 2 == 1 {
+        params.width -= params.width % 2;
+        params.height -= params.height % 2;
+        notify(format!(
+            "Crop size rounded down to {}x{} for H.264 encoding",
+            params.width, params.height
+        ));
+    }
+
+    Ok((params, media_info.duration))
+}
+
+/// 校验裁剪区域在画面内，且取偶后宽高仍不为零
+fn validate_crop_region(
+    params: &CropParams,
+    frame_width: u32,
+    frame_height: u32,
+) -> Result<(), String> {
+    if params.width < 2 || params.height < 2 {
+        return Err("The crop region must be at least 2x2 pixels".to_string());
+    }
+    let right = params.x.saturating_add(params.width);
+    let bottom = params.y.saturating_add(params.height);
+    if right > frame_width || bottom > frame_height {
+        return Err(format!(
+            "The crop region {}x{} at ({}, {}) extends outside the {}x{} frame",
+            params.width, params.height, params.x, params.y, frame_width, frame_height
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+/// 去隔行 command
+///
+/// 用 yadif / bwdif 把隔行扫描视频（广播、DV 采集素材）转为逐行
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_deinterlace_command;
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::media::VideoStream;
+use crate::models::preset::DeinterlaceParams;
+use crate::models::task::TaskEvent;
+
+/// 执行去隔行
+///
+/// 源视频的场序未标记为隔行时仍按参数处理（部分采集素材没有场序标记），只推送提示
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 去隔行参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 场序参数非法、输入没有视频流或执行失败
+#[tauri::command]
+pub async fn deinterlace_video(
+    app: tauri::AppHandle,
+    params: DeinterlaceParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_deinterlace(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_deinterlace_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备去隔行任务：校验场序参数、填充默认参数并确认输入有视频流
+///
+/// 实际执行（deinterlace_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 去隔行参数
+/// - `notify` - 提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok((DeinterlaceParams, f64))` - 填充默认值后的参数和源文件时长（秒）
+/// - `Err(String)` - 场序参数非法或输入没有视频流
+pub(crate) async fn prepare_deinterlace(
+    app: &tauri::AppHandle,
+    mut params: DeinterlaceParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<(DeinterlaceParams, f64), String> {
+    if params.parity.is_some_and(|p| !(-1..=1).contains(&p)) {
+        return Err(format!(
+            "Parity must be -1 (auto), 0 (top field first) or 1 (bottom field first), got {}",
+            params.parity.unwrap_or_default()
+        ));
+    }
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    let stream = media_info
+        .video_streams
+        .first()
+        .ok_or_else(|| "The input file has no video stream".to_string())?;
+
+    if !stream.is_interlaced {
+        notify("Source is not flagged as interlaced; deinterlacing anyway".to_string());
+    }
+
+    Ok((params, media_info.duration))
+}
+
+/// 未指定是否去隔行时按源视频场序自动判断，源为隔行时开启去隔行并推送提示
+///
+/// 转换、压缩、缩放和水印共用
+///
+/// # 参数
+/// - `deinterlace` - 参数中的去隔行开关，仅在为 None 时修改
+/// - `source` - 源文件的主视频流，没有视频流时为 None
+/// - `notify` - 提示回调（实际执行时推送 Notice 事件）
+pub(crate) fn apply_auto_deinterlace(
+    deinterlace: &mut Option<bool>,
+    source: Option<&VideoStream>,
+    notify: &mut (dyn FnMut(String) + Send),
+) {
+    if deinterlace.is_none() && source.is_some_and(|v| v.is_interlaced) {
+        *deinterlace = Some(true);
+        notify("Source is interlaced; deinterlacing with yadif".to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::media::video_stream;
+
+    #[test]
+    fn auto_deinterlace_only_fills_unset_flag_for_interlaced_sources() {
+        let interlaced = video_stream(serde_json::json!({ "isInterlaced": true }));
+        let progressive = video_stream(serde_json::json!({}));
+        let mut notices = Vec::new();
+
+        let mut deinterlace = None;
+        apply_auto_deinterlace(&mut deinterlace, Some(&interlaced), &mut |m| notices.push(m));
+        assert_eq!(deinterlace, Some(true));
+        assert_eq!(notices, ["Source is interlaced; deinterlacing with yadif"]);
+
+        let mut deinterlace = Some(false);
+        apply_auto_deinterlace(&mut deinterlace, Some(&interlaced), &mut |m| notices.push(m));
+        assert_eq!(deinterlace, Some(false));
+
+        let mut deinterlace = None;
+        apply_auto_deinterlace(&mut deinterlace, Some(&progressive), &mut |m| notices.push(m));
+        apply_auto_deinterlace(&mut deinterlace, None, &mut |m| notices.push(m));
+        assert_eq!(deinterlace, None);
+        assert_eq!(notices.len(), 1);
+    }
+}
@@ -0,0 +1,106 @@
+/// 降噪 command
+///
+/// 用 hqdn3d / nlmeans 去除老旧或低码率视频的噪点和压缩块效应
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_denoise_command;
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::{DenoiseMethod, DenoiseParams};
+use crate::models::task::TaskEvent;
+
+/// 执行降噪
+///
+/// nlmeans 比 hqdn3d 慢一个数量级以上，选择 nlmeans 时推送提示
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 降噪参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 强度非法、输入没有视频流或执行失败
+#[tauri::command]
+pub async fn denoise_video(
+    app: tauri::AppHandle,
+    params: DenoiseParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_denoise(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_denoise_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备降噪任务：校验强度、填充默认参数并确认输入有视频流
+///
+/// 实际执行（denoise_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 降噪参数
+/// - `notify` - 提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok((DenoiseParams, f64))` - 填充默认值后的参数和源文件时长（秒）
+/// - `Err(String)` - 强度非法或输入没有视频流
+pub(crate) async fn prepare_denoise(
+    app: &tauri::AppHandle,
+    mut params: DenoiseParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<(DenoiseParams, f64), String> {
+    if !(0.0..=1.0).contains(&params.strength) {
+        return Err(format!(
+            "Denoise strength must be between 0.0 and 1.0, got {}",
+            params.strength
+        ));
+    }
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    if media_info.video_streams.is_empty() {
+        return Err("The input file has no video stream".to_string());
+    }
+
+    if params.method == DenoiseMethod::Nlmeans {
+        notify("nlmeans is much slower than hqdn3d; long videos may take a while".to_string());
+    }
+
+    Ok((params, media_info.duration))
+}
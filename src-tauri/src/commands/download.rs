@@ -3,19 +3,35 @@
 /// 通过 yt-dlp sidecar 解析视频 URL 并下载。
 /// 支持 YouTube、X（Twitter）、Instagram 等上千个站点。
 ///
-/// 提供两个 command：
+/// 提供以下 command：
 /// - `parse_video_url`：解析 URL 获取视频信息和可用格式列表
 /// - `download_video`：按指定格式下载视频，实时推送进度
+/// - `pause_download` / `resume_download`：暂停和继续可续传的下载
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::time::Instant;
 
 use tauri::ipc::Channel;
 use tauri_plugin_shell::ShellExt;
 use tauri_plugin_shell::process::CommandEvent;
+use tokio::sync::Mutex;
 
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_container_fix_command;
+use crate::engine::compatibility::{container_compatibility, Compatibility};
+use crate::engine::process::{probe_output, run_ffmpeg_quiet, send_phase};
 use crate::models::preset::{DownloadParams, FormatInfo, VideoInfo};
-use crate::models::task::{ProgressUpdate, TaskEvent};
-use crate::utils::path::get_file_size;
+use crate::models::task::{ContainerChange, ProgressUpdate, TaskEvent};
+use crate::utils::path::{
+    atomic_temp_path, cleanup_temp_file, commit_temp_output, file_extension, get_file_size,
+};
+
+/// 下载完成后支持转换到的容器格式
+const REMUX_TARGETS: &[&str] = &["mp4", "mkv", "mov", "webm", "avi", "flv"];
+
+/// 容器转换失败的错误标识：文件已下载成功，以原格式保留，前端据此区分下载失败
+pub const REMUX_FAILED: &str = "RemuxFailed";
 
 /// 解析视频 URL，获取视频信息和可用格式列表
 ///
@@ -103,11 +119,15 @@ pub async fn parse_video_url(
 ///
 /// # 参数
 /// - `app` - Tauri AppHandle
-/// - `params` - 下载参数（URL、格式ID、输出路径）
+/// - `params` - 下载参数（URL、格式ID、输出路径、目标容器）
 /// - `on_progress` - 进度推送 Channel
 ///
+/// 指定 remux_to 时由 yt-dlp 在下载后转换容器（能流复制时 `--remux-video`，
+/// 否则在允许重新编码时 `--recode-video`），输出文件扩展名随之改为目标格式，
+/// 完成后用 ffprobe 确认最终容器。转换失败时以 REMUX_FAILED 开头的错误返回，原格式文件保留
+///
 /// # 返回
-/// - `Ok(String)` - 任务 ID
+/// - `Ok(String)` - 任务 ID（任务被暂停时同样返回 Ok）
 /// - `Err(String)` - 错误描述
 #[tauri::command]
 pub async fn download_video(
@@ -116,6 +136,95 @@ pub async fn download_video(
     on_progress: Channel<TaskEvent>,
 ) -> Result<String, String> {
     let task_id = uuid::Uuid::new_v4().to_string();
+    run_download(&app, params, task_id, &on_progress, None).await
+}
+
+/// 暂停可续传的下载
+///
+/// 终止 yt-dlp 进程但保留 .part 文件，下载任务推送 Paused 事件后可用 resume_download 继续。
+/// 只有以 resumable 模式启动的下载支持暂停
+///
+/// # 参数
+/// - `task_id` - 下载任务 ID
+///
+/// # 返回
+/// - `Ok(())` - 暂停信号已发送
+/// - `Err(String)` - 任务不存在、已结束或不是可续传的下载
+#[tauri::command]
+pub async fn pause_download(task_id: String) -> Result<(), String> {
+    let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+    queue.pause_task(&task_id)
+}
+
+/// 继续已暂停的下载
+///
+/// 以原参数和同一 task_id 重新启动 yt-dlp（--continue 从 .part 文件续传），
+/// 启动后先按已下载部分的大小推送一次进度
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `task_id` - 已暂停的下载任务 ID
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 没有该暂停任务或下载失败
+#[tauri::command]
+pub async fn resume_download(
+    app: tauri::AppHandle,
+    task_id: String,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let paused = PAUSED_DOWNLOADS
+        .lock()
+        .await
+        .remove(&task_id)
+        .ok_or_else(|| format!("No paused download with task ID {}", task_id))?;
+    let params = paused.params.clone();
+    run_download(&app, params, task_id, &on_progress, Some(paused)).await
+}
+
+/// 放弃已暂停的下载并删除已下载的部分
+///
+/// 暂停的下载没有运行中的进程，取消任务时由此清理
+///
+/// # 返回
+/// 是否存在该暂停任务
+pub(crate) async fn discard_paused_download(task_id: &str) -> bool {
+    let Some(paused) = PAUSED_DOWNLOADS.lock().await.remove(task_id) else {
+        return false;
+    };
+    for part in partial_download_files(&paused.params.output_path) {
+        cleanup_temp_file(&part);
+    }
+    true
+}
+
+/// 执行一次下载（新下载或暂停后继续）
+///
+/// `resume` 为暂停时保存的状态：继续时按已下载部分推送初始进度
+async fn run_download(
+    app: &tauri::AppHandle,
+    params: DownloadParams,
+    task_id: String,
+    on_progress: &Channel<TaskEvent>,
+    resume: Option<PausedDownload>,
+) -> Result<String, String> {
+    let remux_target = params.remux_to.as_deref().map(str::to_lowercase);
+    if let Some(target) = remux_target.as_deref() {
+        if !REMUX_TARGETS.contains(&target) {
+            return Err(format!(
+                "Cannot convert downloads to {}; supported formats: {}",
+                target,
+                REMUX_TARGETS.join(", ")
+            ));
+        }
+    }
+    let recode = params.allow_recode
+        && remux_target
+            .as_deref()
+            .is_some_and(|target| needs_recode(target, &params.source_codecs));
+
     let start_time = Instant::now();
 
     // 通知前端任务开始（时长设为 0，下载任务用百分比进度）
@@ -123,37 +232,70 @@ pub async fn download_video(
         task_id: task_id.clone(),
         total_duration: 0.0,
     });
+    let mut last_percent = 0.0;
+    if let Some(paused) = resume.as_ref() {
+        last_percent = paused.resumed_percent();
+        let _ = on_progress.send(download_progress(&task_id, last_percent));
+    }
 
     // 构建 yt-dlp 下载参数
     // --newline: 每行输出一条进度（而非覆盖同一行），方便解析
-    // --no-part: 不使用 .part 临时文件
-    // --ffmpeg-location: 指定 ffmpeg 路径（用于合并音视频流）
+    // --no-part: 不使用 .part 临时文件（可续传模式下保留 .part 并用 --continue 续传）
+    // --ffmpeg-location: 指定 ffmpeg 路径（用于合并音视频流和容器转换）
     let ffmpeg_dir = get_sidecar_dir()?;
 
+    // 转换容器时扩展名交给 yt-dlp 决定（%(ext)s），转换后的文件名才能按目标格式替换扩展名
+    let output_template = match remux_target {
+        Some(_) => ytdlp_output_template(&params.output_path),
+        None => params.output_path.clone(),
+    };
+    let mut args = vec![
+        "-f", &params.format_id,
+        "-o", &output_template,
+        "--newline",
+        "--no-playlist",
+        "--ffmpeg-location", &ffmpeg_dir,
+    ];
+    if params.resumable {
+        args.push("--continue");
+    } else {
+        // 禁止断点续传：避免遗留文件触发 Range 请求返回 HTTP 416
+        args.extend(["--no-part", "--no-continue"]);
+    }
+    if let Some(target) = remux_target.as_deref() {
+        args.push(if recode { "--recode-video" } else { "--remux-video" });
+        args.push(target);
+    }
+    args.push(&params.url);
+
+    let expected_output = match remux_target.as_deref() {
+        Some(target) => Path::new(&params.output_path)
+            .with_extension(target)
+            .to_string_lossy()
+            .to_string(),
+        None => params.output_path.clone(),
+    };
+
     let (mut rx, child) = app
         .shell()
         .sidecar("yt-dlp")
         .map_err(|e| format!("创建 yt-dlp sidecar 失败: {}", e))?
-        .args(&[
-            "-f", &params.format_id,
-            "-o", &params.output_path,
-            "--newline",
-            "--no-part",
-            "--no-continue",    // 禁止断点续传：避免遗留文件触发 Range 请求返回 HTTP 416
-            "--no-playlist",
-            "--ffmpeg-location", &ffmpeg_dir,
-            &params.url,
-        ])
+        .args(&args)
         .spawn()
         .map_err(|e| format!("启动 yt-dlp 进程失败: {}", e))?;
 
-    // 注册子进程到任务队列（用于取消功能）
+    // 注册子进程和输出文件到任务队列（用于按 task_id 或输出路径取消，可续传时还可暂停）
     {
         let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
         queue.register_child(&task_id, child);
+        queue.register_output(&task_id, &expected_output);
+        if params.resumable {
+            queue.register_pausable(&task_id);
+        }
     }
 
     let mut stderr_buffer = String::new();
+    let mut paths = DownloadPaths::default();
 
     // 监听子进程事件
     while let Some(event) = rx.recv().await {
@@ -171,17 +313,12 @@ pub async fn download_video(
 
                 // 尝试解析下载进度
                 for single_line in line_str.lines() {
+                    if let Some(phase) = paths.observe(single_line) {
+                        send_phase(on_progress, &task_id, phase);
+                    }
                     if let Some(percent) = parse_download_progress(single_line) {
-                        let _ = on_progress.send(TaskEvent::Progress(ProgressUpdate {
-                            task_id: task_id.clone(),
-                            percent,
-                            speed: 0.0,
-                            current_time: 0.0,
-                            eta: 0.0,
-                            output_size: 0,
-                            frame: 0,
-                            fps: 0.0,
-                        }));
+                        last_percent = percent;
+                        let _ = on_progress.send(download_progress(&task_id, percent));
                     }
                 }
             }
@@ -189,27 +326,22 @@ pub async fn download_video(
             CommandEvent::Stdout(line) => {
                 let line_str = String::from_utf8_lossy(&line);
                 for single_line in line_str.lines() {
+                    if let Some(phase) = paths.observe(single_line) {
+                        send_phase(on_progress, &task_id, phase);
+                    }
                     if let Some(percent) = parse_download_progress(single_line) {
-                        let _ = on_progress.send(TaskEvent::Progress(ProgressUpdate {
-                            task_id: task_id.clone(),
-                            percent,
-                            speed: 0.0,
-                            current_time: 0.0,
-                            eta: 0.0,
-                            output_size: 0,
-                            frame: 0,
-                            fps: 0.0,
-                        }));
+                        last_percent = percent;
+                        let _ = on_progress.send(download_progress(&task_id, percent));
                     }
                 }
             }
             CommandEvent::Terminated(payload) => {
                 let elapsed = start_time.elapsed().as_secs_f64();
 
-                // 检查是否被取消
-                let is_cancelled = {
+                // 检查是否被取消或暂停
+                let (is_cancelled, is_paused) = {
                     let queue = crate::engine::queue::TASK_QUEUE.lock().await;
-                    queue.is_cancelled(&task_id)
+                    (queue.is_cancelled(&task_id), queue.is_paused(&task_id))
                 };
 
                 // 从任务队列清理
@@ -225,23 +357,62 @@ pub async fn download_video(
                     return Ok(task_id);
                 }
 
-                let exit_code = payload.code.unwrap_or(-1);
-                if exit_code == 0 {
-                    let output_size = get_file_size(&params.output_path);
-                    let _ = on_progress.send(TaskEvent::Completed {
+                // 暂停：记住参数和已下载部分的大小，resume_download 据此续传
+                if is_paused {
+                    let paused = PausedDownload {
+                        partial_size: partial_download_size(&params.output_path),
+                        params,
+                        percent: last_percent,
+                    };
+                    PAUSED_DOWNLOADS.lock().await.insert(task_id.clone(), paused);
+                    let _ = on_progress.send(TaskEvent::Paused {
                         task_id: task_id.clone(),
-                        output_path: params.output_path.clone(),
-                        output_size,
-                        elapsed,
+                        percent: last_percent,
                     });
                     return Ok(task_id);
-                } else {
-                    let error_msg = extract_ytdlp_error(&stderr_buffer);
-                    let _ = on_progress.send(TaskEvent::Failed {
-                        task_id: task_id.clone(),
-                        error: error_msg.clone(),
-                    });
-                    return Err(error_msg);
+                }
+
+                let exit_code = payload.code.unwrap_or(-1);
+                let outcome = match remux_target.as_deref() {
+                    Some(target) => {
+                        let stderr = &stderr_buffer;
+                        settle_converted_download(app, target, recode, exit_code, &paths, stderr)
+                            .await
+                    }
+                    None if exit_code == 0 => {
+                        let output_path = &params.output_path;
+                        let note = if params.keep_raw_container {
+                            None
+                        } else {
+                            fix_mislabeled_container(app, &task_id, output_path, on_progress)
+                                .await
+                        };
+                        Ok((params.output_path.clone(), note, None))
+                    }
+                    None => Err(extract_ytdlp_error(&stderr_buffer)),
+                };
+
+                match outcome {
+                    Ok((output_path, note, container_change)) => {
+                        let output_size = get_file_size(&output_path);
+                        let _ = on_progress.send(TaskEvent::Completed {
+                            task_id: task_id.clone(),
+                            output_path,
+                            output_size,
+                            elapsed,
+                            verification: Vec::new(),
+                            note,
+                            container_change,
+                        });
+                        return Ok(task_id);
+                    }
+                    Err(error_msg) => {
+                        let _ = on_progress.send(TaskEvent::Failed {
+                            task_id: task_id.clone(),
+                            error: error_msg.clone(),
+                        });
+                        return Err(error_msg);
+                    }
                 }
             }
             _ => {}
@@ -251,6 +422,291 @@ pub async fn download_video(
     Err("yt-dlp 进程事件流意外关闭".to_string())
 }
 
+/// 已暂停的下载（task_id -> 续传状态），继续或取消时移除
+static PAUSED_DOWNLOADS: once_cell::sync::Lazy<Mutex<HashMap<String, PausedDownload>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 暂停的下载任务的续传状态
+struct PausedDownload {
+    /// 原下载参数
+    params: DownloadParams,
+    /// 暂停时的进度百分比
+    percent: f64,
+    /// 暂停时 .part 文件的总大小（字节）
+    partial_size: u64,
+}
+
+impl PausedDownload {
+    /// 按当前 .part 文件大小重新计算续传起点的进度
+    ///
+    /// 由暂停时的大小和百分比推算总大小；.part 文件被删除时从 0 开始
+    fn resumed_percent(&self) -> f64 {
+        if self.partial_size == 0 || self.percent <= 0.0 {
+            return self.percent;
+        }
+        let total = self.partial_size as f64 * 100.0 / self.percent;
+        let current = partial_download_size(&self.params.output_path) as f64;
+        (current / total * 100.0).clamp(0.0, 100.0)
+    }
+}
+
+/// 构造下载进度事件（yt-dlp 只提供百分比，其余字段为 0）
+fn download_progress(task_id: &str, percent: f64) -> TaskEvent {
+    TaskEvent::Progress(ProgressUpdate {
+        task_id: task_id.to_string(),
+        percent,
+        speed: 0.0,
+        current_time: 0.0,
+        eta: 0.0,
+        output_size: 0,
+        frame: 0,
+        fps: 0.0,
+        phase: None,
+    })
+}
+
+/// 输出路径对应的 yt-dlp .part 文件
+///
+/// 分离的音视频流各有一个 .part 文件（如 `name.f137.mp4.part`），按输出文件名的主干匹配
+fn partial_download_files(output_path: &str) -> Vec<String> {
+    let path = Path::new(output_path);
+    let (Some(dir), Some(stem)) = (path.parent(), path.file_stem()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", stem.to_string_lossy());
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy())
+                .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".part"))
+        })
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+/// 输出路径对应的 .part 文件总大小（字节）
+fn partial_download_size(output_path: &str) -> u64 {
+    partial_download_files(output_path)
+        .iter()
+        .map(|part| get_file_size(part))
+        .sum()
+}
+
+/// 从 yt-dlp 输出中收集的文件路径
+#[derive(Default)]
+struct DownloadPaths {
+    /// 下载（合并）得到的文件
+    downloaded: Option<String>,
+    /// 容器转换后的文件（源文件已是目标格式时 yt-dlp 跳过转换，为 None）
+    converted: Option<String>,
+    /// 是否已进入容器转换阶段
+    post_processing: bool,
+}
+
+impl DownloadPaths {
+    /// 解析一行 yt-dlp 输出，记录其中的文件路径
+    ///
+    /// # 返回
+    /// 刚进入容器转换阶段时返回阶段描述
+    fn observe(&mut self, line: &str) -> Option<&'static str> {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("[download] Destination: ") {
+            self.downloaded = Some(path.to_string());
+        } else if let Some(path) = line.strip_prefix("[Merger] Merging formats into ") {
+            self.downloaded = Some(path.trim_matches('"').to_string());
+        } else if let Some(path) = line
+            .strip_prefix("[download] ")
+            .and_then(|rest| rest.strip_suffix(" has already been downloaded"))
+        {
+            self.downloaded = Some(path.to_string());
+        } else if line.starts_with("[VideoRemuxer]") || line.starts_with("[VideoConvertor]") {
+            if let Some((_, path)) = line.split_once("; Destination: ") {
+                self.converted = Some(path.to_string());
+            }
+            if !self.post_processing {
+                self.post_processing = true;
+                return Some(if line.starts_with("[VideoConvertor]") {
+                    "Re-encoding…"
+                } else {
+                    "Remuxing…"
+                });
+            }
+        }
+        None
+    }
+}
+
+/// 生成转换容器时的 yt-dlp 输出模板：`<去掉扩展名的输出路径>.%(ext)s`
+///
+/// 路径中的 `%` 需转义为 `%%`，否则会被当作模板字段
+fn ytdlp_output_template(output_path: &str) -> String {
+    let stem = Path::new(output_path).with_extension("");
+    format!("{}.%(ext)s", stem.to_string_lossy().replace('%', "%%"))
+}
+
+/// 把 yt-dlp 的编码名（RFC 6381 形式，如 "avc1.640028"、"mp4a.40.2"）映射为 ffmpeg 编码名
+fn ytdlp_codec_name(codec: &str) -> Option<&'static str> {
+    let base = codec.split('.').next().unwrap_or(codec).to_ascii_lowercase();
+    Some(match base.as_str() {
+        "avc1" | "avc3" | "h264" => "h264",
+        "hvc1" | "hev1" | "hevc" | "h265" => "hevc",
+        "vp09" | "vp9" => "vp9",
+        "vp8" => "vp8",
+        "av01" | "av1" => "av1",
+        "mp4a" | "aac" => "aac",
+        "opus" => "opus",
+        "vorbis" => "vorbis",
+        "mp3" => "mp3",
+        "ac-3" | "ac3" => "ac3",
+        "ec-3" | "eac3" => "eac3",
+        "flac" => "flac",
+        _ => return None,
+    })
+}
+
+/// 所选格式中是否有编码无法流复制到目标容器
+///
+/// 无法识别的编码不做判断（视为可以流复制）
+fn needs_recode(target: &str, codecs: &[String]) -> bool {
+    codecs
+        .iter()
+        .filter_map(|codec| ytdlp_codec_name(codec))
+        .any(|codec| container_compatibility(target, codec) == Compatibility::Unsupported)
+}
+
+/// ffprobe 报告的容器是否为目标格式
+///
+/// MKV/WebM 共用 matroska 解复用器，MP4/MOV 共用 mov 解复用器，无法进一步区分
+fn container_matches(format_name: &str, target: &str) -> bool {
+    let demuxer = match target {
+        "mkv" | "webm" => "matroska",
+        "mp4" | "mov" => "mov",
+        other => other,
+    };
+    format_name.split(',').any(|name| name == demuxer)
+}
+
+/// 确认容器转换结果
+///
+/// yt-dlp 在转换阶段失败时下载的文件仍然保留，返回以 REMUX_FAILED 开头的错误，
+/// 与下载本身失败区分开；成功时用 ffprobe 确认最终容器确实是目标格式
+///
+/// # 返回
+/// - `Ok((输出路径, 提示, 容器转换结果))`
+/// - `Err(String)` - 下载失败或容器转换失败
+async fn settle_converted_download(
+    app: &tauri::AppHandle,
+    target: &str,
+    recode: bool,
+    exit_code: i32,
+    paths: &DownloadPaths,
+    stderr: &str,
+) -> Result<(String, Option<String>, Option<ContainerChange>), String> {
+    let downloaded_ext = paths
+        .downloaded
+        .as_deref()
+        .map(|path| file_extension(path).to_lowercase())
+        .unwrap_or_else(|| target.to_string());
+
+    if exit_code != 0 {
+        let kept = paths.downloaded.as_deref().filter(|path| Path::new(path).exists());
+        let post_processing = paths.post_processing || stderr.contains("ERROR: Postprocessing");
+        return Err(match kept {
+            Some(kept) if post_processing => format!(
+                "{}: Downloaded OK but converting to {} failed; file kept as .{} at {} ({})",
+                REMUX_FAILED,
+                target.to_uppercase(),
+                downloaded_ext,
+                kept,
+                extract_ytdlp_error(stderr)
+            ),
+            _ => extract_ytdlp_error(stderr),
+        });
+    }
+
+    let final_path = paths
+        .converted
+        .clone()
+        .or_else(|| paths.downloaded.clone())
+        .ok_or_else(|| "yt-dlp 未输出下载文件路径".to_string())?;
+    let settings = load_settings(app).await;
+    let probe = probe_output(app, &final_path, settings.probe_timeout_ms).await?;
+    let format_name = probe.format.and_then(|f| f.format_name).unwrap_or_default();
+    if !container_matches(&format_name, target) {
+        return Err(format!(
+            "{}: Downloaded OK but the file is {} instead of {}; kept at {}",
+            REMUX_FAILED,
+            format_name,
+            target.to_uppercase(),
+            final_path
+        ));
+    }
+
+    let change = ContainerChange {
+        downloaded: downloaded_ext,
+        final_format: target.to_string(),
+        recoded: recode && paths.converted.is_some(),
+    };
+    Ok((final_path, None, Some(change)))
+}
+
+/// 按扩展名应为 MP4 容器的输出
+const MP4_EXTENSIONS: &[&str] = &["mp4", "m4v", "mov"];
+
+/// 修正扩展名与实际容器不符的下载文件
+///
+/// 部分站点的 "mp4" 格式实际是 MPEG-TS 流，播放器无法拖动进度。
+/// 探测到 .mp4/.m4v/.mov 文件的容器为 mpegts 时，用 `-c copy` 重封装到同目录临时文件，
+/// 成功后原子替换原文件。修正失败时保留原始下载，不影响下载结果
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `task_id` - 下载任务 ID
+/// - `path` - 下载得到的文件路径
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// 做了修正时返回说明文字，否则返回 None
+async fn fix_mislabeled_container(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    path: &str,
+    on_progress: &Channel<TaskEvent>,
+) -> Option<String> {
+    let extension = file_extension(path).to_lowercase();
+    if !MP4_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+    let settings = load_settings(app).await;
+    let probe = probe_output(app, path, settings.probe_timeout_ms).await.ok()?;
+    let format_name = probe.format.and_then(|f| f.format_name).unwrap_or_default();
+    if !format_name.contains("mpegts") {
+        return None;
+    }
+
+    send_phase(on_progress, task_id, "Fixing container…");
+    let temp_path = atomic_temp_path(path)?;
+    if let Err(e) = run_ffmpeg_quiet(app, build_container_fix_command(path, &temp_path)).await {
+        log::warn!("下载文件容器修正失败，保留原始文件 {}: {}", path, e);
+        cleanup_temp_file(&temp_path);
+        return None;
+    }
+    if let Err(e) = commit_temp_output(&temp_path, path) {
+        log::warn!("{}", e);
+        return None;
+    }
+    Some(format!(
+        "The download was an MPEG-TS stream saved as .{}; it was remuxed into a proper {} container",
+        extension,
+        extension.to_uppercase()
+    ))
+}
+
 /// 解析 yt-dlp JSON 中的格式列表
 ///
 /// 从 formats 数组中筛选出有意义的格式：
@@ -0,0 +1,665 @@
+/// ffmpeg 命令预览 command
+///
+/// 按功能名和参数构建与实际执行相同的 ffmpeg 命令但不执行，
+/// 用于界面展示"高级：显示 ffmpeg 命令"和提交问题报告
+
+use serde::Deserialize;
+
+use crate::commands::analyze::prepare_defect_analysis;
+use crate::commands::attachment::prepare_extract_attachments;
+use crate::commands::audio::{prepare_audio, prepare_extract_multi_format, PreparedAudio};
+use crate::commands::chapters::prepare_embed_chapters;
+use crate::commands::chromakey::prepare_chromakey;
+use crate::commands::compose::prepare_intro_outro;
+use crate::commands::compress::{prepare_compress, PreparedCompress};
+use crate::commands::convert::{
+    prepare_convert, prepare_multi_resolution, PreparedConvert, PreparedMultiResolution,
+};
+use crate::commands::crop::prepare_crop;
+use crate::commands::deinterlace::prepare_deinterlace;
+use crate::commands::denoise::prepare_denoise;
+use crate::commands::export::{prepare_platform_export, PreparedPlatformExport};
+use crate::commands::gif::{gif_mp4_output_path, prepare_gif};
+use crate::commands::hdr::prepare_hdr_to_sdr;
+use crate::commands::lut::prepare_lut;
+use crate::commands::merge::{prepare_merge, PreparedMerge};
+use crate::commands::metadata::prepare_set_metadata;
+use crate::commands::resize::prepare_resize;
+use crate::commands::reverse::prepare_reverse;
+use crate::commands::settings::load_settings;
+use crate::commands::slideshow::prepare_slideshow;
+use crate::commands::speed::prepare_speed;
+use crate::commands::stabilize::prepare_stabilize;
+use crate::commands::streaming::{prepare_streaming, PreparedStreaming};
+use crate::commands::subtitle::{burn_in_fonts_dir, prepare_strip_subtitles, prepare_subtitle};
+use crate::commands::thumbnail::{
+    prepare_thumbnail, prepare_thumbnail_series, PreparedThumbnailSeries,
+};
+use crate::commands::transform::{prepare_auto_orient, prepare_rotate, prepare_transform};
+use crate::commands::trim::{build_segment_concat_command, generate_indexed_output, prepare_trim};
+use crate::commands::watermark::{
+    prepare_remove_logo, prepare_watermark, prepare_watermark_params,
+};
+use crate::commands::zoom_pan::prepare_zoom_pan;
+use crate::engine::builder::{
+    build_audio_command, build_auto_orient_command, build_chromakey_command,
+    build_compress_command, build_convert_audio_only_command, build_convert_command,
+    build_convert_two_pass_commands, build_crop_command, build_dash_command,
+    build_defect_detect_command, build_deinterlace_command, build_denoise_command,
+    build_embed_chapters_command, build_extract_attachments_command,
+    build_extract_multi_format_command, build_gif_command, build_gif_mp4_command,
+    build_hdr_tonemap_command, build_hls_command, build_lut_command, build_merge_command,
+    build_multi_resolution_command, build_platform_export_command, build_remove_logo_command,
+    build_resize_command, build_reverse_command, build_rotate_command, build_set_metadata_command,
+    build_slideshow_command, build_speed_command, build_stabilize_detect_command,
+    build_stabilize_transform_command, build_strip_subtitles_command, build_subtitle_command,
+    build_thumbnail_command, build_thumbnail_series_command, build_transform_command,
+    build_trim_command, build_trim_segment_command, build_watermark_command,
+    build_zoom_pan_command,
+};
+use crate::models::media::Chapter;
+use crate::models::preset::{
+    AudioParams, AutoOrientParams, ChangeSpeedParams, ChromakeyParams, CompressParams,
+    ConvertParams, CropParams, DefectAnalysisParams, DeinterlaceParams, DenoiseParams, GifParams,
+    HdrToSdrParams, AdaptiveStreamParams, IntroOutroParams, LutParams, MergeParams, MetadataParams,
+    MultiFormatAudioParams, MultiResolutionParams, PlatformExportParams, RemoveLogoParams,
+    ResizeParams, ReverseParams, RotateParams, SlideshowParams, SpeedParams, StabilizeParams,
+    SubtitleParams, ThumbnailParams, ThumbnailSeriesParams, TransformParams, TrimParams,
+    WatermarkParams, ZoomPanParams,
+};
+use crate::models::task::CommandPreview;
+use crate::utils::path::{file_extension, temp_file_path};
+use crate::utils::text_image;
+
+/// 支持预览的功能名
+const PREVIEW_FEATURES: &[&str] = &[
+    "convert",
+    "compress",
+    "gif",
+    "resize",
+    "multiResolution",
+    "trim",
+    "merge",
+    "introOutro",
+    "slideshow",
+    "audio",
+    "extractMultiFormat",
+    "watermark",
+    "removeLogo",
+    "crop",
+    "zoomPan",
+    "deinterlace",
+    "denoise",
+    "hdrToSdr",
+    "speed",
+    "changeSpeed",
+    "reverse",
+    "subtitle",
+    "stripSubtitles",
+    "thumbnail",
+    "thumbnails",
+    "extractAttachments",
+    "platformExport",
+    "autoOrient",
+    "rotate",
+    "transform",
+    "lut",
+    "stabilize",
+    "chromakey",
+    "metadata",
+    "embedChapters",
+    "analyzeDefects",
+    "streaming",
+];
+
+/// 移除字幕流的参数（strip_subtitles 以独立参数调用）
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StripSubtitlesParams {
+    input_path: String,
+    output_path: String,
+}
+
+/// 导出附件的参数（extract_attachments 以独立参数调用）
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtractAttachmentsParams {
+    input_path: String,
+    output_dir: String,
+    indices: Option<Vec<u32>>,
+}
+
+/// 写入章节的参数（embed_chapters 以独立参数调用）
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EmbedChaptersParams {
+    path: String,
+    chapters: Vec<Chapter>,
+}
+
+/// 预览 ffmpeg 命令
+///
+/// 与实际执行的 command 共用参数准备逻辑（默认值、ffprobe 探测时长/码率、自动调整），
+/// 因此预览的参数与实际执行一致。多步执行的功能（两遍编码、多片段裁剪后合并、
+/// 防抖的分析和补偿）按执行顺序返回多条命令；其中的临时文件（passlog、文件列表、
+/// 片段、字体目录等）为占位路径，预览不会创建。压缩复用缓存的第一遍统计时
+/// 实际只执行第二遍，文字水印渲染的临时图片在预览后即删除，预览不体现这两点
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `feature` - 功能名（见 PREVIEW_FEATURES）
+/// - `params` - 对应功能的参数 JSON（与实际 command 的参数相同）
+///
+/// # 返回
+/// - `Ok(Vec<CommandPreview>)` - 按执行顺序排列的命令预览（无需执行 ffmpeg 时为空）
+/// - `Err(String)` - 功能名不支持、参数无法解析或预处理失败
+#[tauri::command]
+pub async fn preview_command(
+    app: tauri::AppHandle,
+    feature: String,
+    params: serde_json::Value,
+) -> Result<Vec<CommandPreview>, String> {
+    let mut notices = Vec::new();
+    let mut notify = |message: String| notices.push(message);
+
+    let passes = match feature.as_str() {
+        "convert" => {
+            let params: ConvertParams = parse_params(params)?;
+            let PreparedConvert {
+                params,
+                rotation,
+                audio_only,
+                thread_count,
+                ..
+            } = prepare_convert(&app, params, &mut notify).await?;
+            if audio_only {
+                vec![build_convert_audio_only_command(&params, thread_count)]
+            } else if params.two_pass.unwrap_or(false) {
+                let passlog_prefix = temp_file_path("passlog", "stats")?;
+                let (pass1, pass2) = build_convert_two_pass_commands(
+                    &params,
+                    rotation,
+                    &passlog_prefix,
+                    thread_count,
+                );
+                vec![pass1, pass2]
+            } else {
+                vec![build_convert_command(&params, rotation, thread_count)]
+            }
+        }
+        "compress" => {
+            let params: CompressParams = parse_params(params)?;
+            let PreparedCompress {
+                params,
+                duration,
+                bitrate,
+                video_stream,
+                two_pass,
+                thread_count,
+            } = prepare_compress(&app, params, &mut notify).await?;
+            let passlog_prefix = if two_pass {
+                Some(temp_file_path("passlog", "stats")?)
+            } else {
+                None
+            };
+            build_compress_command(
+                &params,
+                duration,
+                bitrate,
+                video_stream.as_ref(),
+                passlog_prefix.as_deref(),
+                thread_count,
+            )
+        }
+        "gif" => {
+            let params: GifParams = parse_params(params)?;
+            let params = prepare_gif(&app, params).await.map_err(|e| e.to_string())?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            if params.output_as_mp4.unwrap_or(false) {
+                let output_path = gif_mp4_output_path(&params);
+                vec![build_gif_mp4_command(&params, &output_path, thread_count)]
+            } else {
+                vec![build_gif_command(&params, thread_count)]
+            }
+        }
+        "resize" => {
+            let params: ResizeParams = parse_params(params)?;
+            let (params, _) = prepare_resize(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_resize_command(&params, thread_count)]
+        }
+        "multiResolution" => {
+            let params: MultiResolutionParams = parse_params(params)?;
+            let PreparedMultiResolution {
+                params,
+                outputs,
+                has_audio,
+                ..
+            } = prepare_multi_resolution(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_multi_resolution_command(&params, &outputs, has_audio, thread_count)]
+        }
+        "trim" => {
+            let params: TrimParams = parse_params(params)?;
+            let (params, _) = prepare_trim(&app, params, &mut notify)
+                .await
+                .map_err(|e| e.to_string())?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            trim_passes(&params, thread_count)?
+        }
+        "merge" => {
+            let params: MergeParams = parse_params(params)?;
+            merge_passes(&app, params, &mut notify).await?
+        }
+        "introOutro" => {
+            let params: IntroOutroParams = parse_params(params)?;
+            let params = prepare_intro_outro(&app, params).await?;
+            merge_passes(&app, params, &mut notify).await?
+        }
+        "slideshow" => {
+            let params: SlideshowParams = parse_params(params)?;
+            let (params, _, use_concat_list) = prepare_slideshow(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            let concat_file = if use_concat_list {
+                Some(temp_file_path("slideshow", "txt")?)
+            } else {
+                None
+            };
+            vec![build_slideshow_command(&params, concat_file.as_deref(), thread_count)]
+        }
+        "audio" => {
+            let params: AudioParams = parse_params(params)?;
+            let PreparedAudio {
+                params,
+                duration,
+                source_tags,
+                ..
+            } = prepare_audio(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_audio_command(&params, duration, &source_tags, thread_count)]
+        }
+        "extractMultiFormat" => {
+            let params: MultiFormatAudioParams = parse_params(params)?;
+            let (track, outputs, _) = prepare_extract_multi_format(&app, &params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_extract_multi_format_command(
+                &params.input_path,
+                track,
+                &outputs,
+                thread_count,
+            )]
+        }
+        "watermark" => {
+            let params: WatermarkParams = parse_params(params)?;
+            let (params, _) = prepare_watermark(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            let (params, text_image_path) = prepare_watermark_params(params)?;
+            if let Some(path) = &text_image_path {
+                text_image::cleanup_text_image(path);
+            }
+            vec![build_watermark_command(&params, thread_count)]
+        }
+        "removeLogo" => {
+            let params: RemoveLogoParams = parse_params(params)?;
+            let (params, _) = prepare_remove_logo(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_remove_logo_command(&params, thread_count)]
+        }
+        "crop" => {
+            let params: CropParams = parse_params(params)?;
+            let (params, _) = prepare_crop(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_crop_command(&params, thread_count)]
+        }
+        "zoomPan" => {
+            let params: ZoomPanParams = parse_params(params)?;
+            let (params, source) = prepare_zoom_pan(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_zoom_pan_command(
+                &params,
+                source.width,
+                source.height,
+                source.duration,
+                source.is_image,
+                thread_count,
+            )]
+        }
+        "deinterlace" => {
+            let params: DeinterlaceParams = parse_params(params)?;
+            let (params, _) = prepare_deinterlace(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_deinterlace_command(&params, thread_count)]
+        }
+        "denoise" => {
+            let params: DenoiseParams = parse_params(params)?;
+            let (params, _) = prepare_denoise(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_denoise_command(&params, thread_count)]
+        }
+        "hdrToSdr" => {
+            let params: HdrToSdrParams = parse_params(params)?;
+            let (params, _) = prepare_hdr_to_sdr(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_hdr_tonemap_command(&params, thread_count)]
+        }
+        "speed" | "changeSpeed" => {
+            let params: SpeedParams = if feature == "speed" {
+                parse_params(params)?
+            } else {
+                parse_params::<ChangeSpeedParams>(params)?.into()
+            };
+            let (params, _, sample_rate) = prepare_speed(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_speed_command(&params, sample_rate, thread_count)]
+        }
+        "reverse" => {
+            let params: ReverseParams = parse_params(params)?;
+            let (params, _, file_size) = prepare_reverse(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_reverse_command(&params, file_size, thread_count)]
+        }
+        "subtitle" => {
+            let params: SubtitleParams = parse_params(params)?;
+            let prepared = prepare_subtitle(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            let needs_fonts_dir = prepared.needs_fonts_dir();
+            let mut params = prepared.params;
+            if needs_fonts_dir {
+                let placeholder_id = uuid::Uuid::new_v4().to_string();
+                let dir = burn_in_fonts_dir(&placeholder_id)?;
+                params.fonts_dir = Some(dir.to_string_lossy().to_string());
+            }
+            vec![build_subtitle_command(&params, thread_count)]
+        }
+        "stripSubtitles" => {
+            let params: StripSubtitlesParams = parse_params(params)?;
+            prepare_strip_subtitles(&app, &params.input_path, &params.output_path, &mut notify)
+                .await?;
+            vec![build_strip_subtitles_command(&params.input_path, &params.output_path)]
+        }
+        "thumbnail" => {
+            let params: ThumbnailParams = parse_params(params)?;
+            let duration = prepare_thumbnail(&app, &params).await?;
+            vec![build_thumbnail_command(&params, duration)]
+        }
+        "thumbnails" => {
+            let params: ThumbnailSeriesParams = parse_params(params)?;
+            let PreparedThumbnailSeries {
+                interval,
+                start,
+                max_frames,
+                output_pattern,
+                ..
+            } = prepare_thumbnail_series(&app, &params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_thumbnail_series_command(
+                &params,
+                interval,
+                start,
+                max_frames,
+                &output_pattern,
+                thread_count,
+            )]
+        }
+        "extractAttachments" => {
+            let params: ExtractAttachmentsParams = parse_params(params)?;
+            let targets = prepare_extract_attachments(
+                &app,
+                &params.input_path,
+                &params.output_dir,
+                params.indices,
+            )
+            .await?;
+            if targets.is_empty() {
+                Vec::new()
+            } else {
+                vec![build_extract_attachments_command(&params.input_path, &targets)]
+            }
+        }
+        "platformExport" => {
+            let params: PlatformExportParams = parse_params(params)?;
+            let PreparedPlatformExport {
+                params,
+                profile,
+                plan,
+                ..
+            } = prepare_platform_export(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            for message in &plan.notices {
+                notify(message.clone());
+            }
+            vec![build_platform_export_command(&params, profile, &plan, thread_count)]
+        }
+        "autoOrient" => {
+            let params: AutoOrientParams = parse_params(params)?;
+            let (params, _, rotation) = prepare_auto_orient(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_auto_orient_command(&params, rotation, thread_count)]
+        }
+        "rotate" => {
+            let params: RotateParams = parse_params(params)?;
+            let (params, _) = prepare_rotate(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_rotate_command(&params, thread_count)]
+        }
+        "transform" => {
+            let params: TransformParams = parse_params(params)?;
+            let (params, _) = prepare_transform(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_transform_command(&params, thread_count)]
+        }
+        "lut" => {
+            let params: LutParams = parse_params(params)?;
+            let (params, _) = prepare_lut(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_lut_command(&params, thread_count)]
+        }
+        "stabilize" => {
+            let params: StabilizeParams = parse_params(params)?;
+            let (params, _) = prepare_stabilize(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            let trf_path = temp_file_path("stabilize", "trf")?;
+            vec![
+                build_stabilize_detect_command(&params, &trf_path),
+                build_stabilize_transform_command(&params, &trf_path, thread_count),
+            ]
+        }
+        "chromakey" => {
+            let params: ChromakeyParams = parse_params(params)?;
+            let (params, _) = prepare_chromakey(&app, params).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            vec![build_chromakey_command(&params, thread_count)]
+        }
+        "metadata" => {
+            let params: MetadataParams = parse_params(params)?;
+            let (_, temp_output) = prepare_set_metadata(&params)?;
+            vec![build_set_metadata_command(&params, &temp_output)]
+        }
+        "embedChapters" => {
+            let params: EmbedChaptersParams = parse_params(params)?;
+            let temp_output = prepare_embed_chapters(&app, &params.path, &params.chapters).await?;
+            let metadata_path = if params.chapters.is_empty() {
+                None
+            } else {
+                Some(temp_file_path("chapters", "txt")?)
+            };
+            vec![build_embed_chapters_command(
+                &params.path,
+                metadata_path.as_deref(),
+                &temp_output,
+            )]
+        }
+        "analyzeDefects" => {
+            let params: DefectAnalysisParams = parse_params(params)?;
+            let (threshold, noise, min_duration) = prepare_defect_analysis(&params)?;
+            vec![build_defect_detect_command(
+                &params.input_path,
+                threshold,
+                noise,
+                min_duration,
+            )]
+        }
+        "streaming" => {
+            let params: AdaptiveStreamParams = parse_params(params)?;
+            let PreparedStreaming {
+                params,
+                renditions,
+                has_audio,
+                is_dash,
+                ..
+            } = prepare_streaming(&app, params, &mut notify).await?;
+            let thread_count = load_settings(&app).await.ffmpeg_threads;
+            if is_dash {
+                vec![build_dash_command(&params, &renditions, has_audio, thread_count)]
+            } else {
+                vec![build_hls_command(&params, &renditions, has_audio, thread_count)]
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Command preview is not available for '{}'; supported features: {}",
+                feature,
+                PREVIEW_FEATURES.join(", ")
+            ))
+        }
+    };
+
+    Ok(passes
+        .into_iter()
+        .map(|args| CommandPreview {
+            command_line: shell_command_line(&args),
+            args,
+            notices: notices.clone(),
+        })
+        .collect())
+}
+
+/// 视频裁剪的命令：单片段一条；多片段时逐段切割，合并模式再追加一条拼接命令
+fn trim_passes(params: &TrimParams, thread_count: Option<u32>) -> Result<Vec<Vec<String>>, String> {
+    if params.segments.len() == 1 {
+        return Ok(vec![build_trim_command(params, thread_count)]);
+    }
+
+    let ext = file_extension(&params.output_path);
+    let mut passes = Vec::with_capacity(params.segments.len() + 1);
+    for (i, segment) in params.segments.iter().enumerate() {
+        let output_path = if params.merge_segments {
+            temp_file_path(&format!("trim_seg_{}", i), &ext)?
+        } else {
+            generate_indexed_output(&params.output_path, i + 1)
+        };
+        passes.push(build_trim_segment_command(
+            params,
+            &output_path,
+            segment.start,
+            segment.end,
+            false,
+            thread_count,
+        ));
+    }
+    if params.merge_segments {
+        let concat_file = temp_file_path("concat_trim", "txt")?;
+        passes.push(build_segment_concat_command(&concat_file, &params.output_path));
+    }
+    Ok(passes)
+}
+
+/// 视频合并（含片头片尾拼接）的命令，concat 文件列表为占位路径
+async fn merge_passes(
+    app: &tauri::AppHandle,
+    params: MergeParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<Vec<Vec<String>>, String> {
+    let PreparedMerge {
+        params,
+        durations,
+        sample_aspect_ratios,
+        ..
+    } = prepare_merge(app, params, notify).await?;
+    let thread_count = load_settings(app).await.ffmpeg_threads;
+    let concat_file = temp_file_path("concat", "txt")?;
+    Ok(vec![build_merge_command(
+        &params,
+        &concat_file,
+        &durations,
+        &sample_aspect_ratios,
+        thread_count,
+    )])
+}
+
+/// 把参数 JSON 解析为对应功能的参数结构体
+pub(crate) fn parse_params<T: serde::de::DeserializeOwned>(
+    params: serde_json::Value,
+) -> Result<T, String> {
+    serde_json::from_value(params).map_err(|e| format!("Invalid parameters: {}", e))
+}
+
+/// 拼接为单行 shell 命令，含特殊字符的参数用单引号包裹
+fn shell_command_line(args: &[String]) -> String {
+    std::iter::once("ffmpeg".to_string())
+        .chain(args.iter().map(|arg| shell_quote(arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 按 POSIX shell 规则为单个参数加引号
+///
+/// 仅由安全字符组成的参数原样返回；其余用单引号包裹，内部单引号转义为 `'\''`
+fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=+,@%".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_quote_leaves_safe_args_unquoted() {
+        assert_eq!(shell_quote("-c:v"), "-c:v");
+        assert_eq!(shell_quote("/tmp/out_1.mp4"), "/tmp/out_1.mp4");
+        assert_eq!(shell_quote("scale=1280:-2"), "scale=1280:-2");
+    }
+
+    #[test]
+    fn shell_quote_wraps_spaces_and_special_chars() {
+        assert_eq!(shell_quote("/tmp/my clip.mp4"), "'/tmp/my clip.mp4'");
+        assert_eq!(shell_quote("[0:v][1:v]overlay"), "'[0:v][1:v]overlay'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("it's.mp4"), r"'it'\''s.mp4'");
+        assert_eq!(shell_quote("'"), r"''\'''");
+    }
+
+    #[test]
+    fn shell_quote_keeps_empty_arg_visible() {
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn shell_command_line_prefixes_ffmpeg_and_quotes_each_arg() {
+        let args: Vec<String> = ["-i", "/tmp/a b.mov", "-metadata", "title=", "-vf", "", "out.mp4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            shell_command_line(&args),
+            "ffmpeg -i '/tmp/a b.mov' -metadata title= -vf '' out.mp4"
+        );
+    }
+
+    #[test]
+    fn shell_command_line_without_args_is_just_ffmpeg() {
+        assert_eq!(shell_command_line(&[]), "ffmpeg");
+    }
+}
@@ -0,0 +1,226 @@
+/// 输出体积估算 command
+///
+/// 开始转换/压缩前探测输入文件，按码率（精确）或 CRF 经验表（近似）估算输出体积，
+/// 便于用户在编码前发现超出磁盘空间或上传限制的参数
+
+use crate::commands::compress::{prepare_compress, PreparedCompress};
+use crate::commands::convert::{prepare_convert, PreparedConvert};
+use crate::commands::dry_run::parse_params;
+use crate::engine::builder::{
+    compress_video_codec, quality_level_to_crf, ratio_to_crf, size_target_video_kbps,
+    videotoolbox_encoder, DEFAULT_VT_BITRATE,
+};
+use crate::engine::presets::{build_size_estimate, parse_bitrate, VideoRateControl};
+use crate::models::media::MediaInfo;
+use crate::models::preset::{CompressMode, CompressParams, ConvertParams};
+use crate::models::task::SizeEstimate;
+
+/// 支持体积估算的功能名
+const ESTIMATE_FEATURES: &[&str] = &["convert", "compress"];
+
+/// 参数未指定音频码率时的兜底值（bps），与 builder 的默认音频码率一致
+const FALLBACK_AUDIO_BPS: u64 = 128_000;
+
+/// 估算输出体积
+///
+/// 与实际执行的 command 共用参数准备逻辑（默认值、ffprobe 探测、自动调整），
+/// 因此估算使用的编码器和码率与实际执行一致。码率模式（指定码率、按目标大小压缩、
+/// 流复制）按码率 × 时长计算，可信度为 exact；CRF 模式按画面尺寸和帧率查经验表，
+/// 可信度为 approximate
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `feature` - 功能名（"convert" / "compress"）
+/// - `params` - 对应功能的参数 JSON（与实际 command 的参数相同）
+///
+/// # 返回
+/// - `Ok(SizeEstimate)` - 估算体积和参与计算的时长、码率、CRF
+/// - `Err(String)` - 功能名不支持、参数无法解析或探测失败
+#[tauri::command]
+pub async fn estimate_output_size(
+    app: tauri::AppHandle,
+    feature: String,
+    params: serde_json::Value,
+) -> Result<SizeEstimate, String> {
+    let mut notices = Vec::new();
+    let mut notify = |message: String| notices.push(message);
+
+    let (rate_control, audio_bps, duration, video_encoder, exact) = match feature.as_str() {
+        "convert" => {
+            let params: ConvertParams = parse_params(params)?;
+            let PreparedConvert {
+                params,
+                total_duration,
+                media_info,
+                ..
+            } = prepare_convert(&app, params, &mut notify).await?;
+            let (rate_control, encoder, exact) = convert_rate_control(&params, &media_info);
+            let audio_bps = convert_audio_bitrate(&params, &media_info);
+            (rate_control, audio_bps, total_duration, encoder, exact)
+        }
+        "compress" => {
+            let params: CompressParams = parse_params(params)?;
+            let PreparedCompress {
+                params,
+                duration,
+                bitrate,
+                video_stream,
+                ..
+            } = prepare_compress(&app, params, &mut notify).await?;
+            let (width, height, frame_rate) = video_stream
+                .as_ref()
+                .map(|v| (v.width, v.height, v.frame_rate))
+                .unwrap_or_default();
+            let (rate_control, encoder, exact) =
+                compress_rate_control(&params, duration, bitrate, width, height, frame_rate);
+            let audio_bps = params
+                .audio_bitrate
+                .as_deref()
+                .and_then(parse_bitrate)
+                .unwrap_or(FALLBACK_AUDIO_BPS);
+            (rate_control, audio_bps, duration, encoder, exact)
+        }
+        _ => {
+            return Err(format!(
+                "Size estimation is not available for '{}'; supported features: {}",
+                feature,
+                ESTIMATE_FEATURES.join(", ")
+            ))
+        }
+    };
+
+    let mut estimate =
+        build_size_estimate(&rate_control, audio_bps, duration, video_encoder, exact);
+    estimate.notices = notices;
+    Ok(estimate)
+}
+
+/// 格式转换的视频码率控制方式
+///
+/// 与 builder 的编码器选择一致：WebM 的不兼容编码器替换为 VP9，硬件编码未指定码率时
+/// 使用 VideoToolbox 默认码率
+///
+/// # 返回
+/// (码率控制方式, 编码器名, 是否按码率精确计算)
+fn convert_rate_control(
+    params: &ConvertParams,
+    media_info: &MediaInfo,
+) -> (VideoRateControl, String, bool) {
+    let Some(source) = media_info.video_streams.first() else {
+        return (VideoRateControl::None, "none".to_string(), true);
+    };
+
+    let is_webm = params.output_format.eq_ignore_ascii_case("webm");
+    let codec = if is_webm
+        && !matches!(params.video_codec.as_str(), "libvpx" | "libvpx-vp9" | "libaom-av1" | "copy")
+    {
+        "libvpx-vp9"
+    } else {
+        params.video_codec.as_str()
+    };
+
+    if codec == "copy" {
+        // 流复制沿用源码率；源码率为估算值时结果也只是近似
+        let bitrate = source.bitrate.unwrap_or(media_info.bitrate);
+        let exact = source.bitrate.is_some() && !source.bitrate_estimated;
+        return (VideoRateControl::Copy(bitrate), codec.to_string(), exact);
+    }
+
+    let hardware = params.hardware_accel.unwrap_or(false) && !is_webm;
+    let bitrate = if hardware {
+        Some(params.video_bitrate.as_deref().unwrap_or(DEFAULT_VT_BITRATE))
+    } else {
+        params.video_bitrate.as_deref()
+    };
+    let encoder = if hardware {
+        videotoolbox_encoder(codec).to_string()
+    } else {
+        codec.to_string()
+    };
+    if let Some(bps) = bitrate.and_then(parse_bitrate) {
+        return (VideoRateControl::Bitrate(bps), encoder, true);
+    }
+
+    let rate_control = VideoRateControl::Crf {
+        encoder: codec.to_string(),
+        crf: params.quality.unwrap_or(23),
+        width: source.width,
+        height: source.height,
+        frame_rate: source.frame_rate,
+    };
+    (rate_control, encoder, false)
+}
+
+/// 格式转换输出的音频码率之和（bps）
+///
+/// 输出音轨与 convert_stream_maps 一致：指定了音轨时按所选音轨，保留全部流时为全部音轨，
+/// 否则只有第一条音轨。流复制沿用源音轨码率
+fn convert_audio_bitrate(params: &ConvertParams, media_info: &MediaInfo) -> u64 {
+    let streams: Vec<_> = match params.audio_stream_indexes.as_ref().filter(|i| !i.is_empty()) {
+        Some(indexes) => indexes
+            .iter()
+            .filter_map(|&i| media_info.audio_streams.get(i as usize))
+            .collect(),
+        None if params.keep_all_streams == Some(true) => media_info.audio_streams.iter().collect(),
+        None => media_info.audio_streams.iter().take(1).collect(),
+    };
+
+    let encoded_bps = params
+        .audio_bitrate
+        .as_deref()
+        .and_then(parse_bitrate)
+        .unwrap_or(FALLBACK_AUDIO_BPS);
+    streams
+        .iter()
+        .map(|stream| match params.audio_codec.as_str() {
+            "copy" => stream.bitrate.unwrap_or(encoded_bps),
+            _ => encoded_bps,
+        })
+        .sum()
+}
+
+/// 视频压缩的视频码率控制方式
+///
+/// 与 build_compress_command 一致：BySize 反算码率，硬件 ByRatio 按源码率 × 比例，
+/// 其余映射到 CRF（硬件 ByQuality 使用 -q:v，按同等级的 CRF 近似）
+///
+/// # 返回
+/// (码率控制方式, 编码器名, 是否按码率精确计算)
+fn compress_rate_control(
+    params: &CompressParams,
+    duration: f64,
+    input_bitrate: u64,
+    width: u32,
+    height: u32,
+    frame_rate: f64,
+) -> (VideoRateControl, String, bool) {
+    let codec = compress_video_codec(params);
+    let hardware = params.hardware_accel.unwrap_or(false);
+    let encoder = if hardware {
+        videotoolbox_encoder(codec).to_string()
+    } else {
+        codec.to_string()
+    };
+
+    let crf = match params.mode {
+        CompressMode::BySize => {
+            let kbps = size_target_video_kbps(params, duration);
+            return (VideoRateControl::Bitrate(kbps * 1000), encoder, true);
+        }
+        CompressMode::ByRatio if hardware => {
+            let ratio = params.compress_ratio.unwrap_or(0.5);
+            let kbps = ((input_bitrate as f64 * ratio) as u64 / 1000).max(100);
+            return (VideoRateControl::Bitrate(kbps * 1000), encoder, true);
+        }
+        CompressMode::ByRatio => ratio_to_crf(params.compress_ratio.unwrap_or(0.5)),
+        CompressMode::ByQuality => quality_level_to_crf(params.quality_level.unwrap_or(5)),
+    };
+    let rate_control = VideoRateControl::Crf {
+        encoder: codec.to_string(),
+        crf,
+        width,
+        height,
+        frame_rate,
+    };
+    (rate_control, encoder, false)
+}
@@ -0,0 +1,152 @@
+/// 短视频平台一键导出 command
+///
+/// 将竖屏重构图、帧率/码率限制、像素格式和响度标准化合并为一次操作，
+/// 输出符合 Instagram Reels / TikTok / YouTube Shorts 规格的视频
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_platform_export_command;
+use crate::engine::presets::{
+    get_short_form_profile, plan_short_form_export, ShortFormPlan, ShortFormProfile,
+};
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::media::VideoStream;
+use crate::models::preset::{PlatformExportParams, PlatformExportResult};
+use crate::models::task::TaskEvent;
+
+/// 按短视频平台规格导出视频
+///
+/// 先探测源视频方向和帧率，计算导出计划（已是竖屏的素材不再重构图），
+/// 再一次性完成转码
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 平台导出参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(PlatformExportResult)` - 任务 ID 和实际采用的导出参数
+/// - `Err(String)` - 平台未知或执行失败
+#[tauri::command]
+pub async fn export_for_platform(
+    app: tauri::AppHandle,
+    params: PlatformExportParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<PlatformExportResult, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let PreparedPlatformExport {
+        params,
+        profile,
+        plan,
+        duration,
+    } = prepare_platform_export(&app, params).await?;
+    for message in &plan.notices {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message: message.clone(),
+        });
+    }
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    // 构建导出命令
+    let args = build_platform_export_command(&params, profile, &plan, thread_count);
+
+    // 执行 ffmpeg
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await?;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(PlatformExportResult {
+            task_id,
+            settings: plan.settings,
+        })
+    }
+}
+
+/// 准备好、可直接构建命令的平台导出任务
+pub(crate) struct PreparedPlatformExport {
+    /// 填充默认值后的参数
+    pub params: PlatformExportParams,
+    /// 目标平台规格
+    pub profile: &'static ShortFormProfile,
+    /// 导出计划（滤镜链、实际参数、响度目标和提示）
+    pub plan: ShortFormPlan,
+    /// 源文件时长（秒）
+    pub duration: f64,
+}
+
+/// 准备平台导出任务：查找平台规格、填充默认参数、探测源视频并计算导出计划
+///
+/// 实际执行（export_for_platform）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 平台导出参数
+///
+/// # 返回
+/// - `Ok(PreparedPlatformExport)` - 参数、平台规格和导出计划（提示由调用方推送）
+/// - `Err(String)` - 平台未知、输入没有视频流或响度目标非法
+pub(crate) async fn prepare_platform_export(
+    app: &tauri::AppHandle,
+    mut params: PlatformExportParams,
+) -> Result<PreparedPlatformExport, String> {
+    let profile = get_short_form_profile(&params.platform)
+        .ok_or_else(|| format!("Unknown export platform: {}", params.platform))?;
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    // 获取输入文件时长和视频流信息
+    let (duration, video_stream) =
+        probe_source(app, &params.input_path, settings.probe_timeout_ms).await?;
+    if video_stream.is_none() {
+        return Err("The input file has no video stream".to_string());
+    }
+
+    let reframe_mode = params.reframe_mode.as_deref().unwrap_or("crop");
+    let mut plan = plan_short_form_export(profile, video_stream.as_ref(), reframe_mode);
+
+    // 调用方可覆盖平台规格的响度目标
+    plan.loudness = plan.loudness.with_overrides(
+        params.loudness_target_lufs,
+        params.loudness_range,
+        params.true_peak,
+    );
+    plan.loudness.validate()?;
+    plan.settings.loudness_lufs = plan.loudness.integrated;
+
+    Ok(PreparedPlatformExport {
+        params,
+        profile,
+        plan,
+        duration,
+    })
+}
+
+/// 获取视频时长和第一条视频流信息
+async fn probe_source(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<(f64, Option<VideoStream>), String> {
+    let media_info = probe_media(app, file_path, timeout_ms).await?;
+    Ok((media_info.duration, media_info.video_streams.into_iter().next()))
+}
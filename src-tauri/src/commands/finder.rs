@@ -1,10 +1,13 @@
 /// Finder 操作 command
 ///
 /// 提供在 macOS Finder 中展示文件的功能，
-/// 用于任务完成后自动打开输出文件所在目录
+/// 用于任务完成后自动打开输出文件所在目录；
+/// 以及把已完成任务的输出文件拖出到 Finder 或其他应用
 
 use std::path::Path;
 
+use crate::models::task::DragBehavior;
+
 /// 在 Finder 中展示指定文件
 ///
 /// 使用 macOS `open -R` 命令打开 Finder 并高亮选中文件。
@@ -43,3 +46,81 @@ pub async fn reveal_in_finder(path: String) -> Result<(), String> {
 
     Ok(())
 }
+
+/// 开始拖出导出任务的输出文件
+///
+/// 只允许拖出本次运行中已成功完成任务的输出文件，正在被任务写入的文件会被拒绝。
+/// macOS 上在主线程发起原生拖拽会话；无法发起时（其他平台或会话启动失败）
+/// 把文件路径复制到剪贴板，并返回实际采用的行为
+///
+/// # 参数
+/// - `window` - 发起拖拽的窗口
+/// - `path` - 输出文件的完整路径
+///
+/// # 返回
+/// - `Ok(DragBehavior)` - 实际采用的行为
+/// - `Err(String)` - 文件不存在、仍在写入、不是已完成任务的输出或写入剪贴板失败
+#[tauri::command]
+pub async fn start_drag(window: tauri::WebviewWindow, path: String) -> Result<DragBehavior, String> {
+    if !Path::new(&path).is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+    {
+        let queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        if queue.is_output_in_progress(&path) {
+            return Err("This file is still being written by a running task".to_string());
+        }
+        if !queue.is_completed_output(&path) {
+            return Err("Only outputs of completed tasks can be dragged out".to_string());
+        }
+    }
+
+    match start_native_drag(&window, &path).await {
+        Ok(()) => return Ok(DragBehavior::NativeDrag),
+        Err(e) => log::warn!("无法发起原生拖拽，改为复制文件路径: {}", e),
+    }
+    copy_to_clipboard(path).await?;
+    Ok(DragBehavior::CopiedPath)
+}
+
+/// 拖拽时跟随光标的图标
+#[cfg(target_os = "macos")]
+const DRAG_ICON: &[u8] = include_bytes!("../../icons/32x32.png");
+
+/// 在主线程发起原生拖拽会话，等待会话启动结果
+#[cfg(target_os = "macos")]
+async fn start_native_drag(window: &tauri::WebviewWindow, path: &str) -> Result<(), String> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let source = window.clone();
+    let file = std::path::PathBuf::from(path);
+    window
+        .run_on_main_thread(move || {
+            let result = drag::start_drag(
+                &source,
+                drag::DragItem::Files(vec![file]),
+                drag::Image::Raw(DRAG_ICON.to_vec()),
+                |_, _| {},
+                drag::Options::default(),
+            );
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        })
+        .map_err(|e| format!("切换到主线程失败: {}", e))?;
+    rx.await.map_err(|_| "拖拽会话未返回结果".to_string())?
+}
+
+/// 当前平台不支持程序发起原生拖拽会话
+#[cfg(not(target_os = "macos"))]
+async fn start_native_drag(_window: &tauri::WebviewWindow, _path: &str) -> Result<(), String> {
+    Err("当前平台不支持发起原生拖拽".to_string())
+}
+
+/// 在阻塞线程池中把文本写入系统剪贴板
+async fn copy_to_clipboard(text: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text))
+            .map_err(|e| format!("写入剪贴板失败: {}", e))
+    })
+    .await
+    .map_err(|e| format!("写入剪贴板失败: {}", e))?
+}
@@ -1,20 +1,34 @@
 /// GIF 制作 command
 ///
 /// 使用 ffmpeg 高质量两步法（palettegen + paletteuse）
-/// 将视频片段转换为色彩还原度高的 GIF 动图
+/// 将视频片段转换为色彩还原度高的 GIF 动图（可同时叠加 logo），
+/// 也可输出为体积更小的无声循环 MP4
+
+use std::path::Path;
 
 use tauri::ipc::Channel;
 
-use crate::engine::builder::build_gif_command;
-use crate::engine::process::run_ffmpeg;
-use crate::models::preset::GifParams;
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{build_gif_command, build_gif_mp4_command};
+use crate::engine::gif::{check_gif_frame_limit, estimate_gif_size};
+use crate::engine::process::{
+    probe_duration, run_ffmpeg, run_ffmpeg_quiet, send_phase, ANALYZING_INPUT_PHASE,
+};
+use crate::models::error::CommandError;
+use crate::models::preset::{GifParams, TimeSegment};
 use crate::models::task::TaskEvent;
+use crate::utils::path::{file_extension, get_file_size};
+use crate::utils::segment::{validate_segments, SegmentPolicy};
 
 /// 执行 GIF 制作
 ///
 /// 从视频中截取指定时间范围的片段，
 /// 通过调色板优化算法生成高质量 GIF。
-/// 支持自定义帧率、尺寸、颜色数和抖动算法
+/// 支持自定义帧率、尺寸、颜色数和抖动算法。
+///
+/// output_as_mp4 时改为输出无声 H.264 MP4（扩展名自动改为 .mp4），
+/// output_as_webp 时改为输出动画 WebP（扩展名自动改为 .webp）；
+/// 同时设置 also_gif 时在 MP4 完成后再生成 GIF，并推送两者的体积对比
 ///
 /// # 参数
 /// - `app` - Tauri AppHandle
@@ -23,20 +37,34 @@ use crate::models::task::TaskEvent;
 ///
 /// # 返回
 /// - `Ok(String)` - 任务 ID
-/// - `Err(String)` - 错误描述
+/// - `Err(CommandError)` - 截取范围不合法时为结构化的片段错误，其余为错误描述
 #[tauri::command]
 pub async fn create_gif(
     app: tauri::AppHandle,
     params: GifParams,
     on_progress: Channel<TaskEvent>,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let params = prepare_gif(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+    let as_mp4 = params.output_as_mp4.unwrap_or(false);
 
     // GIF 的进度基准为截取片段的时长
     let total_duration = params.duration;
 
-    // 构建 GIF 制作命令
-    let args = build_gif_command(&params);
+    let output_path = if as_mp4 {
+        gif_mp4_output_path(&params)
+    } else {
+        params.output_path.clone()
+    };
+
+    // 构建 GIF（或类 GIF MP4）制作命令
+    let args = if as_mp4 {
+        build_gif_mp4_command(&params, &output_path, thread_count)
+    } else {
+        build_gif_command(&params, thread_count)
+    };
 
     // 执行 ffmpeg
     let result = run_ffmpeg(
@@ -44,19 +72,158 @@ pub async fn create_gif(
         &task_id,
         args,
         total_duration,
-        &params.output_path,
+        &output_path,
         &on_progress,
     )
     .await?;
 
+    // 同时生成 GIF 并报告体积对比
+    if as_mp4 && params.also_gif.unwrap_or(false) && result.error.is_none() {
+        let mut gif_params = params.clone();
+        gif_params.output_path = with_extension(&params.output_path, "gif");
+        match run_ffmpeg_quiet(&app, build_gif_command(&gif_params, thread_count)).await {
+            Ok(()) => {
+                let mp4_size = get_file_size(&output_path);
+                let gif_size = get_file_size(&gif_params.output_path);
+                let _ = on_progress.send(TaskEvent::Notice {
+                    task_id: task_id.clone(),
+                    message: size_comparison_message(mp4_size, gif_size),
+                });
+            }
+            Err(e) => log::warn!("Companion GIF generation failed: {}", e),
+        }
+    }
+
     {
         let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
         queue.cleanup(&task_id);
     }
 
-    if result.error.is_some() {
-        Err(result.error.unwrap())
-    } else {
-        Ok(task_id)
+    match result.error {
+        Some(error) => Err(error.into()),
+        None => Ok(task_id),
     }
 }
+
+/// 准备 GIF 制作任务：按视频时长截断截取范围，校验叠加 logo 和预估体积
+///
+/// 实际执行（create_gif）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - GIF 制作参数
+///
+/// # 返回
+/// - `Ok(GifParams)` - 截取范围调整后的参数
+/// - `Err(CommandError)` - 截取范围（结构化片段错误）、logo 或预估体积不合法
+pub(crate) async fn prepare_gif(
+    app: &tauri::AppHandle,
+    mut params: GifParams,
+) -> Result<GifParams, CommandError> {
+    let settings = load_settings(app).await;
+
+    // 截取范围按时间片段校验，超出视频末尾时截断时长
+    let media_duration = probe_duration(app, &params.input_path, settings.probe_timeout_ms)
+        .await
+        .unwrap_or(0.0);
+    let requested = TimeSegment {
+        start: params.start_time,
+        end: params.start_time + params.duration,
+    };
+    let segment = validate_segments(&[requested], media_duration, SegmentPolicy::Clamp)?
+        .remove(0);
+    params.start_time = segment.start;
+    params.duration = segment.end - segment.start;
+
+    validate_overlay(&params)?;
+
+    // 动画 WebP 由输出扩展名决定封装格式
+    if params.output_as_webp.unwrap_or(false) && !params.output_as_mp4.unwrap_or(false) {
+        params.output_path = with_extension(&params.output_path, "webp");
+    }
+
+    let as_mp4 = params.output_as_mp4.unwrap_or(false);
+    if !as_mp4 || params.also_gif.unwrap_or(false) {
+        check_gif_size(&params, settings.max_gif_size_mb)?;
+    }
+
+    Ok(params)
+}
+
+/// 与 GIF 输出路径同名、扩展名为 `.mp4` 的路径（output_as_mp4 时的实际输出）
+pub(crate) fn gif_mp4_output_path(params: &GifParams) -> String {
+    with_extension(&params.output_path, "mp4")
+}
+
+/// 估算 GIF 输出体积
+///
+/// 前端在用户调整宽度/帧率/时长时调用，用于提前提示体积
+///
+/// # 参数
+/// - `width` - 输出宽度（像素）
+/// - `fps` - 输出帧率
+/// - `duration` - 片段时长（秒）
+///
+/// # 返回
+/// 估算体积（字节）
+#[tauri::command]
+pub fn estimate_gif_output_size(width: u32, fps: u32, duration: f64) -> u64 {
+    estimate_gif_size(width, fps, duration)
+}
+
+/// 校验叠加图片参数
+fn validate_overlay(params: &GifParams) -> Result<(), String> {
+    let Some(ref image) = params.overlay_image else {
+        return Ok(());
+    };
+    if !Path::new(image).is_file() {
+        return Err(format!("Overlay image not found: {}", image));
+    }
+    if params.overlay_scale.is_some_and(|s| !(s > 0.0 && s <= 1.0)) {
+        return Err("Overlay scale must be between 0 and 1".to_string());
+    }
+    Ok(())
+}
+
+/// 检查 GIF 参数是否会产生失控的输出
+///
+/// 帧数超过硬上限时直接拒绝；估算体积超过上限时，除非 force 否则拒绝
+fn check_gif_size(params: &GifParams, max_size_mb: u32) -> Result<(), String> {
+    check_gif_frame_limit(params.fps, params.duration)?;
+
+    let estimated = estimate_gif_size(params.width, params.fps, params.duration);
+    let limit = max_size_mb as u64 * 1024 * 1024;
+    if estimated > limit && !params.force.unwrap_or(false) {
+        return Err(format!(
+            "The GIF is estimated at about {:.0} MB, above the {} MB limit; reduce the width, \
+             frame rate or duration, use the MP4 output option, or confirm to create it anyway",
+            estimated as f64 / 1024.0 / 1024.0,
+            max_size_mb
+        ));
+    }
+    Ok(())
+}
+
+/// 将路径的扩展名替换为指定扩展名（已一致时原样返回）
+fn with_extension(path: &str, ext: &str) -> String {
+    if file_extension(path) == ext {
+        return path.to_string();
+    }
+    Path::new(path).with_extension(ext).to_string_lossy().to_string()
+}
+
+/// 生成 MP4 与 GIF 的体积对比说明
+fn size_comparison_message(mp4_size: u64, gif_size: u64) -> String {
+    let to_mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+    if gif_size == 0 {
+        return format!("MP4: {:.2} MB", to_mb(mp4_size));
+    }
+    let saving = (1.0 - mp4_size as f64 / gif_size as f64) * 100.0;
+    format!(
+        "MP4: {:.2} MB, GIF: {:.2} MB (MP4 is {:.0}% {})",
+        to_mb(mp4_size),
+        to_mb(gif_size),
+        saving.abs(),
+        if saving >= 0.0 { "smaller" } else { "larger" }
+    )
+}
@@ -0,0 +1,129 @@
+/// HDR 转 SDR command
+///
+/// 用 zscale + tonemap 把 HDR10（PQ）/ HLG 视频色调映射为 SDR
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{
+    build_hdr_tonemap_command, SDR_TONEMAP_PRIMARIES, SDR_TONEMAP_TRANSFERS,
+};
+use crate::engine::process::{
+    filter_available, probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE,
+};
+use crate::models::preset::HdrToSdrParams;
+use crate::models::task::TaskEvent;
+
+/// 执行 HDR 转 SDR
+///
+/// 输入未标记为 PQ / HLG 时仍按参数处理，但推送提示：对 SDR 素材做色调映射会使画面变暗或发灰
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - HDR 转 SDR 参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 目标色彩参数不支持、ffmpeg 缺少 zscale、输入没有视频流或执行失败
+#[tauri::command]
+pub async fn hdr_to_sdr(
+    app: tauri::AppHandle,
+    params: HdrToSdrParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_hdr_to_sdr(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_hdr_tonemap_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备 HDR 转 SDR 任务：校验目标色彩参数和 zscale 可用性、填充默认参数并探测输入
+///
+/// 实际执行（hdr_to_sdr）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - HDR 转 SDR 参数
+/// - `notify` - 提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok((HdrToSdrParams, f64))` - 填充默认值后的参数和源文件时长（秒）
+/// - `Err(String)` - 目标色彩参数不支持、ffmpeg 缺少 zscale 或输入没有视频流
+pub(crate) async fn prepare_hdr_to_sdr(
+    app: &tauri::AppHandle,
+    mut params: HdrToSdrParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<(HdrToSdrParams, f64), String> {
+    if !SDR_TONEMAP_PRIMARIES.contains(&params.target_primaries.as_str()) {
+        return Err(format!(
+            "Unsupported target primaries: {} (expected one of {})",
+            params.target_primaries,
+            SDR_TONEMAP_PRIMARIES.join(", ")
+        ));
+    }
+    if !SDR_TONEMAP_TRANSFERS.contains(&params.target_trc.as_str()) {
+        return Err(format!(
+            "Unsupported target transfer: {} (expected one of {})",
+            params.target_trc,
+            SDR_TONEMAP_TRANSFERS.join(", ")
+        ));
+    }
+
+    if !filter_available(app, "zscale").await {
+        return Err(
+            "HDR to SDR is unavailable: the bundled ffmpeg was built without libzimg (zscale)"
+                .to_string(),
+        );
+    }
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    let Some(video) = media_info.video_streams.first() else {
+        return Err("The input file has no video stream".to_string());
+    };
+
+    if !video.is_hdr() {
+        notify(format!(
+            "The input is not tagged as HDR (transfer: {}); tone mapping SDR footage \
+             will darken or flatten the picture",
+            video.color_transfer.as_deref().unwrap_or("unknown")
+        ));
+    }
+
+    Ok((params, media_info.duration))
+}
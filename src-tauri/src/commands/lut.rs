@@ -0,0 +1,108 @@
+/// LUT 调色 command
+///
+/// 用 .cube / .3dl 3D LUT 文件为视频调色，支持按强度与原画面混合
+
+use std::path::Path;
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_lut_command;
+use crate::engine::process::{probe_duration, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::LutParams;
+use crate::models::task::TaskEvent;
+
+/// 套用 LUT 调色
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - LUT 调色参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - LUT 文件不存在、格式不匹配、强度超出范围或执行失败
+#[tauri::command]
+pub async fn apply_lut(
+    app: tauri::AppHandle,
+    params: LutParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_lut(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+    let args = build_lut_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备 LUT 调色任务：校验 LUT 文件、填充默认参数并探测时长
+///
+/// 实际执行（apply_lut）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - LUT 调色参数
+///
+/// # 返回
+/// - `Ok((LutParams, f64))` - 填充默认值后的参数和源文件时长（秒）
+/// - `Err(String)` - LUT 文件不存在、格式不匹配、强度超出范围或探测失败
+pub(crate) async fn prepare_lut(
+    app: &tauri::AppHandle,
+    mut params: LutParams,
+) -> Result<(LutParams, f64), String> {
+    validate_lut(&params)?;
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let duration = probe_duration(app, &params.input_path, settings.probe_timeout_ms).await?;
+    Ok((params, duration))
+}
+
+/// 校验 LUT 文件和调色强度
+fn validate_lut(params: &LutParams) -> Result<(), String> {
+    let path = Path::new(&params.lut_path);
+    if !path.is_file() {
+        return Err(format!("LUT file not found: {}", params.lut_path));
+    }
+    let expected = params.lut_format.extension();
+    let matches_format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(expected));
+    if !matches_format {
+        return Err(format!("LUT file must be a .{} file", expected));
+    }
+    if !(0.0..=1.0).contains(&params.intensity) {
+        return Err(format!(
+            "LUT intensity must be between 0.0 and 1.0, got {}",
+            params.intensity
+        ));
+    }
+    Ok(())
+}
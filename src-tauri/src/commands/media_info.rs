@@ -3,8 +3,9 @@
 /// 调用 ffprobe sidecar 获取视频文件的完整元信息，
 /// 包括视频/音频/字幕流的编码器、分辨率、帧率、码率等
 
-use crate::engine::process::run_ffprobe;
-use crate::models::media::{FfprobeOutput, MediaInfo};
+use crate::commands::settings::load_settings;
+use crate::engine::process::probe_media;
+use crate::models::media::MediaInfo;
 
 /// 获取媒体文件信息
 ///
@@ -23,13 +24,6 @@ pub async fn get_media_info(
     app: tauri::AppHandle,
     file_path: String,
 ) -> Result<MediaInfo, String> {
-    // 调用 ffprobe 获取 JSON 输出
-    let json_str = run_ffprobe(&app, &file_path).await?;
-
-    // 解析 JSON 为内部结构体
-    let ffprobe_output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("解析 ffprobe JSON 输出失败: {}", e))?;
-
-    // 转换为应用内部使用的 MediaInfo
-    Ok(ffprobe_output.to_media_info(&file_path))
+    let settings = load_settings(&app).await;
+    probe_media(&app, &file_path, settings.probe_timeout_ms).await
 }
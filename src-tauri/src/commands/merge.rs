@@ -5,13 +5,21 @@
 
 use tauri::ipc::Channel;
 
+use crate::commands::settings::load_settings;
 use crate::engine::builder::build_merge_command;
-use crate::engine::process::{run_ffmpeg, run_ffprobe};
-use crate::models::media::FfprobeOutput;
+use crate::engine::presets::DEFAULT_LOUDNESS_TARGET;
+use crate::engine::process::{
+    probe_media, run_ffmpeg_with_phase, send_phase, ANALYZING_INPUT_PHASE,
+};
 use crate::models::preset::MergeParams;
-use crate::models::task::TaskEvent;
+use crate::models::task::{TaskEvent, TaskResult};
 use crate::utils::path::{cleanup_temp_file, temp_file_path};
 
+/// 合并输入的默认最短时长（秒）
+const DEFAULT_MIN_CLIP_DURATION: f64 = 0.5;
+/// 归一化合并时允许的最大输出声道数（AAC 最多支持 7.1）
+const MAX_TARGET_CHANNELS: u32 = 8;
+
 /// 执行视频合并
 ///
 /// 根据参数决定使用 concat demuxer（同格式快速拼接）
@@ -32,44 +40,75 @@ pub async fn merge_videos(
     params: MergeParams,
     on_progress: Channel<TaskEvent>,
 ) -> Result<String, String> {
-    // 验证至少有两个输入文件
-    if params.input_paths.len() < 2 {
-        return Err("至少需要两个视频文件才能合并".to_string());
-    }
-
     let task_id = uuid::Uuid::new_v4().to_string();
 
-    // 收集每个视频的时长（用于转场 offset 计算和总时长统计）
-    let mut durations: Vec<f64> = Vec::new();
-    for path in &params.input_paths {
-        durations.push(get_duration(&app, path).await.unwrap_or(0.0));
+    let result = run_merge(&app, params, &task_id, &on_progress).await?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
     }
+}
 
-    // 计算所有输入文件的总时长
-    let total_duration: f64 = durations.iter().sum();
+/// 执行合并任务
+///
+/// merge_videos 和片头片尾拼接共用：校验输入、生成文件列表、执行合并并清理临时文件
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 合并参数
+/// - `task_id` - 任务 ID
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(TaskResult)` - 任务已执行（成功、失败或取消）
+/// - `Err(String)` - 启动前失败（输入不合法、探测失败等）
+pub(crate) async fn run_merge(
+    app: &tauri::AppHandle,
+    params: MergeParams,
+    task_id: &str,
+    on_progress: &Channel<TaskEvent>,
+) -> Result<TaskResult, String> {
+    send_phase(on_progress, task_id, ANALYZING_INPUT_PHASE);
+    let PreparedMerge {
+        params,
+        durations,
+        sample_aspect_ratios,
+        total_duration,
+        concat_content,
+    } = prepare_merge(app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.to_string(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(app).await.ffmpeg_threads;
 
     // 为 concat demuxer 创建临时文件列表
     let concat_file = temp_file_path("concat", "txt")?;
-    let concat_content = params
-        .input_paths
-        .iter()
-        .map(|p| format!("file '{}'", p.replace('\'', "'\\''")))
-        .collect::<Vec<_>>()
-        .join("\n");
     std::fs::write(&concat_file, &concat_content)
         .map_err(|e| format!("创建合并文件列表失败: {}", e))?;
 
     // 构建合并命令
-    let args = build_merge_command(&params, &concat_file, &durations);
+    let args = build_merge_command(
+        &params,
+        &concat_file,
+        &durations,
+        &sample_aspect_ratios,
+        thread_count,
+    );
 
     // 执行 ffmpeg
-    let result = run_ffmpeg(
-        &app,
-        &task_id,
+    let phase = format!("Merging {} clips", params.input_paths.len());
+    let result = run_ffmpeg_with_phase(
+        app,
+        task_id,
         args,
         total_duration,
         &params.output_path,
-        &on_progress,
+        on_progress,
+        &phase,
     )
     .await;
 
@@ -78,26 +117,229 @@ pub async fn merge_videos(
 
     {
         let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
-        queue.cleanup(&task_id);
+        queue.cleanup(task_id);
     }
 
-    let result = result?;
-    if result.error.is_some() {
-        Err(result.error.unwrap())
-    } else {
-        Ok(task_id)
-    }
+    result
 }
 
-/// 从 ffprobe 获取视频时长
-async fn get_duration(app: &tauri::AppHandle, file_path: &str) -> Result<f64, String> {
-    let json_str = run_ffprobe(app, file_path).await?;
-    let output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("解析 ffprobe 输出失败: {}", e))?;
-    Ok(output
-        .format
+/// 准备好、可直接构建命令的合并任务
+pub(crate) struct PreparedMerge {
+    /// 填充默认值并调整后的参数
+    pub params: MergeParams,
+    /// 各输入文件的时长（秒）
+    pub durations: Vec<f64>,
+    /// 各输入文件的像素宽高比
+    pub sample_aspect_ratios: Vec<f64>,
+    /// 成片总时长（秒，已扣除转场和音频重叠）
+    pub total_duration: f64,
+    /// concat demuxer 文件列表的内容
+    pub concat_content: String,
+}
+
+/// 准备合并任务：填充默认参数、逐个探测校验输入、确定输出声道并调整音频交叉淡化
+///
+/// 实际执行（run_merge）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 合并参数
+/// - `notify` - 自动调整参数时的提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok(PreparedMerge)` - 调整后的参数、各输入的探测结果和文件列表内容
+/// - `Err(String)` - 输入不足、输入不合法或探测失败
+pub(crate) async fn prepare_merge(
+    app: &tauri::AppHandle,
+    mut params: MergeParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<PreparedMerge, String> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    // 验证至少有两个输入文件
+    if params.input_paths.len() < 2 {
+        return Err("至少需要两个视频文件才能合并".to_string());
+    }
+
+    // 校验响度标准化目标
+    if params.normalize_loudness.unwrap_or(false) {
+        DEFAULT_LOUDNESS_TARGET
+            .with_overrides(params.loudness_target_lufs, params.loudness_range, params.true_peak)
+            .validate()?;
+    }
+
+    // 收集每个视频的时长（用于转场 offset 计算和总时长统计）和像素宽高比（用于归一化），
+    // 在启动 ffmpeg 之前拒绝损坏、零时长或没有视频帧的输入
+    let min_duration = params.min_clip_duration.unwrap_or(DEFAULT_MIN_CLIP_DURATION);
+    let transition_duration = params.transition.as_ref().map(|t| t.duration);
+    let mut durations: Vec<f64> = Vec::new();
+    let mut sample_aspect_ratios: Vec<f64> = Vec::new();
+    let mut max_channels = 0;
+    for path in &params.input_paths {
+        let probe = probe_merge_input(app, path, settings.probe_timeout_ms)
+            .await
+            .map_err(|e| format!("Cannot read {}: {}", display_name(path), e))?;
+        validate_merge_input(path, &probe, min_duration, transition_duration)?;
+        durations.push(probe.duration);
+        sample_aspect_ratios.push(probe.sar);
+        max_channels = max_channels.max(probe.channels);
+    }
+
+    // 归一化时的输出声道数：未指定则保留输入中最多的声道（至少为立体声）
+    if let Some(channels) = params.target_channels {
+        if !(1..=MAX_TARGET_CHANNELS).contains(&channels) {
+            return Err(format!(
+                "Target channels must be between 1 and {}, got {}",
+                MAX_TARGET_CHANNELS, channels
+            ));
+        }
+    }
+    params
+        .target_channels
+        .get_or_insert(max_channels.clamp(2, MAX_TARGET_CHANNELS));
+
+    // 仅音频交叉淡化：时长需在每段都能容纳前后两次重叠，否则收窄或放弃
+    if params.transition.is_none() {
+        if let Some(fade) = params.audio_only_crossfade.filter(|d| *d > 0.0) {
+            let adjusted = clamp_audio_crossfade(fade, &durations);
+            if adjusted != Some(fade) {
+                let message = match adjusted {
+                    Some(d) => format!(
+                        "Audio crossfade shortened from {:.2}s to {:.2}s to fit the shortest clip",
+                        fade, d
+                    ),
+                    None => "Audio crossfade disabled: clip durations could not be determined"
+                        .to_string(),
+                };
+                notify(message);
+                params.audio_only_crossfade = adjusted;
+            }
+        }
+    }
+    let audio_fade = params
+        .audio_only_crossfade
+        .filter(|d| *d > 0.0 && params.transition.is_none());
+
+    // 计算所有输入文件的总时长（每次转场或音频重叠都会使成片缩短对应秒数）
+    let per_join = params
+        .transition
         .as_ref()
-        .and_then(|f| f.duration.as_ref())
-        .and_then(|d| d.parse::<f64>().ok())
-        .unwrap_or(0.0))
+        .map(|t| t.duration)
+        .or(audio_fade)
+        .unwrap_or(0.0);
+    let overlap = per_join * (durations.len() - 1) as f64;
+    let total_duration: f64 = durations.iter().sum::<f64>() - overlap;
+
+    // concat demuxer 文件列表：仅音频交叉淡化且直接复制视频时，
+    // 非末尾片段用 outpoint 截掉与下一段音频重叠的尾部
+    let last = params.input_paths.len() - 1;
+    let concat_content = params
+        .input_paths
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let entry = format!("file '{}'", p.replace('\'', "'\\''"));
+            match audio_fade {
+                Some(fade) if i < last && !params.normalize => {
+                    format!("{}\noutpoint {}", entry, durations[i] - fade)
+                }
+                _ => entry,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(PreparedMerge {
+        params,
+        durations,
+        sample_aspect_ratios,
+        total_duration,
+        concat_content,
+    })
+}
+
+/// 把音频交叉淡化时长限制在最短片段的一半以内
+///
+/// 中间片段的开头和结尾各要参与一次重叠，因此每段至少需要 2 倍淡化时长。
+/// 任一片段时长未知时无法计算截断点，返回 None
+fn clamp_audio_crossfade(fade: f64, durations: &[f64]) -> Option<f64> {
+    let shortest = durations.iter().copied().fold(f64::INFINITY, f64::min);
+    if !shortest.is_finite() || shortest <= 0.0 {
+        return None;
+    }
+    Some(fade.min(shortest / 2.0))
+}
+
+/// 合并输入的探测结果
+struct MergeInputProbe {
+    /// 时长（秒）
+    duration: f64,
+    /// 第一条视频流的像素宽高比
+    sar: f64,
+    /// 是否有视频流（不含封面图）
+    has_video: bool,
+    /// 容器标注的视频帧数（未标注时为 None）
+    frame_count: Option<u64>,
+    /// 第一条音频流的声道数（无音频时为 0）
+    channels: u32,
+}
+
+/// 从 ffprobe 获取视频时长和第一条视频流的像素宽高比
+async fn probe_merge_input(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<MergeInputProbe, String> {
+    let info = probe_media(app, file_path, timeout_ms).await?;
+    let video = info.video_streams.first();
+    Ok(MergeInputProbe {
+        duration: info.duration,
+        sar: video.map(|v| v.sar()).unwrap_or(1.0),
+        has_video: video.is_some(),
+        frame_count: video.and_then(|v| v.nb_frames),
+        channels: info.audio_streams.first().map(|a| a.channels).unwrap_or(0),
+    })
+}
+
+/// 校验单个合并输入
+///
+/// 零时长或过短的片段会让转场 offset 计算出负值（xfade 报出难以理解的滤镜错误），
+/// 静态图片或没有视频帧的文件也无法参与拼接，均在启动 ffmpeg 之前报错并指明文件
+fn validate_merge_input(
+    path: &str,
+    probe: &MergeInputProbe,
+    min_duration: f64,
+    transition_duration: Option<f64>,
+) -> Result<(), String> {
+    let name = display_name(path);
+    if !probe.has_video || probe.frame_count == Some(0) {
+        return Err(format!("{} has no decodable video frames", name));
+    }
+    if probe.duration < min_duration {
+        return Err(format!(
+            "{} is too short to merge ({:.2}s, minimum {:.2}s)",
+            name, probe.duration, min_duration
+        ));
+    }
+    if let Some(transition) = transition_duration {
+        if probe.duration <= transition {
+            return Err(format!(
+                "{} ({:.2}s) must be longer than the {:.2}s transition",
+                name, probe.duration, transition
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 错误信息中展示的文件名
+fn display_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
 }
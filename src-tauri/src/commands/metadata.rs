@@ -0,0 +1,88 @@
+/// 元数据编辑 command
+///
+/// 写入标题、艺术家、年份、注释等容器标签和流语言等流级标签。
+/// 通过流复制重封装完成，不重新编码
+
+use crate::engine::builder::build_set_metadata_command;
+use crate::engine::process::run_ffmpeg_quiet;
+use crate::models::preset::MetadataParams;
+use crate::utils::path::{atomic_temp_path, cleanup_temp_file, commit_temp_output};
+
+/// 写入元数据标签
+///
+/// 先写入输出文件同目录的临时文件，成功后替换目标文件，
+/// 因此未指定输出路径时可以直接修改原文件
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 元数据编辑参数
+///
+/// # 返回
+/// - `Ok(String)` - 写入后的文件路径
+/// - `Err(String)` - 标签无效或重封装失败
+#[tauri::command]
+pub async fn set_metadata(app: tauri::AppHandle, params: MetadataParams) -> Result<String, String> {
+    let (output_path, temp_output) = prepare_set_metadata(&params)?;
+
+    let args = build_set_metadata_command(&params, &temp_output);
+    if let Err(error) = run_ffmpeg_quiet(&app, args).await {
+        cleanup_temp_file(&temp_output);
+        return Err(error);
+    }
+    commit_temp_output(&temp_output, &output_path)?;
+    Ok(output_path)
+}
+
+/// 准备元数据写入：校验标签参数并确定输出路径和同目录的临时输出路径
+///
+/// 实际执行（set_metadata）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `params` - 元数据编辑参数
+///
+/// # 返回
+/// - `Ok((String, String))` - 最终输出路径和重封装写入的临时路径
+/// - `Err(String)` - 标签无效或无法在输出目录写入
+pub(crate) fn prepare_set_metadata(params: &MetadataParams) -> Result<(String, String), String> {
+    validate_metadata_params(params)?;
+
+    let output_path = params
+        .output_path
+        .clone()
+        .unwrap_or_else(|| params.input_path.clone());
+    let Some(temp_output) = atomic_temp_path(&output_path) else {
+        return Err(format!("Cannot write metadata to {}", output_path));
+    };
+    Ok((output_path, temp_output))
+}
+
+/// 校验标签参数
+///
+/// 标签名不能为空、不能含 `=` 或控制字符（ffmpeg 按第一个 `=` 拆分键值）；
+/// 流说明符只能由字母、数字和冒号组成
+fn validate_metadata_params(params: &MetadataParams) -> Result<(), String> {
+    if params.tags.is_empty()
+        && params.stream_tags.is_empty()
+        && !params.clear_existing.unwrap_or(false)
+    {
+        return Err("No metadata changes were given".to_string());
+    }
+
+    let keys = params
+        .tags
+        .iter()
+        .map(|t| t.key.as_str())
+        .chain(params.stream_tags.iter().map(|t| t.key.as_str()));
+    for key in keys {
+        if key.trim().is_empty() || key.contains('=') || key.chars().any(char::is_control) {
+            return Err(format!("Invalid metadata key: '{}'", key));
+        }
+    }
+
+    if let Some(tag) = params.stream_tags.iter().find(|t| {
+        t.stream.is_empty() || !t.stream.chars().all(|c| c.is_ascii_alphanumeric() || c == ':')
+    }) {
+        return Err(format!("Invalid stream specifier: '{}'", tag.stream));
+    }
+    Ok(())
+}
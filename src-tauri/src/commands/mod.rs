@@ -4,31 +4,79 @@
 /// 每个 command 对应一个功能模块，处理参数校验、
 /// 调用 engine 层执行任务、通过 Channel 推送进度
 
+/// 画面缺陷检查（黑场、静止画面）
+pub mod analyze;
+/// 附件导出（MKV 内嵌字体、封面图）
+pub mod attachment;
 /// 音频处理（提取、替换、静音、调节）
 pub mod audio;
+/// 批量处理（多文件共用进度 Channel）
+pub mod batch;
+/// 章节导出与写入（CUE 表单、章节编辑）
+pub mod chapters;
+/// 绿幕抠像（合成背景或输出透明视频）
+pub mod chromakey;
+/// 画面裁剪（任意矩形区域）
+pub mod crop;
+/// 去隔行（yadif / bwdif）
+pub mod deinterlace;
+/// 降噪（hqdn3d / nlmeans）
+pub mod denoise;
+/// ffmpeg 命令预览（构建命令但不执行）
+pub mod dry_run;
 /// 视频下载（YouTube/X/Instagram 等，基于 yt-dlp sidecar）
 pub mod download;
+/// 输出体积估算（开始转换/压缩前预估文件大小）
+pub mod estimate;
+/// 短视频平台一键导出（Reels/TikTok/Shorts）
+pub mod export;
 /// Finder 操作（在 macOS Finder 中展示文件）
 pub mod finder;
 /// 视频压缩（按大小、按比例、按质量）
 pub mod compress;
+/// 片头片尾拼接（按正片参数归一化）
+pub mod compose;
 /// 格式转换
 pub mod convert;
 /// GIF 制作
 pub mod gif;
+/// HDR 转 SDR（zscale + tonemap 色调映射）
+pub mod hdr;
+/// LUT 调色（.cube / .3dl）
+pub mod lut;
 /// 媒体信息获取（ffprobe 调用）
 pub mod media_info;
 /// 视频合并/拼接
 pub mod merge;
+/// 元数据编辑（标题、艺术家、流语言等标签）
+pub mod metadata;
 /// 分辨率/帧率调整
 pub mod resize;
+/// 倒放（视频/音频）
+pub mod reverse;
+/// 预览文件读取（按令牌返回预览临时文件内容）
+pub mod preview;
 /// 设置管理（读写 settings.json）
 pub mod settings;
+/// 图片幻灯片（图片序列生成视频）
+pub mod slideshow;
+/// 视频变速（慢放、快放）
+pub mod speed;
+/// 视频防抖（vidstab 两遍处理）
+pub mod stabilize;
+/// 自适应流打包（HLS 码率阶梯）
+pub mod streaming;
 /// 字幕处理（嵌入、提取、烧录）
 pub mod subtitle;
 /// 任务管理（取消运行中的任务）
 pub mod task;
+/// 缩略图（时间轴预览帧、单帧截图、缩略图拼图）
+pub mod thumbnail;
+/// 画面方向变换（按旋转元数据自动校正、手动旋转/翻转）
+pub mod transform;
 /// 视频裁剪/剪切
 pub mod trim;
 /// 水印叠加（图片/文字）
 pub mod watermark;
+/// 缩放平移（Ken Burns 推拉效果）
+pub mod zoom_pan;
@@ -0,0 +1,21 @@
+/// 预览文件读取 command
+///
+/// 预览类 command 返回令牌而非临时文件路径，
+/// 前端通过此 command 按令牌取回文件内容（二进制 ArrayBuffer）
+
+use tauri::ipc::Response;
+
+use crate::engine::preview::read_preview;
+
+/// 读取预览文件内容
+///
+/// # 参数
+/// - `token` - 预览类 command 返回的令牌
+///
+/// # 返回
+/// - `Ok(Response)` - 文件原始字节（前端收到 ArrayBuffer）
+/// - `Err(String)` - 令牌无效、已过期或读取失败
+#[tauri::command]
+pub async fn get_preview(token: String) -> Result<Response, String> {
+    read_preview(&token).await.map(Response::new)
+}
@@ -5,9 +5,10 @@
 
 use tauri::ipc::Channel;
 
+use crate::commands::deinterlace::apply_auto_deinterlace;
+use crate::commands::settings::load_settings;
 use crate::engine::builder::build_resize_command;
-use crate::engine::process::{run_ffmpeg, run_ffprobe};
-use crate::models::media::FfprobeOutput;
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
 use crate::models::preset::ResizeParams;
 use crate::models::task::TaskEvent;
 
@@ -31,12 +32,19 @@ pub async fn resize_video(
     on_progress: Channel<TaskEvent>,
 ) -> Result<String, String> {
     let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
 
-    // 获取输入文件时长
-    let duration = get_duration(&app, &params.input_path).await?;
+    let (params, duration) = prepare_resize(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
 
     // 构建分辨率/帧率调整命令
-    let args = build_resize_command(&params);
+    let args = build_resize_command(&params, thread_count);
 
     // 执行 ffmpeg
     let result = run_ffmpeg(
@@ -61,15 +69,31 @@ pub async fn resize_video(
     }
 }
 
-/// 从 ffprobe 获取视频时长
-async fn get_duration(app: &tauri::AppHandle, file_path: &str) -> Result<f64, String> {
-    let json_str = run_ffprobe(app, file_path).await?;
-    let output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("解析 ffprobe 输出失败: {}", e))?;
-    Ok(output
-        .format
-        .as_ref()
-        .and_then(|f| f.duration.as_ref())
-        .and_then(|d| d.parse::<f64>().ok())
-        .unwrap_or(0.0))
+/// 准备分辨率/帧率调整任务：填充默认参数、探测时长并按场序自动去隔行
+///
+/// 实际执行（resize_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 调整参数
+/// - `notify` - 自动调整参数时的提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok((ResizeParams, f64))` - 调整后的参数和源文件时长（秒）
+/// - `Err(String)` - 探测失败
+pub(crate) async fn prepare_resize(
+    app: &tauri::AppHandle,
+    mut params: ResizeParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<(ResizeParams, f64), String> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    // 获取输入文件时长和场序
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    apply_auto_deinterlace(&mut params.deinterlace, media_info.video_streams.first(), notify);
+
+    Ok((params, media_info.duration))
 }
@@ -0,0 +1,106 @@
+/// 倒放 command
+///
+/// 视频和音频分别可选倒放，整条流缓存在内存中处理
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_reverse_command;
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::ReverseParams;
+use crate::models::task::TaskEvent;
+
+/// 倒放视频和/或音频
+///
+/// reverse/areverse 需要缓存整条流，无法获取时长的输入（如直播流、损坏文件）
+/// 可能无限占用内存，因此时长为 0 时直接拒绝
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 倒放参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 未选择倒放内容、无法获取时长或执行失败
+#[tauri::command]
+pub async fn reverse_video(
+    app: tauri::AppHandle,
+    params: ReverseParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration, file_size) = prepare_reverse(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_reverse_command(&params, file_size, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备倒放任务：校验倒放内容、填充默认参数并探测时长和文件大小
+///
+/// 实际执行（reverse_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 倒放参数
+///
+/// # 返回
+/// - `Ok((ReverseParams, f64, u64))` - 填充默认值后的参数、源文件时长（秒）和文件大小（字节）
+/// - `Err(String)` - 未选择倒放内容或无法获取时长
+pub(crate) async fn prepare_reverse(
+    app: &tauri::AppHandle,
+    mut params: ReverseParams,
+) -> Result<(ReverseParams, f64, u64), String> {
+    if !params.reverse_video && !params.reverse_audio {
+        return Err("Select video, audio or both to reverse".to_string());
+    }
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let (duration, file_size) =
+        get_duration_and_size(app, &params.input_path, settings.probe_timeout_ms).await?;
+    if duration <= 0.0 {
+        return Err(
+            "Cannot determine the input duration; reversing needs a file with a known length"
+                .to_string(),
+        );
+    }
+    Ok((params, duration, file_size))
+}
+
+/// 从 ffprobe 获取时长和文件大小
+async fn get_duration_and_size(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<(f64, u64), String> {
+    let info = probe_media(app, file_path, timeout_ms).await?;
+    Ok((info.duration, info.file_size))
+}
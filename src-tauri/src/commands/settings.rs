@@ -1,14 +1,22 @@
 /// 设置管理 command
 ///
-/// 读取和保存应用设置，使用 JSON 文件持久化存储。
-/// 设置文件位于 $APPDATA/com.clipforge.app/settings.json
+/// 读取和保存应用设置与用户预设，使用 JSON 文件持久化存储。
+/// 设置文件位于 $APPDATA/com.clipforge.app/settings.json，
+/// 用户预设位于同目录的 presets.json
 
 use tauri::Manager;
 
+use crate::engine::presets::{
+    get_all_presets, normalize_preset_params, read_user_presets, strip_preset_paths,
+    validate_user_preset, write_user_presets,
+};
+use crate::models::preset::PresetInfo;
 use crate::models::settings::AppSettings;
 
 /// 设置文件名
 const SETTINGS_FILE: &str = "settings.json";
+/// 用户预设文件名
+const PRESETS_FILE: &str = "presets.json";
 
 /// 获取应用设置
 ///
@@ -44,6 +52,16 @@ pub async fn get_settings(app: tauri::AppHandle) -> Result<AppSettings, String>
     Ok(settings)
 }
 
+/// 读取当前设置（供其他 command 内部使用）
+///
+/// 任何读取失败都回退为默认设置，保证处理任务不会因设置文件问题而中断
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+pub async fn load_settings(app: &tauri::AppHandle) -> AppSettings {
+    get_settings(app.clone()).await.unwrap_or_default()
+}
+
 /// 保存应用设置
 ///
 /// 将设置序列化为 JSON 并写入应用数据目录。
@@ -77,9 +95,175 @@ pub async fn save_settings(
     std::fs::write(&settings_path, json)
         .map_err(|e| format!("写入设置文件失败: {}", e))?;
 
+    // 并发上限立即生效（等待中的任务按新上限启动）
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.set_max_concurrent(settings.max_concurrent as usize);
+    }
+
     Ok(())
 }
 
+/// 获取全部预设（内置预设 + 用户预设）
+///
+/// 用户预设从 presets.json 读取，文件缺失、损坏或其中个别条目不合法时
+/// 跳过对应内容，不影响内置预设的返回
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+///
+/// # 返回
+/// - `Ok(Vec<PresetInfo>)` - 内置预设在前，用户预设在后
+#[tauri::command]
+pub async fn list_presets(app: tauri::AppHandle) -> Result<Vec<PresetInfo>, String> {
+    let mut presets = get_all_presets();
+    presets.extend(load_user_presets(&app));
+    Ok(presets)
+}
+
+/// 保存用户预设
+///
+/// 按 id 新增或覆盖 presets.json 中的条目，不允许覆盖内置预设
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `preset` - 要保存的预设（params 必须为参数对象）
+///
+/// # 返回
+/// - `Ok(())` - 保存成功
+/// - `Err(String)` - 预设不合法或写入失败
+#[tauri::command]
+pub async fn save_preset(app: tauri::AppHandle, preset: PresetInfo) -> Result<(), String> {
+    validate_user_preset(&preset)?;
+
+    let mut presets = load_user_presets(&app);
+    match presets.iter_mut().find(|p| p.id == preset.id) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+    save_user_presets(&app, &presets)
+}
+
+/// 删除用户预设
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `id` - 预设 ID
+///
+/// # 返回
+/// - `Ok(())` - 删除成功（预设不存在时也视为成功）
+/// - `Err(String)` - 写入失败
+#[tauri::command]
+pub async fn delete_preset(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let mut presets = load_user_presets(&app);
+    let before = presets.len();
+    presets.retain(|p| p.id != id);
+    if presets.len() == before {
+        return Ok(());
+    }
+    save_user_presets(&app, &presets)
+}
+
+/// 将当前操作参数保存为用户预设
+///
+/// 参数先按功能模块的结构体做一次反序列化/序列化往返校验，
+/// 再去掉输入/输出路径后写入 presets.json
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `name` - 预设名称
+/// - `category` - 功能模块（如 "convert", "compress"）
+/// - `params` - 当前操作的完整参数对象
+/// - `description` - 可选的预设描述
+///
+/// # 返回
+/// - `Ok(PresetInfo)` - 新建的预设（id 自动生成）
+/// - `Err(String)` - 参数不合法或写入失败
+#[tauri::command]
+pub async fn save_params_as_preset(
+    app: tauri::AppHandle,
+    name: String,
+    category: String,
+    params: serde_json::Value,
+    description: Option<String>,
+) -> Result<PresetInfo, String> {
+    let mut params = normalize_preset_params(&category, &params)?;
+    strip_preset_paths(&mut params);
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let preset = PresetInfo {
+        id: format!("user_{}", &id[..8]),
+        name,
+        description: description.unwrap_or_default(),
+        category,
+        params: Some(params),
+    };
+    save_preset(app, preset.clone()).await?;
+    Ok(preset)
+}
+
+/// 应用预设
+///
+/// 把调用方提供的字段（通常是 inputPath/outputPath）合并到预设参数上，
+/// 校验后返回可直接传给对应 command 的完整参数。
+/// 支持用户预设和携带参数的内置预设（如 "convert_streaming"）
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `id` - 预设 ID
+/// - `overrides` - 覆盖到预设参数上的字段对象
+///
+/// # 返回
+/// - `Ok(Value)` - 完整的操作参数
+/// - `Err(String)` - 预设不存在或合并后的参数不合法
+#[tauri::command]
+pub async fn apply_preset(
+    app: tauri::AppHandle,
+    id: String,
+    overrides: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let preset = load_user_presets(&app)
+        .into_iter()
+        .chain(get_all_presets().into_iter().filter(|p| p.params.is_some()))
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Preset not found: {}", id))?;
+
+    let mut params = preset.params.unwrap_or_default();
+    if let (Some(target), Some(extra)) = (params.as_object_mut(), overrides.as_object()) {
+        for (key, value) in extra {
+            target.insert(key.clone(), value.clone());
+        }
+    }
+    normalize_preset_params(&preset.category, &params)
+}
+
+/// 读取 presets.json 中的用户预设
+///
+/// 逐条校验，跳过不合法的条目；整个文件无法解析时返回空列表
+pub(crate) fn load_user_presets(app: &tauri::AppHandle) -> Vec<PresetInfo> {
+    match get_presets_path(app) {
+        Ok(path) => read_user_presets(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// 将用户预设写回 presets.json
+pub(crate) fn save_user_presets(
+    app: &tauri::AppHandle,
+    presets: &[PresetInfo],
+) -> Result<(), String> {
+    write_user_presets(&get_presets_path(app)?, presets)
+}
+
+/// 获取用户预设文件的完整路径
+fn get_presets_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
+    Ok(app_data.join(PRESETS_FILE))
+}
+
 /// 获取设置文件的完整路径
 ///
 /// 使用 Tauri 的 app_data_dir() 获取应用数据目录，
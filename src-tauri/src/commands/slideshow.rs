@@ -0,0 +1,161 @@
+/// 图片幻灯片 command
+///
+/// 把一组图片按顺序拼成视频，支持统一尺寸、转场效果和背景音乐
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_slideshow_command;
+use crate::engine::process::run_ffmpeg;
+use crate::models::preset::SlideshowParams;
+use crate::models::task::TaskEvent;
+use crate::utils::path::{cleanup_temp_file, file_extension, temp_file_path};
+
+/// 输出帧率上限
+const MAX_FPS: u32 = 120;
+
+/// 生成图片幻灯片视频
+///
+/// 无转场且图片格式一致时用 concat demuxer 读取文件列表（列表中每项带显示时长），
+/// 否则每张图片作为独立的循环输入
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 幻灯片参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 参数非法或执行失败
+#[tauri::command]
+pub async fn create_slideshow(
+    app: tauri::AppHandle,
+    params: SlideshowParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let (params, total_duration, use_concat_list) = prepare_slideshow(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    let concat_file = if use_concat_list {
+        let path = temp_file_path("slideshow", "txt")?;
+        std::fs::write(&path, concat_list(&params))
+            .map_err(|e| format!("创建幻灯片文件列表失败: {}", e))?;
+        Some(path)
+    } else {
+        None
+    };
+
+    let args = build_slideshow_command(&params, concat_file.as_deref(), thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        total_duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    if let Some(path) = &concat_file {
+        cleanup_temp_file(path);
+    }
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备幻灯片任务：填充默认参数、校验并计算总时长
+///
+/// 实际执行（create_slideshow）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 幻灯片参数
+///
+/// # 返回
+/// - `Ok((SlideshowParams, f64, bool))` - 填充默认值后的参数、总时长（秒）、是否使用 concat 文件列表
+/// - `Err(String)` - 参数非法
+pub(crate) async fn prepare_slideshow(
+    app: &tauri::AppHandle,
+    mut params: SlideshowParams,
+) -> Result<(SlideshowParams, f64, bool), String> {
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    validate_slideshow(&params)?;
+
+    let n = params.image_paths.len() as f64;
+    let transition = params.transition.as_ref().map(|t| t.duration).unwrap_or(0.0);
+    let total_duration = n * params.frame_duration + transition;
+
+    // concat demuxer 要求所有条目编码一致，图片格式混用时改为逐张输入
+    let first_ext = file_extension(&params.image_paths[0]);
+    let same_format = params.image_paths.iter().all(|p| file_extension(p) == first_ext);
+    let use_concat_list = params.transition.is_none() && same_format;
+
+    Ok((params, total_duration, use_concat_list))
+}
+
+/// 校验幻灯片参数
+fn validate_slideshow(params: &SlideshowParams) -> Result<(), String> {
+    if params.image_paths.is_empty() {
+        return Err("Add at least one image".to_string());
+    }
+    if let Some(missing) = params
+        .image_paths
+        .iter()
+        .find(|p| !std::path::Path::new(p).is_file())
+    {
+        return Err(format!("Image not found: {}", missing));
+    }
+    if !params.frame_duration.is_finite() || params.frame_duration <= 0.0 {
+        return Err("Image duration must be greater than 0".to_string());
+    }
+    if params.fps == 0 || params.fps > MAX_FPS {
+        return Err(format!("Frame rate must be between 1 and {}", MAX_FPS));
+    }
+    let even = |v: u32| v > 0 && v.is_multiple_of(2);
+    if !even(params.width) || !even(params.height) {
+        return Err("Width and height must be positive even numbers".to_string());
+    }
+    if let Some(transition) = &params.transition {
+        if transition.duration <= 0.0 || transition.duration >= params.frame_duration {
+            return Err(format!(
+                "Transition duration must be between 0 and the {:.2}s image duration",
+                params.frame_duration
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 生成 concat demuxer 文件列表
+///
+/// 每张图片后跟 duration 指令；concat demuxer 会忽略最后一项的 duration，
+/// 因此最后一张图片再重复一次使其完整显示
+fn concat_list(params: &SlideshowParams) -> String {
+    let entry = |path: &str| format!("file '{}'", path.replace('\'', "'\\''"));
+    let mut lines: Vec<String> = params
+        .image_paths
+        .iter()
+        .map(|p| format!("{}\nduration {:.3}", entry(p), params.frame_duration))
+        .collect();
+    if let Some(last) = params.image_paths.last() {
+        lines.push(entry(last));
+    }
+    lines.join("\n")
+}
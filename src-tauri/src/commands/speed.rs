@@ -0,0 +1,138 @@
+/// 视频变速 command
+///
+/// 慢放与快放（延时摄影效果），视频和音频分别指定倍率或使用统一倍率，
+/// 音频变速默认保持音高不变
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::build_speed_command;
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::{ChangeSpeedParams, SpeedParams};
+use crate::models::task::TaskEvent;
+
+/// 最小变速倍率
+const MIN_SPEED: f64 = 0.25;
+/// 最大变速倍率
+const MAX_SPEED: f64 = 8.0;
+
+/// 执行视频变速
+///
+/// 输出时长为源时长除以视频倍率，进度按输出时长计算
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 变速参数（视频/音频倍率、是否保留音频）
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 倍率超出范围或执行失败
+#[tauri::command]
+pub async fn speed_video(
+    app: tauri::AppHandle,
+    params: SpeedParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    run_speed(&app, params, &on_progress).await
+}
+
+/// 按统一倍率变速（慢动作或快进）
+///
+/// 视频和音频使用同一倍率，音画保持同步；keep_audio_pitch 为 false 时音高随速度升降
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 变速参数（倍率、是否保持音高）
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 倍率超出范围或执行失败
+#[tauri::command]
+pub async fn change_speed(
+    app: tauri::AppHandle,
+    params: ChangeSpeedParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    run_speed(&app, params.into(), &on_progress).await
+}
+
+/// speed_video / change_speed 的共同实现
+async fn run_speed(
+    app: &tauri::AppHandle,
+    params: SpeedParams,
+    on_progress: &Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration, sample_rate) = prepare_speed(app, params).await?;
+    let thread_count = load_settings(app).await.ffmpeg_threads;
+
+    let args = build_speed_command(&params, sample_rate, thread_count);
+
+    let result = run_ffmpeg(
+        app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        on_progress,
+    )
+    .await?;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备变速任务：校验倍率、填充默认参数并探测时长和音频采样率
+///
+/// 实际执行（speed_video / change_speed）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 变速参数
+///
+/// # 返回
+/// - `Ok((SpeedParams, f64, u32))` - 填充默认值后的参数、输出时长（秒）和源音频采样率
+/// - `Err(String)` - 倍率超出范围或探测失败
+pub(crate) async fn prepare_speed(
+    app: &tauri::AppHandle,
+    mut params: SpeedParams,
+) -> Result<(SpeedParams, f64, u32), String> {
+    if !(MIN_SPEED..=MAX_SPEED).contains(&params.video_speed) {
+        return Err(format!(
+            "Video speed must be between {} and {}, got {}",
+            MIN_SPEED, MAX_SPEED, params.video_speed
+        ));
+    }
+    if params.keep_audio && !(MIN_SPEED..=MAX_SPEED).contains(&params.audio_speed) {
+        return Err(format!(
+            "Audio speed must be between {} and {}, got {}",
+            MIN_SPEED, MAX_SPEED, params.audio_speed
+        ));
+    }
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    // 进度按输出时长计算（setpts 之后的时间轴）
+    let duration = media_info.duration / params.video_speed;
+    // 不保持音高时 asetrate 需要源采样率
+    let sample_rate = media_info.audio_streams.first().map(|a| a.sample_rate).unwrap_or(0);
+
+    Ok((params, duration, sample_rate))
+}
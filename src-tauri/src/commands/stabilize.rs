@@ -0,0 +1,127 @@
+/// 视频防抖 command
+///
+/// 两遍处理：先用 vidstabdetect 分析抖动写入临时 .trf 文件，
+/// 再用 vidstabtransform 按分析结果补偿画面并重新编码
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{build_stabilize_detect_command, build_stabilize_transform_command};
+use crate::engine::process::{
+    filter_available, probe_duration, run_ffmpeg, run_ffmpeg_quiet, send_phase,
+    ANALYZING_INPUT_PHASE,
+};
+use crate::models::preset::StabilizeParams;
+use crate::models::task::TaskEvent;
+use crate::utils::path::{cleanup_temp_file, temp_file_path};
+
+/// 执行视频防抖
+///
+/// 分析遍不推送进度（只解码不编码，相对较快），补偿遍按正常任务推送进度
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 防抖参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 参数超出范围、ffmpeg 缺少 vidstab 滤镜或执行失败
+#[tauri::command]
+pub async fn stabilize_video(
+    app: tauri::AppHandle,
+    params: StabilizeParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_stabilize(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+    let trf_path = temp_file_path("stabilize", "trf")?;
+
+    // 第一遍：分析抖动
+    if let Err(e) = run_ffmpeg_quiet(&app, build_stabilize_detect_command(&params, &trf_path)).await
+    {
+        cleanup_temp_file(&trf_path);
+        return Err(format!("Stabilization analysis failed: {}", e));
+    }
+
+    // 第二遍：补偿画面并编码
+    let args = build_stabilize_transform_command(&params, &trf_path, thread_count);
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    cleanup_temp_file(&trf_path);
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备防抖任务：校验参数和 vidstab 滤镜可用性、填充默认参数并探测时长
+///
+/// 实际执行（stabilize_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 防抖参数
+///
+/// # 返回
+/// - `Ok((StabilizeParams, f64))` - 填充默认值后的参数和源文件时长（秒）
+/// - `Err(String)` - 参数超出范围、ffmpeg 缺少 vidstab 滤镜或探测失败
+pub(crate) async fn prepare_stabilize(
+    app: &tauri::AppHandle,
+    mut params: StabilizeParams,
+) -> Result<(StabilizeParams, f64), String> {
+    validate_stabilize(&params)?;
+
+    if !filter_available(app, "vidstabdetect").await
+        || !filter_available(app, "vidstabtransform").await
+    {
+        return Err(
+            "Stabilization is unavailable: the bundled ffmpeg was built without libvidstab"
+                .to_string(),
+        );
+    }
+
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let duration = probe_duration(app, &params.input_path, settings.probe_timeout_ms).await?;
+    Ok((params, duration))
+}
+
+/// 校验抖动程度和平滑帧数范围
+fn validate_stabilize(params: &StabilizeParams) -> Result<(), String> {
+    if !(1..=10).contains(&params.shakiness) {
+        return Err(format!(
+            "Shakiness must be between 1 and 10, got {}",
+            params.shakiness
+        ));
+    }
+    if !(1..=100).contains(&params.smoothing) {
+        return Err(format!(
+            "Smoothing must be between 1 and 100, got {}",
+            params.smoothing
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,356 @@
+/// 自适应流打包 command
+///
+/// 把单个视频转成多档清晰度的 HLS 或 DASH 码率阶梯，
+/// 输出可直接部署到静态文件服务器的播放列表/清单和分片
+
+use std::path::Path;
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{
+    build_dash_command, build_hls_command, DASH_MANIFEST, DEFAULT_HLS_SEGMENT_DURATION,
+    HLS_MASTER_PLAYLIST, HLS_VARIANT_PLAYLIST,
+};
+use crate::engine::presets::auto_bitrate_ladder;
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::{
+    AdaptiveStreamParams, AdaptiveStreamResult, RenditionOutput, StreamRendition,
+};
+use crate::models::task::TaskEvent;
+
+/// 生成 HLS / DASH 码率阶梯
+///
+/// 单次 ffmpeg 调用输出全部清晰度：各档共享同一次解码，
+/// 进度按源视频时长计算（各档时长相同，最长一档即源时长）。
+/// 两种格式共用同一套清晰度规划，DASH 完成后解析 MPD 确认 Representation 数量
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 自适应流打包参数（格式、清晰度列表、分片时长、输出目录）
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(AdaptiveStreamResult)` - master 播放列表路径和各档输出大小
+/// - `Err(String)` - 参数无效、输出目录非空（未确认覆盖）或执行失败
+#[tauri::command]
+pub async fn generate_adaptive_stream(
+    app: tauri::AppHandle,
+    params: AdaptiveStreamParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<AdaptiveStreamResult, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let PreparedStreaming {
+        params,
+        renditions,
+        has_audio,
+        is_dash,
+        duration,
+    } = prepare_streaming(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    prepare_output_dir(&params.output_dir, params.overwrite.unwrap_or(false))?;
+
+    let (args, manifest_name) = if is_dash {
+        let args = build_dash_command(&params, &renditions, has_audio, thread_count);
+        (args, DASH_MANIFEST)
+    } else {
+        let args = build_hls_command(&params, &renditions, has_audio, thread_count);
+        (args, HLS_MASTER_PLAYLIST)
+    };
+    let master_path = Path::new(&params.output_dir)
+        .join(manifest_name)
+        .to_string_lossy()
+        .to_string();
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &master_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        return Err(error);
+    }
+
+    let outputs = if is_dash {
+        verify_dash_manifest(&master_path, renditions.len() + usize::from(has_audio))?;
+        renditions
+            .iter()
+            .enumerate()
+            .map(|(i, rendition)| RenditionOutput {
+                name: rendition.name(),
+                playlist_path: master_path.clone(),
+                size: dash_representation_size(Path::new(&params.output_dir), i),
+            })
+            .collect()
+    } else {
+        renditions
+            .iter()
+            .map(|rendition| {
+                let dir = Path::new(&params.output_dir).join(rendition.name());
+                RenditionOutput {
+                    name: rendition.name(),
+                    playlist_path: dir.join(HLS_VARIANT_PLAYLIST).to_string_lossy().to_string(),
+                    size: dir_size(&dir),
+                }
+            })
+            .collect()
+    };
+
+    Ok(AdaptiveStreamResult {
+        task_id,
+        master_playlist_path: master_path,
+        renditions: outputs,
+    })
+}
+
+/// 准备好、可直接构建命令的 HLS / DASH 打包任务
+pub(crate) struct PreparedStreaming {
+    /// 填充默认值后的参数（清晰度列表已移入 renditions）
+    pub params: AdaptiveStreamParams,
+    /// 实际输出的清晰度阶梯
+    pub renditions: Vec<StreamRendition>,
+    /// 源文件是否有音频流
+    pub has_audio: bool,
+    /// 是否输出 DASH（否则为 HLS）
+    pub is_dash: bool,
+    /// 源文件时长（秒）
+    pub duration: f64,
+}
+
+/// 准备 HLS / DASH 打包任务：校验格式和分片时长、探测源文件并确定清晰度阶梯
+///
+/// 实际执行（generate_adaptive_stream）和命令预览（preview_command）共用；
+/// 不检查、不清理输出目录
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 打包参数
+/// - `notify` - 提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok(PreparedStreaming)` - 清晰度阶梯和源文件信息
+/// - `Err(String)` - 参数无效或输入没有视频流
+pub(crate) async fn prepare_streaming(
+    app: &tauri::AppHandle,
+    mut params: AdaptiveStreamParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<PreparedStreaming, String> {
+    let settings = load_settings(app).await;
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let is_dash = match params.format.as_deref().unwrap_or("hls") {
+        "hls" => false,
+        "dash" => true,
+        other => return Err(format!("Unsupported streaming format: {}", other)),
+    };
+
+    let segment = params.segment_duration.unwrap_or(DEFAULT_HLS_SEGMENT_DURATION);
+    if !segment.is_finite() || segment < 1.0 {
+        return Err("Segment duration must be at least 1 second".to_string());
+    }
+
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    let source = media_info
+        .video_streams
+        .first()
+        .ok_or_else(|| "The input file has no video stream".to_string())?;
+    let has_audio = !media_info.audio_streams.is_empty();
+
+    let renditions = match params.renditions.take().filter(|r| !r.is_empty()) {
+        Some(renditions) => validate_renditions(renditions)?,
+        None => auto_bitrate_ladder(source.height),
+    };
+    if let Some(top) = renditions.iter().find(|r| r.height > source.height) {
+        notify(format!(
+            "Rendition {} is larger than the {}p source and will be upscaled",
+            top.name(),
+            source.height
+        ));
+    }
+
+    Ok(PreparedStreaming {
+        params,
+        renditions,
+        has_audio,
+        is_dash,
+        duration: media_info.duration,
+    })
+}
+
+/// 校验自定义清晰度列表
+///
+/// 高度需为正偶数、码率为正，且不能有重复高度（重复会导致子目录冲突）。
+/// 返回按高度从高到低排序的列表
+fn validate_renditions(
+    mut renditions: Vec<StreamRendition>,
+) -> Result<Vec<StreamRendition>, String> {
+    for rendition in &renditions {
+        if rendition.height == 0 || rendition.height % 2 != 0 {
+            return Err(format!(
+                "Rendition height must be a positive even number, got {}",
+                rendition.height
+            ));
+        }
+        if rendition.video_bitrate_kbps == 0 || rendition.audio_bitrate_kbps == Some(0) {
+            return Err(format!("Rendition {} has a zero bitrate", rendition.name()));
+        }
+    }
+    renditions.sort_by_key(|r| std::cmp::Reverse(r.height));
+    if let Some(pair) = renditions.windows(2).find(|pair| pair[0].height == pair[1].height) {
+        return Err(format!("Duplicate rendition {}", pair[0].name()));
+    }
+    Ok(renditions)
+}
+
+/// 检查并创建输出目录
+///
+/// 目录已存在且非空时，只有确认覆盖才继续（同名文件由 ffmpeg 覆盖写入）
+fn prepare_output_dir(output_dir: &str, overwrite: bool) -> Result<(), String> {
+    let dir = Path::new(output_dir);
+    if dir.exists() {
+        if !dir.is_dir() {
+            return Err("The output path is not a directory".to_string());
+        }
+        let non_empty = std::fs::read_dir(dir)
+            .map_err(|e| format!("读取输出目录失败: {}", e))?
+            .next()
+            .is_some();
+        if non_empty && !overwrite {
+            return Err(
+                "The output directory is not empty; confirm overwrite to replace its contents"
+                    .to_string(),
+            );
+        }
+    }
+    std::fs::create_dir_all(dir).map_err(|e| format!("创建输出目录失败: {}", e))
+}
+
+/// 计算目录中全部文件的总大小（字节，不递归）
+fn dir_size(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|meta| meta.is_file())
+                .map(|meta| meta.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// 确认 DASH 清单中的 Representation 数量与预期一致
+///
+/// 每档视频一个 Representation，有音频时另有一个音频 Representation
+fn verify_dash_manifest(manifest_path: &str, expected: usize) -> Result<(), String> {
+    let mpd = std::fs::read_to_string(manifest_path)
+        .map_err(|e| format!("读取 DASH 清单失败: {}", e))?;
+    let actual = count_representations(&mpd);
+    if actual != expected {
+        return Err(format!(
+            "The DASH manifest has {} representations, expected {}",
+            actual, expected
+        ));
+    }
+    Ok(())
+}
+
+/// 统计 MPD 中的 Representation 元素数量
+fn count_representations(mpd: &str) -> usize {
+    mpd.match_indices("<Representation")
+        .filter(|(i, tag)| {
+            // 排除 <RepresentationIndex 等同前缀的其他元素
+            mpd[i + tag.len()..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_whitespace() || c == '>' || c == '/')
+        })
+        .count()
+}
+
+/// 计算 DASH 某个 Representation 的文件总大小（init 分片 + 媒体分片）
+fn dash_representation_size(dir: &Path, id: usize) -> u64 {
+    let init = format!("init-{}.", id);
+    let chunk = format!("chunk-{}-", id);
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    name.starts_with(&init) || name.starts_with(&chunk)
+                })
+                .filter_map(|entry| entry.metadata().ok())
+                .map(|meta| meta.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 两档视频 + 一路音频的 MPD 清单，视频档带 RepresentationIndex 子元素
+    const MPD: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<MPD xmlns="urn:mpeg:dash:schema:mpd:2011" type="static">
+  <Period id="0" start="PT0.0S">
+    <AdaptationSet id="0" contentType="video" segmentAlignment="true">
+      <Representation id="0" mimeType="video/mp4" codecs="avc1.640028" bandwidth="5000000" width="1920" height="1080">
+        <RepresentationIndex sourceURL="index-0.sidx"/>
+        <SegmentTemplate timescale="1000" initialization="init-$RepresentationID$.m4s" media="chunk-$RepresentationID$-$Number%05d$.m4s"/>
+      </Representation>
+      <Representation id="1" mimeType="video/mp4" codecs="avc1.64001f" bandwidth="2800000" width="1280" height="720">
+        <RepresentationIndex sourceURL="index-1.sidx"/>
+      </Representation>
+    </AdaptationSet>
+    <AdaptationSet id="1" contentType="audio">
+      <Representation id="2" mimeType="audio/mp4" codecs="mp4a.40.2" bandwidth="128000"/>
+    </AdaptationSet>
+  </Period>
+</MPD>
+"#;
+
+    #[test]
+    fn count_representations_skips_same_prefix_elements() {
+        assert_eq!(count_representations(MPD), 3);
+        assert_eq!(count_representations("<Representation>"), 1);
+        assert_eq!(count_representations("<RepresentationIndex/><Representation/>"), 1);
+        assert_eq!(count_representations(""), 0);
+    }
+
+    #[test]
+    fn verify_dash_manifest_checks_representation_count() {
+        let dir = std::env::temp_dir().join(format!("clipforge_dash_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest = dir.join(DASH_MANIFEST);
+        std::fs::write(&manifest, MPD).unwrap();
+        let manifest = manifest.to_string_lossy();
+
+        assert!(verify_dash_manifest(&manifest, 3).is_ok());
+        let error = verify_dash_manifest(&manifest, 4).unwrap_err();
+        assert_eq!(error, "The DASH manifest has 3 representations, expected 4");
+        assert!(verify_dash_manifest(&dir.join("missing.mpd").to_string_lossy(), 3).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
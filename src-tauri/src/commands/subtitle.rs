@@ -5,13 +5,23 @@
 /// - Extract: extract subtitle stream to a standalone file
 /// - BurnIn: burn hard subtitles into the video frames
 
+use std::path::{Path, PathBuf};
+
 use tauri::ipc::Channel;
 
-use crate::engine::builder::build_subtitle_command;
-use crate::engine::process::{run_ffmpeg, run_ffprobe};
-use crate::models::media::FfprobeOutput;
+use crate::commands::settings::load_settings;
+use crate::commands::attachment::attachment_file_name;
+use crate::engine::builder::{
+    build_extract_attachments_command, build_strip_subtitles_command, build_subtitle_command,
+    get_system_fonts_dir,
+};
+use crate::engine::process::{
+    probe_media, probe_output, run_ffmpeg, run_ffmpeg_quiet, send_phase, ANALYZING_INPUT_PHASE,
+};
+use crate::models::media::AttachmentStream;
 use crate::models::preset::{SubtitleMode, SubtitleParams};
 use crate::models::task::TaskEvent;
+use crate::utils::path::{cleanup_temp_dir, file_extension, get_temp_dir};
 
 /// Execute subtitle processing
 ///
@@ -23,6 +33,11 @@ use crate::models::task::TaskEvent;
 /// subtitle streams before attempting extraction — avoids the confusing
 /// "Error opening output files: Invalid argument" from ffmpeg.
 ///
+/// For BurnIn mode, fonts embedded as attachments in the input (typical for
+/// MKV releases with styled ASS subtitles) and any `fonts_dir` supplied by the
+/// caller are gathered into a per-task fonts directory so libass renders the
+/// intended typefaces. The directory is removed once the task finishes.
+///
 /// @param app - Tauri AppHandle
 /// @param params - Subtitle processing parameters (mode, subtitle path, style, etc.)
 /// @param on_progress - Progress push Channel
@@ -35,6 +50,150 @@ pub async fn process_subtitle(
 ) -> Result<String, String> {
     let task_id = uuid::Uuid::new_v4().to_string();
 
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let prepared = prepare_subtitle(&app, params).await?;
+    let needs_fonts_dir = prepared.needs_fonts_dir();
+    let PreparedSubtitle {
+        mut params,
+        duration,
+        font_attachments,
+    } = prepared;
+    let settings = load_settings(&app).await;
+    let thread_count = settings.ffmpeg_threads;
+
+    // For BurnIn mode: collect embedded attachment fonts (and the caller's
+    // fonts_dir) into a per-task directory used as libass fontsdir
+    let mut task_fonts_dir: Option<PathBuf> = None;
+    if needs_fonts_dir {
+        let dir = burn_in_fonts_dir(&task_id)?;
+        let font_refs: Vec<&AttachmentStream> = font_attachments.iter().collect();
+        let fonts = prepare_fonts_dir(
+            &app,
+            &dir,
+            &params.input_path,
+            &font_refs,
+            params.fonts_dir.as_deref(),
+        )
+        .await;
+
+        match fonts {
+            Ok(fonts) => {
+                if !fonts.is_empty() {
+                    log::info!("Burn-in fonts: {:?}", fonts);
+                    let _ = on_progress.send(TaskEvent::Notice {
+                        task_id: task_id.clone(),
+                        message: format!(
+                            "Using {} subtitle font(s): {}",
+                            fonts.len(),
+                            fonts.join(", ")
+                        ),
+                    });
+                }
+                params.fonts_dir = Some(dir.to_string_lossy().to_string());
+            }
+            Err(e) => {
+                // Fall back to system fonts rather than failing the burn-in
+                log::warn!("Failed to prepare subtitle fonts: {}", e);
+                params.fonts_dir = None;
+            }
+        }
+        task_fonts_dir = Some(dir);
+    }
+
+    // Build ffmpeg command
+    let args = build_subtitle_command(&params, thread_count);
+
+    // Execute ffmpeg
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    if let Some(dir) = &task_fonts_dir {
+        cleanup_temp_dir(&dir.to_string_lossy());
+    }
+    let result = result?;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    if result.error.is_some() {
+        Err(result.error.unwrap())
+    } else {
+        // For Embed mode: verify the output file actually contains subtitle streams.
+        // This catches silent failures where ffmpeg exits 0 but drops the subtitle track.
+        if matches!(params.mode, SubtitleMode::Embed) {
+            let verify_output =
+                probe_output(&app, &params.output_path, settings.probe_timeout_ms).await?;
+
+            let sub_count = verify_output
+                .streams
+                .as_ref()
+                .map(|streams| {
+                    streams
+                        .iter()
+                        .filter(|s| s.codec_type.as_deref() == Some("subtitle"))
+                        .count()
+                })
+                .unwrap_or(0);
+
+            if sub_count == 0 {
+                return Err(
+                    "Subtitle embedding failed: the output file contains no subtitle tracks. Please check the subtitle file format."
+                        .to_string(),
+                );
+            }
+        }
+
+        Ok(task_id)
+    }
+}
+
+/// A subtitle task that is ready to build its ffmpeg command
+pub(crate) struct PreparedSubtitle {
+    /// Params with the settings defaults filled in
+    pub params: SubtitleParams,
+    /// Input duration in seconds (for progress)
+    pub duration: f64,
+    /// Font attachments to extract for BurnIn mode
+    pub font_attachments: Vec<AttachmentStream>,
+}
+
+impl PreparedSubtitle {
+    /// Whether BurnIn needs a per-task libass fonts directory
+    /// (the input embeds fonts or the caller supplied a fonts_dir)
+    pub fn needs_fonts_dir(&self) -> bool {
+        matches!(self.params.mode, SubtitleMode::BurnIn)
+            && (!self.font_attachments.is_empty() || self.params.fonts_dir.is_some())
+    }
+}
+
+/// Prepare a subtitle task
+///
+/// Fills in the settings defaults, validates the subtitle file (Embed/BurnIn)
+/// or the requested subtitle stream (Extract) and probes the input.
+/// Shared by process_subtitle and preview_command so the previewed command
+/// matches the executed one.
+///
+/// @param app - Tauri AppHandle
+/// @param params - Subtitle processing parameters
+/// @returns Ok(PreparedSubtitle), or Err(String) if validation or probing fails
+pub(crate) async fn prepare_subtitle(
+    app: &tauri::AppHandle,
+    mut params: SubtitleParams,
+) -> Result<PreparedSubtitle, String> {
+    // Fall back to the settings defaults for unspecified encoding params
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
     // For Embed/BurnIn modes: validate that the subtitle file exists and is readable
     if matches!(params.mode, SubtitleMode::Embed | SubtitleMode::BurnIn) {
         match &params.subtitle_path {
@@ -63,22 +222,15 @@ pub async fn process_subtitle(
     }
 
     // Probe input file for duration and stream info
-    let json_str = run_ffprobe(&app, &params.input_path).await?;
-    let probe_output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
+    let probe = probe_output(app, &params.input_path, settings.probe_timeout_ms).await?;
 
-    let duration = probe_output
-        .format
-        .as_ref()
-        .and_then(|f| f.duration.as_ref())
-        .and_then(|d| d.parse::<f64>().ok())
-        .unwrap_or(0.0);
+    let duration = probe.duration();
 
     // For Extract mode: verify the video has subtitle streams before proceeding.
     // Without this check, ffmpeg fails with "Error opening output files: Invalid argument"
     // when -map 0:s:0 can't find a subtitle stream.
     if matches!(params.mode, SubtitleMode::Extract) {
-        let subtitle_count = probe_output
+        let subtitle_count = probe
             .streams
             .as_ref()
             .map(|streams| {
@@ -106,54 +258,212 @@ pub async fn process_subtitle(
         }
     }
 
-    // Build ffmpeg command
-    let args = build_subtitle_command(&params);
+    let font_attachments = if matches!(params.mode, SubtitleMode::BurnIn) {
+        probe
+            .to_media_info(&params.input_path)
+            .attachment_streams
+            .into_iter()
+            .filter(|a| a.is_font())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(PreparedSubtitle {
+        params,
+        duration,
+        font_attachments,
+    })
+}
+
+/// Per-task fonts directory used as the libass fontsdir for BurnIn
+///
+/// @param task_id - Task ID (the first 8 characters name the directory)
+pub(crate) fn burn_in_fonts_dir(task_id: &str) -> Result<PathBuf, String> {
+    Ok(get_temp_dir()?.join(format!("fonts_{}", &task_id[..8])))
+}
+
+/// Subtitle-only container extensions that cannot hold a stripped output
+const SUBTITLE_ONLY_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "vtt", "sub", "sup"];
+
+/// Remove all subtitle streams from a file
+///
+/// Remuxes the input with every stream except subtitles copied as-is
+/// (`-map 0 -map -0:s -c copy`). Useful when a player chokes on a particular
+/// subtitle codec. Inputs without subtitles are still remuxed, but a notice is
+/// sent since the operation changes nothing.
+///
+/// @param app - Tauri AppHandle
+/// @param input_path - Input media path
+/// @param output_path - Output media path (must be a container, not a subtitle format)
+/// @param on_progress - Progress push Channel
+/// @returns Ok(String) task ID, or Err(String) error description
+#[tauri::command]
+pub async fn strip_subtitles(
+    app: tauri::AppHandle,
+    input_path: String,
+    output_path: String,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let duration = prepare_strip_subtitles(&app, &input_path, &output_path, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+
+    let args = build_strip_subtitles_command(&input_path, &output_path);
 
-    // Execute ffmpeg
     let result = run_ffmpeg(
         &app,
         &task_id,
         args,
         duration,
-        &params.output_path,
+        &output_path,
         &on_progress,
     )
-    .await?;
+    .await;
 
     {
         let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
         queue.cleanup(&task_id);
     }
 
-    if result.error.is_some() {
-        Err(result.error.unwrap())
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
     } else {
-        // For Embed mode: verify the output file actually contains subtitle streams.
-        // This catches silent failures where ffmpeg exits 0 but drops the subtitle track.
-        if matches!(params.mode, SubtitleMode::Embed) {
-            let verify_json = run_ffprobe(&app, &params.output_path).await?;
-            let verify_output: FfprobeOutput = serde_json::from_str(&verify_json)
-                .map_err(|e| format!("Failed to verify output: {}", e))?;
+        Ok(task_id)
+    }
+}
 
-            let sub_count = verify_output
-                .streams
-                .as_ref()
-                .map(|streams| {
-                    streams
-                        .iter()
-                        .filter(|s| s.codec_type.as_deref() == Some("subtitle"))
-                        .count()
-                })
-                .unwrap_or(0);
+/// Validate a subtitle strip and probe the input
+///
+/// Shared by strip_subtitles and preview_command.
+///
+/// @param app - Tauri AppHandle
+/// @param input_path - Input media path
+/// @param output_path - Output media path
+/// @param notify - Notice callback (sent as Notice events when executing)
+/// @returns Ok(f64) input duration in seconds, or Err(String) error description
+pub(crate) async fn prepare_strip_subtitles(
+    app: &tauri::AppHandle,
+    input_path: &str,
+    output_path: &str,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<f64, String> {
+    let ext = file_extension(output_path);
+    if SUBTITLE_ONLY_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(format!(
+            "Output format .{} is a subtitle format; choose a video or audio container",
+            ext
+        ));
+    }
 
-            if sub_count == 0 {
-                return Err(
-                    "Subtitle embedding failed: the output file contains no subtitle tracks. Please check the subtitle file format."
-                        .to_string(),
-                );
-            }
-        }
+    let settings = load_settings(app).await;
+    let info = probe_media(app, input_path, settings.probe_timeout_ms).await?;
 
-        Ok(task_id)
+    if info.video_streams.is_empty() && info.audio_streams.is_empty() {
+        return Err("The input has no video or audio streams to keep".to_string());
+    }
+
+    if info.subtitle_streams.is_empty() {
+        notify("The input has no subtitle streams; the output is a plain remux".to_string());
+    }
+
+    Ok(info.duration)
+}
+
+/// Populate a per-task fonts directory for libass
+///
+/// Extracts the input's font attachments into `dir`, then links in fonts from
+/// the caller's `extra_dir` and the system fonts directory. libass accepts a
+/// single fontsdir, so the system fonts are linked in as well to keep CJK
+/// fallback working. Existing names are never overwritten, so embedded fonts
+/// win over same-named system fonts.
+///
+/// @param app - Tauri AppHandle
+/// @param dir - Per-task fonts directory to create
+/// @param input_path - Input media containing the attachments
+/// @param font_attachments - Font attachment streams to extract
+/// @param extra_dir - Optional caller-supplied fonts directory
+/// @returns Ok(Vec<String>) file names of the embedded and caller fonts used
+async fn prepare_fonts_dir(
+    app: &tauri::AppHandle,
+    dir: &Path,
+    input_path: &str,
+    font_attachments: &[&AttachmentStream],
+    extra_dir: Option<&str>,
+) -> Result<Vec<String>, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create fonts directory: {}", e))?;
+
+    let mut used_fonts = Vec::new();
+
+    if !font_attachments.is_empty() {
+        // Same-named attachments would overwrite each other; keep the first
+        let targets: Vec<(u32, String)> = font_attachments
+            .iter()
+            .filter_map(|a| {
+                let name = attachment_file_name(a);
+                if used_fonts.contains(&name) {
+                    return None;
+                }
+                used_fonts.push(name.clone());
+                Some((a.index, dir.join(name).to_string_lossy().to_string()))
+            })
+            .collect();
+        let args = build_extract_attachments_command(input_path, &targets);
+        run_ffmpeg_quiet(app, args).await?;
+    }
+
+    if let Some(extra) = extra_dir {
+        used_fonts.extend(link_fonts(Path::new(extra), dir));
     }
+    link_fonts(Path::new(&get_system_fonts_dir()), dir);
+
+    Ok(used_fonts)
+}
+
+/// Symlink every font file in `source` into `target`, skipping existing names
+///
+/// @returns file names that were linked
+fn link_fonts(source: &Path, target: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(source) else {
+        return Vec::new();
+    };
+
+    let mut linked = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_font = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .is_some_and(|e| matches!(e.as_str(), "ttf" | "otf" | "ttc"));
+        if !is_font {
+            continue;
+        }
+        let name = entry.file_name();
+        let dest = target.join(&name);
+        if dest.exists() {
+            continue;
+        }
+        if symlink_file(&path, &dest).is_ok() {
+            linked.push(name.to_string_lossy().to_string());
+        }
+    }
+    linked
+}
+
+#[cfg(unix)]
+fn symlink_file(src: &Path, dest: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(src, dest)
+}
+
+#[cfg(not(unix))]
+fn symlink_file(src: &Path, dest: &Path) -> std::io::Result<()> {
+    std::fs::copy(src, dest).map(|_| ())
 }
@@ -1,12 +1,20 @@
 /// 任务管理 command
 ///
 /// 提供取消正在运行的 ffmpeg 任务的能力，
-/// 通过向子进程发送 kill 信号来终止执行
+/// 通过向子进程发送 kill 信号来终止执行；
+/// 以及定时任务（指定时间 / 系统空闲时执行）的创建、查询和取消
+
+use tauri::ipc::Channel;
+
+use crate::commands::download::discard_paused_download;
+use crate::engine::scheduler;
+use crate::models::task::{ScheduledTask, TaskEvent};
 
 /// 取消指定的 ffmpeg 任务
 ///
 /// 前端点击取消按钮时调用此 command，
-/// 通过全局任务队列查找并终止对应的 ffmpeg 子进程
+/// 通过全局任务队列查找并终止对应的 ffmpeg 子进程；
+/// 已暂停的下载没有运行中的进程，直接放弃并删除已下载的部分
 ///
 /// # 参数
 /// - `task_id` - 要取消的任务 ID（UUID v4 格式）
@@ -16,6 +24,106 @@
 /// - `Err(String)` - 任务不存在或已完成
 #[tauri::command]
 pub async fn cancel_task(task_id: String) -> Result<(), String> {
+    let result = crate::engine::queue::TASK_QUEUE.lock().await.cancel_task(&task_id);
+    if result.is_err() && discard_paused_download(&task_id).await {
+        return Ok(());
+    }
+    result
+}
+
+/// 按文件路径取消任务
+///
+/// 前端丢失 task_id（如重新加载页面）但知道正在处理的文件时调用，
+/// 取消所有以该文件为输入或输出的运行中/排队中任务
+///
+/// # 参数
+/// - `path` - 任务的输入或输出文件路径
+///
+/// # 返回
+/// - `Ok(Vec<String>)` - 已取消的任务 ID
+/// - `Err(String)` - 没有使用该文件的任务
+#[tauri::command]
+pub async fn cancel_by_path(path: String) -> Result<Vec<String>, String> {
     let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
-    queue.cancel_task(&task_id)
+    queue.cancel_by_path(&path)
+}
+
+/// 创建定时任务
+///
+/// 任务参数原样持久化到 scheduled.json，到达 schedule_at 时间或系统持续空闲时
+/// 由后台调度器按 max_concurrent 限制提交执行，应用重启后仍然有效
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `kind` - 任务类型（"convert" / "compress" / "trim" / "merge" / "audio" /
+///   "watermark" / "resize" / "gif" / "subtitle" / "export"）
+/// - `params` - 对应 command 的参数对象
+/// - `schedule_at` - 计划执行时间（ISO 8601 / RFC 3339）
+/// - `run_when_idle` - 是否等待系统空闲后执行
+///
+/// # 返回
+/// - `Ok(ScheduledTask)` - 已创建的定时任务
+/// - `Err(String)` - 类型不支持、参数非法或时间格式错误
+#[tauri::command]
+pub async fn schedule_task(
+    app: tauri::AppHandle,
+    kind: String,
+    params: serde_json::Value,
+    schedule_at: Option<String>,
+    run_when_idle: Option<bool>,
+) -> Result<ScheduledTask, String> {
+    let run_when_idle = run_when_idle.unwrap_or(false);
+    if schedule_at.is_none() && !run_when_idle {
+        return Err("A scheduled task needs scheduleAt or runWhenIdle".to_string());
+    }
+    if let Some(at) = schedule_at.as_deref() {
+        scheduler::parse_schedule_time(at)?;
+    }
+    scheduler::validate_params(&kind, &params)?;
+
+    let task = ScheduledTask {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind,
+        params,
+        schedule_at,
+        run_when_idle,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        running: false,
+    };
+    scheduler::add_task(&app, task.clone()).await?;
+    Ok(task)
+}
+
+/// 列出所有尚未执行完的定时任务（包括正在执行的）
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+#[tauri::command]
+pub async fn list_scheduled_tasks(app: tauri::AppHandle) -> Result<Vec<ScheduledTask>, String> {
+    scheduler::list_tasks(&app).await
+}
+
+/// 取消尚未执行的定时任务
+///
+/// 已触发执行的任务请使用 cancel_task 取消
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `id` - 定时任务 ID
+#[tauri::command]
+pub async fn cancel_scheduled_task(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    scheduler::remove_task(&app, &id).await
+}
+
+/// 订阅定时任务的执行事件
+///
+/// 前端启动（或重新加载）后调用，定时任务触发后的 Started/Progress/Completed
+/// 等事件推送到最近一次注册的 Channel
+///
+/// # 参数
+/// - `on_event` - 事件推送 Channel
+#[tauri::command]
+pub async fn subscribe_scheduled_events(on_event: Channel<TaskEvent>) -> Result<(), String> {
+    scheduler::set_event_channel(on_event).await;
+    Ok(())
 }
@@ -0,0 +1,555 @@
+/// 缩略图 command
+///
+/// 为前端时间轴拖动（scrubber）生成指定时间点的预览帧，
+/// 输出 PNG 到 clipforge 临时目录并登记到预览注册表，前端按令牌读取；
+/// 另支持导出单帧截图、等间隔缩略图拼图、一组独立缩略图或悬停预览短片到用户指定位置
+
+use std::path::Path;
+use std::time::{Instant, SystemTime};
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{
+    build_preview_snippet_command, build_scrub_thumbnails_command, build_thumbnail_command,
+    build_thumbnail_series_command, FfmpegCommand, DEFAULT_PREVIEW_CLIP_WIDTH,
+    THUMBNAIL_SERIES_PATTERN,
+};
+use crate::engine::preview::register_previews;
+use crate::engine::process::{
+    probe_duration, run_ffmpeg, run_ffmpeg_quiet, send_phase, ANALYZING_INPUT_PHASE,
+};
+use crate::models::preset::{
+    PreviewClipParams, ThumbnailMode, ThumbnailParams, ThumbnailSeriesParams,
+};
+use crate::models::task::{ProgressUpdate, TaskEvent};
+use crate::utils::path::{cleanup_temp_file, file_extension, get_file_size, temp_file_path};
+
+/// 单次请求允许的最大时间点数量（每个时间点对应一路解码输入）
+const MAX_SCRUB_TIMESTAMPS: usize = 32;
+/// 缩略图拼图允许的最大缩略图数量
+const MAX_CONTACT_SHEET_COUNT: u32 = 100;
+/// 批量缩略图允许的最大数量
+const MAX_THUMBNAIL_SERIES_COUNT: u32 = 1000;
+/// 悬停预览默认片段数量
+const DEFAULT_PREVIEW_SNIPPETS: u32 = 6;
+/// 悬停预览默认总时长（秒）
+const DEFAULT_PREVIEW_DURATION: f64 = 6.0;
+/// 悬停预览允许的最大片段数量
+const MAX_PREVIEW_SNIPPETS: u32 = 30;
+/// 悬停预览允许的最长总时长（秒）
+const MAX_PREVIEW_DURATION: f64 = 60.0;
+/// 单个预览片段的最短时长（秒），过短的片段只剩一两帧，看不出内容
+const MIN_PREVIEW_SNIPPET_SECONDS: f64 = 0.5;
+
+/// 生成时间轴预览缩略图
+///
+/// 前端在用户拖动时间轴时调用，一次 ffmpeg 调用生成全部请求的帧
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `input_path` - 输入视频文件路径
+/// - `timestamps` - 时间点列表（秒）
+/// - `width` - 缩略图宽度（像素）
+///
+/// # 返回
+/// - `Ok(Vec<String>)` - 与 timestamps 顺序一一对应的预览令牌（通过 get_preview 读取 PNG）
+/// - `Err(String)` - 参数非法或 ffmpeg 执行失败
+#[tauri::command]
+pub async fn scrub_thumbnails(
+    app: tauri::AppHandle,
+    input_path: String,
+    timestamps: Vec<f64>,
+    width: u32,
+) -> Result<Vec<String>, String> {
+    if timestamps.is_empty() {
+        return Ok(Vec::new());
+    }
+    if timestamps.len() > MAX_SCRUB_TIMESTAMPS {
+        return Err(format!(
+            "Too many timestamps requested ({}), at most {} per call",
+            timestamps.len(),
+            MAX_SCRUB_TIMESTAMPS
+        ));
+    }
+    if width == 0 {
+        return Err("Thumbnail width must be greater than 0".to_string());
+    }
+
+    let output_paths = timestamps
+        .iter()
+        .map(|_| temp_file_path("scrub", "png"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let args = build_scrub_thumbnails_command(&input_path, &timestamps, width, &output_paths);
+    if let Err(e) = run_ffmpeg_quiet(&app, args).await {
+        for path in &output_paths {
+            cleanup_temp_file(path);
+        }
+        return Err(e);
+    }
+
+    Ok(register_previews("scrub", output_paths).await)
+}
+
+/// 提取单帧截图或缩略图拼图
+///
+/// 单帧在解码器之前定位，几乎立即完成；拼图需要解码整段视频。
+/// 两种方式都只输出一帧（拼图在最后才写出），没有可用的进度，只推送开始/完成事件
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 缩略图参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 参数非法或 ffmpeg 执行失败
+#[tauri::command]
+pub async fn extract_thumbnail(
+    app: tauri::AppHandle,
+    params: ThumbnailParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let duration = prepare_thumbnail(&app, &params).await?;
+
+    let args = build_thumbnail_command(&params, duration);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        0.0,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 批量导出缩略图
+///
+/// 按间隔时直接用 `fps=1/<间隔>` 从头取帧；按数量时用探测到的时长反算间隔，
+/// 从第一个间隔的中点开始取 count 帧。输出目录中同名的旧文件会被覆盖，
+/// 返回值只包含本次写出的文件
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 批量缩略图参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(Vec<String>)` - 按时间顺序排列的缩略图文件路径
+/// - `Err(String)` - 参数非法、时长未知或 ffmpeg 执行失败
+#[tauri::command]
+pub async fn extract_thumbnails(
+    app: tauri::AppHandle,
+    params: ThumbnailSeriesParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<Vec<String>, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let PreparedThumbnailSeries {
+        duration,
+        interval,
+        start,
+        max_frames,
+        output_pattern,
+    } = prepare_thumbnail_series(&app, &params).await?;
+
+    std::fs::create_dir_all(&params.output_dir)
+        .map_err(|e| format!("创建输出目录失败: {}", e))?;
+
+    let started_at = SystemTime::now();
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+    let args = build_thumbnail_series_command(
+        &params,
+        interval,
+        start,
+        max_frames,
+        &output_pattern,
+        thread_count,
+    );
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        (duration - start).max(0.0),
+        &output_pattern,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        return Err(error);
+    }
+    Ok(collect_thumbnail_series(Path::new(&params.output_dir), started_at))
+}
+
+/// 准备单帧截图或缩略图拼图：校验参数并探测时长
+///
+/// 实际执行（extract_thumbnail）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 缩略图参数
+///
+/// # 返回
+/// - `Ok(f64)` - 源文件时长（秒，未知时为 0）
+/// - `Err(String)` - 参数非法或时间点超出视频
+pub(crate) async fn prepare_thumbnail(
+    app: &tauri::AppHandle,
+    params: &ThumbnailParams,
+) -> Result<f64, String> {
+    if !matches!(params.format.as_str(), "jpg" | "png") {
+        return Err(format!("Unsupported thumbnail format: {}", params.format));
+    }
+    if params.width == Some(0) {
+        return Err("Thumbnail width must be greater than 0".to_string());
+    }
+    if let Some(count) = params.count {
+        if count == 0 || count > MAX_CONTACT_SHEET_COUNT {
+            return Err(format!(
+                "Thumbnail count must be between 1 and {}",
+                MAX_CONTACT_SHEET_COUNT
+            ));
+        }
+    }
+
+    let settings = load_settings(app).await;
+    let duration = probe_duration(app, &params.input_path, settings.probe_timeout_ms).await?;
+    if params.timestamp.is_some_and(|ts| ts < 0.0 || (duration > 0.0 && ts > duration)) {
+        return Err(format!(
+            "Timestamp is outside the video (duration {:.2}s)",
+            duration
+        ));
+    }
+    if params.count.is_some() && duration <= 0.0 {
+        return Err("Cannot build a contact sheet: the video duration is unknown".to_string());
+    }
+    Ok(duration)
+}
+
+/// 准备好、可直接构建命令的批量缩略图任务
+pub(crate) struct PreparedThumbnailSeries {
+    /// 源文件时长（秒）
+    pub duration: f64,
+    /// 取帧间隔（秒）
+    pub interval: f64,
+    /// 第一帧的时间点（秒）
+    pub start: f64,
+    /// 最多输出的帧数（按间隔时不限制）
+    pub max_frames: Option<u32>,
+    /// 输出文件名模式（含帧序号占位符）
+    pub output_pattern: String,
+}
+
+/// 准备批量缩略图任务：校验参数、探测时长并计算取帧间隔和起点
+///
+/// 实际执行（extract_thumbnails）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 批量缩略图参数
+///
+/// # 返回
+/// - `Ok(PreparedThumbnailSeries)` - 取帧计划和输出文件名模式
+/// - `Err(String)` - 参数非法或按数量取帧时时长未知
+pub(crate) async fn prepare_thumbnail_series(
+    app: &tauri::AppHandle,
+    params: &ThumbnailSeriesParams,
+) -> Result<PreparedThumbnailSeries, String> {
+    if params.width == Some(0) {
+        return Err("Thumbnail width must be greater than 0".to_string());
+    }
+    match params.mode {
+        ThumbnailMode::ByCount => match params.count {
+            Some(count) if (1..=MAX_THUMBNAIL_SERIES_COUNT).contains(&count) => {}
+            _ => {
+                return Err(format!(
+                    "Thumbnail count must be between 1 and {}",
+                    MAX_THUMBNAIL_SERIES_COUNT
+                ))
+            }
+        },
+        ThumbnailMode::ByInterval => {
+            if !params.interval_sec.is_some_and(|i| i.is_finite() && i > 0.0) {
+                return Err("Thumbnail interval must be greater than 0 seconds".to_string());
+            }
+        }
+    }
+
+    let settings = load_settings(app).await;
+    let duration = probe_duration(app, &params.input_path, settings.probe_timeout_ms).await?;
+
+    let (interval, start, max_frames) = match params.mode {
+        ThumbnailMode::ByCount => {
+            if duration <= 0.0 {
+                return Err(
+                    "Cannot space thumbnails evenly: the video duration is unknown".to_string(),
+                );
+            }
+            let count = params.count.unwrap_or(1);
+            let interval = duration / count as f64;
+            (interval, interval / 2.0, Some(count))
+        }
+        ThumbnailMode::ByInterval => {
+            let interval = params.interval_sec.unwrap_or(1.0);
+            if duration > 0.0 && duration / interval > MAX_THUMBNAIL_SERIES_COUNT as f64 {
+                return Err(format!(
+                    "The interval would produce more than {} thumbnails",
+                    MAX_THUMBNAIL_SERIES_COUNT
+                ));
+            }
+            (interval, 0.0, None)
+        }
+    };
+
+    let output_pattern = Path::new(&params.output_dir)
+        .join(THUMBNAIL_SERIES_PATTERN)
+        .to_string_lossy()
+        .to_string();
+
+    Ok(PreparedThumbnailSeries {
+        duration,
+        interval,
+        start,
+        max_frames,
+        output_pattern,
+    })
+}
+
+/// 生成悬停预览短片
+///
+/// 在全片范围内等间隔截取 snippet_count 个片段（每段位于各自区间的中部），
+/// 逐段编码到临时文件后用 concat demuxer 直接复制拼接，输出无声的低分辨率短片。
+/// 片段截取占进度的 0-90%，拼接占 90-100%；临时片段和文件列表无论成败都会清理
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 悬停预览参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 参数非法、视频过短或 ffmpeg 执行失败
+#[tauri::command]
+pub async fn preview_clip(
+    app: tauri::AppHandle,
+    params: PreviewClipParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let count = params.snippet_count.unwrap_or(DEFAULT_PREVIEW_SNIPPETS);
+    let total = params.total_duration.unwrap_or(DEFAULT_PREVIEW_DURATION);
+    let width = params.width.unwrap_or(DEFAULT_PREVIEW_CLIP_WIDTH);
+
+    if !matches!(file_extension(&params.output_path).as_str(), "mp4" | "m4v" | "mov" | "mkv") {
+        return Err("Preview clips must be saved as MP4, M4V, MOV or MKV".to_string());
+    }
+    if count == 0 || count > MAX_PREVIEW_SNIPPETS {
+        return Err(format!(
+            "Snippet count must be between 1 and {}",
+            MAX_PREVIEW_SNIPPETS
+        ));
+    }
+    if !total.is_finite() || total <= 0.0 || total > MAX_PREVIEW_DURATION {
+        return Err(format!(
+            "Preview length must be between 0 and {} seconds",
+            MAX_PREVIEW_DURATION
+        ));
+    }
+    let snippet_len = total / count as f64;
+    if snippet_len < MIN_PREVIEW_SNIPPET_SECONDS {
+        return Err(format!(
+            "Each snippet must be at least {}s long; use fewer snippets or a longer preview",
+            MIN_PREVIEW_SNIPPET_SECONDS
+        ));
+    }
+    if width == 0 {
+        return Err("Preview width must be greater than 0".to_string());
+    }
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let settings = load_settings(&app).await;
+    let duration = probe_duration(&app, &params.input_path, settings.probe_timeout_ms).await?;
+    if duration <= 0.0 {
+        return Err("Cannot space preview snippets: the video duration is unknown".to_string());
+    }
+    if duration < total {
+        return Err(format!(
+            "The video ({:.2}s) is shorter than the {:.2}s preview",
+            duration, total
+        ));
+    }
+
+    let start_time = Instant::now();
+    let _ = on_progress.send(TaskEvent::Started {
+        task_id: task_id.clone(),
+        total_duration: total,
+    });
+
+    let mut temp_files: Vec<String> = Vec::new();
+    let result = build_preview_clip(
+        &app,
+        &task_id,
+        &params,
+        preview_snippet_starts(duration, count, snippet_len),
+        snippet_len,
+        &mut temp_files,
+        &on_progress,
+    )
+    .await;
+
+    for temp in &temp_files {
+        cleanup_temp_file(temp);
+    }
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    result?;
+
+    let _ = on_progress.send(TaskEvent::Completed {
+        task_id: task_id.clone(),
+        output_path: params.output_path.clone(),
+        output_size: get_file_size(&params.output_path),
+        elapsed: start_time.elapsed().as_secs_f64(),
+        verification: Vec::new(),
+        note: None,
+        container_change: None,
+    });
+    Ok(task_id)
+}
+
+/// 截取各预览片段并拼接为输出文件
+///
+/// 生成的临时文件（片段和 concat 文件列表）追加到 `temp_files`，由调用方统一清理
+async fn build_preview_clip(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    params: &PreviewClipParams,
+    starts: Vec<f64>,
+    snippet_len: f64,
+    temp_files: &mut Vec<String>,
+    on_progress: &Channel<TaskEvent>,
+) -> Result<(), String> {
+    let count = starts.len();
+    let thread_count = load_settings(app).await.ffmpeg_threads;
+
+    // 步骤 1：逐个片段编码到临时文件（片段之间编码参数一致，便于直接拼接）
+    for (i, start) in starts.into_iter().enumerate() {
+        let temp_path = temp_file_path(&format!("preview_seg_{}", i), "mp4")?;
+        temp_files.push(temp_path.clone());
+
+        let args = build_preview_snippet_command(
+            &params.input_path,
+            &temp_path,
+            start,
+            snippet_len,
+            params.width.unwrap_or(DEFAULT_PREVIEW_CLIP_WIDTH),
+            thread_count,
+        );
+        run_ffmpeg_quiet(app, args)
+            .await
+            .map_err(|e| format!("截取第 {} 个预览片段失败: {}", i + 1, e))?;
+
+        // 阶段标签为接下来的步骤
+        let done = (i + 1) as f64;
+        let phase = if i + 1 < count {
+            format!("Extracting snippet {}/{}", i + 2, count)
+        } else {
+            "Joining snippets".to_string()
+        };
+        let _ = on_progress.send(TaskEvent::Progress(ProgressUpdate {
+            task_id: task_id.to_string(),
+            percent: done / count as f64 * 90.0,
+            speed: 0.0,
+            current_time: done * snippet_len,
+            eta: 0.0,
+            output_size: 0,
+            frame: 0,
+            fps: 0.0,
+            phase: Some(phase),
+        }));
+    }
+
+    // 步骤 2：创建 concat demuxer 文件列表
+    let concat_file = temp_file_path("concat_preview", "txt")?;
+    let concat_content = temp_files
+        .iter()
+        .map(|p| format!("file '{}'", p.replace('\'', "'\\''")))
+        .collect::<Vec<_>>()
+        .join("\n");
+    temp_files.push(concat_file.clone());
+    std::fs::write(&concat_file, &concat_content)
+        .map_err(|e| format!("创建预览文件列表失败: {}", e))?;
+
+    // 步骤 3：使用 concat demuxer 直接复制拼接
+    let mut concat_cmd = FfmpegCommand::new()
+        .pre_args_pair("-f", "concat")
+        .pre_args_pair("-safe", "0")
+        .input(&concat_file)
+        .video_codec("copy");
+    if file_extension(&params.output_path) != "mkv" {
+        concat_cmd = concat_cmd.faststart();
+    }
+    let concat_args = concat_cmd.output(&params.output_path).build();
+    run_ffmpeg_quiet(app, concat_args)
+        .await
+        .map_err(|e| format!("拼接预览片段失败: {}", e))
+}
+
+/// 计算各预览片段的起始时间（秒）
+///
+/// 把全片均分为 count 个区间，每个片段以区间中点为中心，并限制在视频范围内
+fn preview_snippet_starts(duration: f64, count: u32, snippet_len: f64) -> Vec<f64> {
+    let latest = (duration - snippet_len).max(0.0);
+    (0..count)
+        .map(|i| {
+            let center = duration * (i as f64 + 0.5) / count as f64;
+            (center - snippet_len / 2.0).clamp(0.0, latest)
+        })
+        .collect()
+}
+
+/// 收集本次写出的缩略图文件
+///
+/// 从 thumb_0001.jpg 起依次查找，遇到不存在或修改时间早于本次任务开始的文件即停止，
+/// 避免把上次导出留下的编号更大的旧文件计入结果
+fn collect_thumbnail_series(dir: &Path, started_at: SystemTime) -> Vec<String> {
+    (1..)
+        .map(|i| dir.join(format!("thumb_{:04}.jpg", i)))
+        .take_while(|path| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .is_ok_and(|modified| modified >= started_at)
+        })
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
@@ -0,0 +1,268 @@
+/// 画面方向变换 command
+///
+/// 根据源文件的旋转元数据自动校正画面方向，
+/// 修复手机拍摄视频在部分播放器中横躺显示的问题；
+/// 也支持手动旋转 90°/180° 和水平/垂直翻转
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{
+    build_auto_orient_command, build_rotate_command, build_transform_command, transform_filter,
+};
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::{AutoOrientParams, RotateParams, TransformParams};
+use crate::models::task::TaskEvent;
+
+/// 按旋转元数据自动校正视频方向
+///
+/// 读取第一条视频流的 rotate 标签或显示矩阵，把旋转烘焙进像素并清除旋转标记，
+/// 使输出在任何播放器中都正向显示。源视频无旋转时直接流复制
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 方向校正参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 错误描述
+#[tauri::command]
+pub async fn auto_orient(
+    app: tauri::AppHandle,
+    params: AutoOrientParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration, rotation) = prepare_auto_orient(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_auto_orient_command(&params, rotation, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 手动旋转或翻转视频
+///
+/// 在显示方向的画面上旋转/翻转并重新编码，输出不带旋转标记
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 旋转/翻转参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 错误描述
+#[tauri::command]
+pub async fn rotate_video(
+    app: tauri::AppHandle,
+    params: RotateParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_rotate(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+    let args = build_rotate_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 旋转并翻转视频
+///
+/// 在显示方向的画面上按 rotate 顺时针旋转后再水平/垂直翻转，一次重编码完成组合变换，
+/// 输出不带旋转标记
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 变换参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 错误描述
+#[tauri::command]
+pub async fn transform_video(
+    app: tauri::AppHandle,
+    params: TransformParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_transform(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+    let args = build_transform_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 准备方向校正任务：填充默认参数并探测时长和旋转角度
+///
+/// 实际执行（auto_orient）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 方向校正参数
+/// - `notify` - 提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok((AutoOrientParams, f64, u32))` - 填充默认值后的参数、源文件时长（秒）和旋转角度
+/// - `Err(String)` - 探测失败或没有视频流
+pub(crate) async fn prepare_auto_orient(
+    app: &tauri::AppHandle,
+    mut params: AutoOrientParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<(AutoOrientParams, f64, u32), String> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let (duration, rotation) =
+        probe_orientation(app, &params.input_path, settings.probe_timeout_ms).await?;
+    if rotation == 0 {
+        notify("No rotation metadata found; streams are copied unchanged".to_string());
+    }
+    Ok((params, duration, rotation))
+}
+
+/// 准备手动旋转/翻转任务：填充默认参数并探测时长
+///
+/// 实际执行（rotate_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 旋转/翻转参数
+///
+/// # 返回
+/// - `Ok((RotateParams, f64))` - 填充默认值后的参数和源文件时长（秒）
+/// - `Err(String)` - 探测失败或没有视频流
+pub(crate) async fn prepare_rotate(
+    app: &tauri::AppHandle,
+    mut params: RotateParams,
+) -> Result<(RotateParams, f64), String> {
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let (duration, _) =
+        probe_orientation(app, &params.input_path, settings.probe_timeout_ms).await?;
+    Ok((params, duration))
+}
+
+/// 准备旋转并翻转任务：校验变换组合、填充默认参数并探测时长
+///
+/// 实际执行（transform_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 变换参数
+///
+/// # 返回
+/// - `Ok((TransformParams, f64))` - 填充默认值后的参数和源文件时长（秒）
+/// - `Err(String)` - 旋转角度非法、没有任何变换或探测失败
+pub(crate) async fn prepare_transform(
+    app: &tauri::AppHandle,
+    mut params: TransformParams,
+) -> Result<(TransformParams, f64), String> {
+    let rotate = params.rotate.unwrap_or(0);
+    if rotate % 90 != 0 {
+        return Err(format!("Rotation must be a multiple of 90 degrees, got {}", rotate));
+    }
+    if transform_filter(rotate, params.flip_h, params.flip_v).is_none() {
+        return Err("Nothing to transform: choose a rotation or a flip".to_string());
+    }
+
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let (duration, _) =
+        probe_orientation(app, &params.input_path, settings.probe_timeout_ms).await?;
+    Ok((params, duration))
+}
+
+/// 从 ffprobe 获取视频时长和第一条视频流的旋转角度
+async fn probe_orientation(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<(f64, u32), String> {
+    let info = probe_media(app, file_path, timeout_ms).await?;
+    let stream = info
+        .video_streams
+        .first()
+        .ok_or_else(|| "No video stream found".to_string())?;
+    Ok((info.duration, stream.rotation))
+}
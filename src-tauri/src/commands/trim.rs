@@ -3,16 +3,41 @@
 /// 支持单片段和多片段裁剪，可选精确切割（重编码）或快速切割（流复制）。
 /// 多片段裁剪时先分别切割各片段到临时文件，再合并为最终输出
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tauri::ipc::Channel;
 
-use crate::engine::builder::{build_trim_command, build_trim_segment_command, FfmpegCommand};
-use crate::engine::process::{run_ffmpeg, run_ffmpeg_quiet, run_ffprobe};
-use crate::models::media::FfprobeOutput;
-use crate::models::preset::TrimParams;
+use crate::commands::settings::load_settings;
+use crate::engine::analysis::{
+    first_keyframe_at_or_after, leading_black_end, parse_blackdetect, parse_keyframe_times,
+};
+use crate::engine::builder::{
+    build_blackdetect_command, build_trim_command, build_trim_segment_command, FfmpegCommand,
+};
+use crate::engine::process::{
+    probe_duration, run_ffmpeg, run_ffmpeg_capture, run_ffmpeg_quiet, run_ffprobe_with_args,
+    send_phase, ANALYZING_INPUT_PHASE,
+};
+use crate::models::preset::{
+    TimeSegment, TrimLeadingBlackParams, TrimLeadingBlackResult, TrimParams,
+};
 use crate::models::task::{ProgressUpdate, TaskEvent};
-use crate::utils::path::{cleanup_temp_file, file_extension, get_file_size, temp_file_path};
+use crate::utils::path::{
+    cleanup_temp_file, ensure_file_settled, file_extension, get_file_size, temp_file_path,
+};
+use crate::models::error::CommandError;
+use crate::utils::segment::{validate_segments, SegmentPolicy};
+
+/// 默认黑场像素亮度阈值（blackdetect pix_th）
+const DEFAULT_BLACK_THRESHOLD: f64 = 0.10;
+/// 默认最短黑场时长（秒）
+const DEFAULT_MIN_BLACK_DURATION: f64 = 0.5;
+/// 片头黑场分析的最长时长（秒）
+const LEADING_BLACK_SCAN_SECONDS: f64 = 60.0;
+/// 黑场结束后查找关键帧的范围（秒）
+const KEYFRAME_SEARCH_SECONDS: u32 = 30;
+/// 多片段合并时合并步骤的阶段标签
+const MERGING_PHASE: &str = "Merging segments";
 
 /// 执行视频裁剪
 ///
@@ -28,19 +53,28 @@ use crate::utils::path::{cleanup_temp_file, file_extension, get_file_size, temp_
 ///
 /// # 返回
 /// - `Ok(String)` - 任务 ID
-/// - `Err(String)` - 错误描述
+/// - `Err(CommandError)` - 片段不合法时为结构化的片段错误（含出错片段下标），其余为错误描述
 #[tauri::command]
 pub async fn trim_video(
     app: tauri::AppHandle,
     params: TrimParams,
     on_progress: Channel<TaskEvent>,
-) -> Result<String, String> {
-    // 验证至少有一个裁剪片段
-    if params.segments.is_empty() {
-        return Err("至少需要一个裁剪片段".to_string());
-    }
+) -> Result<String, CommandError> {
+    let settings = load_settings(&app).await;
+
+    // 正在录制的文件先等待写入完成
+    let max_wait = Duration::from_secs(settings.growing_file_wait_seconds.into());
+    ensure_file_settled(&params.input_path, max_wait).await?;
 
     let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, media_duration) = prepare_trim(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
 
     // === 单片段：直接裁剪，带实时进度 ===
     if params.segments.len() == 1 {
@@ -48,15 +82,13 @@ pub async fn trim_video(
         let segment_duration = segment.end - segment.start;
 
         // 精确切割用片段时长做进度基准；快速切割用整个视频时长（copy 进度不太准）
-        let total_duration = if params.precise_cut {
+        let total_duration = if params.reencodes() || media_duration <= 0.0 {
             segment_duration
         } else {
-            get_duration(&app, &params.input_path)
-                .await
-                .unwrap_or(segment_duration)
+            media_duration
         };
 
-        let args = build_trim_command(&params);
+        let args = build_trim_command(&params, settings.ffmpeg_threads);
 
         let result = run_ffmpeg(
             &app,
@@ -73,17 +105,182 @@ pub async fn trim_video(
             queue.cleanup(&task_id);
         }
 
-        if result.error.is_some() {
-            Err(result.error.unwrap())
-        } else {
-            Ok(task_id)
+        match result.error {
+            Some(error) => Err(error.into()),
+            None => Ok(task_id),
         }
     } else if params.merge_segments {
         // === 多片段 + 合并：先分别切割，再 concat 合并 ===
-        trim_multi_merge(&app, &task_id, &params, &on_progress).await
+        let threads = settings.ffmpeg_threads;
+        Ok(trim_multi_merge(&app, &task_id, &params, threads, &on_progress).await?)
     } else {
         // === 多片段 + 不合并：各片段输出为独立文件 ===
-        trim_multi_separate(&app, &task_id, &params, &on_progress).await
+        let threads = settings.ffmpeg_threads;
+        Ok(trim_multi_separate(&app, &task_id, &params, threads, &on_progress).await?)
+    }
+}
+
+/// 准备视频裁剪任务：填充默认参数、校验片段并把超出时长的终点截断到视频末尾
+///
+/// 实际执行（trim_video）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 裁剪参数
+/// - `notify` - 自动调整参数时的提示回调（实际执行时推送 Notice 事件）
+///
+/// # 返回
+/// - `Ok((TrimParams, f64))` - 调整后的参数和源文件时长（秒，探测失败时为 0）
+/// - `Err(CommandError)` - 片段不合法时为结构化的片段错误
+pub(crate) async fn prepare_trim(
+    app: &tauri::AppHandle,
+    mut params: TrimParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<(TrimParams, f64), CommandError> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    // 校验片段列表，超出时长的终点截断到视频末尾
+    let media_duration = probe_duration(app, &params.input_path, settings.probe_timeout_ms)
+        .await
+        .unwrap_or(0.0);
+    let segments = validate_segments(&params.segments, media_duration, SegmentPolicy::Clamp)?;
+
+    if segments
+        .iter()
+        .zip(&params.segments)
+        .any(|(a, b)| a.start != b.start || a.end != b.end)
+    {
+        notify(format!(
+            "Segments extending past the end of the video were clamped to {:.3}s",
+            media_duration
+        ));
+    }
+    params.segments = segments;
+
+    Ok((params, media_duration))
+}
+
+/// 去除片头黑场
+///
+/// 1. 用 blackdetect 分析视频开头，找到从片头开始的黑场结束位置
+/// 2. 快速切割时取黑场结束后的第一个关键帧作为起点（流复制只能从关键帧开始）；
+///    精确切割时直接从黑场结束位置重编码
+/// 3. 从起点裁剪到视频末尾
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 去除片头黑场参数（阈值、最短黑场时长、切割方式）
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(TrimLeadingBlackResult)` - 任务 ID 和实际去除的时长
+/// - `Err(String)` - 片头没有黑场、整段视频都是黑场或执行失败
+#[tauri::command]
+pub async fn trim_leading_black(
+    app: tauri::AppHandle,
+    mut params: TrimLeadingBlackParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<TrimLeadingBlackResult, String> {
+    let settings = load_settings(&app).await;
+    params.audio_bitrate.get_or_insert_with(|| settings.default_audio_bitrate.clone());
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    let threshold = params.black_threshold.unwrap_or(DEFAULT_BLACK_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err("Black threshold must be between 0 and 1".to_string());
+    }
+    let min_black = params.min_black_duration.unwrap_or(DEFAULT_MIN_BLACK_DURATION);
+    if !min_black.is_finite() || min_black <= 0.0 {
+        return Err("Minimum black duration must be greater than 0".to_string());
+    }
+
+    let max_wait = Duration::from_secs(settings.growing_file_wait_seconds.into());
+    ensure_file_settled(&params.input_path, max_wait).await?;
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let media_duration = probe_duration(&app, &params.input_path, settings.probe_timeout_ms).await?;
+
+    // 只分析开头一段，片中的暗场不影响结果
+    let args = build_blackdetect_command(
+        &params.input_path,
+        threshold,
+        min_black,
+        Some(LEADING_BLACK_SCAN_SECONDS),
+    );
+    let stderr = run_ffmpeg_capture(&app, args).await?;
+    let black_end = leading_black_end(&parse_blackdetect(&stderr))
+        .ok_or_else(|| "No leading black frames detected".to_string())?;
+
+    let start = if params.precise_cut {
+        black_end
+    } else {
+        let keyframe =
+            find_keyframe_after(&app, &params.input_path, black_end, settings.probe_timeout_ms);
+        match keyframe.await {
+            Some(keyframe) => keyframe,
+            None => {
+                let _ = on_progress.send(TaskEvent::Notice {
+                    task_id: task_id.clone(),
+                    message: "No keyframe found after the black frames; cutting at the nearest earlier keyframe".to_string(),
+                });
+                black_end
+            }
+        }
+    };
+    if media_duration > 0.0 && start >= media_duration {
+        return Err("The whole video is black".to_string());
+    }
+
+    let trim_params = TrimParams {
+        input_path: params.input_path.clone(),
+        output_path: params.output_path.clone(),
+        segments: vec![TimeSegment {
+            start,
+            end: media_duration,
+        }],
+        precise_cut: params.precise_cut,
+        merge_segments: false,
+        audio_bitrate: params.audio_bitrate.clone(),
+        quality: params.quality,
+        preset: params.preset.clone(),
+        accuracy: None,
+    };
+    let args = build_trim_command(&trim_params, settings.ffmpeg_threads);
+
+    // 与 trim_video 单片段一致：快速切割用整个视频时长做进度基准
+    let total_duration = if params.precise_cut {
+        media_duration - start
+    } else {
+        media_duration
+    };
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        total_duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await?;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(TrimLeadingBlackResult {
+            task_id,
+            trimmed_seconds: start,
+        })
     }
 }
 
@@ -99,6 +296,7 @@ async fn trim_multi_merge(
     app: &tauri::AppHandle,
     task_id: &str,
     params: &TrimParams,
+    thread_count: Option<u32>,
     on_progress: &Channel<TaskEvent>,
 ) -> Result<String, String> {
     let start_time = Instant::now();
@@ -113,6 +311,8 @@ async fn trim_multi_merge(
 
     let mut temp_files: Vec<String> = Vec::new();
     let mut processed_duration: f64 = 0.0;
+    let segment_count = params.segments.len();
+    let _ = on_progress.send(segment_progress(task_id, 0.0, 0.0, cutting_phase(0, segment_count)));
 
     // 步骤 1：逐个片段裁剪到临时文件
     for (i, segment) in params.segments.iter().enumerate() {
@@ -120,12 +320,12 @@ async fn trim_multi_merge(
         let segment_duration = segment.end - segment.start;
 
         let args = build_trim_segment_command(
-            &params.input_path,
+            params,
             &temp_path,
             segment.start,
             segment.end,
-            params.precise_cut,
             false, // 中间步骤不需要 -progress
+            thread_count,
         );
 
         run_ffmpeg_quiet(app, args).await.map_err(|e| {
@@ -139,18 +339,14 @@ async fn trim_multi_merge(
         temp_files.push(temp_path);
         processed_duration += segment_duration;
 
-        // 推送整体进度（片段切割占 0-90%，合并占 90-100%）
+        // 推送整体进度（片段切割占 0-90%，合并占 90-100%），阶段标签为接下来的步骤
         let percent = (processed_duration / total_segments_duration * 90.0).min(90.0);
-        let _ = on_progress.send(TaskEvent::Progress(ProgressUpdate {
-            task_id: task_id.to_string(),
-            percent,
-            speed: 0.0,
-            current_time: processed_duration,
-            eta: 0.0,
-            output_size: 0,
-            frame: 0,
-            fps: 0.0,
-        }));
+        let phase = if i + 1 < segment_count {
+            cutting_phase(i + 1, segment_count)
+        } else {
+            MERGING_PHASE.to_string()
+        };
+        let _ = on_progress.send(segment_progress(task_id, percent, processed_duration, phase));
     }
 
     // 步骤 2：创建 concat demuxer 文件列表
@@ -164,15 +360,7 @@ async fn trim_multi_merge(
         .map_err(|e| format!("创建合并文件列表失败: {}", e))?;
 
     // 步骤 3：使用 concat demuxer 合并（-c copy，极快）
-    // -f concat 和 -safe 0 必须在 -i 之前，告诉 ffmpeg 输入格式为 concat demuxer
-    let concat_args = FfmpegCommand::new()
-        .pre_args_pair("-f", "concat")
-        .pre_args_pair("-safe", "0")
-        .input(&concat_file)
-        .video_codec("copy")
-        .audio_codec("copy")
-        .output(&params.output_path)
-        .build();
+    let concat_args = build_segment_concat_command(&concat_file, &params.output_path);
 
     let concat_result = run_ffmpeg_quiet(app, concat_args).await;
 
@@ -194,6 +382,9 @@ async fn trim_multi_merge(
         output_path: params.output_path.clone(),
         output_size,
         elapsed,
+        verification: Vec::new(),
+        note: None,
+        container_change: None,
     });
 
     {
@@ -212,6 +403,7 @@ async fn trim_multi_separate(
     app: &tauri::AppHandle,
     task_id: &str,
     params: &TrimParams,
+    thread_count: Option<u32>,
     on_progress: &Channel<TaskEvent>,
 ) -> Result<String, String> {
     let start_time = Instant::now();
@@ -225,18 +417,20 @@ async fn trim_multi_separate(
 
     let mut processed_duration: f64 = 0.0;
     let mut last_output_path = String::new();
+    let segment_count = params.segments.len();
+    let _ = on_progress.send(segment_progress(task_id, 0.0, 0.0, cutting_phase(0, segment_count)));
 
     for (i, segment) in params.segments.iter().enumerate() {
         let output_path = generate_indexed_output(&params.output_path, i + 1);
         let segment_duration = segment.end - segment.start;
 
         let args = build_trim_segment_command(
-            &params.input_path,
+            params,
             &output_path,
             segment.start,
             segment.end,
-            params.precise_cut,
             false, // 中间步骤不需要 -progress
+            thread_count,
         );
 
         run_ffmpeg_quiet(app, args)
@@ -245,16 +439,8 @@ async fn trim_multi_separate(
 
         processed_duration += segment_duration;
         let percent = (processed_duration / total_segments_duration * 100.0).min(100.0);
-        let _ = on_progress.send(TaskEvent::Progress(ProgressUpdate {
-            task_id: task_id.to_string(),
-            percent,
-            speed: 0.0,
-            current_time: processed_duration,
-            eta: 0.0,
-            output_size: 0,
-            frame: 0,
-            fps: 0.0,
-        }));
+        let phase = cutting_phase((i + 1).min(segment_count - 1), segment_count);
+        let _ = on_progress.send(segment_progress(task_id, percent, processed_duration, phase));
 
         last_output_path = output_path;
     }
@@ -268,6 +454,9 @@ async fn trim_multi_separate(
         output_path: last_output_path,
         output_size,
         elapsed,
+        verification: Vec::new(),
+        note: None,
+        container_change: None,
     });
 
     {
@@ -278,6 +467,47 @@ async fn trim_multi_separate(
     Ok(task_id.to_string())
 }
 
+/// 构建把已切割片段按文件列表无损拼接的命令
+///
+/// -f concat 和 -safe 0 必须在 -i 之前，告诉 ffmpeg 输入格式为 concat demuxer
+///
+/// # 参数
+/// - `concat_file` - concat demuxer 文件列表路径
+/// - `output_path` - 输出文件路径
+///
+/// # 返回
+/// ffmpeg 参数列表
+pub(crate) fn build_segment_concat_command(concat_file: &str, output_path: &str) -> Vec<String> {
+    FfmpegCommand::new()
+        .pre_args_pair("-f", "concat")
+        .pre_args_pair("-safe", "0")
+        .input(concat_file)
+        .video_codec("copy")
+        .audio_codec("copy")
+        .output(output_path)
+        .build()
+}
+
+/// 多片段裁剪中切割第 index+1 段的阶段标签（如 "Cutting segment 2/5"）
+fn cutting_phase(index: usize, count: usize) -> String {
+    format!("Cutting segment {}/{}", index + 1, count)
+}
+
+/// 构造多片段裁剪的整体进度事件（只有百分比、已处理时长和阶段标签）
+fn segment_progress(task_id: &str, percent: f64, current_time: f64, phase: String) -> TaskEvent {
+    TaskEvent::Progress(ProgressUpdate {
+        task_id: task_id.to_string(),
+        percent,
+        speed: 0.0,
+        current_time,
+        eta: 0.0,
+        output_size: 0,
+        frame: 0,
+        fps: 0.0,
+        phase: Some(phase),
+    })
+}
+
 /// 生成带序号后缀的输出文件路径
 ///
 /// 例如：`/path/video_output.mp4` + index 1 → `/path/video_output_1.mp4`
@@ -288,7 +518,7 @@ async fn trim_multi_separate(
 ///
 /// # 返回
 /// 带序号的输出路径
-fn generate_indexed_output(output_path: &str, index: usize) -> String {
+pub(crate) fn generate_indexed_output(output_path: &str, index: usize) -> String {
     let path = std::path::Path::new(output_path);
     let stem = path
         .file_stem()
@@ -305,15 +535,30 @@ fn generate_indexed_output(output_path: &str, index: usize) -> String {
         .to_string()
 }
 
-/// 从 ffprobe 获取视频时长
-async fn get_duration(app: &tauri::AppHandle, file_path: &str) -> Result<f64, String> {
-    let json_str = run_ffprobe(app, file_path).await?;
-    let output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("解析 ffprobe 输出失败: {}", e))?;
-    Ok(output
-        .format
-        .as_ref()
-        .and_then(|f| f.duration.as_ref())
-        .and_then(|d| d.parse::<f64>().ok())
-        .unwrap_or(0.0))
+/// 查找不早于指定时间的第一个视频关键帧
+///
+/// 只读取目标时间之后 KEYFRAME_SEARCH_SECONDS 秒内的关键帧，查询失败时返回 None
+async fn find_keyframe_after(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    time: f64,
+    timeout_ms: u64,
+) -> Option<f64> {
+    let args = vec![
+        "-v".to_string(),
+        "error".to_string(),
+        "-select_streams".to_string(),
+        "v:0".to_string(),
+        "-skip_frame".to_string(),
+        "nokey".to_string(),
+        "-show_entries".to_string(),
+        "frame=pts_time".to_string(),
+        "-of".to_string(),
+        "csv=p=0".to_string(),
+        "-read_intervals".to_string(),
+        format!("{:.3}%+{}", time, KEYFRAME_SEARCH_SECONDS),
+        file_path.to_string(),
+    ];
+    let output = run_ffprobe_with_args(app, args, timeout_ms).await.ok()?;
+    first_keyframe_at_or_after(&parse_keyframe_times(&output), time)
 }
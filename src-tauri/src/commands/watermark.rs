@@ -7,10 +7,11 @@
 
 use tauri::ipc::Channel;
 
-use crate::engine::builder::build_watermark_command;
-use crate::engine::process::{run_ffmpeg, run_ffprobe};
-use crate::models::media::FfprobeOutput;
-use crate::models::preset::{WatermarkParams, WatermarkType};
+use crate::commands::deinterlace::apply_auto_deinterlace;
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{build_remove_logo_command, build_watermark_command};
+use crate::engine::process::{probe_media, run_ffmpeg, send_phase, ANALYZING_INPUT_PHASE};
+use crate::models::preset::{RemoveLogoParams, WatermarkParams, WatermarkType};
 use crate::models::task::TaskEvent;
 use crate::utils::text_image;
 
@@ -33,15 +34,22 @@ pub async fn add_watermark(
 ) -> Result<String, String> {
     let task_id = uuid::Uuid::new_v4().to_string();
 
-    // Get input file duration
-    let duration = get_duration(&app, &params.input_path).await?;
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_watermark(&app, params, &mut |message| {
+        let _ = on_progress.send(TaskEvent::Notice {
+            task_id: task_id.clone(),
+            message,
+        });
+    })
+    .await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
 
     // For text watermarks: render text to a temporary PNG image,
     // then convert to image watermark params for the overlay approach
     let (effective_params, _text_image_path) = prepare_watermark_params(params)?;
 
     // Build ffmpeg command
-    let args = build_watermark_command(&effective_params);
+    let args = build_watermark_command(&effective_params, thread_count);
 
     // Execute ffmpeg
     let result = run_ffmpeg(
@@ -71,6 +79,132 @@ pub async fn add_watermark(
     }
 }
 
+/// Prepare a watermark task: fill in encoding defaults, probe the input and
+/// enable deinterlacing for interlaced sources
+///
+/// Shared by the actual run (add_watermark) and the command preview (preview_command)
+///
+/// @param app - Tauri AppHandle
+/// @param params - Watermark parameters
+/// @param notify - Called with a notice whenever a parameter is adjusted automatically
+/// @returns Ok((WatermarkParams, f64)) adjusted params and input duration in seconds,
+///          or Err(String) error description
+pub(crate) async fn prepare_watermark(
+    app: &tauri::AppHandle,
+    mut params: WatermarkParams,
+    notify: &mut (dyn FnMut(String) + Send),
+) -> Result<(WatermarkParams, f64), String> {
+    // Fall back to the settings defaults for unspecified encoding params
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    // Get input file duration and field order
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    apply_auto_deinterlace(&mut params.deinterlace, media_info.video_streams.first(), notify);
+
+    Ok((params, media_info.duration))
+}
+
+/// Remove an existing logo or channel bug
+///
+/// The inverse of add_watermark: blurs out a rectangular region with the
+/// delogo filter (interpolated from the surrounding pixels) and re-encodes
+/// with libx264. The region is in display orientation and must lie inside the frame.
+///
+/// @param app - Tauri AppHandle
+/// @param params - Logo region and encoding parameters
+/// @param on_progress - Progress push Channel
+/// @returns Ok(String) task ID, or Err(String) error description
+#[tauri::command]
+pub async fn remove_logo(
+    app: tauri::AppHandle,
+    params: RemoveLogoParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, duration) = prepare_remove_logo(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_remove_logo_command(&params, thread_count);
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await?;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// Prepare a logo removal task: fill in encoding defaults and check the region
+/// against the displayed frame size
+///
+/// Shared by the actual run (remove_logo) and the command preview (preview_command)
+///
+/// @param app - Tauri AppHandle
+/// @param params - Logo region and encoding parameters
+/// @returns Ok((RemoveLogoParams, f64)) params with defaults and input duration in seconds,
+///          or Err(String) error description
+pub(crate) async fn prepare_remove_logo(
+    app: &tauri::AppHandle,
+    mut params: RemoveLogoParams,
+) -> Result<(RemoveLogoParams, f64), String> {
+    let settings = load_settings(app).await;
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    // Probe the frame size to validate the region
+    let media_info = probe_media(app, &params.input_path, settings.probe_timeout_ms).await?;
+    let stream = media_info
+        .video_streams
+        .first()
+        .ok_or_else(|| "The input file has no video stream".to_string())?;
+    // ffmpeg auto-rotates before filtering, so the region refers to the displayed frame
+    let (frame_width, frame_height) = if stream.rotation % 180 == 90 {
+        (stream.height, stream.width)
+    } else {
+        (stream.width, stream.height)
+    };
+    validate_logo_region(&params, frame_width, frame_height)?;
+
+    Ok((params, media_info.duration))
+}
+
+/// Check that the logo region is non-empty and lies inside the frame
+fn validate_logo_region(
+    params: &RemoveLogoParams,
+    frame_width: u32,
+    frame_height: u32,
+) -> Result<(), String> {
+    if params.width == 0 || params.height == 0 {
+        return Err("The logo region must have a non-zero width and height".to_string());
+    }
+    let right = params.x.saturating_add(params.width);
+    let bottom = params.y.saturating_add(params.height);
+    if right > frame_width || bottom > frame_height {
+        return Err(format!(
+            "The logo region {}x{} at ({}, {}) extends outside the {}x{} frame",
+            params.width, params.height, params.x, params.y, frame_width, frame_height
+        ));
+    }
+    Ok(())
+}
+
 /// Prepare effective watermark params
 ///
 /// For image watermarks, returns params unchanged.
@@ -79,7 +213,7 @@ pub async fn add_watermark(
 ///
 /// @param params - Original watermark parameters from frontend
 /// @returns (effective params, optional temp image path for cleanup)
-fn prepare_watermark_params(
+pub(crate) fn prepare_watermark_params(
     params: WatermarkParams,
 ) -> Result<(WatermarkParams, Option<std::path::PathBuf>), String> {
     match params.watermark_type {
@@ -112,16 +246,3 @@ fn prepare_watermark_params(
         }
     }
 }
-
-/// Get video duration from ffprobe
-async fn get_duration(app: &tauri::AppHandle, file_path: &str) -> Result<f64, String> {
-    let json_str = run_ffprobe(app, file_path).await?;
-    let output: FfprobeOutput = serde_json::from_str(&json_str)
-        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
-    Ok(output
-        .format
-        .as_ref()
-        .and_then(|f| f.duration.as_ref())
-        .and_then(|d| d.parse::<f64>().ok())
-        .unwrap_or(0.0))
-}
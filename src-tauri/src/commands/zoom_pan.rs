@@ -0,0 +1,217 @@
+/// 缩放平移（Ken Burns）command
+///
+/// 在片段时长内从起始区域平滑推拉/平移到结束区域，输出分辨率保持为源画面尺寸；
+/// 另提供起始/结束画面的单帧预览，便于确认取景
+
+use tauri::ipc::Channel;
+
+use crate::commands::settings::load_settings;
+use crate::engine::builder::{build_zoom_pan_command, build_zoom_pan_preview_command};
+use crate::engine::preview::register_previews;
+use crate::engine::process::{
+    probe_media, run_ffmpeg, run_ffmpeg_quiet, send_phase, ANALYZING_INPUT_PHASE,
+};
+use crate::models::preset::{ZoomPanParams, ZoomRect};
+use crate::models::task::TaskEvent;
+use crate::utils::path::{cleanup_temp_file, is_image_file, temp_file_path};
+
+/// 图片输入未指定时长时的默认时长（秒）
+const DEFAULT_IMAGE_DURATION: f64 = 5.0;
+
+/// 区域允许的最小边长（占画面的比例），过小的区域放大后只剩马赛克
+const MIN_RECT_FRACTION: f64 = 0.05;
+
+/// 缩放平移的源信息（显示方向的画面尺寸、片段时长、是否图片）
+pub(crate) struct ZoomPanSource {
+    pub width: u32,
+    pub height: u32,
+    pub duration: f64,
+    pub is_image: bool,
+}
+
+/// 执行缩放平移
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 缩放平移参数
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok(String)` - 任务 ID
+/// - `Err(String)` - 区域超出画面、时间范围非法、输入没有视频流或执行失败
+#[tauri::command]
+pub async fn zoom_pan(
+    app: tauri::AppHandle,
+    params: ZoomPanParams,
+    on_progress: Channel<TaskEvent>,
+) -> Result<String, String> {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    send_phase(&on_progress, &task_id, ANALYZING_INPUT_PHASE);
+    let (params, source) = prepare_zoom_pan(&app, params).await?;
+    let thread_count = load_settings(&app).await.ffmpeg_threads;
+
+    let args = build_zoom_pan_command(
+        &params,
+        source.width,
+        source.height,
+        source.duration,
+        source.is_image,
+        thread_count,
+    );
+
+    let result = run_ffmpeg(
+        &app,
+        &task_id,
+        args,
+        source.duration,
+        &params.output_path,
+        &on_progress,
+    )
+    .await;
+
+    {
+        let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.cleanup(&task_id);
+    }
+
+    let result = result?;
+    if let Some(error) = result.error {
+        Err(error)
+    } else {
+        Ok(task_id)
+    }
+}
+
+/// 生成缩放平移起始或结束画面的单帧预览
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 缩放平移参数（output_path 不使用）
+/// - `at_end` - true 时预览结束区域，否则预览起始区域
+///
+/// # 返回
+/// - `Ok(String)` - 预览令牌（通过 get_preview 读取 PNG）
+/// - `Err(String)` - 参数非法或 ffmpeg 执行失败
+#[tauri::command]
+pub async fn zoom_pan_preview(
+    app: tauri::AppHandle,
+    params: ZoomPanParams,
+    at_end: bool,
+) -> Result<String, String> {
+    let settings = load_settings(&app).await;
+    let source = resolve_source(&app, &params, settings.probe_timeout_ms).await?;
+
+    let output_path = temp_file_path("zoom_pan", "png")?;
+    let args = build_zoom_pan_preview_command(
+        &params,
+        source.width,
+        source.height,
+        at_end,
+        source.duration,
+        source.is_image,
+        &output_path,
+    );
+    if let Err(e) = run_ffmpeg_quiet(&app, args).await {
+        cleanup_temp_file(&output_path);
+        return Err(e);
+    }
+
+    Ok(register_previews("zoom_pan", vec![output_path]).await.remove(0))
+}
+
+/// 准备缩放平移任务：校验区域、探测源画面并填充默认参数
+///
+/// 实际执行（zoom_pan）和命令预览（preview_command）共用
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `params` - 缩放平移参数
+///
+/// # 返回
+/// - `Ok((ZoomPanParams, ZoomPanSource))` - 填充默认值后的参数和源信息
+/// - `Err(String)` - 区域超出画面、时间范围非法或输入没有视频流
+pub(crate) async fn prepare_zoom_pan(
+    app: &tauri::AppHandle,
+    mut params: ZoomPanParams,
+) -> Result<(ZoomPanParams, ZoomPanSource), String> {
+    // 未指定的编码参数使用设置中的默认值
+    let settings = load_settings(app).await;
+    let source = resolve_source(app, &params, settings.probe_timeout_ms).await?;
+
+    params.quality.get_or_insert(settings.default_quality);
+    params.preset.get_or_insert_with(|| settings.default_preset.clone());
+
+    Ok((params, source))
+}
+
+/// 校验区域并探测源画面尺寸与片段时长
+///
+/// 输出尺寸取显示方向的源画面尺寸并向下取偶；
+/// 视频的片段时长为显式指定的时长，否则为起点到结尾
+async fn resolve_source(
+    app: &tauri::AppHandle,
+    params: &ZoomPanParams,
+    timeout_ms: u64,
+) -> Result<ZoomPanSource, String> {
+    validate_rect("Start", &params.start_rect)?;
+    validate_rect("End", &params.end_rect)?;
+
+    let media_info = probe_media(app, &params.input_path, timeout_ms).await?;
+    let stream = media_info
+        .video_streams
+        .first()
+        .ok_or_else(|| "The input file has no video stream".to_string())?;
+    let (width, height) = if stream.rotation % 180 == 90 {
+        (stream.height, stream.width)
+    } else {
+        (stream.width, stream.height)
+    };
+
+    let is_image = is_image_file(&params.input_path);
+    let duration = if is_image {
+        params.duration.unwrap_or(DEFAULT_IMAGE_DURATION)
+    } else {
+        let start = params.start_time.unwrap_or(0.0);
+        if start < 0.0 || start >= media_info.duration {
+            return Err(format!(
+                "Start time {:.2}s is outside the {:.2}s video",
+                start, media_info.duration
+            ));
+        }
+        let remaining = media_info.duration - start;
+        params.duration.map_or(remaining, |d| d.min(remaining))
+    };
+    if duration <= 0.0 {
+        return Err("The zoom/pan duration must be greater than 0".to_string());
+    }
+
+    Ok(ZoomPanSource {
+        width: width / 2 * 2,
+        height: height / 2 * 2,
+        duration,
+        is_image,
+    })
+}
+
+/// 校验区域在画面内且不过小
+fn validate_rect(label: &str, rect: &ZoomRect) -> Result<(), String> {
+    if rect.width < MIN_RECT_FRACTION || rect.height < MIN_RECT_FRACTION {
+        return Err(format!(
+            "{} rectangle must cover at least {:.0}% of the frame in each direction",
+            label,
+            MIN_RECT_FRACTION * 100.0
+        ));
+    }
+    // 允许浮点误差
+    let inside = rect.x >= 0.0
+        && rect.y >= 0.0
+        && rect.x + rect.width <= 1.0 + 1e-6
+        && rect.y + rect.height <= 1.0 + 1e-6;
+    if !inside {
+        return Err(format!(
+            "{} rectangle ({:.3}, {:.3}, {:.3}x{:.3}) extends outside the frame",
+            label, rect.x, rect.y, rect.width, rect.height
+        ));
+    }
+    Ok(())
+}
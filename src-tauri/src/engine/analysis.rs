@@ -0,0 +1,134 @@
+/// 分析滤镜输出解析
+///
+/// 解析 blackdetect、freezedetect 等分析滤镜写入 stderr 的日志行，以及 ffprobe 列出的关键帧时间，
+/// 供"去除片头黑场"等一键整理功能计算切点，以及画面缺陷检查生成报告
+
+/// 片头黑场判定的起点容差（秒）：首个黑场起点不晚于此值才视为从片头开始
+const LEADING_TOLERANCE: f64 = 0.05;
+
+/// 一段黑场区间
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlackInterval {
+    /// 黑场开始时间（秒）
+    pub start: f64,
+    /// 黑场结束时间（秒）
+    pub end: f64,
+}
+
+/// 解析 blackdetect 滤镜的 stderr 输出
+///
+/// 日志行格式：`[blackdetect @ 0x...] black_start:0 black_end:1.234 black_duration:1.234`
+///
+/// # 参数
+/// - `stderr` - ffmpeg stderr 输出
+///
+/// # 返回
+/// 按出现顺序排列的黑场区间
+pub fn parse_blackdetect(stderr: &str) -> Vec<BlackInterval> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("black_start:"))
+        .filter_map(|line| {
+            let start = field_value(line, "black_start:")?;
+            let end = field_value(line, "black_end:")?;
+            (end > start).then_some(BlackInterval { start, end })
+        })
+        .collect()
+}
+
+/// 一段静止画面区间
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FreezeInterval {
+    /// 静止开始时间（秒）
+    pub start: f64,
+    /// 静止结束时间（秒）
+    pub end: f64,
+}
+
+/// 解析 freezedetect 滤镜的 stderr 输出
+///
+/// 日志行格式：`[freezedetect @ 0x...] lavfi.freezedetect.freeze_start: 5.005`，
+/// 之后依次为 freeze_duration 和 freeze_end。静止持续到文件末尾时滤镜不输出 freeze_end，
+/// 以媒体时长作为结束时间
+///
+/// # 参数
+/// - `stderr` - ffmpeg stderr 输出
+/// - `media_duration` - 媒体时长（秒），用于补齐未结束的区间
+///
+/// # 返回
+/// 按出现顺序排列的静止区间
+pub fn parse_freezedetect(stderr: &str, media_duration: f64) -> Vec<FreezeInterval> {
+    let mut intervals = Vec::new();
+    let mut open_start: Option<f64> = None;
+    for line in stderr.lines() {
+        if let Some(start) = field_value(line, "freeze_start:") {
+            open_start = Some(start);
+        } else if let Some(end) = field_value(line, "freeze_end:") {
+            if let Some(start) = open_start.take().filter(|start| end > *start) {
+                intervals.push(FreezeInterval { start, end });
+            }
+        }
+    }
+    if let Some(start) = open_start.filter(|start| media_duration > *start) {
+        intervals.push(FreezeInterval {
+            start,
+            end: media_duration,
+        });
+    }
+    intervals
+}
+
+/// 计算片头黑场的结束时间
+///
+/// 只有从片头开始的第一段黑场才算数，片中的暗场不影响结果
+///
+/// # 参数
+/// - `intervals` - parse_blackdetect 解析出的黑场区间
+///
+/// # 返回
+/// 片头黑场结束时间（秒），片头不是黑场时返回 None
+pub fn leading_black_end(intervals: &[BlackInterval]) -> Option<f64> {
+    intervals
+        .first()
+        .filter(|interval| interval.start <= LEADING_TOLERANCE)
+        .map(|interval| interval.end)
+}
+
+/// 解析 ffprobe 以 csv 输出的关键帧时间列表
+///
+/// 每行一个 pts_time（`-show_entries frame=pts_time -of csv=p=0`），无法解析的行被忽略
+///
+/// # 参数
+/// - `output` - ffprobe stdout 输出
+///
+/// # 返回
+/// 升序排列的关键帧时间（秒）
+pub fn parse_keyframe_times(output: &str) -> Vec<f64> {
+    let mut times: Vec<f64> = output
+        .lines()
+        .filter_map(|line| line.trim().trim_end_matches(',').parse::<f64>().ok())
+        .filter(|t| t.is_finite())
+        .collect();
+    times.sort_by(|a, b| a.total_cmp(b));
+    times
+}
+
+/// 取不早于指定时间的第一个关键帧
+///
+/// 流复制切割只能从关键帧开始，选不早于黑场结束的关键帧可保证输出不含黑场
+///
+/// # 参数
+/// - `keyframes` - 升序排列的关键帧时间
+/// - `time` - 目标时间（秒）
+///
+/// # 返回
+/// 关键帧时间，目标时间之后没有关键帧时返回 None
+pub fn first_keyframe_at_or_after(keyframes: &[f64], time: f64) -> Option<f64> {
+    keyframes.iter().copied().find(|t| *t >= time - 0.001)
+}
+
+/// 从日志行中取 `key` 之后的数值
+fn field_value(line: &str, key: &str) -> Option<f64> {
+    let rest = &line[line.find(key)? + key.len()..];
+    rest.split_whitespace().next()?.parse::<f64>().ok()
+}
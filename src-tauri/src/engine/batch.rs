@@ -0,0 +1,179 @@
+/// 批量任务编排
+///
+/// 同时提交一组子任务，按结束顺序汇总推送 BatchProgress。
+/// 子任务的执行方式和事件出口由调用方传入，不依赖 Tauri
+
+use std::future::Future;
+
+use tokio::task::JoinSet;
+
+use crate::models::task::TaskEvent;
+
+/// 同时执行一组子任务并推送汇总进度
+///
+/// 每个子任务分配独立的 task_id；启动前失败（`run_job` 返回 Err）的子任务没有自己的事件，
+/// 补发 Failed 让前端结束该子任务。每个子任务结束（无论成败）时推送一次 BatchProgress
+///
+/// # 参数
+/// - `jobs` - 每个子任务的参数
+/// - `run_job` - 执行单个子任务（参数、task_id），Err 表示启动前失败
+/// - `emit` - 事件出口（全部子任务共用）
+///
+/// # 返回
+/// 与 jobs 顺序一一对应的子任务 ID
+pub async fn run_batch_jobs<J, F, Fut, E>(jobs: Vec<J>, run_job: F, emit: E) -> Vec<String>
+where
+    F: Fn(J, String) -> Fut,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+    E: Fn(TaskEvent) + Clone + Send + 'static,
+{
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let total = jobs.len();
+    let task_ids: Vec<String> = jobs
+        .iter()
+        .map(|_| uuid::Uuid::new_v4().to_string())
+        .collect();
+
+    let mut running = JoinSet::new();
+    for (params, task_id) in jobs.into_iter().zip(task_ids.iter().cloned()) {
+        let job = run_job(params, task_id.clone());
+        let emit = emit.clone();
+        running.spawn(async move {
+            if let Err(error) = job.await {
+                emit(TaskEvent::Failed { task_id, error });
+            }
+        });
+    }
+
+    let mut completed = 0;
+    while let Some(joined) = running.join_next().await {
+        if let Err(e) = joined {
+            log::error!("批量子任务异常退出: {}", e);
+        }
+        completed += 1;
+        emit(TaskEvent::BatchProgress {
+            batch_id: batch_id.clone(),
+            completed,
+            total,
+        });
+    }
+
+    task_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn three_jobs_report_batch_progress_and_pre_start_failures() {
+        let events: Arc<Mutex<Vec<TaskEvent>>> = Arc::default();
+        let sink = events.clone();
+
+        // 第二个任务启动前失败，其余按不同耗时完成
+        let jobs = vec![(30, true), (0, false), (10, true)];
+        let task_ids = run_batch_jobs(
+            jobs,
+            |(delay_ms, ok), _task_id| async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                if ok {
+                    Ok(())
+                } else {
+                    Err("Input file not found".to_string())
+                }
+            },
+            move |event| sink.lock().unwrap().push(event),
+        )
+        .await;
+
+        assert_eq!(task_ids.len(), 3);
+        assert!(task_ids[0] != task_ids[1] && task_ids[1] != task_ids[2]);
+
+        let events = events.lock().unwrap();
+        let failed: Vec<(&str, &str)> = events
+            .iter()
+            .filter_map(|e| match e {
+                TaskEvent::Failed { task_id, error } => Some((task_id.as_str(), error.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(failed, [(task_ids[1].as_str(), "Input file not found")]);
+
+        let progress: Vec<(&str, usize, usize)> = events
+            .iter()
+            .filter_map(|e| match e {
+                TaskEvent::BatchProgress {
+                    batch_id,
+                    completed,
+                    total,
+                } => Some((batch_id.as_str(), *completed, *total)),
+                _ => None,
+            })
+            .collect();
+        let batch_id = progress[0].0;
+        assert_eq!(progress, [(batch_id, 1, 3), (batch_id, 2, 3), (batch_id, 3, 3)]);
+        assert!(!task_ids.iter().any(|id| id == batch_id));
+    }
+
+
+    #[tokio::test]
+    async fn completed_events_arrive_before_final_batch_progress() {
+        let events: Arc<Mutex<Vec<TaskEvent>>> = Arc::default();
+        let sink = events.clone();
+        let emit = move |event| sink.lock().unwrap().push(event);
+
+        // 子任务像 run_ffmpeg 一样经同一个事件出口推送自己的 Completed
+        let job_emit = emit.clone();
+        let task_ids = run_batch_jobs(
+            vec![20, 0, 10],
+            move |delay_ms, task_id| {
+                let emit = job_emit.clone();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                    emit(TaskEvent::Completed {
+                        task_id,
+                        output_path: format!("/videos/out_{}.mp4", delay_ms),
+                        output_size: 1024,
+                        elapsed: 0.1,
+                        verification: Vec::new(),
+                        note: None,
+                        container_change: None,
+                    });
+                    Ok(())
+                }
+            },
+            emit,
+        )
+        .await;
+
+        let events = events.lock().unwrap();
+        let final_progress = events
+            .iter()
+            .position(|e| {
+                matches!(
+                    e,
+                    TaskEvent::BatchProgress {
+                        completed: 3,
+                        total: 3,
+                        ..
+                    }
+                )
+            })
+            .expect("missing final BatchProgress");
+        assert_eq!(final_progress, events.len() - 1);
+
+        let mut completed: Vec<&str> = events[..final_progress]
+            .iter()
+            .filter_map(|e| match e {
+                TaskEvent::Completed { task_id, .. } => Some(task_id.as_str()),
+                _ => None,
+            })
+            .collect();
+        completed.sort_unstable();
+        let mut expected: Vec<&str> = task_ids.iter().map(String::as_str).collect();
+        expected.sort_unstable();
+        assert_eq!(completed, expected);
+    }
+}
@@ -6,7 +6,26 @@
 ///
 /// 所有构建函数返回 `Vec<String>` 参数数组，交由 process.rs 执行
 
+use crate::engine::presets::{
+    get_platform_constraints, plan_platform_constraints, ConstraintPlan, LoudnessTarget,
+    ShortFormPlan, ShortFormProfile, DEFAULT_LOUDNESS_TARGET,
+};
+use crate::models::media::{FfprobeOutput, VideoStream};
 use crate::models::preset::*;
+use crate::utils::path::{file_extension, is_image_file};
+
+/// 参数未指定音频码率时的兜底值（正常情况下由 command 层填入设置中的默认码率）
+const DEFAULT_AUDIO_BITRATE: &str = "128k";
+/// 参数未指定 CRF 时的兜底值（正常情况下由 command 层填入设置中的默认质量）
+const DEFAULT_CRF: u32 = 18;
+/// 参数未指定编码预设时的兜底值（正常情况下由 command 层填入设置中的默认预设）
+const DEFAULT_PRESET: &str = "medium";
+/// VideoToolbox 硬件编码未指定码率时的默认码率
+pub const DEFAULT_VT_BITRATE: &str = "5M";
+/// 去隔行滤镜：每帧输出一帧（保持原帧率），隔行源转逐行
+const DEINTERLACE_FILTER: &str = "yadif=mode=send_frame";
+/// 默认 HLS 分片时长（秒）
+pub const DEFAULT_HLS_SEGMENT_DURATION: f64 = 6.0;
 
 // ============================================================
 // 通用 FfmpegCommand 构建器
@@ -18,20 +37,28 @@ use crate::models::preset::*;
 /// 最终通过 build() 方法生成完整的参数数组。
 ///
 /// ffmpeg 要求参数按特定顺序排列：
-/// 全局选项 → 输入选项 → -i 输入文件 → 输出选项 → 滤镜 → 输出路径
+/// 全局选项 → 输入选项 → -i 输入文件 → 输出选项 → 流映射 → 元数据 → 滤镜 → 线程数 → 输出路径
 pub struct FfmpegCommand {
     /// 输入前的全局参数（如 -y, -hide_banner, -progress, -ss 等）
     pre_args: Vec<String>,
-    /// 输入文件列表（按添加顺序）
-    inputs: Vec<String>,
+    /// 输入文件列表（按添加顺序），每项附带仅作用于该输入的选项（如 -stream_loop）
+    inputs: Vec<(Vec<String>, String)>,
     /// 输入后的输出参数（如 -c:v, -crf, -preset 等）
     post_args: Vec<String>,
+    /// 流映射（-map 参数的流说明符，按添加顺序决定输出流顺序）
+    maps: Vec<String>,
+    /// 容器级元数据标签（-metadata key=value）
+    metadata_tags: Vec<(String, String)>,
+    /// 流级元数据标签（流说明符, 键, 值），输出为 -metadata:s:<说明符> key=value
+    stream_metadata_tags: Vec<(String, String, String)>,
     /// 视频滤镜链（-vf 参数）
     video_filters: Vec<String>,
     /// 音频滤镜链（-af 参数）
     audio_filters: Vec<String>,
     /// 复杂滤镜图（-filter_complex 参数）
     complex_filter: Option<String>,
+    /// 编码线程数上限（-threads，作为输出选项紧贴在输出路径之前）
+    threads: Option<u32>,
     /// 输出文件路径
     output: String,
 }
@@ -45,16 +72,30 @@ impl FfmpegCommand {
             pre_args: vec!["-y".to_string(), "-hide_banner".to_string()],
             inputs: Vec::new(),
             post_args: Vec::new(),
+            maps: Vec::new(),
+            metadata_tags: Vec::new(),
+            stream_metadata_tags: Vec::new(),
             video_filters: Vec::new(),
             audio_filters: Vec::new(),
             complex_filter: None,
+            threads: None,
             output: String::new(),
         }
     }
 
     /// 添加输入文件
     pub fn input(mut self, path: &str) -> Self {
-        self.inputs.push(path.to_string());
+        self.inputs.push((Vec::new(), path.to_string()));
+        self
+    }
+
+    /// 添加带输入选项的输入文件
+    ///
+    /// 选项紧贴在对应的 -i 之前，只作用于该输入
+    /// （如 `-stream_loop -1` 只循环第二个输入，不影响第一个）
+    pub fn input_with_options(mut self, options: &[&str], path: &str) -> Self {
+        let options = options.iter().map(|o| o.to_string()).collect();
+        self.inputs.push((options, path.to_string()));
         self
     }
 
@@ -71,6 +112,14 @@ impl FfmpegCommand {
         self
     }
 
+    /// 限制编码使用的线程数（-threads），None 时不限制
+    ///
+    /// 作为输出选项放在输出路径之前，约束的是编码器；放在 -i 前面只会作用于第一个输入的解码
+    pub fn thread_count(mut self, threads: Option<u32>) -> Self {
+        self.threads = threads.map(|t| t.max(1));
+        self
+    }
+
     /// 添加输出参数（单个参数，放在 -i 之后）
     pub fn arg(mut self, arg: &str) -> Self {
         self.post_args.push(arg.to_string());
@@ -84,6 +133,27 @@ impl FfmpegCommand {
         self
     }
 
+    /// 添加流映射（-map），如 "0:v"、"1:a:0"、"0:a?" 或滤镜图输出标签 "[v]"
+    ///
+    /// 输出流按添加顺序排列；映射统一在输出参数之后输出
+    pub fn map(mut self, stream_specifier: &str) -> Self {
+        self.maps.push(stream_specifier.to_string());
+        self
+    }
+
+    /// 设置容器级元数据标签（-metadata key=value），值为空时删除该标签
+    pub fn metadata_tag(mut self, key: &str, value: &str) -> Self {
+        self.metadata_tags.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// 设置流级元数据标签（-metadata:s:<说明符> key=value），说明符如 "v:0"、"a:1"、"s"
+    pub fn stream_metadata_tag(mut self, stream_spec: &str, key: &str, value: &str) -> Self {
+        self.stream_metadata_tags
+            .push((stream_spec.to_string(), key.to_string(), value.to_string()));
+        self
+    }
+
     /// 设置视频编码器
     pub fn video_codec(self, codec: &str) -> Self {
         self.args_pair("-c:v", codec)
@@ -145,6 +215,22 @@ impl FfmpegCommand {
         self.pre_args_pair("-progress", "pipe:1").pre_arg("-nostats")
     }
 
+    /// 设置两遍编码的遍数和统计文件前缀
+    ///
+    /// libx265 不读取 -pass/-passlogfile，需通过 x265-params 传入，
+    /// 统计文件名与其他编码器保持一致（<前缀>-0.log）
+    pub fn pass_log(self, encoder: &str, pass: u8, passlog_prefix: &str) -> Self {
+        if encoder == "libx265" {
+            self.args_pair(
+                "-x265-params",
+                &format!("pass={}:stats={}-0.log", pass, passlog_prefix),
+            )
+        } else {
+            self.args_pair("-pass", &pass.to_string())
+                .args_pair("-passlogfile", passlog_prefix)
+        }
+    }
+
     /// 添加 MP4 web 优化参数（-movflags +faststart）
     pub fn faststart(self) -> Self {
         self.args_pair("-movflags", "+faststart")
@@ -153,23 +239,40 @@ impl FfmpegCommand {
     /// 构建最终的命令行参数数组
     ///
     /// 按照 ffmpeg 要求的顺序组装参数：
-    /// 全局/输入前参数 → -i 输入文件 → 输出参数 → 滤镜 → 输出路径
+    /// 全局/输入前参数 → -i 输入文件 → 输出参数 → 流映射 → 元数据 → 滤镜 → 线程数 → 输出路径
     pub fn build(self) -> Vec<String> {
         let mut result: Vec<String> = Vec::new();
 
         // 1. 全局/输入前参数（-y, -hide_banner, -progress, -ss 等）
         result.extend(self.pre_args);
 
-        // 2. 输入文件（-i 参数）
-        for input in &self.inputs {
+        // 2. 输入文件（-i 参数，输入选项紧贴在各自的 -i 之前）
+        for (options, input) in self.inputs {
+            result.extend(options);
             result.push("-i".to_string());
-            result.push(input.clone());
+            result.push(input);
         }
 
         // 3. 输出参数（-c:v, -crf, -preset 等，必须在 -i 之后）
         result.extend(self.post_args);
 
-        // 4. 滤镜（复杂滤镜与简单滤镜互斥，优先使用 -filter_complex）
+        // 4. 流映射（-map，顺序即输出流顺序）
+        for stream_specifier in self.maps {
+            result.push("-map".to_string());
+            result.push(stream_specifier);
+        }
+
+        // 5. 元数据标签（容器级在前，流级在后）
+        for (key, value) in self.metadata_tags {
+            result.push("-metadata".to_string());
+            result.push(format!("{}={}", key, value));
+        }
+        for (stream_spec, key, value) in self.stream_metadata_tags {
+            result.push(format!("-metadata:s:{}", stream_spec));
+            result.push(format!("{}={}", key, value));
+        }
+
+        // 6. 滤镜（复杂滤镜与简单滤镜互斥，优先使用 -filter_complex）
         if let Some(cf) = &self.complex_filter {
             result.push("-filter_complex".to_string());
             result.push(cf.clone());
@@ -186,7 +289,13 @@ impl FfmpegCommand {
             }
         }
 
-        // 5. 输出路径
+        // 7. 编码线程数（输出选项）
+        if let Some(threads) = self.threads {
+            result.push("-threads".to_string());
+            result.push(threads.to_string());
+        }
+
+        // 8. 输出路径
         if !self.output.is_empty() {
             result.push(self.output);
         }
@@ -199,6 +308,29 @@ impl FfmpegCommand {
 // 功能专用命令构建函数
 // ============================================================
 
+/// 构建 ffprobe 媒体信息查询参数
+///
+/// 以 JSON 输出容器、流和章节信息，GUI 的 sidecar 和命令行模式共用
+///
+/// # 参数
+/// - `file_path` - 媒体文件路径
+///
+/// # 返回
+/// ffprobe 命令行参数数组
+pub fn build_ffprobe_info_args(file_path: &str) -> Vec<String> {
+    [
+        "-v", "quiet",
+        "-print_format", "json",
+        "-show_format",
+        "-show_streams",
+        "-show_chapters",
+        file_path,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
 /// 构建格式转换命令
 ///
 /// 根据目标格式和编码器选择构建 ffmpeg 转码命令。
@@ -206,13 +338,60 @@ impl FfmpegCommand {
 ///
 /// # 参数
 /// - `params` - 格式转换参数
+/// - `rotation` - 源视频播放时需顺时针旋转的角度（0/90/180/270）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
 ///
 /// # 返回
 /// ffmpeg 命令行参数数组
-pub fn build_convert_command(params: &ConvertParams) -> Vec<String> {
+pub fn build_convert_command(
+    params: &ConvertParams,
+    rotation: u32,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    build_convert_pass(params, rotation, None, thread_count)
+}
+
+/// 构建两遍编码的格式转换命令
+///
+/// 第一遍只分析视频（-an，输出到 null 复用器），把统计写入 passlog；
+/// 第二遍读取统计按目标码率编码并输出最终文件。两遍使用相同的视频编码参数
+///
+/// # 参数
+/// - `params` - 格式转换参数（需指定 video_bitrate）
+/// - `rotation` - 源视频播放时需顺时针旋转的角度（0/90/180/270）
+/// - `passlog_prefix` - -passlogfile 前缀路径
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// (第一遍参数, 第二遍参数)
+pub fn build_convert_two_pass_commands(
+    params: &ConvertParams,
+    rotation: u32,
+    passlog_prefix: &str,
+    thread_count: Option<u32>,
+) -> (Vec<String>, Vec<String>) {
+    (
+        build_convert_pass(params, rotation, Some((1, passlog_prefix)), thread_count),
+        build_convert_pass(params, rotation, Some((2, passlog_prefix)), thread_count),
+    )
+}
+
+/// 构建格式转换的单遍命令
+///
+/// `pass` 为 None 时是普通单遍编码；为 Some((遍数, passlog 前缀)) 时追加 -pass/-passlogfile，
+/// 第一遍不处理音频、不写容器（-f null）。
+/// 源视频带旋转元数据时，重编码默认用 transpose 转正并清除标记；
+/// 关闭 auto_rotate 或流复制时保留原始画面并显式写回旋转标记
+fn build_convert_pass(
+    params: &ConvertParams,
+    rotation: u32,
+    pass: Option<(u8, &str)>,
+    thread_count: Option<u32>,
+) -> Vec<String> {
     let mut cmd = FfmpegCommand::new()
         .with_progress()
         .input(&params.input_path);
+    let analysis_pass = matches!(pass, Some((1, _)));
 
     // WebM 格式的编码器兼容性处理
     let ext = params.output_format.to_lowercase();
@@ -229,48 +408,139 @@ pub fn build_convert_command(params: &ConvertParams) -> Vec<String> {
         &params.video_codec
     };
 
-    let audio_codec = if is_webm && !matches!(
-        params.audio_codec.as_str(),
-        "libvorbis" | "libopus" | "copy"
-    ) {
-        "libopus" // 默认使用 Opus
-    } else {
-        &params.audio_codec
-    };
+    let audio_codec = convert_audio_codec(params, is_webm);
 
     // 设置视频编码器
-    if params.hardware_accel.unwrap_or(false) && video_codec != "copy" && !is_webm {
-        // VideoToolbox 硬件加速编码（WebM 不支持硬件加速）
-        cmd = cmd.video_codec("h264_videotoolbox")
-            .video_bitrate("5M");
+    let hardware = params.hardware_accel.unwrap_or(false) && video_codec != "copy" && !is_webm;
+    if hardware {
+        // VideoToolbox 硬件加速编码（WebM 不支持硬件加速），按所选编码器匹配 H.264/HEVC
+        cmd = cmd.video_codec(videotoolbox_encoder(video_codec))
+            .video_bitrate(params.video_bitrate.as_deref().unwrap_or(DEFAULT_VT_BITRATE));
     } else {
         cmd = cmd.video_codec(video_codec);
         // 软件编码时设置 CRF 和 preset（copy 模式不需要）
         if video_codec != "copy" {
-            // VP9 编码器使用 CRF 模式，需要设置 -b:v 0
             if video_codec == "libvpx-vp9" {
-                cmd = cmd.args_pair("-b:v", "0");
-                if let Some(quality) = params.quality {
-                    cmd = cmd.crf(quality);
+                // VP9：指定目标码率时按码率编码，否则 CRF 模式需要设置 -b:v 0
+                if let Some(ref bitrate) = params.video_bitrate {
+                    cmd = cmd.video_bitrate(bitrate);
+                } else {
+                    cmd = cmd.args_pair("-b:v", "0");
+                    if let Some(quality) = params.quality {
+                        cmd = cmd.crf(quality);
+                    }
                 }
                 // VP9 使用 cpu-used 代替 preset
                 cmd = cmd.args_pair("-cpu-used", "2");
             } else {
-                // 其他编码器使用标准 CRF + preset
-                if let Some(quality) = params.quality {
-                    cmd = cmd.crf(quality);
+                // 其他编码器：指定目标码率时按码率编码，否则使用 CRF；
+                // 不支持 CRF/preset 的编码器（如 ProRes、GIF）不传这两个参数
+                if let Some(ref bitrate) = params.video_bitrate {
+                    cmd = cmd.video_bitrate(bitrate);
+                } else if let Some(quality) = params.quality {
+                    if encoder_supports_crf(video_codec) {
+                        cmd = cmd.crf(quality);
+                    }
                 }
                 if let Some(ref preset) = params.preset {
-                    cmd = cmd.preset(preset);
+                    if encoder_supports_preset(video_codec) {
+                        cmd = cmd.preset(preset);
+                    }
+                }
+            }
+            if let Some((number, passlog_prefix)) = pass {
+                cmd = cmd.pass_log(video_codec, number, passlog_prefix);
+            }
+        }
+    }
+
+    // 输出像素格式（流复制时无法转换）
+    if let Some(pix_fmt) = params.pixel_format.as_deref().filter(|_| video_codec != "copy") {
+        cmd = cmd.args_pair("-pix_fmt", pix_fmt);
+    }
+
+    // 固定关键帧间隔；流媒体输出另外关闭场景切换关键帧并使用 closed GOP，
+    // 保证每个 GOP 等长且可独立解码，切片边界与关键帧对齐
+    if video_codec != "copy" {
+        if let Some(gop) = params.gop_size {
+            cmd = cmd
+                .args_pair("-g", &gop.to_string())
+                .args_pair("-keyint_min", &gop.to_string());
+        }
+        if params.streaming == Some(true) && !hardware {
+            match video_codec {
+                "libx264" => {
+                    cmd = cmd.args_pair("-sc_threshold", "0").args_pair("-flags", "+cgop");
                 }
+                "libx265" => cmd = cmd.args_pair("-x265-params", "scenecut=0:open-gop=0"),
+                _ => {}
+            }
+        }
+    }
+
+    // 去隔行需在其他视频滤镜之前（流复制时无法应用滤镜）
+    if params.deinterlace == Some(true) && video_codec != "copy" {
+        cmd = cmd.video_filter(DEINTERLACE_FILTER);
+    }
+
+    // 方向校正：关闭 ffmpeg 的隐式自动旋转，由这里显式决定转正还是保留标记
+    let transpose = rotation_transpose_filter(rotation);
+    if video_codec != "copy" && transpose.is_some() {
+        cmd = cmd.pre_arg("-noautorotate");
+        match transpose.filter(|_| params.auto_rotate.unwrap_or(true)) {
+            Some(filter) => {
+                cmd = cmd
+                    .video_filter(filter)
+                    .stream_metadata_tag("v:0", "rotate", "0");
+            }
+            None => {
+                cmd = cmd.stream_metadata_tag("v:0", "rotate", &rotation.to_string());
+            }
+        }
+    } else if transpose.is_some() {
+        cmd = cmd.stream_metadata_tag("v:0", "rotate", &rotation.to_string());
+    }
+
+    // 色彩范围标记：重编码时用 setrange 修正帧上的范围标记，
+    // -color_range 写入输出流/容器的标记（流复制时只能改标记）
+    if let Some(range) = params.color_range.as_deref().filter(|r| matches!(*r, "tv" | "pc")) {
+        if video_codec != "copy" {
+            cmd = cmd.video_filter(&format!("setrange={}", range));
+        }
+        cmd = cmd.args_pair("-color_range", range);
+    }
+
+    // 第一遍只需要视频统计：不处理音频、不写容器
+    if analysis_pass {
+        if let Some(ref extra) = params.extra_args {
+            for arg in extra {
+                cmd = cmd.arg(arg);
             }
         }
+        return cmd
+            .arg("-an")
+            .args_pair("-f", "null")
+            .output("-")
+            .thread_count(thread_count)
+            .build();
     }
 
-    // 设置音频编码器
+    // 设置音频编码器（对所有输出音轨生效）
     cmd = cmd.audio_codec(audio_codec);
     if audio_codec != "copy" {
-        cmd = cmd.audio_bitrate("128k");
+        cmd = cmd.audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE));
+        if let Some(rate) = params.audio_sample_rate {
+            cmd = cmd.args_pair("-ar", &rate.to_string());
+        }
+    }
+
+    // 多轨输入：显式映射所选音轨，保留全部流时连同字幕一起输出
+    let stream_maps = convert_stream_maps(params);
+    for specifier in &stream_maps {
+        cmd = cmd.map(specifier);
+    }
+    if params.keep_all_streams == Some(true) {
+        cmd = cmd.args_pair("-c:s", convert_subtitle_codec(&ext));
     }
 
     // MP4/MOV 格式添加 faststart 优化
@@ -278,7 +548,21 @@ pub fn build_convert_command(params: &ConvertParams) -> Vec<String> {
         cmd = cmd.faststart();
     }
 
-    // HEVC 的 Apple 兼容性标签
+    // 保留全局元数据、章节和各流的语言等标签（跨容器转换时 ffmpeg 不一定自动带上）。
+    // 显式映射多条音轨时不能用 `0:s:a` 统一覆盖（会把第一条音轨的语言写到每条音轨上），
+    // 此时各流标签由 ffmpeg 按映射关系逐流复制
+    if params.preserve_metadata.unwrap_or(true) {
+        cmd = cmd
+            .args_pair("-map_metadata", "0")
+            .args_pair("-map_chapters", "0");
+        if stream_maps.is_empty() {
+            cmd = cmd
+                .args_pair("-map_metadata:s:v", "0:s:v")
+                .args_pair("-map_metadata:s:a", "0:s:a");
+        }
+    }
+
+    // HEVC 的 Apple 兼容性标签（软件和硬件编码都需要）
     if video_codec == "libx265" {
         cmd = cmd.args_pair("-tag:v", "hvc1");
     }
@@ -291,7 +575,137 @@ pub fn build_convert_command(params: &ConvertParams) -> Vec<String> {
     }
 
     cmd = cmd.output(&params.output_path);
-    cmd.build()
+    cmd.thread_count(thread_count).build()
+}
+
+/// 按探测结果构建单遍格式转换命令
+///
+/// 纯音频输入（没有主视频流，封面图不算）使用 build_convert_audio_only_command，
+/// 否则按主视频流的旋转角度使用 build_convert_command
+///
+/// # 参数
+/// - `params` - 格式转换参数
+/// - `probe` - 输入文件的 ffprobe 探测结果
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_convert_command_for_source(
+    params: &ConvertParams,
+    probe: &FfprobeOutput,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    if probe.is_audio_only() {
+        build_convert_audio_only_command(params, thread_count)
+    } else {
+        let rotation = probe.main_video_stream().map(|s| s.rotation()).unwrap_or(0);
+        build_convert_command(params, rotation, thread_count)
+    }
+}
+
+/// 构建纯音频输入的格式转换命令
+///
+/// 输入没有视频流（mp3/m4a 等）时使用：不设置视频编码、CRF、preset 和 faststart，
+/// 只按参数转换音频编码、码率和采样率，并保留元数据和章节
+///
+/// # 参数
+/// - `params` - 格式转换参数（视频相关参数被忽略）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_convert_audio_only_command(
+    params: &ConvertParams,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let ext = params.output_format.to_lowercase();
+    let audio_codec = convert_audio_codec(params, ext == "webm");
+
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .arg("-vn")
+        .audio_codec(audio_codec);
+    if audio_codec != "copy" {
+        cmd = cmd.audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE));
+        if let Some(rate) = params.audio_sample_rate {
+            cmd = cmd.args_pair("-ar", &rate.to_string());
+        }
+    }
+
+    // 所选音轨与视频输入一致；视频映射是可选的（`?`），-vn 下不会报错
+    let stream_maps = convert_stream_maps(params);
+    for specifier in &stream_maps {
+        cmd = cmd.map(specifier);
+    }
+
+    if params.preserve_metadata.unwrap_or(true) {
+        cmd = cmd
+            .args_pair("-map_metadata", "0")
+            .args_pair("-map_chapters", "0");
+        if stream_maps.is_empty() {
+            cmd = cmd.args_pair("-map_metadata:s:a", "0:s:a");
+        }
+    }
+
+    if let Some(ref extra) = params.extra_args {
+        for arg in extra {
+            cmd = cmd.arg(arg);
+        }
+    }
+
+    cmd.output(&params.output_path).thread_count(thread_count).build()
+}
+
+/// 格式转换使用的音频编码器
+///
+/// WebM 只支持 Vorbis/Opus，选择了不兼容的编码器时自动替换为 Opus
+fn convert_audio_codec(params: &ConvertParams, is_webm: bool) -> &str {
+    if is_webm && !matches!(params.audio_codec.as_str(), "libvorbis" | "libopus" | "copy") {
+        "libopus"
+    } else {
+        &params.audio_codec
+    }
+}
+
+/// 计算格式转换的流映射
+///
+/// 指定音轨时输出第一路视频加所选音轨（按指定顺序）；保留全部流时输出全部音轨和字幕。
+/// 视频和"全部音轨"使用可选映射（`?`），纯音频或无音轨的输入也不会报错。
+/// 两项都未设置时返回空列表，沿用 ffmpeg 的默认选流
+///
+/// # 参数
+/// - `params` - 格式转换参数
+///
+/// # 返回
+/// `-map` 的流说明符列表
+pub fn convert_stream_maps(params: &ConvertParams) -> Vec<String> {
+    let keep_all = params.keep_all_streams == Some(true);
+    let selected = params.audio_stream_indexes.as_ref().filter(|i| !i.is_empty());
+    if selected.is_none() && !keep_all {
+        return Vec::new();
+    }
+
+    let mut maps = vec!["0:v:0?".to_string()];
+    match selected {
+        Some(indexes) => maps.extend(indexes.iter().map(|i| format!("0:a:{}", i))),
+        None => maps.push("0:a?".to_string()),
+    }
+    if keep_all {
+        maps.push("0:s?".to_string());
+    }
+    maps
+}
+
+/// 按输出容器选择保留字幕时的字幕编码
+///
+/// MP4/MOV 只接受 mov_text，WebM 只接受 WebVTT，MKV 等其他容器原样复制
+fn convert_subtitle_codec(format: &str) -> &'static str {
+    match format {
+        "mp4" | "mov" | "m4v" => "mov_text",
+        "webm" => "webvtt",
+        _ => "copy",
+    }
 }
 
 /// 构建视频压缩命令
@@ -305,27 +719,114 @@ pub fn build_convert_command(params: &ConvertParams) -> Vec<String> {
 /// - `params` - 视频压缩参数
 /// - `input_duration` - 输入视频时长（秒），用于 BySize 模式的码率计算
 /// - `input_bitrate` - 输入视频总码率（bps），用于 ByRatio 模式
+/// - `source_video` - 源视频流信息，用于判断是否违反发布平台约束
+/// - `passlog_prefix` - 两遍编码的 -passlogfile 前缀（仅 BySize 软件编码且启用两遍时使用）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// 每遍一组 ffmpeg 参数：单遍编码只有一组；两遍编码时第一遍只分析视频（-an -f null），
+/// 第二遍读取统计输出最终文件
 pub fn build_compress_command(
     params: &CompressParams,
     input_duration: f64,
     input_bitrate: u64,
+    source_video: Option<&VideoStream>,
+    passlog_prefix: Option<&str>,
+    thread_count: Option<u32>,
+) -> Vec<Vec<String>> {
+    let two_pass = matches!(params.mode, CompressMode::BySize)
+        && params.two_pass_options.two_pass
+        && !params.hardware_accel.unwrap_or(false);
+    let build_pass = |pass: Option<(u8, &str)>| {
+        build_compress_pass(params, input_duration, input_bitrate, source_video, pass, thread_count)
+    };
+    match passlog_prefix.filter(|_| two_pass) {
+        Some(prefix) => vec![build_pass(Some((1, prefix))), build_pass(Some((2, prefix)))],
+        None => vec![build_pass(None)],
+    }
+}
+
+/// 录屏内容的关键帧间隔（秒）：画面大部分时间静止，关键帧越稀疏体积越小
+const SCREENCAST_KEYFRAME_SECONDS: f64 = 10.0;
+/// 源帧率未知时录屏内容使用的关键帧间隔（帧）
+const SCREENCAST_DEFAULT_GOP: u32 = 300;
+
+/// 画面内容类型对应的编码参数
+///
+/// 软件编码按内容类型选择 tune（x265 没有 film/stillimage，这两种只对 x264 生效）；
+/// 录屏内容另外把关键帧间隔拉长到 10 秒，硬件编码也适用
+///
+/// # 参数
+/// - `content_type` - 画面内容类型
+/// - `video_codec` - 软件编码器（"libx264" / "libx265"）
+/// - `hardware` - 是否使用 VideoToolbox 硬件编码（没有 tune 参数）
+/// - `frame_rate` - 源视频帧率，未知时为 0
+///
+/// # 返回
+/// 需要追加的 (参数名, 参数值) 列表
+fn content_type_args(
+    content_type: ContentType,
+    video_codec: &str,
+    hardware: bool,
+    frame_rate: f64,
+) -> Vec<(&'static str, String)> {
+    let mut args = Vec::new();
+    let tune = match (content_type, video_codec) {
+        (ContentType::Film, "libx264") => Some("film"),
+        (ContentType::Animation, _) => Some("animation"),
+        (ContentType::Screencast, "libx264") => Some("stillimage"),
+        _ => None,
+    };
+    if let Some(tune) = tune.filter(|_| !hardware) {
+        args.push(("-tune", tune.to_string()));
+    }
+    if content_type == ContentType::Screencast {
+        let gop = if frame_rate > 0.0 {
+            (frame_rate * SCREENCAST_KEYFRAME_SECONDS).round() as u32
+        } else {
+            SCREENCAST_DEFAULT_GOP
+        };
+        args.push(("-g", gop.to_string()));
+    }
+    args
+}
+
+/// 视频压缩使用的软件编码器
+///
+/// 平台约束按 H.264 规格制定（profile/level），指定平台时固定使用 H.264
+pub fn compress_video_codec(params: &CompressParams) -> &'static str {
+    match params.video_codec.as_deref() {
+        Some("libx265") if params.platform.is_none() => "libx265",
+        _ => "libx264",
+    }
+}
+
+/// 构建视频压缩的单遍命令
+///
+/// `pass` 为 None 时是普通单遍编码；为 Some((遍数, passlog 前缀)) 时追加两遍编码参数，
+/// 第一遍不处理音频、不写容器（-f null）
+fn build_compress_pass(
+    params: &CompressParams,
+    input_duration: f64,
+    input_bitrate: u64,
+    source_video: Option<&VideoStream>,
+    pass: Option<(u8, &str)>,
+    thread_count: Option<u32>,
 ) -> Vec<String> {
     let mut cmd = FfmpegCommand::new()
         .with_progress()
         .input(&params.input_path);
 
-    let preset = params.preset.as_deref().unwrap_or("medium");
+    let preset = params.preset.as_deref().unwrap_or(DEFAULT_PRESET);
+    let video_codec = compress_video_codec(params);
 
     if params.hardware_accel.unwrap_or(false) {
         // VideoToolbox 硬件加速模式：使用码率控制
-        cmd = cmd.video_codec("h264_videotoolbox");
+        cmd = cmd.video_codec(videotoolbox_encoder(video_codec));
         match params.mode {
             CompressMode::BySize => {
-                // 根据目标大小反算码率: target_bitrate = target_size_bytes * 8 / duration
-                let target_mb = params.target_size_mb.unwrap_or(50.0);
-                let audio_kbps = 128.0;
-                let video_kbps = (target_mb * 1024.0 * 8.0 / input_duration) - audio_kbps;
-                let video_kbps = video_kbps.max(100.0) as u64;
+                // 根据目标大小反算码率
+                let video_kbps = size_target_video_kbps(params, input_duration);
                 cmd = cmd.video_bitrate(&format!("{}k", video_kbps));
             }
             CompressMode::ByRatio => {
@@ -344,18 +845,33 @@ pub fn build_compress_command(
         }
     } else {
         // 软件编码模式：使用 CRF 控制质量
-        cmd = cmd.video_codec("libx264").preset(preset);
+        cmd = cmd.video_codec(video_codec).preset(preset);
         match params.mode {
             CompressMode::BySize => {
                 // 按目标大小：计算码率后使用 -b:v + -maxrate + -bufsize
-                let target_mb = params.target_size_mb.unwrap_or(50.0);
-                let audio_kbps = 128.0;
-                let video_kbps = (target_mb * 1024.0 * 8.0 / input_duration) - audio_kbps;
-                let video_kbps = video_kbps.max(100.0) as u64;
+                let video_kbps = size_target_video_kbps(params, input_duration);
+                cmd = cmd.video_bitrate(&format!("{}k", video_kbps));
+
+                // 严格 CBR：码率上下限都锁定为目标码率，缓冲区默认 1 秒
+                // 否则为 VBR 上限：默认 maxrate = 1.5 倍、bufsize = 2 倍目标码率
+                let strict_cbr = params.strict_cbr.unwrap_or(false);
+                let maxrate_multiplier = if strict_cbr {
+                    1.0
+                } else {
+                    params.maxrate_multiplier.unwrap_or(1.5).max(1.0)
+                };
+                let bufsize_multiplier = params
+                    .bufsize_multiplier
+                    .unwrap_or(if strict_cbr { 1.0 } else { 2.0 })
+                    .max(0.1);
+                let maxrate_kbps = (video_kbps as f64 * maxrate_multiplier) as u64;
+                let bufsize_kbps = (video_kbps as f64 * bufsize_multiplier) as u64;
+                if strict_cbr {
+                    cmd = cmd.args_pair("-minrate", &format!("{}k", video_kbps));
+                }
                 cmd = cmd
-                    .video_bitrate(&format!("{}k", video_kbps))
-                    .args_pair("-maxrate", &format!("{}k", video_kbps * 15 / 10))
-                    .args_pair("-bufsize", &format!("{}k", video_kbps * 2));
+                    .args_pair("-maxrate", &format!("{}k", maxrate_kbps))
+                    .args_pair("-bufsize", &format!("{}k", bufsize_kbps.max(1)));
             }
             CompressMode::ByRatio => {
                 // 按比例压缩：映射到 CRF 值
@@ -372,34 +888,82 @@ pub fn build_compress_command(
         }
     }
 
-    cmd = cmd.audio_codec("aac").audio_bitrate("128k").faststart();
+    // 发布平台约束（仅在源视频违反约束时追加对应的滤镜和参数）
+    let constraint_plan = match (params.platform.as_deref(), source_video) {
+        (Some(platform), Some(source)) => get_platform_constraints(platform)
+            .map(|c| plan_platform_constraints(c, source))
+            .unwrap_or_default(),
+        _ => ConstraintPlan::default(),
+    };
+    if params.deinterlace == Some(true) {
+        cmd = cmd.video_filter(DEINTERLACE_FILTER);
+    }
+    for filter in &constraint_plan.video_filters {
+        cmd = cmd.video_filter(filter);
+    }
+    for (key, value) in &constraint_plan.output_args {
+        cmd = cmd.args_pair(key, value);
+    }
+
+    if let Some(content_type) = params.content_type {
+        let hardware = params.hardware_accel.unwrap_or(false);
+        let frame_rate = source_video.map(|v| v.frame_rate).unwrap_or(0.0);
+        for (key, value) in content_type_args(content_type, video_codec, hardware, frame_rate) {
+            cmd = cmd.args_pair(key, &value);
+        }
+    }
+
+    if let Some((number, passlog_prefix)) = pass {
+        cmd = cmd.pass_log(video_codec, number, passlog_prefix);
+        // 第一遍只需要视频统计：不处理音频、不写容器
+        if number == 1 {
+            return cmd
+                .arg("-an")
+                .args_pair("-f", "null")
+                .output("-")
+                .thread_count(thread_count)
+                .build();
+        }
+    }
+
+    // HEVC 的 Apple 兼容性标签
+    if video_codec == "libx265" {
+        cmd = cmd.args_pair("-tag:v", "hvc1");
+    }
+
+    cmd = cmd
+        .audio_codec("aac")
+        .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE))
+        .faststart();
     cmd = cmd.output(&params.output_path);
-    cmd.build()
+    cmd.thread_count(thread_count).build()
 }
 
 /// 构建单个片段的裁剪命令
 ///
-/// 根据 precise_cut 参数选择快速切割（copy）或精确切割（重编码）。
+/// 根据 precise_cut 参数选择快速切割（copy）或精确切割（重编码）；
+/// accuracy 为 Frame 时改用输出端 -ss + -to 的帧级精度切割（重编码，较慢）。
 /// 此函数处理单个时间片段的裁剪，多片段场景由 trim.rs 循环调用
 ///
 /// # 参数
-/// - `input_path` - 输入视频文件路径
+/// - `params` - 裁剪参数（提供输入路径、切割方式和重编码质量/预设/音频码率）
 /// - `output_path` - 输出文件路径
 /// - `start` - 片段起始时间（秒）
 /// - `end` - 片段结束时间（秒）
-/// - `precise_cut` - 是否精确切割（重编码）
 /// - `with_progress_flag` - 是否添加进度监控参数（单片段用 true，多片段中间步骤用 false）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
 ///
 /// # 返回
 /// ffmpeg 命令行参数数组
 pub fn build_trim_segment_command(
-    input_path: &str,
+    params: &TrimParams,
     output_path: &str,
     start: f64,
     end: f64,
-    precise_cut: bool,
     with_progress_flag: bool,
+    thread_count: Option<u32>,
 ) -> Vec<String> {
+    let input_path = params.input_path.as_str();
     let duration = end - start;
     let start_ts = crate::utils::time::seconds_to_timestamp(start);
     let duration_ts = crate::utils::time::seconds_to_timestamp(duration);
@@ -409,7 +973,23 @@ pub fn build_trim_segment_command(
         cmd = cmd.with_progress();
     }
 
-    if precise_cut {
+    if matches!(params.accuracy, Some(TrimAccuracy::Frame)) {
+        // 帧级精度：-ss 放在 -i 之后逐帧解码到起点，-to 指定绝对结束时间，
+        // 起止点不受关键帧位置影响；起点越靠后解码越慢
+        // 参数顺序：-i <input> -ss <start> -to <end> -c:v libx264 ...
+        let end_ts = crate::utils::time::seconds_to_timestamp(end);
+        cmd = cmd
+            .input(input_path)
+            .args_pair("-ss", &start_ts)
+            .args_pair("-to", &end_ts)
+            .video_codec("libx264")
+            .crf(params.quality.unwrap_or(DEFAULT_CRF))
+            .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+            .audio_codec("aac")
+            .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE))
+            .faststart()
+            .output(output_path);
+    } else if params.precise_cut {
         // 精确切割：-ss 放在 -i 之前（快速定位），然后重新编码确保帧级精度
         // 参数顺序：-ss <start> -i <input> -t <duration> -c:v libx264 ...
         cmd = cmd
@@ -417,10 +997,10 @@ pub fn build_trim_segment_command(
             .input(input_path)
             .args_pair("-t", &duration_ts)
             .video_codec("libx264")
-            .crf(18)
-            .preset("medium")
+            .crf(params.quality.unwrap_or(DEFAULT_CRF))
+            .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
             .audio_codec("aac")
-            .audio_bitrate("128k")
+            .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE))
             .arg("-avoid_negative_ts")
             .arg("make_zero")
             .faststart()
@@ -438,7 +1018,7 @@ pub fn build_trim_segment_command(
             .output(output_path);
     }
 
-    cmd.build()
+    cmd.thread_count(thread_count).build()
 }
 
 /// 构建视频裁剪命令（兼容单片段调用）
@@ -448,21 +1028,35 @@ pub fn build_trim_segment_command(
 ///
 /// # 参数
 /// - `params` - 裁剪参数
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
 ///
 /// # 返回
 /// ffmpeg 命令行参数数组
-pub fn build_trim_command(params: &TrimParams) -> Vec<String> {
+pub fn build_trim_command(params: &TrimParams, thread_count: Option<u32>) -> Vec<String> {
     let segment = &params.segments[0];
     build_trim_segment_command(
-        &params.input_path,
+        params,
         &params.output_path,
         segment.start,
         segment.end,
-        params.precise_cut,
         true, // 单片段需要进度监控
+        thread_count,
     )
 }
 
+/// 把声道数转换为 aformat 可识别的声道布局名
+///
+/// 常见声道数使用标准布局（mono / stereo / 5.1 / 7.1），其余使用 "<n>c" 通用写法
+fn channel_layout_for(channels: u32) -> String {
+    match channels {
+        1 => "mono".to_string(),
+        2 => "stereo".to_string(),
+        6 => "5.1".to_string(),
+        8 => "7.1".to_string(),
+        n => format!("{}c", n),
+    }
+}
+
 /// 构建视频合并命令
 ///
 /// 根据参数自动选择合并策略：
@@ -473,6 +1067,8 @@ pub fn build_trim_command(params: &TrimParams) -> Vec<String> {
 /// - `params` - 合并参数
 /// - `concat_file_path` - concat demuxer 使用的临时文件列表路径
 /// - `durations` - 每个输入视频的时长（秒），用于计算转场 offset
+/// - `sample_aspect_ratios` - 每个输入视频的像素宽高比（SAR），缺失按 1.0 处理
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
 ///
 /// # 返回
 /// ffmpeg 命令行参数数组
@@ -480,25 +1076,88 @@ pub fn build_merge_command(
     params: &MergeParams,
     concat_file_path: &str,
     durations: &[f64],
+    sample_aspect_ratios: &[f64],
+    thread_count: Option<u32>,
 ) -> Vec<String> {
     let has_transition = params.transition.is_some();
     let needs_filter = has_transition || params.normalize;
 
+    // 响度标准化（loudnorm 输出 192kHz，统一重采样回 48kHz）
+    let loudness = params.normalize_loudness.unwrap_or(false).then(|| {
+        let target = DEFAULT_LOUDNESS_TARGET.with_overrides(
+            params.loudness_target_lufs,
+            params.loudness_range,
+            params.true_peak,
+        );
+        format!("{},aresample=48000", loudnorm_filter(&target))
+    });
+    // 在滤镜图末尾的音频标签后追加响度标准化，返回新的输出标签
+    let finish_audio = |parts: &mut Vec<String>, label: String| -> String {
+        match &loudness {
+            Some(chain) => {
+                parts.push(format!("[{}]{}[aln]", label, chain));
+                "aln".to_string()
+            }
+            None => label,
+        }
+    };
+
+    // 仅音频交叉淡化（有转场时由转场负责音频衔接）
+    let audio_fade = params
+        .audio_only_crossfade
+        .filter(|d| *d > 0.0 && !has_transition);
+
+    if let Some(fade) = audio_fade.filter(|_| !needs_filter) {
+        // 视频通过 concat demuxer 直接复制（列表文件中已用 outpoint 截掉非末尾片段的尾部），
+        // 音频从各原始文件单独读取，用 acrossfade 链式衔接后重编码
+        let audio_labels: Vec<String> = (1..=params.input_paths.len())
+            .map(|i| format!("{}:a", i))
+            .collect();
+        let (mut audio_parts, audio_out) = audio_crossfade_chain(&audio_labels, fade);
+        let audio_out = finish_audio(&mut audio_parts, audio_out);
+
+        let mut cmd = FfmpegCommand::new()
+            .with_progress()
+            .pre_args_pair("-f", "concat")
+            .pre_args_pair("-safe", "0")
+            .input(concat_file_path);
+        for path in &params.input_paths {
+            cmd = cmd.input(path);
+        }
+        return cmd
+            .complex_filter(&audio_parts.join(";"))
+            .map("0:V:0")
+            .map(&format!("[{}]", audio_out))
+            .video_codec("copy")
+            .audio_codec("aac")
+            .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE))
+            .output(&params.output_path)
+            .thread_count(thread_count)
+            .build();
+    }
+
     if !needs_filter {
         // 简单模式：使用 concat demuxer，无需重编码
         // -f concat 和 -safe 0 必须在 -i 之前，指定输入格式
-        FfmpegCommand::new()
+        let cmd = FfmpegCommand::new()
             .with_progress()
             .pre_args_pair("-f", "concat")
             .pre_args_pair("-safe", "0")
             .input(concat_file_path)
-            .video_codec("copy")
-            .audio_codec("copy")
-            .output(&params.output_path)
-            .build()
+            .video_codec("copy");
+        // 响度标准化时仅音频重编码
+        let cmd = match &loudness {
+            Some(chain) => cmd
+                .audio_filter(chain)
+                .audio_codec("aac")
+                .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE)),
+            None => cmd.audio_codec("copy"),
+        };
+        cmd.output(&params.output_path).thread_count(thread_count).build()
     } else {
         // 复杂模式：使用 filter_complex 进行归一化或添加转场
         let n = params.input_paths.len();
+        let audio_bitrate = params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE);
 
         // 解析目标分辨率
         let (target_w, target_h) = params
@@ -517,21 +1176,31 @@ pub fn build_merge_command(
             })
             .unwrap_or((1920, 1080));
         let target_fps = params.target_fps.unwrap_or(30.0);
+        let channel_layout = channel_layout_for(params.target_channels.unwrap_or(2));
 
         // 构建滤镜图
         let mut filter_parts: Vec<String> = Vec::new();
         let mut concat_inputs = String::new();
 
         for i in 0..n {
+            // 变形（anamorphic）输入先按 SAR 把宽度换算成方形像素，
+            // 否则后续 scale/pad 按存储尺寸计算，setsar=1 后画面会被压扁
+            let sar = sample_aspect_ratios.get(i).copied().unwrap_or(1.0);
+            let sar_fix = if (sar - 1.0).abs() > 0.001 {
+                format!("scale=trunc(iw*{}/2)*2:ih,setsar=1,", sar)
+            } else {
+                String::new()
+            };
+
             // 统一每个输入流的分辨率、宽高比和帧率
             filter_parts.push(format!(
-                "[{i}:v]scale={w}:{h}:force_original_aspect_ratio=decrease,\
+                "[{i}:V:0]{sar_fix}scale={w}:{h}:force_original_aspect_ratio=decrease,\
                  pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps={fps}[v{i}]",
-                i = i, w = target_w, h = target_h, fps = target_fps
+                i = i, sar_fix = sar_fix, w = target_w, h = target_h, fps = target_fps
             ));
             filter_parts.push(format!(
-                "[{i}:a]aresample=44100,aformat=sample_fmts=fltp:channel_layouts=stereo[a{i}]",
-                i = i
+                "[{i}:a]aresample=44100,aformat=sample_fmts=fltp:channel_layouts={layout}[a{i}]",
+                i = i, layout = channel_layout
             ));
             concat_inputs.push_str(&format!("[v{i}][a{i}]", i = i));
         }
@@ -586,6 +1255,7 @@ pub fn build_merge_command(
             }
 
             let last = if n >= 3 { n - 2 } else { 0 };
+            let audio_out = finish_audio(&mut filter_parts, format!("at{}", last));
             let filter_str = filter_parts.join(";");
 
             log::info!("Merge with transition filter: {}", filter_str);
@@ -597,45 +1267,268 @@ pub fn build_merge_command(
             }
             cmd = cmd
                 .complex_filter(&filter_str)
-                .args_pair("-map", &format!("[vt{}]", last))
-                .args_pair("-map", &format!("[at{}]", last))
+                .map(&format!("[vt{}]", last))
+                .map(&format!("[{}]", audio_out))
                 .video_codec("libx264")
-                .crf(18)
-                .preset("medium")
+                .crf(params.quality.unwrap_or(DEFAULT_CRF))
+                .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
                 .audio_codec("aac")
-                .audio_bitrate("128k")
+                .audio_bitrate(audio_bitrate)
                 .faststart()
                 .output(&params.output_path);
-            return cmd.build();
+            return cmd.thread_count(thread_count).build();
         }
 
-        // 无转场归一化合并：使用 concat filter
-        let concat_filter = format!(
-            "{};{}concat=n={}:v=1:a=1[v][a]",
-            filter_parts.join(";"),
-            concat_inputs,
-            n
-        );
+        if let Some(fade) = audio_fade {
+            // 归一化 + 仅音频交叉淡化：非末尾片段的视频截掉尾部 fade 秒后硬切拼接，
+            // 被截掉的那段音频与下一片段开头重叠淡出，保持音画同步
+            let mut video_concat = String::new();
+            for i in 0..n {
+                if i + 1 < n {
+                    let keep = (durations.get(i).copied().unwrap_or(0.0) - fade).max(0.0);
+                    filter_parts.push(format!(
+                        "[v{i}]trim=duration={keep},setpts=PTS-STARTPTS[vc{i}]",
+                        i = i,
+                        keep = keep
+                    ));
+                    video_concat.push_str(&format!("[vc{}]", i));
+                } else {
+                    video_concat.push_str(&format!("[v{}]", i));
+                }
+            }
+            filter_parts.push(format!("{}concat=n={}:v=1:a=0[v]", video_concat, n));
 
-        let mut cmd = FfmpegCommand::new().with_progress();
-        for path in &params.input_paths {
-            cmd = cmd.input(path);
-        }
-        cmd = cmd
-            .complex_filter(&concat_filter)
-            .args_pair("-map", "[v]")
-            .args_pair("-map", "[a]")
-            .video_codec("libx264")
-            .crf(18)
-            .preset("medium")
-            .audio_codec("aac")
-            .audio_bitrate("128k")
+            let audio_labels: Vec<String> = (0..n).map(|i| format!("a{}", i)).collect();
+            let (audio_parts, audio_out) = audio_crossfade_chain(&audio_labels, fade);
+            filter_parts.extend(audio_parts);
+            let audio_out = finish_audio(&mut filter_parts, audio_out);
+
+            let mut cmd = FfmpegCommand::new().with_progress();
+            for path in &params.input_paths {
+                cmd = cmd.input(path);
+            }
+            return cmd
+                .complex_filter(&filter_parts.join(";"))
+                .map("[v]")
+                .map(&format!("[{}]", audio_out))
+                .video_codec("libx264")
+                .crf(params.quality.unwrap_or(DEFAULT_CRF))
+                .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+                .audio_codec("aac")
+                .audio_bitrate(audio_bitrate)
+                .faststart()
+                .output(&params.output_path)
+                .thread_count(thread_count)
+                .build();
+        }
+
+        // 无转场归一化合并：使用 concat filter
+        filter_parts.push(format!("{}concat=n={}:v=1:a=1[v][a]", concat_inputs, n));
+        let audio_out = finish_audio(&mut filter_parts, "a".to_string());
+        let concat_filter = filter_parts.join(";");
+
+        let mut cmd = FfmpegCommand::new().with_progress();
+        for path in &params.input_paths {
+            cmd = cmd.input(path);
+        }
+        cmd = cmd
+            .complex_filter(&concat_filter)
+            .map("[v]")
+            .map(&format!("[{}]", audio_out))
+            .video_codec("libx264")
+            .crf(params.quality.unwrap_or(DEFAULT_CRF))
+            .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+            .audio_codec("aac")
+            .audio_bitrate(audio_bitrate)
             .faststart()
             .output(&params.output_path);
-        cmd.build()
+        cmd.thread_count(thread_count).build()
+    }
+}
+
+/// 构建音频 acrossfade 链
+///
+/// 依次把相邻两段音频交叉淡化：`[a0][a1]acrossfade[ax0];[ax0][a2]acrossfade[ax1];...`
+///
+/// # 参数
+/// - `labels` - 各段音频的流标签（不含方括号，如 "a0" 或 "1:a"）
+/// - `fade` - 交叉淡化时长（秒）
+///
+/// # 返回
+/// (滤镜片段列表, 最终输出标签)
+fn audio_crossfade_chain(labels: &[String], fade: f64) -> (Vec<String>, String) {
+    let mut parts = Vec::new();
+    let mut prev = labels.first().cloned().unwrap_or_default();
+    for (i, label) in labels.iter().enumerate().skip(1) {
+        let out = format!("ax{}", i - 1);
+        parts.push(format!(
+            "[{prev}][{curr}]acrossfade=d={d}:c1=tri:c2=tri[{out}]",
+            prev = prev,
+            curr = label,
+            d = fade,
+            out = out
+        ));
+        prev = out;
+    }
+    (parts, prev)
+}
+
+/// 构建图片幻灯片命令
+///
+/// - 无转场：使用 concat demuxer 读取 `concat_file_path` 列表（每项带 duration），
+///   适合同一格式的图片；
+/// - 有转场，或 `concat_file_path` 为 None（图片格式不一致，concat demuxer 无法混读）：
+///   每张图片作为 `-loop 1 -t <时长>` 的独立输入，用 xfade 链或 concat 滤镜连接
+///
+/// 所有图片统一 scale+pad 到目标尺寸并转为固定帧率的 yuv420p。
+/// 提供背景音乐时以 -shortest 截到视频和音乐中较短者
+///
+/// # 参数
+/// - `params` - 幻灯片参数
+/// - `concat_file_path` - concat demuxer 文件列表路径（仅无转场时使用）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_slideshow_command(
+    params: &SlideshowParams,
+    concat_file_path: Option<&str>,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let n = params.image_paths.len();
+    let normalize = format!(
+        "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,\
+         setsar=1,fps={fps},format=yuv420p",
+        w = params.width,
+        h = params.height,
+        fps = params.fps
+    );
+
+    let mut cmd = FfmpegCommand::new().with_progress();
+    let video_label = match (concat_file_path, params.transition.as_ref()) {
+        (Some(list), None) => {
+            cmd = cmd
+                .input_with_options(&["-f", "concat", "-safe", "0"], list)
+                .complex_filter(&format!("[0:v]{}[vout]", normalize));
+            "vout".to_string()
+        }
+        (_, transition) => {
+            // 有转场时每张图片多显示一个转场时长，使每张图片的完整显示时间仍为 frame_duration
+            let trans = transition.map(|t| t.duration).unwrap_or(0.0);
+            let clip = format!("{:.3}", params.frame_duration + trans);
+            for path in &params.image_paths {
+                cmd = cmd.input_with_options(&["-loop", "1", "-t", &clip], path);
+            }
+            let mut parts: Vec<String> = (0..n)
+                .map(|i| format!("[{i}:v]{f}[v{i}]", i = i, f = normalize))
+                .collect();
+            let last = match transition {
+                Some(t) if n > 1 => {
+                    let mut prev = "v0".to_string();
+                    for i in 1..n {
+                        let out = format!("vx{}", i);
+                        parts.push(format!(
+                            "[{prev}][v{i}]xfade=transition={t}:duration={d}:offset={o:.3}[{out}]",
+                            prev = prev,
+                            i = i,
+                            t = t.transition_type,
+                            d = t.duration,
+                            o = i as f64 * params.frame_duration,
+                            out = out
+                        ));
+                        prev = out;
+                    }
+                    prev
+                }
+                _ => {
+                    let inputs: String = (0..n).map(|i| format!("[v{}]", i)).collect();
+                    parts.push(format!("{}concat=n={}:v=1:a=0[vcat]", inputs, n));
+                    "vcat".to_string()
+                }
+            };
+            cmd = cmd.complex_filter(&parts.join(";"));
+            last
+        }
+    };
+
+    let audio_index = if concat_file_path.is_some() && params.transition.is_none() {
+        1
+    } else {
+        n
+    };
+    cmd = cmd.args_pair("-map", &format!("[{}]", video_label));
+    if let Some(ref audio) = params.audio_path {
+        cmd = cmd
+            .input(audio)
+            .args_pair("-map", &format!("{}:a:0", audio_index))
+            .audio_codec("aac")
+            .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE))
+            .arg("-shortest");
+    }
+
+    cmd.video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 按音频提取的输出格式设置编码器（未知格式按 MP3 处理）
+fn extract_audio_codec(cmd: FfmpegCommand, format: &str) -> FfmpegCommand {
+    match format {
+        "mp3" => cmd.audio_codec("libmp3lame").args_pair("-q:a", "2"),
+        "aac" | "m4a" => cmd.audio_codec("aac").audio_bitrate("256k"),
+        "wav" => cmd.audio_codec("pcm_s16le"),
+        "flac" => cmd.audio_codec("flac"),
+        _ => cmd.audio_codec("libmp3lame").args_pair("-q:a", "2"),
     }
 }
 
+/// 构建多格式音频提取命令
+///
+/// 输入只解码一次，同一条音轨分别映射到每个输出，
+/// 各输出的编码器与 Extract 模式按格式的选择一致
+///
+/// # 参数
+/// - `input_path` - 输入文件路径
+/// - `audio_track` - 音轨序号（第几条音频流）
+/// - `outputs` - (输出格式, 输出路径) 列表
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_extract_multi_format_command(
+    input_path: &str,
+    audio_track: u32,
+    outputs: &[(String, String)],
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new().with_progress().input(input_path);
+    let stream = format!("0:a:{}", audio_track);
+
+    // 多路输出：每路的选项紧跟在自己的输出路径之前，最后一路作为主输出
+    let last = outputs.len().saturating_sub(1);
+    for (i, (format, path)) in outputs.iter().enumerate() {
+        cmd = extract_audio_codec(cmd.args_pair("-map", &stream), format);
+        if format == "m4a" {
+            cmd = cmd.faststart();
+        }
+        if i < last {
+            // -threads 是逐输出的选项，前几路需各自带上
+            if let Some(threads) = thread_count {
+                cmd = cmd.args_pair("-threads", &threads.max(1).to_string());
+            }
+            cmd = cmd.arg(path);
+        }
+    }
+
+    cmd.output(outputs.get(last).map(|(_, path)| path.as_str()).unwrap_or(""))
+        .thread_count(thread_count)
+        .build()
+}
+
 /// 构建音频处理命令
 ///
 /// 根据 AudioMode 选择不同的处理逻辑：
@@ -647,7 +1540,14 @@ pub fn build_merge_command(
 /// # 参数
 /// - `params` - 音频处理参数
 /// - `input_duration` - 输入文件时长（秒），用于淡出时间计算
-pub fn build_audio_command(params: &AudioParams, input_duration: f64) -> Vec<String> {
+/// - `source_tags` - 源文件的描述性元数据标签（见 FfprobeOutput::descriptive_tags），仅 Extract 模式
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_audio_command(
+    params: &AudioParams,
+    input_duration: f64,
+    source_tags: &[(String, String)],
+    thread_count: Option<u32>,
+) -> Vec<String> {
     match params.mode {
         AudioMode::Extract => {
             let format = params.output_format.as_deref().unwrap_or("mp3");
@@ -656,31 +1556,56 @@ pub fn build_audio_command(params: &AudioParams, input_duration: f64) -> Vec<Str
                 .input(&params.input_path)
                 .arg("-vn"); // 去除视频流
 
-            // 根据输出格式选择编码器
-            cmd = match format {
-                "mp3" => cmd.audio_codec("libmp3lame").args_pair("-q:a", "2"),
-                "aac" | "m4a" => cmd.audio_codec("aac").audio_bitrate("256k"),
-                "wav" => cmd.audio_codec("pcm_s16le"),
-                "flac" => cmd.audio_codec("flac"),
-                _ => cmd.audio_codec("libmp3lame").args_pair("-q:a", "2"),
-            };
+            // 以小写键名显式写入源文件的标题、艺术家等标签：
+            // MKV/WebM 的标签键名为大写，部分音频容器的标签映射不识别
+            for (key, value) in source_tags {
+                cmd = cmd.metadata_tag(key, value);
+            }
 
-            cmd = cmd.output(&params.output_path);
-            cmd.build()
+            extract_audio_codec(cmd, format)
+                .output(&params.output_path)
+                .thread_count(thread_count)
+                .build()
         }
         AudioMode::Replace => {
             let replace_path = params.replace_audio_path.as_deref().unwrap_or("");
-            FfmpegCommand::new()
+            let fit_mode = params.fit_mode.as_deref().unwrap_or("pad_silence");
+
+            let mut cmd = FfmpegCommand::new()
                 .with_progress()
-                .input(&params.input_path)
-                .input(replace_path)
-                .video_codec("copy")
+                .input(&params.input_path);
+
+            cmd = match fit_mode {
+                "shortest" => {
+                    // 输出时长取两者较短者（音频短则视频被截断）
+                    cmd.input(replace_path).arg("-shortest")
+                }
+                "loop" => {
+                    // 音频输入无限循环，由视频时长决定输出长度
+                    cmd.input_with_options(&["-stream_loop", "-1"], replace_path)
+                        .arg("-shortest")
+                }
+                _ => {
+                    // pad_silence：音频不足时补静音，超出时截断，输出严格等于视频时长
+                    if input_duration > 0.0 {
+                        let duration_ts = crate::utils::time::seconds_to_timestamp(input_duration);
+                        cmd.input(replace_path)
+                            .audio_filter(&format!("apad=whole_dur={}", input_duration))
+                            .args_pair("-t", &duration_ts)
+                    } else {
+                        // 视频时长未知时退化为 apad + -shortest
+                        cmd.input(replace_path).audio_filter("apad").arg("-shortest")
+                    }
+                }
+            };
+
+            cmd.video_codec("copy")
                 .audio_codec("aac")
-                .audio_bitrate("128k")
-                .args_pair("-map", "0:v:0")
-                .args_pair("-map", "1:a:0")
-                .arg("-shortest")
+                .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE))
+                .map("0:V:0")
+                .map("1:a:0")
                 .output(&params.output_path)
+                .thread_count(thread_count)
                 .build()
         }
         AudioMode::Mute => {
@@ -690,6 +1615,7 @@ pub fn build_audio_command(params: &AudioParams, input_duration: f64) -> Vec<Str
                 .video_codec("copy")
                 .arg("-an") // 删除音轨
                 .output(&params.output_path)
+                .thread_count(thread_count)
                 .build()
         }
         AudioMode::Adjust => {
@@ -710,7 +1636,11 @@ pub fn build_audio_command(params: &AudioParams, input_duration: f64) -> Vec<Str
 
             // EBU R128 响度标准化
             if params.normalize.unwrap_or(false) {
-                filters.push("loudnorm=I=-16:LRA=11:TP=-1.5".to_string());
+                filters.push(loudnorm_filter(&DEFAULT_LOUDNESS_TARGET.with_overrides(
+                    params.loudness_target_lufs,
+                    params.loudness_range,
+                    params.true_peak,
+                )));
             }
 
             // 淡入效果
@@ -732,10 +1662,345 @@ pub fn build_audio_command(params: &AudioParams, input_duration: f64) -> Vec<Str
                 cmd = cmd.audio_filter(&filters.join(","));
             }
 
-            cmd = cmd.audio_codec("aac").audio_bitrate("128k");
+            cmd = cmd
+                .audio_codec("aac")
+                .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE));
             cmd = cmd.output(&params.output_path);
-            cmd.build()
+            cmd.thread_count(thread_count).build()
+        }
+    }
+}
+
+/// 构建去除台标命令
+///
+/// delogo 滤镜用区域边缘像素插值填充矩形区域，视频用 libx264 重编码，音频直接复制。
+/// 区域是否在画面内由 command 层校验
+///
+/// # 参数
+/// - `params` - 去除台标参数
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_remove_logo_command(
+    params: &RemoveLogoParams,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_filter(&format!(
+            "delogo=x={}:y={}:w={}:h={}",
+            params.x, params.y, params.width, params.height
+        ))
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .audio_codec("copy")
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建画面裁剪命令
+///
+/// crop 滤镜截取矩形区域，视频用 libx264 重编码，音频直接复制。
+/// 区域是否在画面内由 command 层校验
+///
+/// # 参数
+/// - `params` - 画面裁剪参数
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_crop_command(params: &CropParams, thread_count: Option<u32>) -> Vec<String> {
+    FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_filter(&format!(
+            "crop={}:{}:{}:{}",
+            params.width, params.height, params.x, params.y
+        ))
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .audio_codec("copy")
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 图片输入缩放平移前的放大倍数
+///
+/// zoompan 的坐标按输入像素取整，直接在原图上缓慢平移会逐像素跳动，先放大再取景可平滑运动
+const ZOOM_PAN_IMAGE_UPSCALE: u32 = 4;
+
+/// 生成缩放平移的进度表达式（0.0-1.0，已套用缓动曲线）
+///
+/// `linear` 为线性进度表达式（如 "clip(t/5,0,1)"）
+fn zoom_pan_progress(linear: &str, easing: ZoomEasing) -> String {
+    match easing {
+        ZoomEasing::Linear => format!("({})", linear),
+        ZoomEasing::EaseInOut => format!("((1-cos(PI*{}))/2)", linear),
+    }
+}
+
+/// 生成起止区域某一分量按进度插值的表达式
+fn zoom_pan_lerp(from: f64, to: f64, progress: &str) -> String {
+    format!("({:.6}+({:.6})*{})", from, to - from, progress)
+}
+
+/// 构建缩放平移（Ken Burns）命令
+///
+/// 输出分辨率固定为 `frame_width`x`frame_height`（调用方保证为偶数）：
+/// - 视频：crop 的宽高只在初始化时求值，不能随时间变化，因此先用逐帧求值的 scale
+///   把画面放大到当前区域恰好为输出尺寸，再按时间插值的坐标 crop 出固定大小的画面
+/// - 图片：先放大再用 zoompan 逐帧取景，zoom 按区域宽度计算（等比缩放）
+///
+/// # 参数
+/// - `params` - 缩放平移参数（区域已校验）
+/// - `frame_width` / `frame_height` - 显示方向的源画面尺寸（即输出尺寸）
+/// - `duration` - 片段时长（秒）
+/// - `is_image` - 输入是否为静态图片
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_zoom_pan_command(
+    params: &ZoomPanParams,
+    frame_width: u32,
+    frame_height: u32,
+    duration: f64,
+    is_image: bool,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let (from, to) = (&params.start_rect, &params.end_rect);
+    let mut cmd = FfmpegCommand::new().with_progress();
+
+    if is_image {
+        let fps = params.fps.unwrap_or(30).max(1);
+        let frames = ((duration * fps as f64).round() as u64).max(1);
+        let progress = zoom_pan_progress(
+            &format!("min(on/{},1)", frames.saturating_sub(1).max(1)),
+            params.easing,
+        );
+        let zoom = format!("1/{}", zoom_pan_lerp(from.width, to.width, &progress));
+        cmd = cmd
+            .input(&params.input_path)
+            .video_filter(&format!(
+                "scale=iw*{u}:ih*{u},\
+                 zoompan=z='{z}':x='iw*{x}':y='ih*{y}':d={d}:s={w}x{h}:fps={fps}",
+                u = ZOOM_PAN_IMAGE_UPSCALE,
+                z = zoom,
+                x = zoom_pan_lerp(from.x, to.x, &progress),
+                y = zoom_pan_lerp(from.y, to.y, &progress),
+                d = frames,
+                w = frame_width,
+                h = frame_height,
+                fps = fps
+            ))
+            .video_filter("format=yuv420p")
+            .arg("-an");
+    } else {
+        let start = crate::utils::time::seconds_to_timestamp(params.start_time.unwrap_or(0.0));
+        let length = format!("{:.3}", duration);
+        cmd = cmd.input_with_options(&["-ss", &start, "-t", &length], &params.input_path);
+
+        let progress = zoom_pan_progress(&format!("clip(t/{:.3},0,1)", duration), params.easing);
+        let rect_width = zoom_pan_lerp(from.width, to.width, &progress);
+        let rect_height = zoom_pan_lerp(from.height, to.height, &progress);
+        cmd = cmd
+            .video_filter(&format!(
+                "scale=w='ceil({w}/{rw}/2)*2':h='ceil({h}/{rh}/2)*2':eval=frame",
+                w = frame_width,
+                h = frame_height,
+                rw = rect_width,
+                rh = rect_height
+            ))
+            .video_filter(&format!(
+                "crop={w}:{h}:x='{w}*{rx}/{rw}':y='{h}*{ry}/{rh}'",
+                w = frame_width,
+                h = frame_height,
+                rx = zoom_pan_lerp(from.x, to.x, &progress),
+                ry = zoom_pan_lerp(from.y, to.y, &progress),
+                rw = rect_width,
+                rh = rect_height
+            ))
+            .video_filter("setsar=1")
+            .audio_codec("aac")
+            .audio_bitrate(DEFAULT_AUDIO_BITRATE);
+    }
+
+    cmd.video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建缩放平移的单帧预览命令
+///
+/// 直接截取起始或结束区域并缩放到输出尺寸，输出一帧 PNG，用于确认取景
+///
+/// # 参数
+/// - `params` - 缩放平移参数（区域已校验）
+/// - `frame_width` / `frame_height` - 显示方向的源画面尺寸（即输出尺寸）
+/// - `at_end` - true 时预览结束区域（视频取片段末尾的帧），否则预览起始区域
+/// - `duration` - 片段时长（秒），视频定位结束帧时使用
+/// - `is_image` - 输入是否为静态图片
+/// - `output_path` - 预览图片路径
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_zoom_pan_preview_command(
+    params: &ZoomPanParams,
+    frame_width: u32,
+    frame_height: u32,
+    at_end: bool,
+    duration: f64,
+    is_image: bool,
+    output_path: &str,
+) -> Vec<String> {
+    let rect = if at_end { &params.end_rect } else { &params.start_rect };
+    let mut cmd = FfmpegCommand::new();
+    cmd = if is_image {
+        cmd.input(&params.input_path)
+    } else {
+        // 结束帧取片段末尾前一点，避免定位到文件结尾之后取不到帧
+        let offset = if at_end { (duration - 0.1).max(0.0) } else { 0.0 };
+        let position = params.start_time.unwrap_or(0.0) + offset;
+        let position = crate::utils::time::seconds_to_timestamp(position);
+        cmd.input_with_options(&["-ss", &position], &params.input_path)
+    };
+    cmd.video_filter(&format!(
+        "crop=iw*{w:.6}:ih*{h:.6}:iw*{x:.6}:ih*{y:.6},scale={ow}:{oh},setsar=1",
+        w = rect.width,
+        h = rect.height,
+        x = rect.x,
+        y = rect.y,
+        ow = frame_width,
+        oh = frame_height
+    ))
+    .args_pair("-frames:v", "1")
+    .output(output_path)
+    .build()
+}
+
+/// 构建去隔行命令
+///
+/// yadif / bwdif 参数一致（mode、parity），未指定场序时由滤镜按帧标记自动检测；
+/// 视频用 libx264 重编码，音频直接复制
+///
+/// # 参数
+/// - `params` - 去隔行参数
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_deinterlace_command(
+    params: &DeinterlaceParams,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_filter(&format!(
+            "{}=mode={}:parity={}",
+            params.method.filter_name(),
+            params.mode.filter_value(),
+            params.parity.unwrap_or(-1)
+        ))
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .audio_codec("copy")
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建降噪命令
+///
+/// 视频用 libx264 重编码，音频直接复制
+///
+/// # 参数
+/// - `params` - 降噪参数
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_denoise_command(params: &DenoiseParams, thread_count: Option<u32>) -> Vec<String> {
+    FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_filter(&denoise_filter(params.method, params.strength))
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .audio_codec("copy")
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// HDR 转 SDR 支持的目标色域原色（同名值也用作 zscale 色彩矩阵和 -colorspace 标记）
+pub const SDR_TONEMAP_PRIMARIES: &[&str] = &["bt709", "smpte170m", "bt470bg"];
+/// HDR 转 SDR 支持的目标传输特性
+pub const SDR_TONEMAP_TRANSFERS: &[&str] = &["bt709", "smpte170m", "iec61966-2-1"];
+
+/// 构建 HDR 转 SDR 的色调映射命令
+///
+/// 滤镜链：先用 zscale 转为线性光（npl=100 以 100 nit 为 SDR 参考白），
+/// 转为浮点 RGB 后换算到目标色域，tonemap 压缩亮度范围，
+/// 最后转换到目标传输特性和色彩矩阵（有限范围）并输出 yuv420p。
+/// 输出流写入对应的色彩标记，视频用 libx264 重编码，音频直接复制
+///
+/// # 参数
+/// - `params` - HDR 转 SDR 参数（目标色域和传输特性需已校验）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_hdr_tonemap_command(
+    params: &HdrToSdrParams,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let primaries = params.target_primaries.as_str();
+    let trc = params.target_trc.as_str();
+    let filter = format!(
+        "zscale=t=linear:npl=100,format=gbrpf32le,zscale=p={p},tonemap={algo},\
+         zscale=t={trc}:m={p}:r=tv,format=yuv420p",
+        p = primaries,
+        algo = params.tonemap_algorithm.filter_value(),
+    );
+    FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_filter(&filter)
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .args_pair("-color_primaries", primaries)
+        .args_pair("-color_trc", trc)
+        .args_pair("-colorspace", primaries)
+        .args_pair("-color_range", "tv")
+        .audio_codec("copy")
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 生成降噪滤镜
+///
+/// 强度（0.0-1.0）线性映射到滤镜参数：
+/// - hqdn3d：亮度空间 1-10，色度空间、亮度时间、色度时间按 ffmpeg 默认值（4:3:6:4.5）的比例推导
+/// - nlmeans：去噪强度 s 为 1-10，patch 7、搜索范围 15（ffmpeg 默认值）
+fn denoise_filter(method: DenoiseMethod, strength: f64) -> String {
+    let strength = strength.clamp(0.0, 1.0);
+    match method {
+        DenoiseMethod::Hqdn3d => {
+            let luma_spatial = 1.0 + strength * 9.0;
+            let chroma_spatial = luma_spatial * 0.75;
+            let luma_temporal = luma_spatial * 1.5;
+            let chroma_temporal = luma_temporal * 0.75;
+            format!(
+                "hqdn3d={:.2}:{:.2}:{:.2}:{:.2}",
+                luma_spatial, chroma_spatial, luma_temporal, chroma_temporal
+            )
         }
+        DenoiseMethod::Nlmeans => format!("nlmeans=s={:.2}:p=7:r=15", 1.0 + strength * 9.0),
     }
 }
 
@@ -747,7 +2012,8 @@ pub fn build_audio_command(params: &AudioParams, input_duration: f64) -> Vec<Str
 ///
 /// # 参数
 /// - `params` - 水印参数
-pub fn build_watermark_command(params: &WatermarkParams) -> Vec<String> {
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_watermark_command(params: &WatermarkParams, thread_count: Option<u32>) -> Vec<String> {
     let mut cmd = FfmpegCommand::new()
         .with_progress()
         .input(&params.input_path);
@@ -757,6 +2023,13 @@ pub fn build_watermark_command(params: &WatermarkParams) -> Vec<String> {
     let offset_x = params.offset_x.unwrap_or(0);
     let offset_y = params.offset_y.unwrap_or(0);
 
+    // 去隔行时先把视频处理为 [dv]，后续以它代替 [0:V:0]
+    let (deinterlace_prefix, video_label) = if params.deinterlace == Some(true) {
+        (format!("[0:V:0]{}[dv];", DEINTERLACE_FILTER), "[dv]")
+    } else {
+        (String::new(), "[0:V:0]")
+    };
+
     match params.watermark_type {
         WatermarkType::Image => {
             let image_path = params.image_path.as_deref().unwrap_or("");
@@ -769,10 +2042,12 @@ pub fn build_watermark_command(params: &WatermarkParams) -> Vec<String> {
             // image_scale = None: use watermark at its natural pixel size (e.g. text-rendered PNG)
             let filter = if let Some(scale) = params.image_scale {
                 // Scale watermark relative to video width using scale2ref
-                // [1:v] = watermark image, [0:v] = video (reference)
+                // [1:v] = watermark image, [0:V:0] = video (reference)
                 // ref_w = video width, iw/ih = watermark dimensions
                 let scale2ref_filter = format!(
-                    "[1:v][0:v]scale2ref=trunc(ref_w*{s}/2)*2:trunc(ref_w*{s}*ih/iw/2)*2[wm_scaled][base]",
+                    "{p}[1:v]{v}scale2ref=trunc(ref_w*{s}/2)*2:trunc(ref_w*{s}*ih/iw/2)*2[wm_scaled][base]",
+                    p = deinterlace_prefix,
+                    v = video_label,
                     s = scale
                 );
 
@@ -801,8 +2076,8 @@ pub fn build_watermark_command(params: &WatermarkParams) -> Vec<String> {
                 };
 
                 let (x, y) = get_image_overlay_position(&params.position, margin, offset_x, offset_y);
-                let overlay = format!(";[0:v][wm]overlay={}:{}", x, y);
-                format!("{}{}", wm_label, overlay)
+                let overlay = format!(";{}[wm]overlay={}:{}", video_label, x, y);
+                format!("{}{}{}", deinterlace_prefix, wm_label, overlay)
             };
 
             cmd = cmd.complex_filter(&filter);
@@ -818,41 +2093,171 @@ pub fn build_watermark_command(params: &WatermarkParams) -> Vec<String> {
 
     cmd = cmd
         .video_codec("libx264")
-        .crf(18)
-        .preset("medium")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
         .audio_codec("copy")
         .output(&params.output_path);
-    cmd.build()
+    cmd.thread_count(thread_count).build()
 }
 
-/// 构建分辨率/帧率调整命令
+/// 构建视频变速命令
+///
+/// 视频使用 `setpts=PTS/速度`。音频保持音高时使用 atempo（单个 atempo 只支持 0.5-2.0，
+/// 超出范围时拆成多个串联）；不保持音高时用 asetrate 按倍率改写采样率再 aresample 回原采样率，
+/// 音高随速度升降。倍率范围由 command 层校验
 ///
 /// # 参数
-/// - `params` - 分辨率/帧率调整参数
-pub fn build_resize_command(params: &ResizeParams) -> Vec<String> {
+/// - `params` - 变速参数
+/// - `sample_rate` - 源音频采样率（Hz），未知时为 0，此时退回 atempo
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_speed_command(
+    params: &SpeedParams,
+    sample_rate: u32,
+    thread_count: Option<u32>,
+) -> Vec<String> {
     let mut cmd = FfmpegCommand::new()
         .with_progress()
-        .input(&params.input_path);
+        .input(&params.input_path)
+        .video_filter(&format!("setpts=PTS/{}", params.video_speed))
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET));
 
-    let mut vf_parts: Vec<String> = Vec::new();
+    if params.keep_audio {
+        if params.keep_audio_pitch || sample_rate == 0 {
+            for filter in atempo_chain(params.audio_speed) {
+                cmd = cmd.audio_filter(&filter);
+            }
+        } else {
+            cmd = cmd.audio_filter(&asetrate_filter(params.audio_speed, sample_rate));
+        }
+        cmd = cmd
+            .audio_codec("aac")
+            .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE));
+    } else {
+        cmd = cmd.arg("-an");
+    }
 
-    // 分辨率调整
-    if params.width.is_some() || params.height.is_some() {
-        let w = params.width.map(|v| v.to_string()).unwrap_or_else(|| "-2".to_string());
-        let h = params.height.map(|v| v.to_string()).unwrap_or_else(|| "-2".to_string());
+    cmd = cmd.faststart().output(&params.output_path);
+    cmd.thread_count(thread_count).build()
+}
 
-        let scale_algo = params.scale_algorithm.as_deref().unwrap_or("lanczos");
-        let aspect_mode = params.aspect_mode.as_deref().unwrap_or("pad");
+/// 不保持音高的音频变速滤镜：把采样率声明为原来的 speed 倍，再重采样回原采样率
+fn asetrate_filter(speed: f64, sample_rate: u32) -> String {
+    let rate = (f64::from(sample_rate) * speed).round() as u32;
+    format!("asetrate={},aresample={}", rate, sample_rate)
+}
 
-        if params.keep_aspect_ratio {
-            match aspect_mode {
-                "pad" => {
-                    // 缩放到不超过目标尺寸，然后加黑边填充
-                    let tw = params.width.unwrap_or(1920);
-                    let th = params.height.unwrap_or(1080);
-                    vf_parts.push(format!(
-                        "scale={}:{}:force_original_aspect_ratio=decrease:flags={}",
-                        tw, th, scale_algo
+/// 倒放时超过此大小的输入给出内存占用警告（reverse/areverse 需缓存整条流）
+const REVERSE_MEMORY_WARN_BYTES: u64 = 500 * 1024 * 1024;
+
+/// 构建倒放命令
+///
+/// 视频使用 reverse 滤镜，音频使用 areverse 滤镜；不倒放的流直接复制。
+/// 两个滤镜都要把整条流解码缓存在内存中，输入文件较大时记录警告
+///
+/// # 参数
+/// - `params` - 倒放参数
+/// - `file_size` - 输入文件大小（字节），用于估算内存占用
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_reverse_command(
+    params: &ReverseParams,
+    file_size: u64,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    if file_size > REVERSE_MEMORY_WARN_BYTES {
+        log::warn!(
+            "倒放输入较大（{} MB），reverse/areverse 会把整条流缓存在内存中: {}",
+            file_size / 1024 / 1024,
+            params.input_path
+        );
+    }
+
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path);
+
+    if params.reverse_video {
+        cmd = cmd
+            .video_filter("reverse")
+            .video_codec("libx264")
+            .crf(params.quality.unwrap_or(DEFAULT_CRF))
+            .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET));
+    } else {
+        cmd = cmd.video_codec("copy");
+    }
+
+    if params.reverse_audio {
+        cmd = cmd
+            .audio_filter("areverse")
+            .audio_codec("aac")
+            .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE));
+    } else {
+        cmd = cmd.audio_codec("copy");
+    }
+
+    cmd = cmd.faststart().output(&params.output_path);
+    cmd.thread_count(thread_count).build()
+}
+
+/// 把任意变速倍率拆成一串 atempo 滤镜
+///
+/// atempo 单个实例的范围是 0.5-2.0，先按 2.0 或 0.5 逐级拆分，剩余部分放在最后，
+/// 如 4.0 → atempo=2,atempo=2，0.3 → atempo=0.5,atempo=0.6
+fn atempo_chain(speed: f64) -> Vec<String> {
+    let mut filters = Vec::new();
+    let mut remaining = speed;
+    while remaining > 2.0 {
+        filters.push("atempo=2".to_string());
+        remaining /= 2.0;
+    }
+    while remaining < 0.5 {
+        filters.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    if (remaining - 1.0).abs() > f64::EPSILON || filters.is_empty() {
+        filters.push(format!("atempo={}", (remaining * 1_000_000.0).round() / 1_000_000.0));
+    }
+    filters
+}
+
+/// 构建分辨率/帧率调整命令
+///
+/// # 参数
+/// - `params` - 分辨率/帧率调整参数
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_resize_command(params: &ResizeParams, thread_count: Option<u32>) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path);
+
+    let mut vf_parts: Vec<String> = Vec::new();
+
+    // 去隔行放在缩放之前，避免按场缩放产生锯齿
+    if params.deinterlace == Some(true) {
+        vf_parts.push(DEINTERLACE_FILTER.to_string());
+    }
+
+    // 分辨率调整
+    if params.width.is_some() || params.height.is_some() {
+        let w = params.width.map(|v| v.to_string()).unwrap_or_else(|| "-2".to_string());
+        let h = params.height.map(|v| v.to_string()).unwrap_or_else(|| "-2".to_string());
+
+        let scale_algo = params.scale_algorithm.as_deref().unwrap_or("lanczos");
+        let aspect_mode = params.aspect_mode.as_deref().unwrap_or("pad");
+
+        if params.keep_aspect_ratio {
+            match aspect_mode {
+                "pad" => {
+                    // 缩放到不超过目标尺寸，然后加黑边填充
+                    let tw = params.width.unwrap_or(1920);
+                    let th = params.height.unwrap_or(1080);
+                    vf_parts.push(format!(
+                        "scale={}:{}:force_original_aspect_ratio=decrease:flags={}",
+                        tw, th, scale_algo
                     ));
                     vf_parts.push(format!(
                         "pad={}:{}:(ow-iw)/2:(oh-ih)/2:black",
@@ -869,6 +2274,17 @@ pub fn build_resize_command(params: &ResizeParams) -> Vec<String> {
                     ));
                     vf_parts.push(format!("crop={}:{}", tw, th));
                 }
+                "fit_downscale_only" => {
+                    // 仅缩小不放大：目标尺寸取 min(源尺寸, 目标尺寸)，
+                    // 小于目标的源视频原样通过，避免小素材被拉大变糊。
+                    // force_divisible_by=2 保证 libx264 yuv420p 需要的偶数宽高
+                    let tw = params.width.unwrap_or(1920);
+                    let th = params.height.unwrap_or(1080);
+                    vf_parts.push(format!(
+                        "scale='min(iw,{})':'min(ih,{})':force_original_aspect_ratio=decrease:force_divisible_by=2:flags={}",
+                        tw, th, scale_algo
+                    ));
+                }
                 _ => {
                     // stretch: 直接拉伸（不保持比例）
                     vf_parts.push(format!("scale={}:{}:flags={}", w, h, scale_algo));
@@ -894,50 +2310,179 @@ pub fn build_resize_command(params: &ResizeParams) -> Vec<String> {
 
     cmd = cmd
         .video_codec("libx264")
-        .crf(18)
-        .preset("medium")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
         .audio_codec("copy")
         .faststart()
         .output(&params.output_path);
-    cmd.build()
+    cmd.thread_count(thread_count).build()
 }
 
 /// 构建 GIF 制作命令
 ///
 /// 使用高质量两步法（palettegen + paletteuse）合并为单条 filter_complex 命令，
-/// 生成色彩还原度高的 GIF
+/// 生成色彩还原度高的 GIF。输出扩展名为 .webp 时改为生成动画 WebP。
+/// 设置了叠加图片时，水印在 palettegen 之前叠加，调色板同时覆盖水印颜色
 ///
 /// # 参数
 /// - `params` - GIF 制作参数
-pub fn build_gif_command(params: &GifParams) -> Vec<String> {
-    let start_ts = crate::utils::time::seconds_to_timestamp(params.start_time);
-    let duration_ts = crate::utils::time::seconds_to_timestamp(params.duration);
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_gif_command(params: &GifParams, thread_count: Option<u32>) -> Vec<String> {
+    let chain = gif_video_chain(params, -1);
+
+    // 动画 WebP：真彩色，无需调色板
+    if crate::utils::path::file_extension(&params.output_path) == "webp" {
+        return gif_inputs(params)
+            .complex_filter(&chain)
+            .args_pair("-map", "[gv]")
+            .video_codec("libwebp")
+            .args_pair("-q:v", "75")
+            .arg("-an")
+            .args_pair("-loop", &webp_loop_flag(params.loop_count).to_string())
+            .output(&params.output_path)
+            .thread_count(thread_count)
+            .build();
+    }
     let max_colors = params.max_colors.unwrap_or(256);
     let dither = params.dither.as_deref().unwrap_or("bayer");
 
     // 构建 filter_complex：一步完成调色板生成和应用
-    // fps → scale → split → palettegen + paletteuse
+    // fps → scale →（overlay）→ split → palettegen + paletteuse
     let filter = format!(
-        "fps={fps},scale={w}:-1:flags=lanczos,split[s0][s1];\
+        "{chain};[gv]split[s0][s1];\
          [s0]palettegen=max_colors={mc}:stats_mode=diff[p];\
          [s1][p]paletteuse=dither={dither}:bayer_scale=5",
-        fps = params.fps,
-        w = params.width,
+        chain = chain,
         mc = max_colors,
         dither = dither,
     );
 
-    FfmpegCommand::new()
-        .with_progress()
-        .pre_args_pair("-ss", &start_ts)
-        .pre_args_pair("-t", &duration_ts)
-        .input(&params.input_path)
+    gif_inputs(params)
         .complex_filter(&filter)
-        .args_pair("-loop", &params.loop_count.to_string())
+        .args_pair("-loop", &gif_loop_flag(params.loop_count).to_string())
         .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建"类 GIF" MP4 命令
+///
+/// 跳过调色板流程，按 GIF 相同的时间范围、帧率、宽度和叠加图片编码为
+/// 无音轨的 H.264 yuv420p MP4（faststart），适合替代体积较大的 GIF。
+/// 循环播放由播放端（如 <video loop>）负责
+///
+/// # 参数
+/// - `params` - GIF 制作参数
+/// - `output_path` - MP4 输出路径
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_gif_mp4_command(
+    params: &GifParams,
+    output_path: &str,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    // 质量预设映射到 CRF
+    let crf = match params.quality.as_deref() {
+        Some("low") => 28,
+        Some("high") => 18,
+        _ => 23,
+    };
+
+    // yuv420p 要求宽高为偶数，高度使用 -2 而不是 GIF 的 -1
+    gif_inputs(params)
+        .complex_filter(&gif_video_chain(params, -2))
+        .args_pair("-map", "[gv]")
+        .video_codec("libx264")
+        .crf(crf)
+        .preset(DEFAULT_PRESET)
+        .args_pair("-pix_fmt", "yuv420p")
+        .arg("-an")
+        .faststart()
+        .output(output_path)
+        .thread_count(thread_count)
         .build()
 }
 
+/// GIF 叠加图片的默认宽度（占输出宽度的比例）
+const DEFAULT_GIF_OVERLAY_SCALE: f64 = 0.2;
+
+/// GIF / WebP / 类 GIF MP4 共用的输入：截取范围的源视频，以及可选的叠加图片
+///
+/// -ss / -t 作为输入选项只作用于源视频，叠加图片作为单帧输入由 overlay 重复使用
+fn gif_inputs(params: &GifParams) -> FfmpegCommand {
+    let start_ts = crate::utils::time::seconds_to_timestamp(params.start_time);
+    let duration_ts = crate::utils::time::seconds_to_timestamp(params.duration);
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input_with_options(&["-ss", &start_ts, "-t", &duration_ts], &params.input_path);
+    if let Some(ref image) = params.overlay_image {
+        cmd = cmd.input(image);
+    }
+    cmd
+}
+
+/// GIF 类输出共用的视频滤镜链：降帧、缩放，可选叠加图片，输出标签为 [gv]
+///
+/// 叠加图片按输出宽度的比例缩放，位置沿用水印的九宫格定位
+///
+/// # 参数
+/// - `params` - GIF 制作参数
+/// - `height` - 缩放高度参数（GIF 用 -1，需要偶数高度的 MP4 用 -2）
+fn gif_video_chain(params: &GifParams, height: i32) -> String {
+    let base = format!(
+        "[0:v]fps={},scale={}:{}:flags=lanczos",
+        params.fps, params.width, height
+    );
+    if params.overlay_image.is_none() {
+        return format!("{}[gv]", base);
+    }
+
+    let scale = params.overlay_scale.unwrap_or(DEFAULT_GIF_OVERLAY_SCALE);
+    let overlay_width = ((params.width as f64 * scale / 2.0).round() as u32).max(1) * 2;
+    let position = params
+        .overlay_position
+        .clone()
+        .unwrap_or(WatermarkPosition::BottomRight);
+    let (x, y) = get_image_overlay_position(&position, 10, 0, 0);
+    format!(
+        "{base}[gb];[1:v]scale={ow}:-1:flags=lanczos[gw];[gb][gw]overlay={x}:{y}[gv]",
+        base = base,
+        ow = overlay_width,
+        x = x,
+        y = y
+    )
+}
+
+/// 将播放次数换算为 GIF 封装器的 -loop 值
+///
+/// GIF 的 -loop：0=无限循环，-1=只播放一次，n=在首次播放后额外重复 n 次
+///
+/// # 参数
+/// - `loop_count` - 播放次数（0=无限, -1 或 1=一次, n=共 n 次）
+fn gif_loop_flag(loop_count: i32) -> i32 {
+    match loop_count {
+        0 => 0,
+        n if n <= 1 => -1,
+        n => n - 1,
+    }
+}
+
+/// 将播放次数换算为 WebP 封装器的 -loop 值
+///
+/// WebP 的 -loop：0=无限循环，n=共播放 n 次（不支持 -1）
+///
+/// # 参数
+/// - `loop_count` - 播放次数（0=无限, -1 或 1=一次, n=共 n 次）
+fn webp_loop_flag(loop_count: i32) -> i32 {
+    match loop_count {
+        0 => 0,
+        n if n <= 1 => 1,
+        n => n,
+    }
+}
+
 /// 构建字幕处理命令
 ///
 /// 支持三种模式：
@@ -947,7 +2492,8 @@ pub fn build_gif_command(params: &GifParams) -> Vec<String> {
 ///
 /// # 参数
 /// - `params` - 字幕处理参数
-pub fn build_subtitle_command(params: &SubtitleParams) -> Vec<String> {
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+pub fn build_subtitle_command(params: &SubtitleParams, thread_count: Option<u32>) -> Vec<String> {
     match params.mode {
         SubtitleMode::Embed => {
             let subtitle_path = params.subtitle_path.as_deref().unwrap_or("");
@@ -967,14 +2513,15 @@ pub fn build_subtitle_command(params: &SubtitleParams) -> Vec<String> {
                 .video_codec("copy")
                 .audio_codec("copy")
                 .args_pair("-c:s", sub_codec)
-                .args_pair("-map", "0:v")
+                .map("0:v")
                 // Use 0:a? (optional) to avoid fatal error if input has no audio stream
-                .args_pair("-map", "0:a?")
-                .args_pair("-map", "1:0")
+                .map("0:a?")
+                .map("1:0")
                 // Mark the subtitle track as default so players auto-display it
                 .args_pair("-disposition:s:0", "default")
-                .args_pair("-metadata:s:s:0", "handler_name=SubtitleHandler")
+                .stream_metadata_tag("s:0", "handler_name", "SubtitleHandler")
                 .output(&params.output_path)
+                .thread_count(thread_count)
                 .build()
         }
         SubtitleMode::Extract => {
@@ -982,8 +2529,9 @@ pub fn build_subtitle_command(params: &SubtitleParams) -> Vec<String> {
             FfmpegCommand::new()
                 .with_progress()
                 .input(&params.input_path)
-                .args_pair("-map", &format!("0:s:{}", sub_index))
+                .map(&format!("0:s:{}", sub_index))
                 .output(&params.output_path)
+                .thread_count(thread_count)
                 .build()
         }
         SubtitleMode::BurnIn => {
@@ -994,7 +2542,9 @@ pub fn build_subtitle_command(params: &SubtitleParams) -> Vec<String> {
             // Build subtitles/ass filter for burn-in rendering.
             // Use fontsdir to point libass at macOS system fonts so that
             // CJK characters render correctly with the static ffmpeg build.
-            let fonts_dir = get_system_fonts_dir();
+            // A caller-supplied fonts_dir (e.g. fonts extracted from MKV
+            // attachments plus links to the system fonts) takes precedence.
+            let fonts_dir = params.fonts_dir.clone().unwrap_or_else(get_system_fonts_dir);
 
             if let Some(ref sub_path) = params.subtitle_path {
                 let ext = crate::utils::path::file_extension(sub_path);
@@ -1052,12 +2602,12 @@ pub fn build_subtitle_command(params: &SubtitleParams) -> Vec<String> {
 
             cmd = cmd
                 .video_codec("libx264")
-                .crf(18)
-                .preset("medium")
+                .crf(params.quality.unwrap_or(DEFAULT_CRF))
+                .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
                 .audio_codec("copy")
                 .faststart()
                 .output(&params.output_path);
-            cmd.build()
+            cmd.thread_count(thread_count).build()
         }
     }
 }
@@ -1069,12 +2619,53 @@ pub fn build_subtitle_command(params: &SubtitleParams) -> Vec<String> {
 /// 将质量等级 (1-10) 映射到 CRF 值
 ///
 /// 等级 10 对应最高质量 (CRF 16)，等级 1 对应最低质量 (CRF 34)
-fn quality_level_to_crf(level: u32) -> u32 {
+pub fn quality_level_to_crf(level: u32) -> u32 {
     let clamped = level.clamp(1, 10);
     // CRF 范围 16-34，等级越高 CRF 越低（质量越好）
     34 - ((clamped - 1) * 2)
 }
 
+/// 生成 EBU R128 响度标准化滤镜
+///
+/// 所有需要响度标准化的功能（音频调节、合并、平台导出）统一通过此函数生成 loudnorm 参数
+///
+/// # 参数
+/// - `target` - 响度目标（综合响度、响度范围、真峰值）
+///
+/// # 返回
+/// loudnorm 滤镜字符串，如 "loudnorm=I=-16:LRA=11:TP=-1.5"
+pub fn loudnorm_filter(target: &LoudnessTarget) -> String {
+    format!(
+        "loudnorm=I={}:LRA={}:TP={}",
+        target.integrated, target.range, target.true_peak
+    )
+}
+
+/// 软件编码器对应的 VideoToolbox 硬件编码器
+///
+/// libx265 使用 hevc_videotoolbox，其余（libx264 等）使用 h264_videotoolbox
+pub fn videotoolbox_encoder(codec: &str) -> &'static str {
+    match codec {
+        "libx265" => "hevc_videotoolbox",
+        _ => "h264_videotoolbox",
+    }
+}
+
+/// 编码器是否支持 -crf 恒定质量模式
+///
+/// 只有 x264/x265/VPx/AV1 软件编码器有 CRF；VideoToolbox、ProRes、GIF 等传入会报错或被忽略
+fn encoder_supports_crf(codec: &str) -> bool {
+    matches!(
+        codec,
+        "libx264" | "libx265" | "libvpx" | "libvpx-vp9" | "libaom-av1" | "libsvtav1"
+    )
+}
+
+/// 编码器是否支持 x264 风格的 -preset 速度预设（"ultrafast" ~ "veryslow"）
+fn encoder_supports_preset(codec: &str) -> bool {
+    matches!(codec, "libx264" | "libx265")
+}
+
 /// 将质量等级 (1-10) 映射到 VideoToolbox 的 -q:v 值
 ///
 /// VT 的 q:v 范围 0-100，数值越高质量越好
@@ -1084,10 +2675,25 @@ fn quality_level_to_vt_q(level: u32) -> u32 {
     30 + ((clamped - 1) * 55 / 9)
 }
 
+/// BySize 压缩的目标视频码率（kbps）
+///
+/// target_bitrate = target_size_bytes * 8 / duration，扣除按 128 kbps 预留的音频码率，
+/// 最低 100 kbps
+///
+/// # 参数
+/// - `params` - 视频压缩参数（target_size_mb 默认 50 MB）
+/// - `input_duration` - 输入视频时长（秒）
+pub fn size_target_video_kbps(params: &CompressParams, input_duration: f64) -> u64 {
+    let target_mb = params.target_size_mb.unwrap_or(50.0);
+    let audio_kbps = 128.0;
+    let video_kbps = (target_mb * 1024.0 * 8.0 / input_duration) - audio_kbps;
+    video_kbps.max(100.0) as u64
+}
+
 /// 将压缩比例 (0.0-1.0) 映射到 CRF 值
 ///
 /// 比例 1.0（不压缩）对应 CRF 16，比例 0.1（极端压缩）对应 CRF 34
-fn ratio_to_crf(ratio: f64) -> u32 {
+pub fn ratio_to_crf(ratio: f64) -> u32 {
     let clamped = ratio.clamp(0.1, 1.0);
     // 线性插值：ratio 越小 CRF 越高
     let crf = 16.0 + (1.0 - clamped) * 20.0;
@@ -1137,7 +2743,7 @@ fn get_image_overlay_position(
 /// On macOS, `/System/Library/Fonts/Supplemental` contains pre-installed
 /// fonts including "Arial Unicode MS" (22MB, full CJK coverage).
 /// Falls back to `/usr/share/fonts` on Linux.
-fn get_system_fonts_dir() -> String {
+pub(crate) fn get_system_fonts_dir() -> String {
     if cfg!(target_os = "macos") {
         "/System/Library/Fonts/Supplemental".to_string()
     } else {
@@ -1176,3 +2782,1999 @@ fn escape_filter_path(path: &str) -> String {
         .replace(':', "\\:")
         .replace(';', "\\;")
 }
+
+/// 构建时间轴预览缩略图命令（scrubber 用）
+///
+/// 单个 ffmpeg 进程内为每个时间点打开一个快速定位（-ss 在 -i 之前）的输入，
+/// 各取一帧缩放后分别输出为 PNG。相比 select 过滤器无需解码整段视频，
+/// 相比逐个时间点启动进程又避免了 N 次进程开销
+///
+/// # 参数
+/// - `input_path` - 输入视频文件路径
+/// - `timestamps` - 时间点列表（秒）
+/// - `width` - 缩略图宽度（高度按比例自动计算）
+/// - `output_paths` - 与 timestamps 一一对应的 PNG 输出路径
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_scrub_thumbnails_command(
+    input_path: &str,
+    timestamps: &[f64],
+    width: u32,
+    output_paths: &[String],
+) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new();
+
+    // 每个时间点一个快速定位的输入
+    for &ts in timestamps {
+        let ts = crate::utils::time::seconds_to_timestamp(ts.max(0.0));
+        cmd = cmd.input_with_options(&["-ss", &ts], input_path);
+    }
+
+    // 每路输入独立缩放：[0:V:0]scale=...[v0];[1:V:0]scale=...[v1];...
+    let filter = (0..timestamps.len())
+        .map(|i| format!("[{i}:V:0]scale={w}:-2[v{i}]", i = i, w = width))
+        .collect::<Vec<_>>()
+        .join(";");
+    cmd = cmd.complex_filter(&filter);
+
+    // 多路输出：前 N-1 路以参数形式追加，最后一路作为主输出
+    let last = output_paths.len().saturating_sub(1);
+    for (i, path) in output_paths.iter().enumerate() {
+        cmd = cmd
+            .args_pair("-map", &format!("[v{}]", i))
+            .args_pair("-frames:v", "1");
+        if i < last {
+            cmd = cmd.arg(path);
+        }
+    }
+
+    cmd.output(output_paths.get(last).map(String::as_str).unwrap_or(""))
+        .build()
+}
+
+/// 缩略图拼图中单张缩略图的默认宽度（像素）
+pub const DEFAULT_CONTACT_SHEET_WIDTH: u32 = 320;
+
+/// 计算缩略图拼图的网格（列数, 行数）
+///
+/// 列数取 count 的平方根向上取整，行数按列数补足，使拼图接近正方形
+pub fn contact_sheet_grid(count: u32) -> (u32, u32) {
+    let count = count.max(1);
+    let cols = (count as f64).sqrt().ceil() as u32;
+    (cols, count.div_ceil(cols))
+}
+
+/// 构建缩略图提取命令
+///
+/// - 单帧：`-ss` 放在 `-i` 之前做输入定位，直接跳到最近的关键帧再解码到目标时间，
+///   不需要从头解码，长视频也能立即完成
+/// - 拼图：从第一个间隔的中点开始，用 `fps=1/<间隔>` 每隔一段取一帧，
+///   再用 `tile` 拼成一张图（避开常见的片头黑帧）
+///
+/// # 参数
+/// - `params` - 缩略图参数
+/// - `duration` - 视频总时长（秒），仅拼图使用
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_thumbnail_command(params: &ThumbnailParams, duration: f64) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new().with_progress();
+
+    let mut filters = Vec::new();
+    match params.count {
+        Some(count) => {
+            let (cols, rows) = contact_sheet_grid(count);
+            let interval = duration / count.max(1) as f64;
+            let start = crate::utils::time::seconds_to_timestamp(interval / 2.0);
+            cmd = cmd.input_with_options(&["-ss", &start], &params.input_path);
+            filters.push(format!("fps=1/{:.3}", interval));
+            filters.push(format!(
+                "scale={}:-2",
+                params.width.unwrap_or(DEFAULT_CONTACT_SHEET_WIDTH)
+            ));
+            filters.push(format!("tile={}x{}", cols, rows));
+        }
+        None => {
+            let ts = crate::utils::time::seconds_to_timestamp(params.timestamp.unwrap_or(0.0));
+            cmd = cmd.input_with_options(&["-ss", &ts], &params.input_path);
+            if let Some(width) = params.width {
+                filters.push(format!("scale={}:-2", width));
+            }
+        }
+    }
+
+    if !filters.is_empty() {
+        cmd = cmd.video_filter(&filters.join(","));
+    }
+    cmd = cmd.args_pair("-frames:v", "1").arg("-an");
+    if params.format == "jpg" {
+        cmd = cmd.args_pair("-q:v", "2");
+    }
+    cmd.output(&params.output_path).build()
+}
+
+/// 批量缩略图的输出文件名模板
+pub const THUMBNAIL_SERIES_PATTERN: &str = "thumb_%04d.jpg";
+
+/// 构建批量缩略图导出命令
+///
+/// 用 `fps=1/<间隔>` 每隔一段取一帧，按 `thumb_%04d.jpg` 编号写出。
+/// `start` 大于 0 时在输入端定位（按数量取帧时从第一个间隔的中点开始，避开片头黑帧），
+/// `max_frames` 限制输出帧数
+///
+/// # 参数
+/// - `params` - 批量缩略图参数
+/// - `interval` - 取帧间隔（秒）
+/// - `start` - 第一帧的时间点（秒）
+/// - `max_frames` - 最多输出的帧数，None 表示直到视频结束
+/// - `output_pattern` - 带 `%04d` 编号的输出路径模板
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_thumbnail_series_command(
+    params: &ThumbnailSeriesParams,
+    interval: f64,
+    start: f64,
+    max_frames: Option<u32>,
+    output_pattern: &str,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new().with_progress();
+    if start > 0.0 {
+        let ts = crate::utils::time::seconds_to_timestamp(start);
+        cmd = cmd.input_with_options(&["-ss", &ts], &params.input_path);
+    } else {
+        cmd = cmd.input(&params.input_path);
+    }
+
+    let mut filters = vec![format!("fps=1/{:.3}", interval)];
+    if let Some(width) = params.width {
+        filters.push(format!("scale={}:-2", width));
+    }
+    cmd = cmd.video_filter(&filters.join(","));
+    if let Some(frames) = max_frames {
+        cmd = cmd.args_pair("-frames:v", &frames.to_string());
+    }
+    cmd.arg("-an")
+        .args_pair("-q:v", "2")
+        .output(output_pattern)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 悬停预览短片的默认输出宽度（像素）
+pub const DEFAULT_PREVIEW_CLIP_WIDTH: u32 = 480;
+/// 悬停预览片段的编码质量（CRF），预览只需看清内容，取较高值换体积
+const PREVIEW_CLIP_CRF: u32 = 28;
+
+/// 构建悬停预览单个片段的截取命令
+///
+/// `-ss` 放在 `-i` 之前做输入定位，只编码视频（去掉音频和字幕）并缩小到指定宽度。
+/// 所有片段使用相同的滤镜和编码参数，之后可用 concat demuxer 直接复制拼接
+///
+/// # 参数
+/// - `input_path` - 输入文件路径
+/// - `output_path` - 片段输出路径
+/// - `start` - 片段起始时间（秒）
+/// - `duration` - 片段时长（秒）
+/// - `width` - 输出宽度（像素）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_preview_snippet_command(
+    input_path: &str,
+    output_path: &str,
+    start: f64,
+    duration: f64,
+    width: u32,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let start_ts = crate::utils::time::seconds_to_timestamp(start);
+    let duration_ts = crate::utils::time::seconds_to_timestamp(duration);
+    FfmpegCommand::new()
+        .input_with_options(&["-ss", &start_ts], input_path)
+        .args_pair("-t", &duration_ts)
+        .map("0:V:0")
+        .video_filter(&format!("scale={}:-2", width))
+        .video_codec("libx264")
+        .crf(PREVIEW_CLIP_CRF)
+        .preset("veryfast")
+        .args_pair("-pix_fmt", "yuv420p")
+        .arg("-an")
+        .output(output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建附件流导出命令
+///
+/// 使用 `-dump_attachment:<索引>` 输入选项按原样（流复制）写出附件，
+/// 附件在打开输入时即被导出，因此输出端只需 `-t 0 -f null` 占位，不做任何解码
+///
+/// # 参数
+/// - `input_path` - 输入文件路径（通常为 MKV）
+/// - `attachments` - (流索引, 目标文件路径) 列表
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_extract_attachments_command(
+    input_path: &str,
+    attachments: &[(u32, String)],
+) -> Vec<String> {
+    let options: Vec<String> = attachments
+        .iter()
+        .flat_map(|(index, path)| [format!("-dump_attachment:{}", index), path.clone()])
+        .collect();
+    let options: Vec<&str> = options.iter().map(String::as_str).collect();
+
+    FfmpegCommand::new()
+        .input_with_options(&options, input_path)
+        .args_pair("-t", "0")
+        .args_pair("-f", "null")
+        .output("-")
+        .build()
+}
+
+/// 构建短视频平台一键导出命令
+///
+/// 组合重构图/缩放（见 plan_short_form_export）、响度标准化和 H.264 编码，
+/// 单次 ffmpeg 调用输出可直接上传的 MP4
+///
+/// # 参数
+/// - `params` - 平台导出参数
+/// - `profile` - 平台导出规格
+/// - `plan` - 根据源视频计算出的导出计划
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_platform_export_command(
+    params: &PlatformExportParams,
+    profile: &ShortFormProfile,
+    plan: &ShortFormPlan,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path);
+
+    for filter in &plan.video_filters {
+        cmd = cmd.video_filter(filter);
+    }
+
+    // 单遍 loudnorm 会把音频升采样到 192kHz，需显式指定输出采样率
+    cmd = cmd
+        .video_codec("libx264")
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .video_bitrate(profile.video_bitrate)
+        .args_pair("-maxrate", profile.max_bitrate)
+        .args_pair("-bufsize", profile.buffer_size)
+        .args_pair("-profile:v", "high")
+        .args_pair("-level", profile.level)
+        .args_pair("-pix_fmt", "yuv420p")
+        .audio_filter(&loudnorm_filter(&plan.loudness))
+        .audio_codec("aac")
+        .audio_bitrate(profile.audio_bitrate)
+        .args_pair("-ar", &profile.sample_rate.to_string())
+        .faststart()
+        .output(&params.output_path);
+    cmd.thread_count(thread_count).build()
+}
+
+/// 构建自动方向校正命令
+///
+/// 重编码时由 ffmpeg 的自动旋转按显示矩阵/rotate 标签把画面转正，
+/// 并且不会把旋转信息写入输出，避免播放器二次旋转。音频直接复制。
+/// 不再手动加 transpose：自动旋转关闭后显示矩阵仍会被带到输出，
+/// 手动转正的画面会被播放器再旋转一次。
+/// rotation 不是 90/180/270 时整体流复制（无需校正）
+///
+/// # 参数
+/// - `params` - 方向校正参数
+/// - `rotation` - 源视频播放时需顺时针旋转的角度（0/90/180/270）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_auto_orient_command(
+    params: &AutoOrientParams,
+    rotation: u32,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    if !matches!(rotation, 90 | 180 | 270) {
+        return FfmpegCommand::new()
+            .with_progress()
+            .input(&params.input_path)
+            .args_pair("-c", "copy")
+            .output(&params.output_path)
+            .thread_count(thread_count)
+            .build();
+    }
+
+    FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .audio_codec("copy")
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 把顺时针旋转角度转换为等效的 transpose 滤镜，0° 或非 90° 倍数返回 None
+fn rotation_transpose_filter(rotation: u32) -> Option<&'static str> {
+    match rotation {
+        90 => Some("transpose=clock"),
+        180 => Some("transpose=clock,transpose=clock"),
+        270 => Some("transpose=cclock"),
+        _ => None,
+    }
+}
+
+/// 构建手动旋转/翻转命令
+///
+/// 旋转用 transpose，180° 用 hflip+vflip（等价且比两次 transpose 快），翻转用 hflip/vflip。
+/// ffmpeg 先按源文件的旋转元数据自动校正，滤镜作用在显示方向的画面上；
+/// 输出清除 rotate 标记，避免播放器二次旋转
+///
+/// # 参数
+/// - `params` - 旋转/翻转参数
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_rotate_command(params: &RotateParams, thread_count: Option<u32>) -> Vec<String> {
+    let filter = match params.rotate {
+        RotateAngle::Rotate90CW => "transpose=clock",
+        RotateAngle::Rotate90CCW => "transpose=cclock",
+        RotateAngle::Rotate180 => "hflip,vflip",
+        RotateAngle::FlipHorizontal => "hflip",
+        RotateAngle::FlipVertical => "vflip",
+    };
+
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_filter(filter)
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .stream_metadata_tag("v", "rotate", "0");
+    cmd = if params.keep_audio {
+        cmd.audio_codec("copy")
+    } else {
+        cmd.arg("-an")
+    };
+    cmd.output(&params.output_path).thread_count(thread_count).build()
+}
+
+/// 构建旋转+翻转组合变换命令
+///
+/// ffmpeg 先按源文件的旋转元数据自动校正，滤镜作用在显示方向的画面上；
+/// 输出清除 rotate 标记，避免播放器二次旋转。音频直接复制
+///
+/// # 参数
+/// - `params` - 变换参数（旋转角度由 command 层校验为 90 的倍数）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_transform_command(params: &TransformParams, thread_count: Option<u32>) -> Vec<String> {
+    let filter = transform_filter(params.rotate.unwrap_or(0), params.flip_h, params.flip_v)
+        .unwrap_or_else(|| "null".to_string());
+
+    FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_filter(&filter)
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .audio_codec("copy")
+        .stream_metadata_tag("v:0", "rotate", "0")
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 组合旋转和翻转滤镜链，无任何变换时返回 None
+///
+/// 90°/270° 用 transpose（clock 即 transpose=1，cclock 即 transpose=2）；
+/// 180° 等价于同时水平和垂直翻转，与请求的翻转合并后相互抵消的方向不再输出滤镜
+///
+/// # 参数
+/// - `rotate` - 顺时针旋转角度（90 的倍数，负数表示逆时针）
+/// - `flip_h` - 旋转后是否水平翻转
+/// - `flip_v` - 旋转后是否垂直翻转
+pub fn transform_filter(rotate: i32, flip_h: bool, flip_v: bool) -> Option<String> {
+    let (transpose, flip_h, flip_v) = match rotate.rem_euclid(360) {
+        90 => (Some("transpose=clock"), flip_h, flip_v),
+        180 => (None, !flip_h, !flip_v),
+        270 => (Some("transpose=cclock"), flip_h, flip_v),
+        _ => (None, flip_h, flip_v),
+    };
+
+    let filters: Vec<&str> = transpose
+        .into_iter()
+        .chain(flip_h.then_some("hflip"))
+        .chain(flip_v.then_some("vflip"))
+        .collect();
+    if filters.is_empty() {
+        None
+    } else {
+        Some(filters.join(","))
+    }
+}
+
+/// 构建 LUT 调色命令
+///
+/// 强度为 1 时直接用 lut3d 滤镜；小于 1 时把画面 split 为两路，
+/// 一路套用 LUT，再用 mix 按 (1-强度):强度 的权重与原画面混合。音频直接复制
+///
+/// # 参数
+/// - `params` - LUT 调色参数（强度范围由 command 层校验）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_lut_command(params: &LutParams, thread_count: Option<u32>) -> Vec<String> {
+    let lut = format!("lut3d=file='{}'", escape_filter_path(&params.lut_path));
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path);
+
+    if params.intensity >= 1.0 {
+        cmd = cmd.video_filter(&lut);
+    } else {
+        let intensity = params.intensity.max(0.0);
+        cmd = cmd.complex_filter(&format!(
+            "[0:V:0]split[orig][src];[src]{lut}[graded];\
+             [orig][graded]mix=inputs=2:weights='{keep:.3} {apply:.3}'",
+            lut = lut,
+            keep = 1.0 - intensity,
+            apply = intensity
+        ));
+    }
+
+    cmd.video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .audio_codec("copy")
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建防抖分析命令（第一遍）
+///
+/// vidstabdetect 把每帧的运动向量写入 trf 文件，输出端为 null，不生成视频
+///
+/// # 参数
+/// - `params` - 防抖参数
+/// - `trf_path` - 运动数据文件路径
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_stabilize_detect_command(params: &StabilizeParams, trf_path: &str) -> Vec<String> {
+    FfmpegCommand::new()
+        .input(&params.input_path)
+        .video_filter(&format!(
+            "vidstabdetect=shakiness={}:result='{}'",
+            params.shakiness,
+            escape_filter_path(trf_path)
+        ))
+        .arg("-an")
+        .arg("-sn")
+        .args_pair("-f", "null")
+        .output("-")
+        .build()
+}
+
+/// 构建防抖补偿命令（第二遍）
+///
+/// vidstabtransform 读取第一遍的 trf 文件补偿画面，补偿插值会让画面略软，
+/// 之后串联轻度 unsharp 锐化
+///
+/// # 参数
+/// - `params` - 防抖参数
+/// - `trf_path` - 第一遍生成的运动数据文件路径
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_stabilize_transform_command(
+    params: &StabilizeParams,
+    trf_path: &str,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let crop = if params.crop_black { "black" } else { "keep" };
+    FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .video_filter(&format!(
+            "vidstabtransform=input='{}':smoothing={}:crop={}",
+            escape_filter_path(trf_path),
+            params.smoothing,
+            crop
+        ))
+        .video_filter("unsharp=5:5:0.8:3:3:0.4")
+        .video_codec("libx264")
+        .crf(params.quality.unwrap_or(DEFAULT_CRF))
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .audio_codec("copy")
+        .faststart()
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建绿幕抠像命令
+///
+/// 有背景时背景按前景尺寸缩放（图片背景循环输入），抠像后的前景叠加在背景上，
+/// 以前景时长为准输出 H.264；无背景时保留透明通道，
+/// .webm 输出 VP9 yuva420p（需关闭 alt-ref 才能编码 alpha），.png 输出 RGBA 图片序列
+///
+/// # 参数
+/// - `params` - 抠像参数（key_color 需为 "#RRGGBB"）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_chromakey_command(params: &ChromakeyParams, thread_count: Option<u32>) -> Vec<String> {
+    let key = format!(
+        "chromakey=color=0x{}:similarity={:.3}:blend={:.3}",
+        params.key_color.trim_start_matches('#'),
+        params.similarity,
+        params.blend
+    );
+    let crf = params.quality.unwrap_or(DEFAULT_CRF);
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path);
+
+    if let Some(ref background) = params.background_path {
+        cmd = if is_image_file(background) {
+            cmd.input_with_options(&["-loop", "1"], background)
+        } else {
+            cmd.input(background)
+        };
+        return cmd
+            .complex_filter(&format!(
+                "[1:v][0:V:0]scale2ref[bg][fg];[fg]{}[keyed];\
+                 [bg][keyed]overlay=shortest=1,format=yuv420p[vout]",
+                key
+            ))
+            .args_pair("-map", "[vout]")
+            .args_pair("-map", "0:a?")
+            .video_codec("libx264")
+            .crf(crf)
+            .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+            .audio_codec("copy")
+            .faststart()
+            .output(&params.output_path)
+            .thread_count(thread_count)
+            .build();
+    }
+
+    if file_extension(&params.output_path) == "png" {
+        return cmd
+            .video_filter(&format!("{},format=rgba", key))
+            .video_codec("png")
+            .arg("-an")
+            .output(&params.output_path)
+            .thread_count(thread_count)
+            .build();
+    }
+
+    cmd.video_filter(&format!("{},format=yuva420p", key))
+        .video_codec("libvpx-vp9")
+        .args_pair("-pix_fmt", "yuva420p")
+        .args_pair("-b:v", "0")
+        .crf(crf)
+        .args_pair("-auto-alt-ref", "0")
+        .audio_codec("libopus")
+        .output(&params.output_path)
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建移除全部字幕流命令
+///
+/// `-map 0` 选中所有流后用负向映射 `-map -0:s` 排除字幕流，
+/// 其余视频、音频、附件等流原样复制，不重新编码
+///
+/// # 参数
+/// - `input_path` - 输入文件路径
+/// - `output_path` - 输出文件路径
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_strip_subtitles_command(input_path: &str, output_path: &str) -> Vec<String> {
+    FfmpegCommand::new()
+        .with_progress()
+        .input(input_path)
+        .args_pair("-map", "0")
+        .args_pair("-map", "-0:s")
+        .args_pair("-c", "copy")
+        .output(output_path)
+        .build()
+}
+
+/// 构建容器修正命令：把标成 .mp4 的 MPEG-TS 原样重封装为真正的 MP4
+///
+/// 所有流直接复制；TS 中的 ADTS 格式 AAC 需经 aac_adtstoasc 转换才能写入 MP4。
+/// 附带 faststart，使修正后的文件可以直接拖动播放
+///
+/// # 参数
+/// - `input_path` - 下载得到的文件路径
+/// - `output_path` - 修正后的输出路径
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_container_fix_command(input_path: &str, output_path: &str) -> Vec<String> {
+    FfmpegCommand::new()
+        .input(input_path)
+        .map("0")
+        .args_pair("-c", "copy")
+        .args_pair("-bsf:a", "aac_adtstoasc")
+        .faststart()
+        .output(output_path)
+        .build()
+}
+
+/// 构建元数据编辑命令
+///
+/// 全部流直接复制、保留章节；clear_existing 时用 `-map_metadata -1` 丢弃源文件的全部标签，
+/// 否则沿用源标签，只覆盖或删除指定的键
+///
+/// # 参数
+/// - `params` - 元数据编辑参数
+/// - `output_path` - 实际写入的输出路径（修改原文件时为同目录临时文件）
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_set_metadata_command(params: &MetadataParams, output_path: &str) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new()
+        .input(&params.input_path)
+        .map("0")
+        .args_pair("-c", "copy")
+        .args_pair("-map_chapters", "0");
+    if params.clear_existing.unwrap_or(false) {
+        cmd = cmd.args_pair("-map_metadata", "-1");
+    }
+    for tag in &params.tags {
+        cmd = cmd.metadata_tag(&tag.key, &tag.value);
+    }
+    for tag in &params.stream_tags {
+        cmd = cmd.stream_metadata_tag(&tag.stream, &tag.key, &tag.value);
+    }
+    cmd.output(output_path).build()
+}
+
+/// 构建写入章节的重封装命令
+///
+/// 第二个输入是 ffmetadata 文件，`-map_chapters 1` 用其中的章节替换原有章节，
+/// 其余流和全局元数据从原文件原样复制。`metadata_path` 为 None 时清除全部章节
+///
+/// # 参数
+/// - `input_path` - 输入文件路径
+/// - `metadata_path` - ffmetadata 章节文件路径
+/// - `output_path` - 输出文件路径
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_embed_chapters_command(
+    input_path: &str,
+    metadata_path: Option<&str>,
+    output_path: &str,
+) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new().input(input_path);
+    cmd = match metadata_path {
+        Some(metadata) => cmd
+            .input_with_options(&["-f", "ffmetadata"], metadata)
+            .args_pair("-map_chapters", "1"),
+        None => cmd.args_pair("-map_chapters", "-1"),
+    };
+    cmd.args_pair("-map", "0")
+        .args_pair("-map_metadata", "0")
+        .args_pair("-c", "copy")
+        .output(output_path)
+        .build()
+}
+
+/// 构建黑场检测命令
+///
+/// 只解码视频并通过 blackdetect 滤镜分析，结果以
+/// `black_start:.. black_end:.. black_duration:..` 日志行输出到 stderr（需 info 日志级别），
+/// 输出端为 null，不生成文件
+///
+/// # 参数
+/// - `input_path` - 输入文件路径
+/// - `pixel_threshold` - 像素亮度阈值（0.0-1.0，低于此值的像素视为黑色）
+/// - `min_duration` - 最短黑场时长（秒），更短的暗场不上报
+/// - `scan_seconds` - 只分析开头的这段时长（None 表示整个文件）
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_blackdetect_command(
+    input_path: &str,
+    pixel_threshold: f64,
+    min_duration: f64,
+    scan_seconds: Option<f64>,
+) -> Vec<String> {
+    let mut cmd = FfmpegCommand::new().input(input_path);
+    if let Some(seconds) = scan_seconds {
+        cmd = cmd.args_pair("-t", &format!("{:.3}", seconds));
+    }
+    cmd.video_filter(&format!(
+        "blackdetect=d={:.3}:pix_th={:.3}",
+        min_duration, pixel_threshold
+    ))
+    .arg("-an")
+    .arg("-sn")
+    .args_pair("-f", "null")
+    .output("-")
+    .build()
+}
+
+/// 构建画面缺陷检测命令
+///
+/// 同一次解码中串联 blackdetect 和 freezedetect，两者都把检测结果写入 stderr 日志，
+/// 输出端为 null，不生成文件
+///
+/// # 参数
+/// - `input_path` - 输入文件路径
+/// - `pixel_threshold` - 黑场像素亮度阈值（0.0-1.0）
+/// - `freeze_noise` - 静止画面噪声容差（0.0-1.0，帧间差异低于此值视为静止）
+/// - `min_duration` - 最短上报时长（秒），对黑场和静止画面都生效
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_defect_detect_command(
+    input_path: &str,
+    pixel_threshold: f64,
+    freeze_noise: f64,
+    min_duration: f64,
+) -> Vec<String> {
+    FfmpegCommand::new()
+        .with_progress()
+        .input(input_path)
+        .video_filter(&format!(
+            "blackdetect=d={d:.3}:pix_th={th:.3},freezedetect=n={n}:d={d:.3}",
+            d = min_duration,
+            th = pixel_threshold,
+            n = freeze_noise
+        ))
+        .arg("-an")
+        .arg("-sn")
+        .args_pair("-f", "null")
+        .output("-")
+        .build()
+}
+
+/// HLS master 播放列表文件名
+pub const HLS_MASTER_PLAYLIST: &str = "master.m3u8";
+/// HLS 各档清晰度的播放列表文件名（位于清晰度子目录中）
+pub const HLS_VARIANT_PLAYLIST: &str = "playlist.m3u8";
+/// DASH 清单文件名
+pub const DASH_MANIFEST: &str = "manifest.mpd";
+
+/// 码率阶梯的公共编码部分（HLS 与 DASH 共用，保证两种打包输出一致）
+///
+/// 单次解码后用 split 分出每档画面并分别缩放，各档独立设置 `-b:v/-maxrate/-bufsize`。
+/// 关键帧按分片时长强制对齐，保证各档分片边界一致、播放器可无缝切换。
+///
+/// `audio_per_rendition` 为 true 时每档各映射一路音频（HLS 变体流需要音视频成对），
+/// 为 false 时只映射一路音频、码率取各档中的最大值（DASH 音频单独成一个自适应集）
+fn build_ladder_encode(
+    params: &AdaptiveStreamParams,
+    renditions: &[StreamRendition],
+    has_audio: bool,
+    audio_per_rendition: bool,
+) -> FfmpegCommand {
+    let segment = params.segment_duration.unwrap_or(DEFAULT_HLS_SEGMENT_DURATION);
+
+    // [0:V:0]split=N[s0][s1]...;[s0]scale=-2:H0[v0];...
+    let split_labels: String = (0..renditions.len()).map(|i| format!("[s{}]", i)).collect();
+    let mut parts = vec![format!("[0:V:0]split={}{}", renditions.len(), split_labels)];
+    for (i, rendition) in renditions.iter().enumerate() {
+        parts.push(format!("[s{}]scale=-2:{}[v{}]", i, rendition.height, i));
+    }
+
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .complex_filter(&parts.join(";"));
+
+    for (i, rendition) in renditions.iter().enumerate() {
+        cmd = cmd.args_pair("-map", &format!("[v{}]", i));
+        if has_audio && audio_per_rendition {
+            cmd = cmd.args_pair("-map", "0:a:0");
+        }
+
+        let video_kbps = rendition.video_bitrate_kbps;
+        cmd = cmd
+            .args_pair(&format!("-b:v:{}", i), &format!("{}k", video_kbps))
+            .args_pair(&format!("-maxrate:v:{}", i), &format!("{}k", video_kbps * 107 / 100))
+            .args_pair(&format!("-bufsize:v:{}", i), &format!("{}k", video_kbps * 3 / 2));
+        if has_audio && audio_per_rendition {
+            let audio_kbps = rendition.audio_bitrate_kbps.unwrap_or(128);
+            cmd = cmd.args_pair(&format!("-b:a:{}", i), &format!("{}k", audio_kbps));
+        }
+    }
+    if has_audio && !audio_per_rendition {
+        let audio_kbps = renditions
+            .iter()
+            .map(|r| r.audio_bitrate_kbps.unwrap_or(128))
+            .max()
+            .unwrap_or(128);
+        cmd = cmd
+            .args_pair("-map", "0:a:0")
+            .args_pair("-b:a:0", &format!("{}k", audio_kbps));
+    }
+
+    cmd = cmd
+        .video_codec("libx264")
+        .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+        .args_pair("-pix_fmt", "yuv420p")
+        .args_pair("-sc_threshold", "0")
+        .args_pair(
+            "-force_key_frames",
+            &format!("expr:gte(t,n_forced*{})", segment),
+        );
+    if has_audio {
+        cmd = cmd.audio_codec("aac").args_pair("-ar", "48000");
+    }
+    cmd
+}
+
+/// 构建 HLS 多码率打包命令
+///
+/// 编码部分见 build_ladder_encode，各档通过 var_stream_map 组合为变体流。
+///
+/// 输出结构：`<output_dir>/<名称>/playlist.m3u8` + 分片，`<output_dir>/master.m3u8`
+///
+/// # 参数
+/// - `params` - 自适应流打包参数
+/// - `renditions` - 清晰度列表（已校验）
+/// - `has_audio` - 源文件是否有音频流（无音频时变体流只含视频）
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_hls_command(
+    params: &AdaptiveStreamParams,
+    renditions: &[StreamRendition],
+    has_audio: bool,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let segment = params.segment_duration.unwrap_or(DEFAULT_HLS_SEGMENT_DURATION);
+    let output_dir = std::path::Path::new(&params.output_dir);
+
+    let stream_map: Vec<String> = renditions
+        .iter()
+        .enumerate()
+        .map(|(i, rendition)| {
+            if has_audio {
+                format!("v:{},a:{},name:{}", i, i, rendition.name())
+            } else {
+                format!("v:{},name:{}", i, rendition.name())
+            }
+        })
+        .collect();
+
+    let segment_pattern = output_dir.join("%v").join("segment_%04d.ts");
+    let playlist_pattern = output_dir.join("%v").join(HLS_VARIANT_PLAYLIST);
+    build_ladder_encode(params, renditions, has_audio, true)
+        .args_pair("-f", "hls")
+        .args_pair("-hls_time", &format!("{}", segment))
+        .args_pair("-hls_playlist_type", "vod")
+        .args_pair("-hls_flags", "independent_segments")
+        .args_pair("-hls_segment_filename", &segment_pattern.to_string_lossy())
+        .args_pair("-master_pl_name", HLS_MASTER_PLAYLIST)
+        .args_pair("-var_stream_map", &stream_map.join(" "))
+        .output(&playlist_pattern.to_string_lossy())
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建 DASH 多码率打包命令
+///
+/// 编码部分与 HLS 相同（见 build_ladder_encode），视频各档组成一个自适应集，
+/// 音频单独一个自适应集。分片文件名以 Representation ID（即输出流序号）区分：
+/// `init-<ID>.m4s`、`chunk-<ID>-<序号>.m4s`，与清单位于同一目录
+///
+/// # 参数
+/// - `params` - 自适应流打包参数
+/// - `renditions` - 清晰度列表（已校验）
+/// - `has_audio` - 源文件是否有音频流
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_dash_command(
+    params: &AdaptiveStreamParams,
+    renditions: &[StreamRendition],
+    has_audio: bool,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let segment = params.segment_duration.unwrap_or(DEFAULT_HLS_SEGMENT_DURATION);
+    let manifest = std::path::Path::new(&params.output_dir).join(DASH_MANIFEST);
+    let adaptation_sets = if has_audio {
+        "id=0,streams=v id=1,streams=a"
+    } else {
+        "id=0,streams=v"
+    };
+
+    build_ladder_encode(params, renditions, has_audio, false)
+        .args_pair("-f", "dash")
+        .args_pair("-seg_duration", &format!("{}", segment))
+        .args_pair("-use_template", "1")
+        .args_pair("-use_timeline", "1")
+        .args_pair("-adaptation_sets", adaptation_sets)
+        .args_pair("-init_seg_name", "init-$RepresentationID$.$ext$")
+        .args_pair("-media_seg_name", "chunk-$RepresentationID$-$Number%05d$.$ext$")
+        .output(&manifest.to_string_lossy())
+        .thread_count(thread_count)
+        .build()
+}
+
+/// 构建多分辨率输出命令
+///
+/// 源视频只解码一次，用 split 分出每档画面并分别缩放，
+/// 每档一个输出文件，音频各自重新编码
+///
+/// # 参数
+/// - `params` - 多分辨率输出参数（提供输入路径和编码参数）
+/// - `outputs` - (输出高度, 输出路径) 列表
+/// - `has_audio` - 源文件是否有音频流
+/// - `thread_count` - 编码线程数上限（设置中的 ffmpeg_threads），None 时不限制
+///
+/// # 返回
+/// ffmpeg 命令行参数数组
+pub fn build_multi_resolution_command(
+    params: &MultiResolutionParams,
+    outputs: &[(u32, String)],
+    has_audio: bool,
+    thread_count: Option<u32>,
+) -> Vec<String> {
+    let split_labels: String = (0..outputs.len()).map(|i| format!("[s{}]", i)).collect();
+    let mut parts = vec![format!("[0:V:0]split={}{}", outputs.len(), split_labels)];
+    for (i, (height, _)) in outputs.iter().enumerate() {
+        parts.push(format!("[s{}]scale=-2:{}:flags=lanczos[v{}]", i, height, i));
+    }
+
+    let mut cmd = FfmpegCommand::new()
+        .with_progress()
+        .input(&params.input_path)
+        .complex_filter(&parts.join(";"));
+
+    // 多路输出：每路的选项紧跟在自己的输出路径之前，最后一路作为主输出
+    let last = outputs.len().saturating_sub(1);
+    for (i, (_, path)) in outputs.iter().enumerate() {
+        cmd = cmd
+            .args_pair("-map", &format!("[v{}]", i))
+            .video_codec("libx264")
+            .crf(params.quality.unwrap_or(DEFAULT_CRF))
+            .preset(params.preset.as_deref().unwrap_or(DEFAULT_PRESET))
+            .args_pair("-pix_fmt", "yuv420p");
+        if has_audio {
+            cmd = cmd
+                .args_pair("-map", "0:a:0")
+                .audio_codec("aac")
+                .audio_bitrate(params.audio_bitrate.as_deref().unwrap_or(DEFAULT_AUDIO_BITRATE));
+        }
+        if path.ends_with(".mp4") || path.ends_with(".mov") {
+            cmd = cmd.faststart();
+        }
+        if i < last {
+            // -threads 是逐输出的选项，前几路需各自带上
+            if let Some(threads) = thread_count {
+                cmd = cmd.args_pair("-threads", &threads.max(1).to_string());
+            }
+            cmd = cmd.arg(path);
+        }
+    }
+
+    cmd.output(outputs.get(last).map(|(_, path)| path.as_str()).unwrap_or(""))
+        .thread_count(thread_count)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::media::video_stream;
+    use serde_json::json;
+
+    /// 带进度监控的命令的固定前缀
+    const PROGRESS_PREFIX: &str = "-y -hide_banner -progress pipe:1 -nostats";
+
+    /// 从 JSON 构造参数结构（未出现的 Option 字段为 None）
+    fn params<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> T {
+        serde_json::from_value(value).expect("valid params")
+    }
+
+    /// 按空白拆分期望的 argv（滤镜等参数中不含空格）
+    fn argv(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    /// 取 argv 中某个选项后面的值（第一次出现）
+    fn value_of<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    }
+
+    #[test]
+    fn resize_fit_downscale_only_never_upscales() {
+        let resize: ResizeParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "width": 1280,
+            "height": 720,
+            "keepAspectRatio": true,
+            "aspectMode": "fit_downscale_only",
+            "quality": 23,
+            "preset": "fast",
+        }));
+        let expected = format!(
+            "{} -i in.mp4 -c:v libx264 -crf 23 -preset fast -c:a copy -movflags +faststart \
+             -vf scale='min(iw,1280)':'min(ih,720)':force_original_aspect_ratio=decrease:\
+             force_divisible_by=2:flags=lanczos out.mp4",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(build_resize_command(&resize, None), argv(&expected));
+    }
+
+    #[test]
+    fn resize_fit_downscale_only_defaults_missing_bound() {
+        let resize: ResizeParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "width": 640,
+            "keepAspectRatio": true,
+            "aspectMode": "fit_downscale_only",
+        }));
+        let args = build_resize_command(&resize, None);
+        let vf = value_of(&args, "-vf").unwrap();
+        assert!(vf.starts_with("scale='min(iw,640)':'min(ih,1080)'"));
+        assert!(!vf.contains("pad=") && !vf.contains("crop="), "{}", vf);
+    }
+
+    #[test]
+    fn compress_for_wechat_applies_only_violated_constraints() {
+        let compress: CompressParams = params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "mode": "byQuality",
+            "qualityLevel": 5,
+            "preset": "medium",
+            "platform": "compress_wechat",
+            "audioBitrate": "128k",
+        }));
+        let source = video_stream(json!({ "frameRate": 60.0 }));
+        let passes = build_compress_command(&compress, 10.0, 0, Some(&source), None, None);
+        let expected = format!(
+            "{} -i in.mov -c:v libx264 -preset medium -crf {} -c:a aac -b:a 128k \
+             -movflags +faststart -vf fps=30 out.mp4",
+            PROGRESS_PREFIX,
+            quality_level_to_crf(5)
+        );
+        assert_eq!(passes, vec![argv(&expected)]);
+    }
+
+    #[test]
+    fn merge_normalize_converts_anamorphic_inputs_to_square_pixels() {
+        let merge: MergeParams = params(json!({
+            "inputPaths": ["a.mp4", "b.mp4"],
+            "outputPath": "out.mp4",
+            "normalize": true,
+            "targetResolution": "1280x720",
+            "targetFps": 25.0,
+            "targetChannels": 2,
+            "quality": 20,
+            "preset": "fast",
+            "audioBitrate": "160k",
+        }));
+        let args = build_merge_command(&merge, "list.txt", &[5.0, 5.0], &[1.0, 1.5], None);
+        let filter = "[0:V:0]scale=1280:720:force_original_aspect_ratio=decrease,\
+                      pad=1280:720:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=25[v0];\
+                      [0:a]aresample=44100,aformat=sample_fmts=fltp:channel_layouts=stereo[a0];\
+                      [1:V:0]scale=trunc(iw*1.5/2)*2:ih,setsar=1,\
+                      scale=1280:720:force_original_aspect_ratio=decrease,\
+                      pad=1280:720:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=25[v1];\
+                      [1:a]aresample=44100,aformat=sample_fmts=fltp:channel_layouts=stereo[a1];\
+                      [v0][a0][v1][a1]concat=n=2:v=1:a=1[v][a]";
+        let expected = format!(
+            "{} -i a.mp4 -i b.mp4 -c:v libx264 -crf 20 -preset fast -c:a aac -b:a 160k \
+             -movflags +faststart -map [v] -map [a] -filter_complex {} out.mp4",
+            PROGRESS_PREFIX, filter
+        );
+        assert_eq!(args, argv(&expected));
+    }
+
+    #[test]
+    fn merge_normalize_keeps_surround_channel_layout() {
+        let merge: MergeParams = params(json!({
+            "inputPaths": ["a.mkv", "b.mkv"],
+            "outputPath": "out.mkv",
+            "normalize": true,
+            "targetResolution": "1920x1080",
+            "targetChannels": 6,
+        }));
+        let args = build_merge_command(&merge, "list.txt", &[5.0, 5.0], &[1.0, 1.0], None);
+        let filter = value_of(&args, "-filter_complex").unwrap();
+        for i in 0..2 {
+            let audio = format!(
+                "[{i}:a]aresample=44100,aformat=sample_fmts=fltp:channel_layouts=5.1[a{i}]",
+                i = i
+            );
+            assert!(filter.contains(&audio), "{}", filter);
+        }
+        assert!(!filter.contains("stereo"), "{}", filter);
+
+        assert_eq!(channel_layout_for(1), "mono");
+        assert_eq!(channel_layout_for(8), "7.1");
+        assert_eq!(channel_layout_for(3), "3c");
+    }
+
+    #[test]
+    fn merge_without_normalize_uses_concat_demuxer() {
+        let merge: MergeParams = params(json!({
+            "inputPaths": ["a.mp4", "b.mp4"],
+            "outputPath": "out.mp4",
+            "normalize": false,
+        }));
+        let expected = format!(
+            "{} -f concat -safe 0 -i list.txt -c:v copy -c:a copy out.mp4",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(
+            build_merge_command(&merge, "list.txt", &[5.0, 5.0], &[1.0, 1.0], None),
+            argv(&expected)
+        );
+    }
+
+    #[test]
+    fn compress_for_bilibili_forces_h264_profile_and_level() {
+        let compress: CompressParams = params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "mode": "byQuality",
+            "videoCodec": "libx265",
+            "platform": "bilibili",
+        }));
+        let source = video_stream(json!({ "codecName": "hevc", "profile": "Main" }));
+        let args = build_compress_command(&compress, 10.0, 0, Some(&source), None, None).remove(0);
+        assert_eq!(value_of(&args, "-c:v"), Some("libx264"));
+        assert_eq!(value_of(&args, "-profile:v"), Some("high"));
+        assert_eq!(value_of(&args, "-level"), Some("4.1"));
+        assert_eq!(value_of(&args, "-vf"), None);
+    }
+
+    #[test]
+    fn convert_passes_crf_and_preset_to_x264() {
+        let convert: ConvertParams = params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "outputFormat": "mp4",
+            "videoCodec": "libx264",
+            "audioCodec": "aac",
+            "quality": 20,
+            "preset": "slow",
+        }));
+        let args = build_convert_command(&convert, 0, None);
+        assert_eq!(value_of(&args, "-crf"), Some("20"));
+        assert_eq!(value_of(&args, "-preset"), Some("slow"));
+    }
+
+    #[test]
+    fn convert_omits_crf_and_preset_for_unsupported_encoders() {
+        for codec in ["prores_ks", "gif", "h264_videotoolbox"] {
+            let convert: ConvertParams = params(json!({
+                "inputPath": "in.mov",
+                "outputPath": "out.mov",
+                "outputFormat": "mov",
+                "videoCodec": codec,
+                "audioCodec": "aac",
+                "quality": 20,
+                "preset": "slow",
+            }));
+            let args = build_convert_command(&convert, 0, None);
+            assert_eq!(value_of(&args, "-c:v"), Some(codec));
+            assert_eq!(value_of(&args, "-crf"), None, "{}", codec);
+            assert_eq!(value_of(&args, "-preset"), None, "{}", codec);
+        }
+    }
+
+    #[test]
+    fn convert_av1_keeps_crf_without_x264_preset() {
+        let convert: ConvertParams = params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mkv",
+            "outputFormat": "mkv",
+            "videoCodec": "libaom-av1",
+            "audioCodec": "aac",
+            "quality": 30,
+            "preset": "medium",
+        }));
+        let args = build_convert_command(&convert, 0, None);
+        assert_eq!(value_of(&args, "-crf"), Some("30"));
+        assert_eq!(value_of(&args, "-preset"), None);
+    }
+
+    /// 构造指定视频编码器和色彩范围的 MP4 格式转换参数
+    fn convert_with_range(video_codec: &str, color_range: &str) -> ConvertParams {
+        params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "outputFormat": "mp4",
+            "videoCodec": video_codec,
+            "audioCodec": "copy",
+            "colorRange": color_range,
+        }))
+    }
+
+    #[test]
+    fn convert_reencode_rewrites_color_range() {
+        let expected = format!(
+            "{} -i in.mov -c:v libx264 -color_range pc -c:a copy -movflags +faststart \
+             -map_metadata 0 -map_chapters 0 -map_metadata:s:v 0:s:v -map_metadata:s:a 0:s:a \
+             -vf setrange=pc out.mp4",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(
+            build_convert_command(&convert_with_range("libx264", "pc"), 0, None),
+            argv(&expected)
+        );
+    }
+
+    #[test]
+    fn convert_stream_copy_only_retags_color_range() {
+        let args = build_convert_command(&convert_with_range("copy", "tv"), 0, None);
+        assert_eq!(value_of(&args, "-color_range"), Some("tv"));
+        assert_eq!(value_of(&args, "-vf"), None);
+    }
+
+    #[test]
+    fn convert_pixel_format_follows_encoder_options() {
+        let mut convert = convert_with_range("libx264", "tv");
+        convert.quality = Some(20);
+        convert.pixel_format = Some("yuv420p10le".to_string());
+        let expected = format!(
+            "{} -i in.mov -c:v libx264 -crf 20 -pix_fmt yuv420p10le -color_range tv -c:a copy \
+             -movflags +faststart -map_metadata 0 -map_chapters 0 -map_metadata:s:v 0:s:v \
+             -map_metadata:s:a 0:s:a -vf setrange=tv out.mp4",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(build_convert_command(&convert, 0, None), argv(&expected));
+
+        convert.video_codec = "copy".to_string();
+        let args = build_convert_command(&convert, 0, None);
+        assert_eq!(value_of(&args, "-pix_fmt"), None);
+    }
+
+    #[test]
+    fn convert_ignores_unknown_color_range() {
+        let args = build_convert_command(&convert_with_range("libx264", "jpeg"), 0, None);
+        assert_eq!(value_of(&args, "-color_range"), None);
+        assert_eq!(value_of(&args, "-vf"), None);
+    }
+
+    #[test]
+    fn precise_trim_falls_back_to_default_crf_and_preset() {
+        let trim: TrimParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "segments": [],
+            "preciseCut": true,
+            "mergeSegments": false,
+        }));
+        let args = build_trim_segment_command(&trim, "out.mp4", 0.0, 2.0, false, None);
+        assert_eq!(value_of(&args, "-crf"), Some(DEFAULT_CRF.to_string().as_str()));
+        assert_eq!(value_of(&args, "-preset"), Some(DEFAULT_PRESET));
+    }
+
+    #[test]
+    fn resize_uses_requested_crf_and_preset() {
+        let resize: ResizeParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "width": 1280,
+            "height": 720,
+            "keepAspectRatio": true,
+            "quality": 26,
+            "preset": "veryfast",
+        }));
+        let args = build_resize_command(&resize, None);
+        assert_eq!(value_of(&args, "-crf"), Some("26"));
+        assert_eq!(value_of(&args, "-preset"), Some("veryfast"));
+    }
+
+    /// 构造指定输出路径和播放次数的 GIF 参数
+    fn gif(output_path: &str, loop_count: i32) -> GifParams {
+        params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": output_path,
+            "startTime": 1.0,
+            "duration": 3.0,
+            "width": 480,
+            "fps": 12,
+            "loopCount": loop_count,
+        }))
+    }
+
+    #[test]
+    fn gif_loop_count_maps_to_muxer_repeats() {
+        for (count, flag) in [(0, "0"), (-1, "-1"), (1, "-1"), (5, "4")] {
+            let args = build_gif_command(&gif("out.gif", count), None);
+            assert_eq!(value_of(&args, "-loop"), Some(flag), "loopCount {}", count);
+            assert!(value_of(&args, "-filter_complex").unwrap().contains("palettegen"));
+        }
+    }
+
+    #[test]
+    fn webp_loop_count_maps_to_total_plays() {
+        for (count, flag) in [(0, "0"), (-1, "1"), (1, "1"), (5, "5")] {
+            let args = build_gif_command(&gif("out.webp", count), None);
+            assert_eq!(value_of(&args, "-loop"), Some(flag), "loopCount {}", count);
+        }
+    }
+
+    #[test]
+    fn webp_output_uses_libwebp_without_palette() {
+        let expected = format!(
+            "{} -ss 00:00:01.000 -t 00:00:03.000 -i in.mp4 -map [gv] -c:v libwebp -q:v 75 -an \
+             -loop 0 -filter_complex [0:v]fps=12,scale=480:-1:flags=lanczos[gv] out.webp",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(build_gif_command(&gif("out.webp", 0), None), argv(&expected));
+    }
+
+    /// 构造方向校正参数
+    fn auto_orient() -> AutoOrientParams {
+        params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "quality": 20,
+            "preset": "fast",
+        }))
+    }
+
+    #[test]
+    fn auto_orient_reencodes_with_ffmpeg_autorotation() {
+        let expected = format!(
+            "{} -i in.mov -c:v libx264 -crf 20 -preset fast -c:a copy out.mp4",
+            PROGRESS_PREFIX
+        );
+        for rotation in [90, 180, 270] {
+            let args = build_auto_orient_command(&auto_orient(), rotation, None);
+            assert_eq!(args, argv(&expected), "rotation {}", rotation);
+        }
+    }
+
+    #[test]
+    fn auto_orient_copies_unrotated_sources() {
+        let expected = format!("{} -i in.mov -c copy out.mp4", PROGRESS_PREFIX);
+        for rotation in [0, 45] {
+            assert_eq!(build_auto_orient_command(&auto_orient(), rotation, None), argv(&expected));
+        }
+    }
+
+    #[test]
+    fn speed_4x_chains_two_atempo_filters() {
+        let speed: SpeedParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "videoSpeed": 4.0,
+            "audioSpeed": 4.0,
+            "keepAudio": true,
+        }));
+        let expected = format!(
+            "{} -i in.mp4 -c:v libx264 -crf {} -preset {} -c:a aac -b:a {} \
+             -movflags +faststart -vf setpts=PTS/4 -af atempo=2,atempo=2 out.mp4",
+            PROGRESS_PREFIX, DEFAULT_CRF, DEFAULT_PRESET, DEFAULT_AUDIO_BITRATE
+        );
+        assert_eq!(build_speed_command(&speed, 48000, None), argv(&expected));
+
+        assert_eq!(atempo_chain(8.0), ["atempo=2", "atempo=2", "atempo=2"]);
+        assert_eq!(atempo_chain(0.3), ["atempo=0.5", "atempo=0.6"]);
+        assert_eq!(atempo_chain(1.0), ["atempo=1"]);
+    }
+
+    #[test]
+    fn transform_rotate_90_with_horizontal_flip() {
+        let transform: TransformParams = params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "rotate": 90,
+            "flipH": true,
+        }));
+        let expected = format!(
+            "{} -i in.mov -c:v libx264 -crf {} -preset {} -c:a copy \
+             -metadata:s:v:0 rotate=0 -vf transpose=clock,hflip out.mp4",
+            PROGRESS_PREFIX, DEFAULT_CRF, DEFAULT_PRESET
+        );
+        assert_eq!(build_transform_command(&transform, None), argv(&expected));
+    }
+
+    #[test]
+    fn transform_filter_combines_rotation_and_flips() {
+        let cases = [
+            ((90, true, false), Some("transpose=clock,hflip")),
+            ((-90, false, true), Some("transpose=cclock,vflip")),
+            ((270, false, false), Some("transpose=cclock")),
+            ((180, false, false), Some("hflip,vflip")),
+            ((180, true, false), Some("vflip")),
+            ((180, true, true), None),
+            ((0, false, false), None),
+            ((360, false, true), Some("vflip")),
+        ];
+        for ((rotate, flip_h, flip_v), expected) in cases {
+            assert_eq!(
+                transform_filter(rotate, flip_h, flip_v).as_deref(),
+                expected,
+                "rotate {} flip_h {} flip_v {}",
+                rotate,
+                flip_h,
+                flip_v
+            );
+        }
+    }
+
+    #[test]
+    fn strip_subtitles_maps_everything_but_subtitle_streams() {
+        let expected = format!("{} -i in.mkv -map 0 -map -0:s -c copy out.mkv", PROGRESS_PREFIX);
+        assert_eq!(build_strip_subtitles_command("in.mkv", "out.mkv"), argv(&expected));
+    }
+
+    #[test]
+    fn watermark_overlays_on_first_real_video_stream() {
+        let watermark: WatermarkParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "watermarkType": "image",
+            "imagePath": "logo.png",
+            "position": "topLeft",
+        }));
+        let filter = "[1:v]null[wm];[0:V:0][wm]overlay=10:10";
+        let args = build_watermark_command(&watermark, None);
+        assert_eq!(value_of(&args, "-filter_complex"), Some(filter));
+    }
+
+    #[test]
+    fn merge_audio_crossfade_maps_first_real_video_stream() {
+        let merge: MergeParams = params(json!({
+            "inputPaths": ["a.mp3", "b.mp3"],
+            "outputPath": "out.mp4",
+            "normalize": false,
+            "audioOnlyCrossfade": 1.0,
+        }));
+        let args = build_merge_command(&merge, "list.txt", &[5.0, 5.0], &[1.0, 1.0], None);
+        assert_eq!(value_of(&args, "-map"), Some("0:V:0"));
+    }
+
+    #[test]
+    fn precise_trim_uses_requested_audio_bitrate() {
+        let trim: TrimParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "segments": [],
+            "preciseCut": true,
+            "mergeSegments": false,
+            "audioBitrate": "192k",
+            "quality": 20,
+            "preset": "slow",
+        }));
+        let args = build_trim_segment_command(&trim, "out.mp4", 1.0, 3.5, false, None);
+        assert_eq!(value_of(&args, "-c:a"), Some("aac"));
+        assert_eq!(value_of(&args, "-b:a"), Some("192k"));
+        assert_eq!(value_of(&args, "-crf"), Some("20"));
+        assert_eq!(value_of(&args, "-preset"), Some("slow"));
+    }
+
+    #[test]
+    fn precise_trim_falls_back_to_default_audio_bitrate() {
+        let trim: TrimParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "segments": [],
+            "preciseCut": true,
+            "mergeSegments": false,
+        }));
+        let args = build_trim_segment_command(&trim, "out.mp4", 0.0, 2.0, false, None);
+        assert_eq!(value_of(&args, "-b:a"), Some(DEFAULT_AUDIO_BITRATE));
+    }
+
+    /// 构造指定画面内容类型的按质量压缩参数
+    fn compress_content(content_type: &str) -> CompressParams {
+        params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "mode": "byQuality",
+            "qualityLevel": 5,
+            "contentType": content_type,
+        }))
+    }
+
+    #[test]
+    fn compress_film_uses_film_tune_without_gop() {
+        let compress = compress_content("film");
+        let args = build_compress_command(&compress, 10.0, 0, None, None, None).remove(0);
+        assert_eq!(value_of(&args, "-tune"), Some("film"));
+        assert_eq!(value_of(&args, "-g"), None);
+    }
+
+    #[test]
+    fn compress_animation_uses_animation_tune_without_gop() {
+        let compress = compress_content("animation");
+        let args = build_compress_command(&compress, 10.0, 0, None, None, None).remove(0);
+        assert_eq!(value_of(&args, "-tune"), Some("animation"));
+        assert_eq!(value_of(&args, "-g"), None);
+    }
+
+    #[test]
+    fn compress_screencast_uses_stillimage_tune_and_long_gop() {
+        let source = video_stream(json!({ "frameRate": 30.0 }));
+        let compress = compress_content("screencast");
+        let args = build_compress_command(&compress, 10.0, 0, Some(&source), None, None).remove(0);
+        assert_eq!(value_of(&args, "-tune"), Some("stillimage"));
+        assert_eq!(value_of(&args, "-g"), Some("300"));
+    }
+
+    /// 构造批量缩略图参数
+    fn thumbnail_series(mode: &str) -> ThumbnailSeriesParams {
+        params(json!({
+            "inputPath": "in.mp4",
+            "outputDir": "thumbs",
+            "mode": mode,
+            "intervalSec": 5.0,
+            "width": 320,
+        }))
+    }
+
+    #[test]
+    fn thumbnail_series_interval_maps_to_fps_filter() {
+        let series = thumbnail_series("byInterval");
+        for (interval, filter) in [(5.0, "fps=1/5.000"), (2.5, "fps=1/2.500")] {
+            let args =
+                build_thumbnail_series_command(&series, interval, 0.0, None, "t_%04d.jpg", None);
+            let expected = format!("{},scale=320:-2", filter);
+            assert_eq!(value_of(&args, "-vf"), Some(expected.as_str()));
+            assert_eq!(value_of(&args, "-frames:v"), None);
+        }
+    }
+
+    #[test]
+    fn thumbnail_series_by_count_seeks_and_limits_frames() {
+        let series = thumbnail_series("byCount");
+        let expected = format!(
+            "{} -ss 00:00:05.000 -i in.mp4 -frames:v 6 -an -q:v 2 \
+             -vf fps=1/10.000,scale=320:-2 t_%04d.jpg",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(
+            build_thumbnail_series_command(&series, 10.0, 5.0, Some(6), "t_%04d.jpg", None),
+            argv(&expected)
+        );
+    }
+
+    #[test]
+    fn convert_maps_selected_audio_tracks_and_kept_subtitles() {
+        let convert: ConvertParams = params(json!({
+            "inputPath": "in.mkv",
+            "outputPath": "out.mp4",
+            "outputFormat": "mp4",
+            "videoCodec": "copy",
+            "audioCodec": "aac",
+            "audioStreamIndexes": [2, 0],
+            "keepAllStreams": true,
+        }));
+        let expected = format!(
+            "{} -i in.mkv -c:v copy -c:a aac -b:a 128k -c:s mov_text -movflags +faststart \
+             -map_metadata 0 -map_chapters 0 -map 0:v:0? -map 0:a:2 -map 0:a:0 -map 0:s? out.mp4",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(build_convert_command(&convert, 0, None), argv(&expected));
+    }
+
+    #[test]
+    fn convert_keep_all_streams_maps_every_audio_track() {
+        let convert: ConvertParams = params(json!({
+            "inputPath": "in.mkv",
+            "outputPath": "out.mkv",
+            "outputFormat": "mkv",
+            "videoCodec": "copy",
+            "audioCodec": "copy",
+            "keepAllStreams": true,
+        }));
+        assert_eq!(convert_stream_maps(&convert), ["0:v:0?", "0:a?", "0:s?"]);
+        let args = build_convert_command(&convert, 0, None);
+        assert_eq!(value_of(&args, "-c:s"), Some("copy"));
+    }
+
+
+    #[test]
+    fn thread_count_is_an_output_option_before_the_output_path() {
+        let resize: ResizeParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "width": 1280,
+            "height": 720,
+            "keepAspectRatio": true,
+            "quality": 23,
+            "preset": "fast",
+        }));
+        let args = build_resize_command(&resize, Some(4));
+        let threads = args.iter().position(|a| a == "-threads").unwrap();
+        let input = args.iter().position(|a| a == "-i").unwrap();
+        assert!(threads > input, "{:?}", args);
+        assert_eq!(&args[threads..], ["-threads", "4", "out.mp4"]);
+
+        let unlimited = build_resize_command(&resize, None);
+        assert!(!unlimited.iter().any(|a| a == "-threads"), "{:?}", unlimited);
+    }
+
+    #[test]
+    fn thread_count_applies_to_every_output() {
+        let outputs = [
+            ("mp3".to_string(), "a.mp3".to_string()),
+            ("flac".to_string(), "a.flac".to_string()),
+        ];
+        let args = build_extract_multi_format_command("in.mp4", 0, &outputs, Some(2));
+        let output_positions: Vec<usize> = ["a.mp3", "a.flac"]
+            .iter()
+            .map(|path| args.iter().position(|a| a == path).unwrap())
+            .collect();
+        for position in output_positions {
+            assert_eq!(&args[position - 2..position], ["-threads", "2"], "{:?}", args);
+        }
+    }
+
+
+    /// 构造 ffprobe 探测结果
+    fn probe(streams: serde_json::Value) -> FfprobeOutput {
+        serde_json::from_value(json!({
+            "streams": streams,
+            "format": { "duration": "10.0" },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn convert_without_video_streams_uses_audio_only_command() {
+        let convert: ConvertParams = params(json!({
+            "inputPath": "in.mp3",
+            "outputPath": "out.m4a",
+            "outputFormat": "m4a",
+            "videoCodec": "libx264",
+            "audioCodec": "aac",
+            "audioBitrate": "192k",
+            "deinterlace": true,
+        }));
+        // MP3 封面图以视频流形式存放，但不算视频
+        let source = probe(json!([
+            { "index": 0, "codec_type": "audio", "codec_name": "mp3" },
+            { "index": 1, "codec_type": "video", "codec_name": "mjpeg",
+              "width": 500, "height": 500, "disposition": { "attached_pic": 1 } },
+        ]));
+        let args = build_convert_command_for_source(&convert, &source, None);
+        assert_eq!(args, build_convert_audio_only_command(&convert, None));
+        assert!(args.iter().any(|a| a == "-vn"), "{:?}", args);
+        assert_eq!(value_of(&args, "-c:v"), None);
+        assert_eq!(value_of(&args, "-vf"), None);
+        assert_eq!(value_of(&args, "-c:a"), Some("aac"));
+
+        let with_video = probe(json!([
+            { "index": 0, "codec_type": "video", "codec_name": "h264",
+              "width": 1920, "height": 1080 },
+            { "index": 1, "codec_type": "audio", "codec_name": "aac" },
+        ]));
+        let args = build_convert_command_for_source(&convert, &with_video, None);
+        assert_eq!(value_of(&args, "-c:v"), Some("libx264"));
+        assert!(value_of(&args, "-vf").is_some_and(|vf| vf.contains("yadif")), "{:?}", args);
+    }
+
+    /// 构造带 rotate 标签的手机竖拍视频 ffprobe 输出
+    fn rotated_probe(rotate: u32) -> FfprobeOutput {
+        probe(json!([
+            { "index": 0, "codec_type": "video", "codec_name": "h264",
+              "width": 1920, "height": 1080, "tags": { "rotate": rotate.to_string() } },
+        ]))
+    }
+
+    /// 构造指定视频编码器的 MOV → MP4 格式转换参数
+    fn rotated_convert(video_codec: &str) -> ConvertParams {
+        params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "outputFormat": "mp4",
+            "videoCodec": video_codec,
+            "audioCodec": "copy",
+        }))
+    }
+
+    #[test]
+    fn convert_rotated_sources_bakes_in_orientation() {
+        let convert = rotated_convert("libx264");
+        let cases = [
+            (90, "transpose=clock"),
+            (180, "transpose=clock,transpose=clock"),
+            (270, "transpose=cclock"),
+        ];
+        for (rotate, filter) in cases {
+            let args = build_convert_command_for_source(&convert, &rotated_probe(rotate), None);
+            assert_eq!(value_of(&args, "-noautorotate"), Some("-i"), "rotation {}", rotate);
+            assert_eq!(value_of(&args, "-vf"), Some(filter), "rotation {}", rotate);
+            assert_eq!(value_of(&args, "-metadata:s:v:0"), Some("rotate=0"));
+        }
+    }
+
+    #[test]
+    fn convert_rotated_sources_keeps_tag_without_auto_rotate() {
+        let mut convert = rotated_convert("libx264");
+        convert.auto_rotate = Some(false);
+        for rotate in [90, 180, 270] {
+            let args = build_convert_command_for_source(&convert, &rotated_probe(rotate), None);
+            assert_eq!(value_of(&args, "-noautorotate"), Some("-i"));
+            assert_eq!(value_of(&args, "-vf"), None, "rotation {}", rotate);
+            let tag = format!("rotate={}", rotate);
+            assert_eq!(value_of(&args, "-metadata:s:v:0"), Some(tag.as_str()));
+        }
+
+        let copy = rotated_convert("copy");
+        let args = build_convert_command_for_source(&copy, &rotated_probe(90), None);
+        assert!(!args.iter().any(|a| a == "-noautorotate"), "{:?}", args);
+        assert_eq!(value_of(&args, "-metadata:s:v:0"), Some("rotate=90"));
+    }
+
+
+    /// 目标 10 MB、时长 10 秒：视频码率 10 × 1024 × 8 / 10 − 128 = 8064k
+    fn compress_by_size(strict_cbr: bool) -> Vec<String> {
+        let compress: CompressParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "mode": "bySize",
+            "targetSizeMb": 10.0,
+            "strictCbr": strict_cbr,
+        }));
+        build_compress_command(&compress, 10.0, 0, None, None, None).remove(0)
+    }
+
+    #[test]
+    fn compress_strict_cbr_locks_rate_and_buffer_to_target() {
+        let args = compress_by_size(true);
+        let start = args.iter().position(|a| a == "-b:v").unwrap();
+        assert_eq!(
+            &args[start..start + 8],
+            ["-b:v", "8064k", "-minrate", "8064k", "-maxrate", "8064k", "-bufsize", "8064k"]
+        );
+    }
+
+    #[test]
+    fn compress_by_size_without_strict_cbr_caps_vbr() {
+        let args = compress_by_size(false);
+        assert_eq!(value_of(&args, "-b:v"), Some("8064k"));
+        assert_eq!(value_of(&args, "-minrate"), None);
+        assert_eq!(value_of(&args, "-maxrate"), Some("12096k"));
+        assert_eq!(value_of(&args, "-bufsize"), Some("16128k"));
+    }
+
+
+    #[test]
+    fn hevc_hardware_encoding_uses_videotoolbox_with_hvc1_tag() {
+        let convert: ConvertParams = params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "outputFormat": "mp4",
+            "videoCodec": "libx265",
+            "audioCodec": "copy",
+            "hardwareAccel": true,
+        }));
+        let args = build_convert_command(&convert, 0, None);
+        assert_eq!(value_of(&args, "-c:v"), Some("hevc_videotoolbox"));
+        assert_eq!(value_of(&args, "-tag:v"), Some("hvc1"));
+
+        let compress: CompressParams = params(json!({
+            "inputPath": "in.mov",
+            "outputPath": "out.mp4",
+            "mode": "byQuality",
+            "videoCodec": "libx265",
+            "hardwareAccel": true,
+        }));
+        let args = build_compress_command(&compress, 10.0, 0, None, None, None).remove(0);
+        assert_eq!(value_of(&args, "-c:v"), Some("hevc_videotoolbox"));
+        assert_eq!(value_of(&args, "-tag:v"), Some("hvc1"));
+    }
+
+
+    #[test]
+    fn frame_accurate_trim_seeks_after_input_with_absolute_end() {
+        let trim: TrimParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "segments": [],
+            "preciseCut": true,
+            "mergeSegments": false,
+            "quality": 20,
+            "preset": "slow",
+            "accuracy": "frame",
+        }));
+        let args = build_trim_segment_command(&trim, "out.mp4", 5.0, 12.5, true, None);
+        let expected = format!(
+            "{} -i in.mp4 -ss 00:00:05.000 -to 00:00:12.500 -c:v libx264 -crf 20 -preset slow \
+             -c:a aac -b:a {} -movflags +faststart out.mp4",
+            PROGRESS_PREFIX, DEFAULT_AUDIO_BITRATE
+        );
+        assert_eq!(args, argv(&expected));
+        assert_eq!(value_of(&args, "-t"), None);
+    }
+
+
+    /// 构造指定容器、音频编码和采样率的格式转换参数
+    fn convert_audio(output_format: &str, audio_codec: &str, sample_rate: u32) -> ConvertParams {
+        params(json!({
+            "inputPath": "in.mov",
+            "outputPath": format!("out.{}", output_format),
+            "outputFormat": output_format,
+            "videoCodec": "copy",
+            "audioCodec": audio_codec,
+            "audioBitrate": "192k",
+            "audioSampleRate": sample_rate,
+        }))
+    }
+
+    #[test]
+    fn convert_audio_copy_ignores_bitrate_and_sample_rate() {
+        let args = build_convert_command(&convert_audio("mp4", "copy", 44100), 0, None);
+        assert_eq!(value_of(&args, "-c:a"), Some("copy"));
+        assert_eq!(value_of(&args, "-b:a"), None);
+        assert_eq!(value_of(&args, "-ar"), None);
+    }
+
+    #[test]
+    fn convert_audio_aac_sets_bitrate_and_sample_rate() {
+        let args = build_convert_command(&convert_audio("mp4", "aac", 44100), 0, None);
+        let start = args.iter().position(|a| a == "-c:a").unwrap();
+        assert_eq!(&args[start..start + 6], ["-c:a", "aac", "-b:a", "192k", "-ar", "44100"]);
+    }
+
+    #[test]
+    fn convert_audio_for_webm_is_replaced_with_opus() {
+        let args = build_convert_command(&convert_audio("webm", "aac", 48000), 0, None);
+        let start = args.iter().position(|a| a == "-c:a").unwrap();
+        assert_eq!(&args[start..start + 6], ["-c:a", "libopus", "-b:a", "192k", "-ar", "48000"]);
+    }
+
+
+    #[test]
+    fn merge_three_inputs_chains_crossfade_offsets() {
+        let merge: MergeParams = params(json!({
+            "inputPaths": ["a.mp4", "b.mp4", "c.mp4"],
+            "outputPath": "out.mp4",
+            "normalize": true,
+            "transition": { "transitionType": "fade", "duration": 1.0 },
+        }));
+        let args = build_merge_command(&merge, "list.txt", &[10.0, 8.0, 6.0], &[1.0; 3], None);
+        let filter = value_of(&args, "-filter_complex").unwrap();
+        let offsets: Vec<&str> = filter
+            .split("offset=")
+            .skip(1)
+            .map(|rest| rest.split('[').next().unwrap())
+            .collect();
+        // 第 i 次转场的 offset = 前 i+1 段时长之和 − (i+1) × 转场时长
+        assert_eq!(offsets, ["9", "16"]);
+        assert!(filter.contains("[v0][v1]xfade=transition=fade:duration=1:offset=9[vt0]"));
+        assert!(filter.contains("[vt0][v2]xfade=transition=fade:duration=1:offset=16[vt1]"));
+        assert!(filter.contains("[at0][a2]acrossfade=d=1[at1]"), "{}", filter);
+        assert_eq!(value_of(&args, "-map"), Some("[vt1]"));
+    }
+
+    /// 按出现顺序收集所有 -map 的流说明符
+    fn maps(args: &[String]) -> Vec<&str> {
+        args.windows(2)
+            .filter(|pair| pair[0] == "-map")
+            .map(|pair| pair[1].as_str())
+            .collect()
+    }
+
+    #[test]
+    fn merge_maps_follow_codec_options_and_precede_filter_graph() {
+        let merge: MergeParams = params(json!({
+            "inputPaths": ["a.mp4", "b.mp4", "c.mp4"],
+            "outputPath": "out.mp4",
+            "normalize": true,
+            "transition": { "transitionType": "fade", "duration": 1.0 },
+        }));
+        let args = build_merge_command(&merge, "list.txt", &[10.0, 8.0, 6.0], &[1.0; 3], None);
+        assert_eq!(maps(&args), ["[vt1]", "[at1]"]);
+        let position = |flag: &str| args.iter().position(|a| a == flag).unwrap();
+        assert!(position("-movflags") < position("-map"), "{:?}", args);
+        assert!(position("-map") < position("-filter_complex"), "{:?}", args);
+        assert_eq!(args.last().map(String::as_str), Some("out.mp4"));
+
+        let audio_only: MergeParams = params(json!({
+            "inputPaths": ["a.mp3", "b.mp3"],
+            "outputPath": "out.mp4",
+            "normalize": false,
+            "audioOnlyCrossfade": 1.0,
+        }));
+        let args = build_merge_command(&audio_only, "list.txt", &[5.0, 5.0], &[1.0, 1.0], None);
+        assert_eq!(maps(&args), ["0:V:0", "[ax0]"]);
+    }
+
+    #[test]
+    fn audio_replace_maps_video_then_new_audio() {
+        let replace: AudioParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "mode": "replace",
+            "replaceAudioPath": "music.mp3",
+            "audioBitrate": "192k",
+        }));
+        let expected = format!(
+            "{} -i in.mp4 -i music.mp3 -t 00:00:12.500 -c:v copy -c:a aac -b:a 192k \
+             -map 0:V:0 -map 1:a:0 -af apad=whole_dur=12.5 out.mp4",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(build_audio_command(&replace, 12.5, &[], None), argv(&expected));
+    }
+
+    #[test]
+    fn subtitle_embed_maps_before_subtitle_metadata() {
+        let embed: SubtitleParams = params(json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "mode": "embed",
+            "subtitlePath": "subs.srt",
+        }));
+        let expected = format!(
+            "{} -i in.mp4 -i subs.srt -c:v copy -c:a copy -c:s mov_text -disposition:s:0 default \
+             -map 0:v -map 0:a? -map 1:0 -metadata:s:s:0 handler_name=SubtitleHandler out.mp4",
+            PROGRESS_PREFIX
+        );
+        assert_eq!(build_subtitle_command(&embed, None), argv(&expected));
+    }
+
+    #[test]
+    fn convert_codec_and_hardware_matrix() {
+        // (容器, 编码器, 硬件加速, 目标码率) → (-c:v, -b:v, -crf, -tag:v)
+        let cases = [
+            ("mp4", "libx264", false, None, "libx264", None, Some("23"), None),
+            ("mp4", "libx264", true, None, "h264_videotoolbox", Some("5M"), None, None),
+            ("mp4", "libx264", true, Some("8M"), "h264_videotoolbox", Some("8M"), None, None),
+            ("mp4", "libx264", false, Some("8M"), "libx264", Some("8M"), None, None),
+            ("mp4", "libx265", false, None, "libx265", None, Some("23"), Some("hvc1")),
+            ("mp4", "libx265", true, None, "hevc_videotoolbox", Some("5M"), None, Some("hvc1")),
+            ("mp4", "copy", true, None, "copy", None, None, None),
+            ("webm", "libx264", true, None, "libvpx-vp9", Some("0"), Some("23"), None),
+        ];
+        for (format, codec, hardware, bitrate, encoder, b_v, crf, tag) in cases {
+            let convert: ConvertParams = params(json!({
+                "inputPath": "in.mov",
+                "outputPath": format!("out.{}", format),
+                "outputFormat": format,
+                "videoCodec": codec,
+                "audioCodec": "copy",
+                "quality": 23,
+                "hardwareAccel": hardware,
+                "videoBitrate": bitrate,
+            }));
+            let args = build_convert_command(&convert, 0, None);
+            let case = format!("{} {} hardware={} bitrate={:?}", format, codec, hardware, bitrate);
+            assert_eq!(value_of(&args, "-c:v"), Some(encoder), "{}", case);
+            assert_eq!(value_of(&args, "-b:v"), b_v, "{}", case);
+            assert_eq!(value_of(&args, "-crf"), crf, "{}", case);
+            assert_eq!(value_of(&args, "-tag:v"), tag, "{}", case);
+        }
+    }
+}
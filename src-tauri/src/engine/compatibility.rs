@@ -0,0 +1,140 @@
+/// 输出容器与编码的兼容性表
+///
+/// 格式转换前校验流复制/重编码后的编码能否封装进目标容器，
+/// 下载时也用它判断是否需要转码。纯查表，不依赖 Tauri
+
+/// 编码与容器的兼容程度
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compatibility {
+    /// 可以封装
+    Supported,
+    /// 可以封装，但部分播放器无法播放（附带提示原因）
+    Limited(&'static str),
+    /// 封装器会拒绝
+    Unsupported,
+}
+
+/// 查询编码（ffprobe 的 codec_name）在输出容器中的兼容程度
+///
+/// 只收录界面提供的容器；未收录的容器或编码不做判断，视为可以封装。
+/// WebM 输出重编码时会自动替换为 VP9/Opus，只有流复制才需要查表
+pub fn container_compatibility(format: &str, codec: &str) -> Compatibility {
+    use Compatibility::{Limited, Supported, Unsupported};
+
+    let (supported, limited): (&[&str], &[(&str, &'static str)]) = match format {
+        "mp4" => (
+            &["h264", "hevc", "av1", "mpeg4", "aac", "mp3", "alac", "ac3", "eac3"],
+            &[
+                ("flac", "FLAC in MP4 is not supported by QuickTime or most browsers"),
+                ("opus", "Opus in MP4 is not supported by QuickTime"),
+            ],
+        ),
+        "mov" => (
+            &[
+                "h264", "hevc", "prores", "mjpeg", "mpeg4", "aac", "mp3", "alac", "ac3",
+                "pcm_s16le", "pcm_s24le",
+            ],
+            &[("flac", "FLAC in MOV is not supported by QuickTime")],
+        ),
+        "webm" => (&["vp8", "vp9", "av1", "vorbis", "opus"], &[]),
+        "avi" => (
+            &["h264", "mpeg4", "mjpeg", "mp3", "ac3", "pcm_s16le"],
+            &[
+                ("hevc", "HEVC in AVI is non-standard and plays in few players"),
+                ("aac", "AAC in AVI is non-standard and plays in few players"),
+            ],
+        ),
+        "ts" => (
+            &["h264", "hevc", "mpeg2video", "aac", "mp3", "ac3", "eac3"],
+            &[("opus", "Opus in MPEG-TS is not supported by most hardware players")],
+        ),
+        "flv" => (&["h264", "flv1", "aac", "mp3"], &[]),
+        _ => return Supported,
+    };
+
+    if supported.contains(&codec) {
+        Supported
+    } else if let Some((_, reason)) = limited.iter().find(|(c, _)| *c == codec) {
+        Limited(reason)
+    } else if is_known_codec(codec) {
+        Unsupported
+    } else {
+        Supported
+    }
+}
+
+/// 兼容性表覆盖的编码；表外的编码无法判断，不拦截
+fn is_known_codec(codec: &str) -> bool {
+    matches!(
+        codec,
+        "h264" | "hevc" | "av1" | "vp8" | "vp9" | "mpeg4" | "mpeg2video" | "prores" | "mjpeg"
+            | "flv1" | "aac" | "mp3" | "opus" | "vorbis" | "flac" | "alac" | "ac3" | "eac3"
+            | "pcm_s16le" | "pcm_s24le"
+    )
+}
+
+/// 编码器名称对应的输出编码（ffprobe 的 codec_name），未知编码器返回自身
+pub fn encoder_output_codec(encoder: &str) -> &str {
+    match encoder {
+        "libx264" | "h264_videotoolbox" => "h264",
+        "libx265" | "hevc_videotoolbox" => "hevc",
+        "libvpx" => "vp8",
+        "libvpx-vp9" => "vp9",
+        "libaom-av1" | "libsvtav1" => "av1",
+        "libmp3lame" => "mp3",
+        "libopus" => "opus",
+        "libvorbis" => "vorbis",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Compatibility::{Limited, Supported, Unsupported};
+
+    #[test]
+    fn codec_container_matrix() {
+        let cases = [
+            ("mp4", "h264", Supported),
+            ("mp4", "hevc", Supported),
+            ("mp4", "vp9", Unsupported),
+            ("mp4", "prores", Unsupported),
+            ("mp4", "flac", Limited("FLAC in MP4 is not supported by QuickTime or most browsers")),
+            ("mp4", "opus", Limited("Opus in MP4 is not supported by QuickTime")),
+            ("mov", "prores", Supported),
+            ("mov", "pcm_s24le", Supported),
+            ("mov", "vp9", Unsupported),
+            ("mov", "flac", Limited("FLAC in MOV is not supported by QuickTime")),
+            ("webm", "vp9", Supported),
+            ("webm", "opus", Supported),
+            ("webm", "h264", Unsupported),
+            ("webm", "aac", Unsupported),
+            ("avi", "mpeg4", Supported),
+            ("avi", "hevc", Limited("HEVC in AVI is non-standard and plays in few players")),
+            ("avi", "aac", Limited("AAC in AVI is non-standard and plays in few players")),
+            ("avi", "opus", Unsupported),
+            ("ts", "mpeg2video", Supported),
+            ("ts", "opus", Limited("Opus in MPEG-TS is not supported by most hardware players")),
+            ("ts", "vp9", Unsupported),
+            ("flv", "flv1", Supported),
+            ("flv", "hevc", Unsupported),
+            // 表外的编码或容器无法判断，不拦截
+            ("mp4", "dvd_subtitle", Supported),
+            ("mkv", "prores", Supported),
+        ];
+        for (format, codec, expected) in cases {
+            assert_eq!(container_compatibility(format, codec), expected, "{} in {}", codec, format);
+        }
+    }
+
+    #[test]
+    fn encoders_map_to_probed_codec_names() {
+        assert_eq!(encoder_output_codec("h264_videotoolbox"), "h264");
+        assert_eq!(encoder_output_codec("libx265"), "hevc");
+        assert_eq!(encoder_output_codec("libsvtav1"), "av1");
+        assert_eq!(encoder_output_codec("libvpx-vp9"), "vp9");
+        assert_eq!(encoder_output_codec("libopus"), "opus");
+        assert_eq!(encoder_output_codec("aac"), "aac");
+    }
+}
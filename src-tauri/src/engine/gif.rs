@@ -0,0 +1,114 @@
+/// GIF 输出规模估算
+///
+/// 按经验公式估算 GIF 体积，并在帧数超过硬上限时给出应降低帧率还是缩短时长的建议，
+/// 用于生成前拦截明显失控的参数
+
+/// GIF 单帧体积系数（字节 / 宽度²）
+///
+/// 按 16:9 画面、256 色调色板的经验值：每像素约 0.27 字节，
+/// 即每帧约 0.15 × 宽度² 字节（1920 宽 30fps 120 秒实测约 2.3 GB）
+const GIF_BYTES_PER_WIDTH_SQUARED: f64 = 0.15;
+
+/// GIF 允许的最大帧数（时长 × 帧率），超出时即使确认也不生成
+pub const MAX_GIF_FRAMES: u64 = 3000;
+
+/// GIF 推荐的最高帧率，帧数超限时据此判断应降低帧率还是缩短时长
+const GIF_RECOMMENDED_MAX_FPS: u32 = 15;
+
+/// 估算 GIF 输出体积
+///
+/// 经验公式：时长 × 帧率 × 宽度² × 系数。只用于拦截明显失控的参数，不追求精确
+///
+/// # 参数
+/// - `width` - 输出宽度（像素）
+/// - `fps` - 输出帧率
+/// - `duration` - 片段时长（秒）
+///
+/// # 返回
+/// 估算体积（字节）
+pub fn estimate_gif_size(width: u32, fps: u32, duration: f64) -> u64 {
+    let frames = gif_frame_count(fps, duration) as f64;
+    let width = width as f64;
+    (frames * width * width * GIF_BYTES_PER_WIDTH_SQUARED) as u64
+}
+
+/// 计算 GIF 帧数（时长 × 帧率，向上取整）
+pub fn gif_frame_count(fps: u32, duration: f64) -> u64 {
+    (duration.max(0.0) * fps as f64).ceil() as u64
+}
+
+/// 检查 GIF 帧数是否超过硬上限
+///
+/// 错误信息指出是帧率还是时长导致超限，并建议改用 MP4 输出
+///
+/// # 参数
+/// - `fps` - 输出帧率
+/// - `duration` - 片段时长（秒）
+///
+/// # 返回
+/// - `Ok(())` - 帧数在上限内
+/// - `Err(String)` - 超限说明
+pub fn check_gif_frame_limit(fps: u32, duration: f64) -> Result<(), String> {
+    let frames = gif_frame_count(fps, duration);
+    if frames <= MAX_GIF_FRAMES {
+        return Ok(());
+    }
+    let hint = if fps > GIF_RECOMMENDED_MAX_FPS {
+        format!(
+            "lower the frame rate ({} fps) to {} or less",
+            fps, GIF_RECOMMENDED_MAX_FPS
+        )
+    } else {
+        format!(
+            "shorten the clip to {:.0}s or less",
+            MAX_GIF_FRAMES as f64 / fps.max(1) as f64
+        )
+    };
+    Err(format!(
+        "The GIF would have {} frames ({:.1}s at {} fps), above the limit of {}; {}, \
+         or use the MP4 output option",
+        frames, duration, fps, MAX_GIF_FRAMES, hint
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_hd_two_minute_gif_is_estimated_in_gigabytes() {
+        // 1920 宽 30fps 120 秒：3600 帧 × 1920² × 0.15 ≈ 2.0 GB
+        assert_eq!(gif_frame_count(30, 120.0), 3600);
+        let size = estimate_gif_size(1920, 30, 120.0);
+        assert_eq!(size, 1_990_656_000);
+        assert!(size > 1_500_000_000 && size < 2_500_000_000, "{}", size);
+    }
+
+    #[test]
+    fn frame_limit_allows_exactly_max_frames() {
+        assert!(check_gif_frame_limit(30, 100.0).is_ok());
+        assert!(check_gif_frame_limit(10, 300.0).is_ok());
+        assert_eq!(gif_frame_count(0, 120.0), 0);
+        assert!(check_gif_frame_limit(0, 120.0).is_ok());
+    }
+
+    #[test]
+    fn frame_limit_suggests_lower_frame_rate_above_recommended_fps() {
+        let error = check_gif_frame_limit(30, 120.0).unwrap_err();
+        assert_eq!(
+            error,
+            "The GIF would have 3600 frames (120.0s at 30 fps), above the limit of 3000; \
+             lower the frame rate (30 fps) to 15 or less, or use the MP4 output option"
+        );
+    }
+
+    #[test]
+    fn frame_limit_suggests_shorter_clip_at_recommended_fps() {
+        let error = check_gif_frame_limit(12, 300.0).unwrap_err();
+        assert_eq!(
+            error,
+            "The GIF would have 3600 frames (300.0s at 12 fps), above the limit of 3000; \
+             shorten the clip to 250s or less, or use the MP4 output option"
+        );
+    }
+}
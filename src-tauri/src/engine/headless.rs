@@ -0,0 +1,193 @@
+/// 无界面（命令行）模式的 ffmpeg 进程执行
+///
+/// 不依赖 Tauri：直接启动 PATH 中或指定路径的 ffmpeg/ffprobe，
+/// 与 GUI 共用命令构建器、进度解析器和事件结构，供 clipforge-cli 使用
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use crate::engine::builder::build_ffprobe_info_args;
+use crate::engine::progress::{extract_error_message, ProgressParser};
+use crate::models::media::FfprobeOutput;
+use crate::models::task::{TaskEvent, TaskResult, TaskStatus};
+use crate::utils::path::get_file_size;
+
+/// 命令行模式使用的 ffmpeg / ffprobe 可执行文件
+#[derive(Clone, Debug)]
+pub struct HeadlessTools {
+    /// ffmpeg 可执行文件路径
+    pub ffmpeg: PathBuf,
+    /// ffprobe 可执行文件路径
+    pub ffprobe: PathBuf,
+}
+
+impl HeadlessTools {
+    /// 定位 ffmpeg / ffprobe
+    ///
+    /// 显式指定的路径优先，否则在 PATH 中查找
+    ///
+    /// # 参数
+    /// - `ffmpeg` - 指定的 ffmpeg 路径
+    /// - `ffprobe` - 指定的 ffprobe 路径
+    ///
+    /// # 返回
+    /// - `Err(String)` - 指定路径不存在或 PATH 中找不到
+    pub fn locate(ffmpeg: Option<PathBuf>, ffprobe: Option<PathBuf>) -> Result<Self, String> {
+        Ok(Self {
+            ffmpeg: resolve_tool("ffmpeg", ffmpeg)?,
+            ffprobe: resolve_tool("ffprobe", ffprobe)?,
+        })
+    }
+
+    /// 探测媒体文件信息
+    ///
+    /// # 参数
+    /// - `file_path` - 媒体文件路径
+    pub fn probe(&self, file_path: &str) -> Result<FfprobeOutput, String> {
+        let output = Command::new(&self.ffprobe)
+            .args(build_ffprobe_info_args(file_path))
+            .output()
+            .map_err(|e| format!("执行 ffprobe 失败: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "ffprobe 执行失败: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("解析 ffprobe 输出失败: {}", e))
+    }
+
+    /// 执行 ffmpeg 并通过回调报告事件
+    ///
+    /// 事件与 GUI 的 Channel 推送一致（Started / Progress / Completed / Failed），
+    /// 进度从 `-progress pipe:1` 的 stdout 解析，stderr 收集后用于提取错误信息
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    /// - `args` - ffmpeg 命令行参数数组（需包含 -progress pipe:1）
+    /// - `total_duration` - 总时长（秒），用于计算进度
+    /// - `output_path` - 输出文件路径
+    /// - `on_event` - 事件回调
+    ///
+    /// # 返回
+    /// - `Ok(TaskResult)` - 任务已执行（成功或失败）
+    /// - `Err(String)` - ffmpeg 无法启动
+    pub fn run_ffmpeg(
+        &self,
+        task_id: &str,
+        args: Vec<String>,
+        total_duration: f64,
+        output_path: &str,
+        mut on_event: impl FnMut(TaskEvent),
+    ) -> Result<TaskResult, String> {
+        let start_time = Instant::now();
+        let mut child = Command::new(&self.ffmpeg)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("启动 ffmpeg 失败: {}", e))?;
+
+        on_event(TaskEvent::Started {
+            task_id: task_id.to_string(),
+            total_duration,
+        });
+
+        // stderr 在独立线程中读完，避免管道写满阻塞 ffmpeg
+        let mut stderr_pipe = child.stderr.take();
+        let stderr_reader = std::thread::spawn(move || {
+            let mut stderr = String::new();
+            if let Some(pipe) = stderr_pipe.as_mut() {
+                let _ = pipe.read_to_string(&mut stderr);
+            }
+            stderr
+        });
+
+        let mut parser = ProgressParser::new(total_duration, task_id);
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Some(update) = parser.parse_line(&line) {
+                    on_event(TaskEvent::Progress(update));
+                }
+            }
+        }
+
+        let status = child
+            .wait()
+            .map_err(|e| format!("等待 ffmpeg 退出失败: {}", e))?;
+        let stderr = stderr_reader.join().unwrap_or_default();
+        let elapsed = start_time.elapsed().as_secs_f64();
+
+        if status.success() {
+            let output_size = get_file_size(output_path);
+            on_event(TaskEvent::Completed {
+                task_id: task_id.to_string(),
+                output_path: output_path.to_string(),
+                output_size,
+                elapsed,
+                verification: Vec::new(),
+                note: None,
+                container_change: None,
+            });
+            Ok(TaskResult {
+                task_id: task_id.to_string(),
+                status: TaskStatus::Completed,
+                output_path: Some(output_path.to_string()),
+                output_size: Some(output_size),
+                elapsed: Some(elapsed),
+                error: None,
+                verification: Vec::new(),
+            })
+        } else {
+            let error = extract_error_message(&stderr, status.code().unwrap_or(-1));
+            on_event(TaskEvent::Failed {
+                task_id: task_id.to_string(),
+                error: error.clone(),
+            });
+            Ok(TaskResult {
+                task_id: task_id.to_string(),
+                status: TaskStatus::Failed,
+                output_path: None,
+                output_size: None,
+                elapsed: Some(elapsed),
+                error: Some(error),
+                verification: Vec::new(),
+            })
+        }
+    }
+}
+
+/// 解析单个工具的路径：指定路径需存在，否则在 PATH 中查找
+fn resolve_tool(name: &str, explicit: Option<PathBuf>) -> Result<PathBuf, String> {
+    if let Some(path) = explicit {
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(format!("{} not found at {}", name, path.display()))
+        };
+    }
+    std::env::var_os("PATH")
+        .iter()
+        .flat_map(std::env::split_paths)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+        .ok_or_else(|| format!("{} not found in PATH; pass --{} <path>", name, name))
+}
+
+/// 判断路径是否为可执行文件
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
@@ -1,14 +1,39 @@
 /// ffmpeg 任务引擎模块
 ///
-/// 导出核心引擎子模块：命令构建、进程管理、进度解析、任务队列和预设管理
+/// 导出核心引擎子模块：批量编排、命令构建、容器兼容性、GIF 规模估算、进程管理、进度解析、任务队列、定时调度、预览注册、预设管理和输出核对。
+/// analysis / batch / builder / compatibility / gif / headless / presets / preview / progress 不依赖 Tauri，命令行模式（clipforge-cli）直接复用；
+/// pass_cache / process / queue / scheduler / verify 依赖 Tauri AppHandle，仅在 desktop feature 下编译
 
+/// 分析滤镜输出解析（黑场检测、关键帧时间）
+pub mod analysis;
+/// 批量任务编排（同时提交子任务并汇总进度）
+pub mod batch;
 /// ffmpeg 命令构建器（将参数结构体转换为命令行参数数组）
 pub mod builder;
+/// 输出容器与编码的兼容性表
+pub mod compatibility;
+/// GIF 输出规模估算（体积经验公式和帧数上限）
+pub mod gif;
+/// 命令行模式的 ffmpeg 进程执行（不依赖 Tauri，直接调用 PATH 中的 ffmpeg）
+pub mod headless;
 /// 内置预设方案管理
 pub mod presets;
+/// 两遍编码统计缓存（同一源文件重复压缩时复用第一遍结果）
+#[cfg(feature = "desktop")]
+pub mod pass_cache;
+/// 预览文件注册表（令牌换取预览内容，集中清理临时文件）
+pub mod preview;
 /// ffmpeg 进程管理（启动、监控、终止 sidecar 进程）
+#[cfg(feature = "desktop")]
 pub mod process;
 /// ffmpeg -progress 输出解析器
 pub mod progress;
 /// 任务队列（子进程注册与取消管理）
+#[cfg(feature = "desktop")]
 pub mod queue;
+/// 定时任务调度（指定时间 / 系统空闲时执行）
+#[cfg(feature = "desktop")]
+pub mod scheduler;
+/// 输出文件编码属性核对（任务完成后与参数要求比对）
+#[cfg(feature = "desktop")]
+pub mod verify;
@@ -0,0 +1,371 @@
+/// 两遍编码统计文件缓存
+///
+/// 针对同一源文件重复压缩时，x264/x265 第一遍的统计结果（passlog）
+/// 只与源文件内容和第一遍的编码参数有关，可以复用以跳过第一遍。
+/// 缓存位于应用数据目录的 stats/ 子目录，按"输入路径 + 修改时间 + 大小 + 第一遍参数签名"生成键：
+/// 源文件被修改或第一遍参数（tune、关键帧间隔、码率控制、去隔行、线程数等）变化后键随之变化，
+/// 旧条目不再命中并最终被 LRU 淘汰。
+///
+/// 一个缓存条目是共享同一前缀的一组文件（如 x264 的 `<key>-0.log` 和 `<key>-0.log.mbtree`），
+/// 命中时直接把前缀作为第二遍的 -passlogfile 使用
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use tauri::Manager;
+
+/// 缓存子目录名
+const STATS_DIR: &str = "stats";
+/// 缓存总大小上限（字节），超出时按最近使用时间淘汰
+const MAX_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+/// 第一遍统计的主文件后缀（ffmpeg 以 `<passlogfile>-<流序号>.log` 命名）
+const PRIMARY_LOG_SUFFIX: &str = "-0.log";
+
+/// 计算缓存键
+///
+/// 使用 FNV-1a 64 位哈希（跨版本稳定，不依赖标准库哈希实现）
+///
+/// # 参数
+/// - `input_path` - 源文件路径
+/// - `modified` - 源文件修改时间
+/// - `size` - 源文件大小（字节）
+/// - `signature` - 第一遍参数签名（见 first_pass_signature）
+///
+/// # 返回
+/// 16 位十六进制字符串
+pub fn stats_cache_key(
+    input_path: &str,
+    modified: SystemTime,
+    size: u64,
+    signature: &str,
+) -> String {
+    let nanos = modified
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let material = format!("{}\n{}\n{}\n{}", input_path, nanos, size, signature);
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in material.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// 第一遍编码命令的参数签名
+///
+/// 去掉输入路径，并把每次编码都不同的 passlog 临时前缀（包括 x265-params 中的 stats=）
+/// 替换为占位符后拼接全部参数。任何会改变第一遍统计的选项都体现在签名里
+///
+/// # 参数
+/// - `pass1` - 第一遍的 ffmpeg 参数
+/// - `input_path` - 源文件路径
+/// - `passlog_prefix` - 第一遍使用的 -passlogfile 前缀
+pub fn first_pass_signature(pass1: &[String], input_path: &str, passlog_prefix: &str) -> String {
+    pass1
+        .iter()
+        .filter(|arg| *arg != input_path)
+        .map(|arg| arg.replace(passlog_prefix, "<passlog>"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 查找源文件对应的缓存统计
+///
+/// 命中时刷新条目的最近使用时间
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `input_path` - 源文件路径
+/// - `signature` - 第一遍参数签名
+///
+/// # 返回
+/// 命中时返回可直接用作 -passlogfile 的前缀路径
+pub fn lookup_stats(app: &tauri::AppHandle, input_path: &str, signature: &str) -> Option<String> {
+    let dir = get_stats_dir(app).ok()?;
+    let key = source_key(input_path, signature)?;
+    let group = entry_files(&dir, &key);
+    if !group.iter().any(|p| file_name(p).ends_with(PRIMARY_LOG_SUFFIX)) {
+        return None;
+    }
+    for path in &group {
+        touch(path);
+    }
+    Some(dir.join(&key).to_string_lossy().to_string())
+}
+
+/// 删除源文件对应的缓存统计
+///
+/// 用缓存统计执行的第二遍失败时调用，避免之后每次重试都命中同一份无法使用的统计
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `input_path` - 源文件路径
+/// - `signature` - 第一遍参数签名
+pub fn invalidate_stats(app: &tauri::AppHandle, input_path: &str, signature: &str) {
+    let (Ok(dir), Some(key)) = (get_stats_dir(app), source_key(input_path, signature)) else {
+        return;
+    };
+    for path in entry_files(&dir, &key) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// 把第一遍生成的统计文件存入缓存
+///
+/// 复制 passlog 前缀下的全部文件，完成后按大小上限淘汰最久未使用的条目
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `input_path` - 源文件路径
+/// - `signature` - 第一遍参数签名
+/// - `passlog_prefix` - 第一遍使用的 -passlogfile 前缀
+///
+/// # 返回
+/// - `Ok(())` - 已缓存
+/// - `Err(String)` - 源文件信息或统计文件不可读、写入失败
+pub fn store_stats(
+    app: &tauri::AppHandle,
+    input_path: &str,
+    signature: &str,
+    passlog_prefix: &str,
+) -> Result<(), String> {
+    let dir = get_stats_dir(app)?;
+    let key = source_key(input_path, signature).ok_or("无法读取源文件信息")?;
+
+    let prefix = Path::new(passlog_prefix);
+    let prefix_name = file_name(prefix);
+    let source_dir = prefix.parent().ok_or("passlog 路径无效")?;
+    let entries = std::fs::read_dir(source_dir)
+        .map_err(|e| format!("读取 passlog 目录失败: {}", e))?;
+
+    let mut copied = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // 只复制 ffmpeg 生成的 "<prefix>-<n>.log*" 文件，避免误匹配同前缀的其他临时文件
+        let Some(rest) = name.strip_prefix(&format!("{}-", prefix_name)) else {
+            continue;
+        };
+        let Some((index, _)) = rest.split_once(".log") else {
+            continue;
+        };
+        if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let dest = dir.join(format!("{}-{}", key, rest));
+        std::fs::copy(entry.path(), &dest).map_err(|e| format!("缓存统计文件失败: {}", e))?;
+        copied += 1;
+    }
+    if copied == 0 {
+        return Err("未找到第一遍统计文件".to_string());
+    }
+
+    evict_lru(&dir, MAX_CACHE_BYTES);
+    Ok(())
+}
+
+/// 一个缓存条目（同一键的全部文件）
+struct CacheEntry {
+    /// 总大小（字节）
+    size: u64,
+    /// 最近使用时间（条目内最新的文件修改时间）
+    last_used: SystemTime,
+    /// 条目包含的文件
+    files: Vec<PathBuf>,
+}
+
+/// 按最近使用时间淘汰缓存条目，直到总大小不超过上限
+///
+/// 同一键的文件作为一个条目整体删除，条目的使用时间取其中最新的文件修改时间
+///
+/// # 参数
+/// - `dir` - 缓存目录
+/// - `max_bytes` - 总大小上限（字节）
+///
+/// # 返回
+/// 被淘汰的缓存键列表（从最旧开始）
+pub fn evict_lru(dir: &Path, max_bytes: u64) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut groups: HashMap<String, CacheEntry> = HashMap::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some((key, _)) = name.split_once('-') else {
+            continue;
+        };
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        let used = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        let group = groups.entry(key.to_string()).or_insert(CacheEntry {
+            size: 0,
+            last_used: SystemTime::UNIX_EPOCH,
+            files: Vec::new(),
+        });
+        group.size += meta.len();
+        group.last_used = group.last_used.max(used);
+        group.files.push(entry.path());
+    }
+
+    let mut total: u64 = groups.values().map(|g| g.size).sum();
+    let mut ordered: Vec<(String, CacheEntry)> = groups.into_iter().collect();
+    ordered.sort_by_key(|(_, group)| group.last_used);
+
+    let mut evicted = Vec::new();
+    for (key, group) in ordered {
+        if total <= max_bytes {
+            break;
+        }
+        for file in &group.files {
+            let _ = std::fs::remove_file(file);
+        }
+        total = total.saturating_sub(group.size);
+        evicted.push(key);
+    }
+    evicted
+}
+
+/// 根据源文件当前的修改时间和大小计算缓存键，文件不可读时返回 None
+fn source_key(input_path: &str, signature: &str) -> Option<String> {
+    let meta = std::fs::metadata(input_path).ok()?;
+    let modified = meta.modified().ok()?;
+    Some(stats_cache_key(input_path, modified, meta.len(), signature))
+}
+
+/// 列出缓存目录中属于指定键的文件
+fn entry_files(dir: &Path, key: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}-", key);
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with(&prefix))
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 刷新文件修改时间，作为 LRU 的最近使用时间
+fn touch(path: &Path) {
+    if let Ok(file) = std::fs::File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+/// 取路径的文件名部分
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// 获取缓存目录（不存在时创建）
+fn get_stats_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
+    let dir = app_data.join(STATS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建统计缓存目录失败: {}", e))?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn cache_key_is_stable_and_covers_every_input() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let key = stats_cache_key("/videos/in.mp4", modified, 1024, "libx264:medium");
+        assert_eq!(key.len(), 16);
+        assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(key, stats_cache_key("/videos/in.mp4", modified, 1024, "libx264:medium"));
+
+        let later = modified + Duration::from_nanos(1);
+        for other in [
+            stats_cache_key("/videos/other.mp4", modified, 1024, "libx264:medium"),
+            stats_cache_key("/videos/in.mp4", later, 1024, "libx264:medium"),
+            stats_cache_key("/videos/in.mp4", modified, 1025, "libx264:medium"),
+            stats_cache_key("/videos/in.mp4", modified, 1024, "libx264:slow"),
+        ] {
+            assert_ne!(key, other);
+        }
+    }
+
+    /// 按内容类型构造目标大小两遍压缩的第一遍命令
+    fn first_pass(content_type: &str, passlog_prefix: &str) -> Vec<String> {
+        let params: crate::models::preset::CompressParams =
+            serde_json::from_value(serde_json::json!({
+                "inputPath": "/videos/in.mp4",
+                "outputPath": "/videos/out.mp4",
+                "mode": "bySize",
+                "targetSizeMb": 10.0,
+                "twoPass": true,
+                "contentType": content_type,
+            }))
+            .unwrap();
+        let source = crate::models::media::video_stream(serde_json::json!({}));
+        let passes = crate::engine::builder::build_compress_command(
+            &params,
+            10.0,
+            0,
+            Some(&source),
+            Some(passlog_prefix),
+            None,
+        );
+        assert_eq!(passes.len(), 2);
+        passes.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn different_content_type_misses_the_cache() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let key = |content_type: &str, prefix: &str| {
+            let signature =
+                first_pass_signature(&first_pass(content_type, prefix), "/videos/in.mp4", prefix);
+            stats_cache_key("/videos/in.mp4", modified, 1024, &signature)
+        };
+        // passlog 临时前缀每次不同，不影响命中
+        assert_eq!(key("film", "/tmp/passlog_a"), key("film", "/tmp/passlog_b"));
+        assert_ne!(key("film", "/tmp/passlog_a"), key("animation", "/tmp/passlog_a"));
+        assert_ne!(key("film", "/tmp/passlog_a"), key("screencast", "/tmp/passlog_a"));
+        assert_ne!(key("animation", "/tmp/passlog_a"), key("screencast", "/tmp/passlog_a"));
+    }
+
+    /// 写入一个缓存文件并把修改时间设为 `age_secs` 秒前
+    fn write_entry(dir: &Path, name: &str, bytes: usize, age_secs: u64) {
+        let path = dir.join(name);
+        std::fs::write(&path, vec![0u8; bytes]).unwrap();
+        let file = std::fs::File::options().append(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(age_secs)).unwrap();
+    }
+
+    #[test]
+    fn evicts_least_recently_used_entries_as_a_group() {
+        let dir = std::env::temp_dir().join(format!("clipforge_stats_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // oldest 的 mbtree 较新，但条目整体的使用时间仍早于 middle
+        write_entry(&dir, "oldest-0.log", 100, 300);
+        write_entry(&dir, "oldest-0.log.mbtree", 100, 250);
+        write_entry(&dir, "middle-0.log", 100, 200);
+        write_entry(&dir, "newest-0.log", 100, 10);
+
+        assert!(evict_lru(&dir, 400).is_empty());
+        assert_eq!(evict_lru(&dir, 250), ["oldest"]);
+        assert!(!dir.join("oldest-0.log").exists());
+        assert!(!dir.join("oldest-0.log.mbtree").exists());
+        assert!(dir.join("middle-0.log").exists());
+
+        assert_eq!(evict_lru(&dir, 100), ["middle"]);
+        assert!(dir.join("newest-0.log").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,9 +1,160 @@
 /// 内置预设管理
 ///
 /// 提供各功能模块的预置方案列表，
-/// 前端通过此模块获取可选的预设选项
+/// 前端通过此模块获取可选的预设选项，
+/// 同时维护各发布平台的编码约束表
 
-use crate::models::preset::PresetInfo;
+use std::path::Path;
+
+use crate::models::media::VideoStream;
+use crate::models::preset::{PlatformExportSettings, PresetInfo, StreamRendition};
+use crate::models::task::{EstimateConfidence, SizeEstimate};
+
+/// 内置预设所属的功能模块（用户预设的 category 必须是其中之一）
+pub const PRESET_CATEGORIES: &[&str] = &[
+    "convert",
+    "compress",
+    "trim",
+    "merge",
+    "audio",
+    "watermark",
+    "resize",
+    "gif",
+    "subtitle",
+    "export",
+    "denoise",
+];
+
+/// 每次执行都会变化、不应保存在预设中的路径参数（camelCase 键）
+const PRESET_PATH_KEYS: &[&str] = &["inputPath", "inputPaths", "outputPath"];
+
+/// 按功能模块的参数结构校验并规整预设参数
+///
+/// 先反序列化为对应的参数结构体（校验字段名和类型），再序列化回 JSON，
+/// 使保存的参数与 command 实际接受的格式完全一致。未设置的可选字段（null）会被去掉
+///
+/// # 参数
+/// - `category` - 功能模块（见 PRESET_CATEGORIES）
+/// - `params` - 参数对象（camelCase 键）
+///
+/// # 返回
+/// - `Ok(Value)` - 规整后的参数对象
+/// - `Err(String)` - 模块未知或参数不符合结构
+pub fn normalize_preset_params(
+    category: &str,
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    use crate::models::preset::*;
+    let mut value = match category {
+        "convert" => round_trip::<ConvertParams>(params),
+        "compress" => round_trip::<CompressParams>(params),
+        "trim" => round_trip::<TrimParams>(params),
+        "merge" => round_trip::<MergeParams>(params),
+        "audio" => round_trip::<AudioParams>(params),
+        "watermark" => round_trip::<WatermarkParams>(params),
+        "resize" => round_trip::<ResizeParams>(params),
+        "gif" => round_trip::<GifParams>(params),
+        "subtitle" => round_trip::<SubtitleParams>(params),
+        "export" => round_trip::<PlatformExportParams>(params),
+        "denoise" => round_trip::<DenoiseParams>(params),
+        _ => Err(format!("Unknown preset category: {}", category)),
+    }?;
+    if let Some(object) = value.as_object_mut() {
+        object.retain(|_, v| !v.is_null());
+    }
+    Ok(value)
+}
+
+/// 去掉参数对象中的输入/输出路径，保存为与具体文件无关的预设参数
+pub fn strip_preset_paths(params: &mut serde_json::Value) {
+    if let Some(object) = params.as_object_mut() {
+        for key in PRESET_PATH_KEYS {
+            object.remove(*key);
+        }
+    }
+}
+
+/// 读取用户预设文件
+///
+/// 逐条校验，跳过不合法的条目；文件缺失或整个文件无法解析时返回空列表
+///
+/// # 参数
+/// - `path` - presets.json 路径
+pub fn read_user_presets(path: &Path) -> Vec<PresetInfo> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let entries: Vec<serde_json::Value> = match serde_json::from_str(&content) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("用户预设文件解析失败，已忽略: {}", e);
+            return Vec::new();
+        }
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let preset = serde_json::from_value::<PresetInfo>(entry)
+                .map_err(|e| e.to_string())
+                .and_then(|p| validate_user_preset(&p).map(|_| p));
+            match preset {
+                Ok(p) => Some(p),
+                Err(e) => {
+                    log::warn!("跳过不合法的用户预设: {}", e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// 将用户预设写入预设文件，目录不存在时自动创建
+///
+/// # 参数
+/// - `path` - presets.json 路径
+/// - `presets` - 全部用户预设
+pub fn write_user_presets(path: &Path, presets: &[PresetInfo]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("创建设置目录失败: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(presets)
+        .map_err(|e| format!("序列化预设失败: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("写入预设文件失败: {}", e))
+}
+
+/// 校验用户预设的结构
+///
+/// id/名称非空、不与内置预设重名、category 为已知功能模块、params 为 JSON 对象
+pub fn validate_user_preset(preset: &PresetInfo) -> Result<(), String> {
+    if preset.id.trim().is_empty() || preset.name.trim().is_empty() {
+        return Err("Preset id and name must not be empty".to_string());
+    }
+    if get_all_presets().iter().any(|p| p.id == preset.id) {
+        return Err(format!("Preset id '{}' is reserved by a built-in preset", preset.id));
+    }
+    if !PRESET_CATEGORIES.contains(&preset.category.as_str()) {
+        return Err(format!(
+            "Unknown preset category: {} (supported: {})",
+            preset.category,
+            PRESET_CATEGORIES.join(", ")
+        ));
+    }
+    if !preset.params.as_ref().is_some_and(|p| p.is_object()) {
+        return Err(format!("Preset '{}' has no parameter object", preset.id));
+    }
+    Ok(())
+}
+
+/// 反序列化为参数结构体后再序列化回 JSON
+fn round_trip<T: serde::de::DeserializeOwned + serde::Serialize>(
+    params: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let typed: T = serde_json::from_value(params.clone())
+        .map_err(|e| format!("Invalid preset params: {}", e))?;
+    serde_json::to_value(typed).map_err(|e| format!("序列化预设参数失败: {}", e))
+}
 
 /// 获取所有内置预设信息列表
 ///
@@ -20,6 +171,10 @@ pub fn get_all_presets() -> Vec<PresetInfo> {
     presets.extend(get_gif_presets());
     // 分辨率预设
     presets.extend(get_resize_presets());
+    // 短视频平台一键导出预设
+    presets.extend(get_export_presets());
+    // 降噪预设
+    presets.extend(get_denoise_presets());
 
     presets
 }
@@ -32,40 +187,104 @@ fn get_convert_presets() -> Vec<PresetInfo> {
             name: "MP4 (H.264)".to_string(),
             description: "通用格式，兼容性最好，适合社交媒体分享".to_string(),
             category: "convert".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "convert_mp4_h265".to_string(),
             name: "MP4 (H.265/HEVC)".to_string(),
             description: "高效压缩，同等质量下文件更小，Apple 设备友好".to_string(),
             category: "convert".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "convert_mkv".to_string(),
             name: "MKV (万能容器)".to_string(),
             description: "支持几乎所有编码格式，适合存档".to_string(),
             category: "convert".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "convert_webm".to_string(),
             name: "WebM (VP9)".to_string(),
             description: "开放格式，适合 Web 播放".to_string(),
             category: "convert".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "convert_mov_prores".to_string(),
             name: "MOV (ProRes)".to_string(),
             description: "专业编辑格式，适合 Final Cut Pro 等后期软件".to_string(),
             category: "convert".to_string(),
+            params: None,
+        },
+        PresetInfo {
+            id: "convert_streaming".to_string(),
+            name: "流媒体分发 (HLS/DASH)".to_string(),
+            description: "2 秒固定关键帧间隔、closed GOP、yuv420p，适合 CDN 切片和在线播放".to_string(),
+            category: "convert".to_string(),
+            params: Some(streaming_preset_params()),
         },
         PresetInfo {
             id: "convert_copy".to_string(),
             name: "快速封装（不重新编码）".to_string(),
             description: "仅改变容器格式，速度极快，无质量损失".to_string(),
             category: "convert".to_string(),
+            params: None,
         },
     ]
 }
 
+// ============================================================
+// 流媒体转换预设
+// ============================================================
+
+/// 流媒体输出的关键帧间隔（秒）
+///
+/// HLS/DASH 切片只能从关键帧开始，2 秒是主流 CDN 和播放器推荐的分片粒度，
+/// 切片时长（通常 2/4/6 秒）都是它的整数倍
+pub const STREAMING_KEYFRAME_SECONDS: f64 = 2.0;
+
+/// 流媒体输出的像素格式（8-bit 4:2:0，浏览器和移动设备硬解兼容性最好）
+pub const STREAMING_PIXEL_FORMAT: &str = "yuv420p";
+
+/// 流媒体输出允许的容器（需要 faststart，让 moov 位于文件头以便边下边播）
+pub const STREAMING_FORMATS: &[&str] = &["mp4", "mov"];
+
+/// 按帧率计算流媒体输出的固定 GOP 长度（帧数）
+///
+/// 帧率未知（0）时按 30fps 计算
+pub fn streaming_gop_size(frame_rate: f64) -> u32 {
+    let fps = if frame_rate > 0.0 { frame_rate } else { 30.0 };
+    ((fps * STREAMING_KEYFRAME_SECONDS).round() as u32).max(1)
+}
+
+/// 内置流媒体转换预设的参数
+///
+/// H.264 + AAC 的 MP4；GOP 长度依赖源帧率，由 convert 在执行时计算
+fn streaming_preset_params() -> serde_json::Value {
+    serde_json::json!({
+        "outputFormat": "mp4",
+        "videoCodec": "libx264",
+        "audioCodec": "aac",
+        "pixelFormat": STREAMING_PIXEL_FORMAT,
+        "streaming": true,
+    })
+}
+
+/// 获取降噪预设
+fn get_denoise_presets() -> Vec<PresetInfo> {
+    vec![PresetInfo {
+        id: "denoise_old_footage".to_string(),
+        name: "老旧素材修复".to_string(),
+        description: "nlmeans 较强降噪，去除录像带噪点和低码率压缩块效应，处理较慢".to_string(),
+        category: "denoise".to_string(),
+        params: Some(serde_json::json!({
+            "method": "nlmeans",
+            "strength": 0.6,
+        })),
+    }]
+}
+
 /// 获取视频压缩预设
 fn get_compress_presets() -> Vec<PresetInfo> {
     vec![
@@ -74,36 +293,42 @@ fn get_compress_presets() -> Vec<PresetInfo> {
             name: "社交媒体优化".to_string(),
             description: "适中文件大小，广泛兼容，适合微信/抖音/B站".to_string(),
             category: "compress".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "compress_wechat".to_string(),
             name: "微信朋友圈（25MB 限制）".to_string(),
             description: "压缩到 25MB 以内，适合微信分享".to_string(),
             category: "compress".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "compress_high_quality".to_string(),
             name: "高质量归档".to_string(),
             description: "接近无损质量，文件较大，适合长期保存".to_string(),
             category: "compress".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "compress_preview".to_string(),
             name: "快速预览".to_string(),
             description: "小文件低画质，适合快速预览和传输".to_string(),
             category: "compress".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "compress_bilibili".to_string(),
             name: "B站投稿优化".to_string(),
             description: "高码率上传，经 B 站二压后仍保持较好画质".to_string(),
             category: "compress".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "compress_youtube".to_string(),
             name: "YouTube 上传".to_string(),
             description: "YouTube 推荐参数，高码率高质量".to_string(),
             category: "compress".to_string(),
+            params: None,
         },
     ]
 }
@@ -116,24 +341,52 @@ fn get_gif_presets() -> Vec<PresetInfo> {
             name: "聊天表情".to_string(),
             description: "320px 宽，10fps，小体积适合聊天发送".to_string(),
             category: "gif".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "gif_social".to_string(),
             name: "社交分享".to_string(),
             description: "480px 宽，12fps，适合微博/Twitter 分享".to_string(),
             category: "gif".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "gif_tutorial".to_string(),
             name: "教程演示".to_string(),
             description: "640px 宽，15fps，清晰展示操作步骤".to_string(),
             category: "gif".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "gif_high_quality".to_string(),
             name: "高质量".to_string(),
             description: "800px 宽，15fps，高清大图".to_string(),
             category: "gif".to_string(),
+            params: None,
+        },
+        PresetInfo {
+            id: "webp_loop".to_string(),
+            name: "动画 WebP（无限循环）".to_string(),
+            description: "480px 宽，15fps，真彩色动图，体积通常小于 GIF".to_string(),
+            category: "gif".to_string(),
+            params: Some(serde_json::json!({
+                "width": 480,
+                "fps": 15,
+                "loopCount": 0,
+                "outputAsWebp": true,
+            })),
+        },
+        PresetInfo {
+            id: "webp_once".to_string(),
+            name: "动画 WebP（播放一次）".to_string(),
+            description: "480px 宽，15fps，播放一次后停在最后一帧".to_string(),
+            category: "gif".to_string(),
+            params: Some(serde_json::json!({
+                "width": 480,
+                "fps": 15,
+                "loopCount": 1,
+                "outputAsWebp": true,
+            })),
         },
     ]
 }
@@ -146,36 +399,947 @@ fn get_resize_presets() -> Vec<PresetInfo> {
             name: "4K UHD (3840x2160)".to_string(),
             description: "超高清，适合大屏展示".to_string(),
             category: "resize".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "resize_1080p".to_string(),
             name: "1080p FHD (1920x1080)".to_string(),
             description: "全高清，最常用的分辨率".to_string(),
             category: "resize".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "resize_720p".to_string(),
             name: "720p HD (1280x720)".to_string(),
             description: "高清，文件较小".to_string(),
             category: "resize".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "resize_480p".to_string(),
             name: "480p SD (854x480)".to_string(),
             description: "标清，适合低带宽场景".to_string(),
             category: "resize".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "resize_vertical_1080".to_string(),
             name: "竖屏 1080p (1080x1920)".to_string(),
             description: "抖音/快手/Reels 竖屏格式".to_string(),
             category: "resize".to_string(),
+            params: None,
         },
         PresetInfo {
             id: "resize_square_1080".to_string(),
             name: "正方形 1080 (1080x1080)".to_string(),
             description: "Instagram 正方形格式".to_string(),
             category: "resize".to_string(),
+            params: None,
         },
     ]
 }
+
+/// 获取短视频平台一键导出预设
+///
+/// id 去掉 "export_" 前缀后与 SHORT_FORM_PROFILES 中的平台标识一致
+fn get_export_presets() -> Vec<PresetInfo> {
+    SHORT_FORM_PROFILES
+        .iter()
+        .map(|p| PresetInfo {
+            id: format!("export_{}", p.id),
+            name: p.name.to_string(),
+            description: format!(
+                "{}x{} 竖屏，≤{}fps，{} 码率，{} LUFS 响度",
+                p.width, p.height, p.max_fps, p.video_bitrate, p.loudness.integrated
+            ),
+            category: "export".to_string(),
+            params: None,
+        })
+        .collect()
+}
+
+// ============================================================
+// 响度标准化目标
+// ============================================================
+
+/// EBU R128 响度标准化目标（对应 loudnorm 滤镜的 I / LRA / TP 参数）
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoudnessTarget {
+    /// 综合响度目标（LUFS）
+    pub integrated: f64,
+    /// 响度范围目标（LU）
+    pub range: f64,
+    /// 真峰值上限（dBTP）
+    pub true_peak: f64,
+}
+
+/// 默认响度目标（-16 LUFS，适合大多数播放场景）
+pub const DEFAULT_LOUDNESS_TARGET: LoudnessTarget = LoudnessTarget {
+    integrated: -16.0,
+    range: 11.0,
+    true_peak: -1.5,
+};
+
+impl LoudnessTarget {
+    /// 用调用方指定的值覆盖对应字段，未指定的字段保持不变
+    pub fn with_overrides(
+        &self,
+        integrated: Option<f64>,
+        range: Option<f64>,
+        true_peak: Option<f64>,
+    ) -> Self {
+        Self {
+            integrated: integrated.unwrap_or(self.integrated),
+            range: range.unwrap_or(self.range),
+            true_peak: true_peak.unwrap_or(self.true_peak),
+        }
+    }
+
+    /// 校验是否在 loudnorm 滤镜接受的范围内
+    ///
+    /// I: -70 ~ -5 LUFS，LRA: 1 ~ 50 LU，TP: -9 ~ 0 dBTP
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-70.0..=-5.0).contains(&self.integrated) {
+            return Err(format!(
+                "Loudness target {} LUFS is out of range (-70 to -5)",
+                self.integrated
+            ));
+        }
+        if !(1.0..=50.0).contains(&self.range) {
+            return Err(format!(
+                "Loudness range {} LU is out of range (1 to 50)",
+                self.range
+            ));
+        }
+        if !(-9.0..=0.0).contains(&self.true_peak) {
+            return Err(format!(
+                "True peak {} dBTP is out of range (-9 to 0)",
+                self.true_peak
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ============================================================
+// 短视频平台导出规格
+// ============================================================
+
+/// 短视频平台的导出规格
+#[derive(Clone, Debug)]
+pub struct ShortFormProfile {
+    /// 平台标识（与导出预设 id 去掉 "export_" 前缀后一致）
+    pub id: &'static str,
+    /// 显示名称
+    pub name: &'static str,
+    /// 输出宽度
+    pub width: u32,
+    /// 输出高度
+    pub height: u32,
+    /// 最大帧率
+    pub max_fps: f64,
+    /// 目标视频码率
+    pub video_bitrate: &'static str,
+    /// 最大视频码率
+    pub max_bitrate: &'static str,
+    /// VBV 缓冲区大小
+    pub buffer_size: &'static str,
+    /// H.264 level（1080x1920@60 需要 4.2）
+    pub level: &'static str,
+    /// 音频码率
+    pub audio_bitrate: &'static str,
+    /// 音频采样率（Hz）
+    pub sample_rate: u32,
+    /// 响度标准化目标
+    pub loudness: LoudnessTarget,
+}
+
+/// 短视频平台规格表
+const SHORT_FORM_PROFILES: &[ShortFormProfile] = &[
+    ShortFormProfile {
+        id: "instagram_reels",
+        name: "Instagram Reels",
+        width: 1080,
+        height: 1920,
+        max_fps: 30.0,
+        video_bitrate: "6M",
+        max_bitrate: "8M",
+        buffer_size: "12M",
+        level: "4.1",
+        audio_bitrate: "128k",
+        sample_rate: 48000,
+        loudness: LoudnessTarget {
+            integrated: -14.0,
+            range: 11.0,
+            true_peak: -1.0,
+        },
+    },
+    ShortFormProfile {
+        id: "tiktok",
+        name: "TikTok",
+        width: 1080,
+        height: 1920,
+        max_fps: 60.0,
+        video_bitrate: "8M",
+        max_bitrate: "10M",
+        buffer_size: "16M",
+        level: "4.2",
+        audio_bitrate: "128k",
+        sample_rate: 48000,
+        loudness: LoudnessTarget {
+            integrated: -14.0,
+            range: 11.0,
+            true_peak: -1.0,
+        },
+    },
+    ShortFormProfile {
+        id: "youtube_shorts",
+        name: "YouTube Shorts",
+        width: 1080,
+        height: 1920,
+        max_fps: 60.0,
+        video_bitrate: "12M",
+        max_bitrate: "15M",
+        buffer_size: "24M",
+        level: "4.2",
+        audio_bitrate: "192k",
+        sample_rate: 48000,
+        loudness: LoudnessTarget {
+            integrated: -14.0,
+            range: 11.0,
+            true_peak: -1.0,
+        },
+    },
+];
+
+/// 按平台标识查找短视频导出规格
+///
+/// 同时接受 "tiktok" 和预设 id 形式的 "export_tiktok"
+pub fn get_short_form_profile(platform: &str) -> Option<&'static ShortFormProfile> {
+    let id = platform.trim_start_matches("export_");
+    SHORT_FORM_PROFILES.iter().find(|p| p.id == id)
+}
+
+/// 短视频导出的执行计划
+#[derive(Clone, Debug)]
+pub struct ShortFormPlan {
+    /// 视频滤镜链（重构图、帧率上限、SAR 归一）
+    pub video_filters: Vec<String>,
+    /// 实际采用的导出参数
+    pub settings: PlatformExportSettings,
+    /// 响度标准化目标（默认取平台规格，可被调用方覆盖）
+    pub loudness: LoudnessTarget,
+    /// 面向用户的调整说明
+    pub notices: Vec<String>,
+}
+
+/// 根据源视频计算短视频平台导出计划
+///
+/// - 源视频已是竖屏时不再重构图，仅等比缩放；
+///   宽高比与目标一致（误差 1% 内）时不加黑边，避免二次加边
+/// - 横屏/方形素材按 reframe_mode 居中裁切（crop）或加黑边（pad）
+/// - 帧率超过平台上限时降帧
+///
+/// # 参数
+/// - `profile` - 平台导出规格
+/// - `source` - 源视频流信息（无法获取时按横屏处理）
+/// - `reframe_mode` - 横屏素材的重构图方式（"crop" / "pad"）
+pub fn plan_short_form_export(
+    profile: &ShortFormProfile,
+    source: Option<&VideoStream>,
+    reframe_mode: &str,
+) -> ShortFormPlan {
+    let (tw, th) = (profile.width, profile.height);
+    let mut video_filters = Vec::new();
+    let mut notices = Vec::new();
+
+    // 按显示宽高比判断横竖屏（考虑旋转和非方形像素）
+    let display_aspect = source.and_then(VideoStream::display_aspect);
+    let target_aspect = tw as f64 / th as f64;
+    let is_vertical = source.is_some_and(VideoStream::is_vertical);
+
+    let reframe = if is_vertical {
+        let aspect = display_aspect.unwrap_or(target_aspect);
+        if (aspect / target_aspect - 1.0).abs() <= 0.01 {
+            video_filters.push(format!("scale={}:{}:flags=lanczos", tw, th));
+        } else {
+            video_filters.push(format!(
+                "scale={}:{}:force_original_aspect_ratio=decrease:flags=lanczos",
+                tw, th
+            ));
+            video_filters.push(format!("pad={}:{}:(ow-iw)/2:(oh-ih)/2:black", tw, th));
+        }
+        "none"
+    } else if reframe_mode == "pad" {
+        video_filters.push(format!(
+            "scale={}:{}:force_original_aspect_ratio=decrease:flags=lanczos",
+            tw, th
+        ));
+        video_filters.push(format!("pad={}:{}:(ow-iw)/2:(oh-ih)/2:black", tw, th));
+        notices.push(format!(
+            "Horizontal source letterboxed into {}x{} for {}",
+            tw, th, profile.name
+        ));
+        "pad"
+    } else {
+        video_filters.push(format!(
+            "scale={}:{}:force_original_aspect_ratio=increase:flags=lanczos",
+            tw, th
+        ));
+        video_filters.push(format!("crop={}:{}", tw, th));
+        notices.push(format!(
+            "Horizontal source center-cropped to {}x{} for {}",
+            tw, th, profile.name
+        ));
+        "crop"
+    };
+    video_filters.push("setsar=1".to_string());
+
+    // 帧率上限
+    let source_fps = source.map(|s| s.frame_rate).unwrap_or(0.0);
+    let fps = if source_fps > profile.max_fps + 0.01 || source_fps <= 0.0 {
+        video_filters.push(format!("fps={}", profile.max_fps));
+        if source_fps > 0.0 {
+            notices.push(format!(
+                "Frame rate capped from {:.2} to {} fps for {}",
+                source_fps, profile.max_fps, profile.name
+            ));
+        }
+        profile.max_fps
+    } else {
+        source_fps
+    };
+
+    let settings = PlatformExportSettings {
+        platform: profile.id.to_string(),
+        width: tw,
+        height: th,
+        fps,
+        reframe: reframe.to_string(),
+        video_codec: "libx264".to_string(),
+        video_bitrate: profile.video_bitrate.to_string(),
+        max_bitrate: profile.max_bitrate.to_string(),
+        audio_codec: "aac".to_string(),
+        audio_bitrate: profile.audio_bitrate.to_string(),
+        sample_rate: profile.sample_rate,
+        loudness_lufs: profile.loudness.integrated,
+        pix_fmt: "yuv420p".to_string(),
+        faststart: true,
+    };
+
+    ShortFormPlan {
+        video_filters,
+        settings,
+        loudness: profile.loudness.clone(),
+        notices,
+    }
+}
+
+// ============================================================
+// 发布平台编码约束
+// ============================================================
+
+/// 发布平台的编码约束
+///
+/// 微信、B站等平台对帧率、分辨率和 H.264 profile/level 有硬性要求，
+/// 超出限制的视频会被拒收或被平台二次压制得很差
+#[derive(Clone, Debug)]
+pub struct PlatformConstraints {
+    /// 平台标识（与压缩预设 id 去掉 "compress_" 前缀后一致）
+    pub id: &'static str,
+    /// 最大帧率
+    pub max_fps: f64,
+    /// 长边最大像素数（横屏为宽，竖屏为高）
+    pub max_long_edge: u32,
+    /// 短边最大像素数
+    pub max_short_edge: u32,
+    /// H.264 profile
+    pub profile: &'static str,
+    /// H.264 level（命令行格式，如 "4.1"）
+    pub level: &'static str,
+    /// level 的 ffprobe 整数形式（如 41），用于与源视频比较
+    pub level_value: i32,
+    /// 像素格式
+    pub pix_fmt: &'static str,
+}
+
+/// 平台约束表
+const PLATFORM_CONSTRAINTS: &[PlatformConstraints] = &[
+    PlatformConstraints {
+        id: "social",
+        max_fps: 60.0,
+        max_long_edge: 1920,
+        max_short_edge: 1080,
+        profile: "high",
+        level: "4.1",
+        level_value: 41,
+        pix_fmt: "yuv420p",
+    },
+    PlatformConstraints {
+        id: "wechat",
+        max_fps: 30.0,
+        max_long_edge: 1920,
+        max_short_edge: 1080,
+        profile: "high",
+        level: "4.1",
+        level_value: 41,
+        pix_fmt: "yuv420p",
+    },
+    PlatformConstraints {
+        id: "bilibili",
+        max_fps: 60.0,
+        max_long_edge: 1920,
+        max_short_edge: 1080,
+        profile: "high",
+        level: "4.1",
+        level_value: 41,
+        pix_fmt: "yuv420p",
+    },
+];
+
+/// 按平台标识查找编码约束
+///
+/// 同时接受 "wechat" 和预设 id 形式的 "compress_wechat"
+pub fn get_platform_constraints(platform: &str) -> Option<&'static PlatformConstraints> {
+    let id = platform.trim_start_matches("compress_");
+    PLATFORM_CONSTRAINTS.iter().find(|c| c.id == id)
+}
+
+/// 平台约束的执行计划
+///
+/// 只包含源视频实际违反的约束项，未违反的约束不产生任何参数
+#[derive(Clone, Debug, Default)]
+pub struct ConstraintPlan {
+    /// 需要追加的视频滤镜（如 fps=30、scale=...）
+    pub video_filters: Vec<String>,
+    /// 需要追加的输出参数（如 -profile:v high）
+    pub output_args: Vec<(String, String)>,
+    /// 面向用户的调整说明
+    pub notices: Vec<String>,
+}
+
+/// 根据源视频流信息计算需要执行的平台约束
+///
+/// 每一项约束仅在源视频违反时生效：
+/// - 帧率超过上限 → fps 滤镜降帧
+/// - 分辨率超过上限 → 等比缩小到上限以内（区分横竖屏）
+/// - 非 H.264 / profile 不兼容 / level 过高 → 指定 profile 和 level
+/// - 像素格式不是 yuv420p → 指定 -pix_fmt
+///
+/// # 参数
+/// - `constraints` - 平台约束
+/// - `source` - 源视频流信息
+pub fn plan_platform_constraints(
+    constraints: &PlatformConstraints,
+    source: &VideoStream,
+) -> ConstraintPlan {
+    let mut plan = ConstraintPlan::default();
+
+    // 帧率上限
+    if source.frame_rate > constraints.max_fps + 0.01 {
+        plan.video_filters.push(format!("fps={}", constraints.max_fps));
+        plan.notices.push(format!(
+            "Frame rate capped from {:.2} to {} fps for {}",
+            source.frame_rate, constraints.max_fps, constraints.id
+        ));
+    }
+
+    // 分辨率上限（按长边/短边比较，竖屏视频同样适用；
+    // 自动旋转后缩放滤镜看到的是显示宽高）
+    let (width, height) = source.display_dimensions();
+    let is_portrait = height > width;
+    let long_edge = width.max(height);
+    let short_edge = width.min(height);
+    if long_edge > constraints.max_long_edge || short_edge > constraints.max_short_edge {
+        let (max_w, max_h) = if is_portrait {
+            (constraints.max_short_edge, constraints.max_long_edge)
+        } else {
+            (constraints.max_long_edge, constraints.max_short_edge)
+        };
+        plan.video_filters.push(format!(
+            "scale={}:{}:force_original_aspect_ratio=decrease:force_divisible_by=2",
+            max_w, max_h
+        ));
+        plan.notices.push(format!(
+            "Resolution downscaled from {}x{} to fit within {}x{} for {}",
+            width, height, max_w, max_h, constraints.id
+        ));
+    }
+
+    // H.264 profile/level
+    let profile_ok = matches!(
+        source.profile.as_deref(),
+        Some("Baseline") | Some("Constrained Baseline") | Some("Main") | Some("High")
+    );
+    let level_ok = source
+        .level
+        .map(|l| l > 0 && l <= constraints.level_value)
+        .unwrap_or(false);
+    if source.codec_name != "h264" || !profile_ok || !level_ok {
+        plan.output_args.push(("-profile:v".to_string(), constraints.profile.to_string()));
+        plan.output_args.push(("-level".to_string(), constraints.level.to_string()));
+        plan.notices.push(format!(
+            "H.264 profile/level set to {}@{} for {}",
+            constraints.profile, constraints.level, constraints.id
+        ));
+    }
+
+    // 像素格式
+    if source.pix_fmt != constraints.pix_fmt {
+        plan.output_args.push(("-pix_fmt".to_string(), constraints.pix_fmt.to_string()));
+        plan.notices.push(format!(
+            "Pixel format converted from {} to {} for {}",
+            if source.pix_fmt.is_empty() { "unknown" } else { &source.pix_fmt },
+            constraints.pix_fmt,
+            constraints.id
+        ));
+    }
+
+    plan
+}
+
+// ============================================================
+// 自适应码率阶梯
+// ============================================================
+
+/// 标准码率阶梯（高度, 视频码率 kbps, 音频码率 kbps），从高到低
+const BITRATE_LADDER: &[(u32, u32, u32)] = &[
+    (1080, 5000, 192),
+    (720, 2800, 128),
+    (480, 1400, 128),
+    (360, 800, 96),
+];
+
+/// 按源视频高度生成码率阶梯
+///
+/// 只保留不高于源分辨率的档位（不做放大）；
+/// 源视频低于最低档时只输出一档源分辨率
+///
+/// # 参数
+/// - `source_height` - 源视频高度（像素）
+///
+/// # 返回
+/// 从高到低排列的清晰度列表
+pub fn auto_bitrate_ladder(source_height: u32) -> Vec<StreamRendition> {
+    let ladder: Vec<StreamRendition> = BITRATE_LADDER
+        .iter()
+        .filter(|(height, _, _)| *height <= source_height)
+        .map(|&(height, video, audio)| StreamRendition {
+            height,
+            video_bitrate_kbps: video,
+            audio_bitrate_kbps: Some(audio),
+        })
+        .collect();
+    if !ladder.is_empty() {
+        return ladder;
+    }
+
+    let (_, video, audio) = BITRATE_LADDER[BITRATE_LADDER.len() - 1];
+    vec![StreamRendition {
+        // 高度需为偶数（yuv420p 要求）
+        height: (source_height.max(2) / 2) * 2,
+        video_bitrate_kbps: video,
+        audio_bitrate_kbps: Some(audio),
+    }]
+}
+
+// ============================================================
+// 输出体积估算
+// ============================================================
+
+/// 各编码器在参考 CRF 下每像素每帧的平均比特数：(编码器, 参考 CRF, bpp)
+///
+/// 经验值：libx264 CRF 23 的 1080p30 约 5 Mbps；x265/VP9/AV1 同等画质约省 35%-50%。
+/// VP9/AV1 的 CRF 量程为 0-63，参考点取 31
+const CRF_BITS_PER_PIXEL: &[(&str, u32, f64)] = &[
+    ("libx264", 23, 0.08),
+    ("libx265", 23, 0.05),
+    ("libvpx-vp9", 31, 0.05),
+    ("libaom-av1", 31, 0.04),
+    ("libsvtav1", 31, 0.04),
+];
+
+/// CRF 每增加多少码率减半（x264 文档的经验规则）
+const CRF_HALVING_STEP: f64 = 6.0;
+
+/// 容器封装开销（约 1%）
+const MUXING_OVERHEAD: f64 = 1.01;
+
+/// 视频码率控制方式（体积估算的输入）
+#[derive(Clone, Debug, PartialEq)]
+pub enum VideoRateControl {
+    /// 指定目标码率（bps），体积可精确计算
+    Bitrate(u64),
+    /// CRF 质量模式，体积按画面尺寸和帧率经验估算
+    Crf {
+        /// 编码器名（libx264 / libx265 / libvpx-vp9 ...），未收录的按 libx264 计算
+        encoder: String,
+        crf: u32,
+        width: u32,
+        height: u32,
+        frame_rate: f64,
+    },
+    /// 流复制，沿用源视频码率（bps）
+    Copy(u64),
+    /// 无视频流（纯音频输出）
+    None,
+}
+
+/// 估算 CRF 编码的视频码率
+///
+/// bpp = 参考 bpp × 2^((参考 CRF - crf) / 6)，码率 = bpp × 宽 × 高 × 帧率
+///
+/// # 参数
+/// - `encoder` - 编码器名
+/// - `crf` - CRF 值
+/// - `width` / `height` - 输出画面尺寸（像素）
+/// - `frame_rate` - 输出帧率，未知（0）时按 30 计算
+///
+/// # 返回
+/// 估算码率（bps）
+pub fn estimate_crf_bitrate(
+    encoder: &str,
+    crf: u32,
+    width: u32,
+    height: u32,
+    frame_rate: f64,
+) -> u64 {
+    let (_, reference_crf, reference_bpp) = CRF_BITS_PER_PIXEL
+        .iter()
+        .find(|(name, _, _)| *name == encoder)
+        .copied()
+        .unwrap_or(CRF_BITS_PER_PIXEL[0]);
+    let bpp = reference_bpp * 2f64.powf((reference_crf as f64 - crf as f64) / CRF_HALVING_STEP);
+    let frame_rate = if frame_rate > 0.0 { frame_rate } else { 30.0 };
+    (bpp * width as f64 * height as f64 * frame_rate) as u64
+}
+
+/// 估算输出文件体积
+///
+/// 体积 = (视频码率 + 音频码率) × 时长 / 8，再加约 1% 的封装开销
+///
+/// # 参数
+/// - `video` - 视频码率控制方式
+/// - `audio_bps` - 所有输出音轨的码率之和（bps）
+/// - `duration` - 输出时长（秒）
+///
+/// # 返回
+/// (估算体积（字节）, 使用的视频码率（bps）)
+pub fn estimate_encoded_size(
+    video: &VideoRateControl,
+    audio_bps: u64,
+    duration: f64,
+) -> (u64, u64) {
+    let video_bps = match video {
+        VideoRateControl::Bitrate(bps) | VideoRateControl::Copy(bps) => *bps,
+        VideoRateControl::Crf {
+            encoder,
+            crf,
+            width,
+            height,
+            frame_rate,
+        } => estimate_crf_bitrate(encoder, *crf, *width, *height, *frame_rate),
+        VideoRateControl::None => 0,
+    };
+    let bytes = (video_bps + audio_bps) as f64 * duration.max(0.0) / 8.0 * MUXING_OVERHEAD;
+    (bytes as u64, video_bps)
+}
+
+/// 汇总输出体积估算结果
+///
+/// 按码率计算的估算可信度为 exact，按 CRF 经验表估算的为 approximate；
+/// 参数自动调整提示由调用方填入
+///
+/// # 参数
+/// - `video` - 视频码率控制方式
+/// - `audio_bps` - 所有输出音轨的码率之和（bps）
+/// - `duration` - 输出时长（秒）
+/// - `video_encoder` - 实际使用的视频编码器名
+/// - `exact` - 码率是否确定（流复制时源码率为估算值则为 false）
+pub fn build_size_estimate(
+    video: &VideoRateControl,
+    audio_bps: u64,
+    duration: f64,
+    video_encoder: String,
+    exact: bool,
+) -> SizeEstimate {
+    let (estimated_bytes, video_bitrate) = estimate_encoded_size(video, audio_bps, duration);
+    let crf = match video {
+        VideoRateControl::Crf { crf, .. } => Some(*crf),
+        _ => None,
+    };
+    SizeEstimate {
+        estimated_bytes,
+        confidence: if exact {
+            EstimateConfidence::Exact
+        } else {
+            EstimateConfidence::Approximate
+        },
+        duration,
+        video_bitrate,
+        audio_bitrate: audio_bps,
+        video_encoder,
+        crf,
+        notices: Vec::new(),
+    }
+}
+
+/// 解析 ffmpeg 码率字符串（"192k" / "4.5M" / "128000"）
+///
+/// # 返回
+/// 码率（bps），无法解析时返回 None
+pub fn parse_bitrate(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (number, multiplier) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1_000.0),
+        'm' | 'M' => (&value[..value.len() - 1], 1_000_000.0),
+        _ => (value, 1.0),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    (number.is_finite() && number >= 0.0).then_some((number * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::media::video_stream;
+    use serde_json::json;
+
+    #[test]
+    fn platform_constraints_accept_compress_preset_ids() {
+        assert_eq!(get_platform_constraints("compress_wechat").unwrap().id, "wechat");
+        assert_eq!(get_platform_constraints("bilibili").unwrap().max_fps, 60.0);
+        assert!(get_platform_constraints("vimeo").is_none());
+    }
+
+    #[test]
+    fn compliant_source_needs_no_constraint_args() {
+        let wechat = get_platform_constraints("wechat").unwrap();
+        let plan = plan_platform_constraints(wechat, &video_stream(json!({})));
+        assert!(plan.video_filters.is_empty());
+        assert!(plan.output_args.is_empty());
+        assert!(plan.notices.is_empty());
+    }
+
+    #[test]
+    fn wechat_caps_frame_rate_resolution_level_and_pixel_format() {
+        let wechat = get_platform_constraints("wechat").unwrap();
+        let source = video_stream(json!({
+            "codecName": "hevc",
+            "width": 3840,
+            "height": 2160,
+            "frameRate": 59.94,
+            "pixFmt": "yuv420p10le",
+            "profile": "Main 10",
+            "level": 153,
+        }));
+        let plan = plan_platform_constraints(wechat, &source);
+        assert_eq!(
+            plan.video_filters,
+            vec![
+                "fps=30".to_string(),
+                "scale=1920:1080:force_original_aspect_ratio=decrease:force_divisible_by=2"
+                    .to_string(),
+            ]
+        );
+        let args: Vec<(&str, &str)> =
+            plan.output_args.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        assert_eq!(
+            args,
+            vec![("-profile:v", "high"), ("-level", "4.1"), ("-pix_fmt", "yuv420p")]
+        );
+        assert_eq!(plan.notices.len(), 4);
+    }
+
+    #[test]
+    fn portrait_source_swaps_resolution_bounds() {
+        let bilibili = get_platform_constraints("bilibili").unwrap();
+        let source = video_stream(json!({ "width": 2160, "height": 3840 }));
+        let plan = plan_platform_constraints(bilibili, &source);
+        assert_eq!(
+            plan.video_filters,
+            vec!["scale=1080:1920:force_original_aspect_ratio=decrease:force_divisible_by=2"]
+        );
+    }
+
+    #[test]
+    fn rotated_portrait_source_uses_display_bounds() {
+        let bilibili = get_platform_constraints("bilibili").unwrap();
+        let source = video_stream(json!({ "width": 3840, "height": 2160, "rotation": 90 }));
+        let plan = plan_platform_constraints(bilibili, &source);
+        assert_eq!(
+            plan.video_filters,
+            vec!["scale=1080:1920:force_original_aspect_ratio=decrease:force_divisible_by=2"]
+        );
+    }
+
+    #[test]
+    fn rotated_phone_video_is_not_reframed_for_short_form() {
+        let tiktok = get_short_form_profile("tiktok").unwrap();
+        let source = video_stream(json!({ "rotation": 90 }));
+        let plan = plan_short_form_export(tiktok, Some(&source), "crop");
+        assert_eq!(plan.video_filters[0], "scale=1080:1920:flags=lanczos");
+        assert!(plan.video_filters.iter().all(|f| !f.starts_with("crop=")));
+        assert!(plan.notices.iter().all(|n| !n.contains("center-cropped")));
+    }
+
+    #[test]
+    fn landscape_source_is_center_cropped_for_short_form() {
+        let tiktok = get_short_form_profile("tiktok").unwrap();
+        let plan = plan_short_form_export(tiktok, Some(&video_stream(json!({}))), "crop");
+        assert!(plan.video_filters.contains(&"crop=1080:1920".to_string()));
+    }
+
+    #[test]
+    fn webp_presets_cover_infinite_and_single_play() {
+        let presets = get_all_presets();
+        let loop_count = |id: &str| {
+            let preset = presets.iter().find(|p| p.id == id).expect(id);
+            assert_eq!(preset.category, "gif");
+            let params = preset.params.as_ref().unwrap();
+            assert_eq!(params["outputAsWebp"], true);
+            params["loopCount"].as_i64().unwrap()
+        };
+        assert_eq!(loop_count("webp_loop"), 0);
+        assert_eq!(loop_count("webp_once"), 1);
+    }
+
+    #[test]
+    fn crf_bitrate_halves_every_six_crf_steps() {
+        let reference = estimate_crf_bitrate("libx264", 23, 1920, 1080, 30.0);
+        assert_eq!(reference, 4_976_640);
+        assert_eq!(estimate_crf_bitrate("libx264", 29, 1920, 1080, 30.0), reference / 2);
+        // 未知帧率按 30 计算，未收录的编码器按 libx264 计算
+        assert_eq!(estimate_crf_bitrate("libx264", 23, 1920, 1080, 0.0), reference);
+        assert_eq!(estimate_crf_bitrate("mpeg4", 23, 1920, 1080, 30.0), reference);
+        assert!(estimate_crf_bitrate("libx265", 23, 1920, 1080, 30.0) < reference);
+    }
+
+    #[test]
+    fn bitrate_estimate_is_exact_with_muxing_overhead() {
+        let video = VideoRateControl::Bitrate(2_000_000);
+        let estimate = build_size_estimate(&video, 128_000, 60.0, "libx264".to_string(), true);
+        assert_eq!(estimate.estimated_bytes, 16_119_600);
+        assert_eq!(estimate.video_bitrate, 2_000_000);
+        assert_eq!(estimate.audio_bitrate, 128_000);
+        assert_eq!(estimate.confidence, EstimateConfidence::Exact);
+        assert_eq!(estimate.crf, None);
+    }
+
+    #[test]
+    fn crf_estimate_is_approximate_and_reports_crf() {
+        let video = VideoRateControl::Crf {
+            encoder: "libx264".to_string(),
+            crf: 23,
+            width: 1920,
+            height: 1080,
+            frame_rate: 30.0,
+        };
+        let estimate = build_size_estimate(&video, 0, 10.0, "libx264".to_string(), false);
+        assert_eq!(estimate.video_bitrate, 4_976_640);
+        assert_eq!(estimate.estimated_bytes, 6_283_008);
+        assert_eq!(estimate.confidence, EstimateConfidence::Approximate);
+        assert_eq!(estimate.crf, Some(23));
+    }
+
+    #[test]
+    fn audio_only_estimate_ignores_video_and_negative_duration() {
+        let (bytes, video_bps) = estimate_encoded_size(&VideoRateControl::None, 192_000, 10.0);
+        assert_eq!((bytes, video_bps), (242_400, 0));
+        assert_eq!(estimate_encoded_size(&VideoRateControl::Copy(1_000), 0, -5.0), (0, 1_000));
+    }
+
+    #[test]
+    fn parses_ffmpeg_bitrate_strings() {
+        assert_eq!(parse_bitrate("192k"), Some(192_000));
+        assert_eq!(parse_bitrate("4.5M"), Some(4_500_000));
+        assert_eq!(parse_bitrate("128000"), Some(128_000));
+        assert_eq!(parse_bitrate("fast"), None);
+        assert_eq!(parse_bitrate("-1k"), None);
+    }
+
+
+    #[test]
+    fn user_presets_round_trip_through_file() {
+        let dir = std::env::temp_dir().join(format!("clipforge_presets_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("presets.json");
+        assert!(read_user_presets(&path).is_empty());
+
+        let preset = PresetInfo {
+            id: "my_gif".to_string(),
+            name: "My GIF".to_string(),
+            description: "Small looping GIF".to_string(),
+            category: "gif".to_string(),
+            params: Some(json!({ "width": 320, "fps": 12 })),
+        };
+        write_user_presets(&path, std::slice::from_ref(&preset)).unwrap();
+        let loaded = read_user_presets(&path);
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value([&preset]).unwrap()
+        );
+
+        // 不合法的条目（未知模块、缺少参数对象）读取时被跳过
+        let mut entries = serde_json::to_value([&preset]).unwrap();
+        let entries = entries.as_array_mut().unwrap();
+        entries.push(json!({ "id": "bad", "name": "Bad", "category": "nope", "params": {} }));
+        entries.push(json!({ "id": "empty", "name": "Empty", "category": "gif" }));
+        std::fs::write(&path, serde_json::to_string(entries).unwrap()).unwrap();
+        let loaded = read_user_presets(&path);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].id, "my_gif");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+
+    #[test]
+    fn convert_preset_round_trips_apart_from_paths() {
+        use crate::models::preset::ConvertParams;
+
+        let params = ConvertParams {
+            input_path: "/videos/source.mov".to_string(),
+            output_path: "/videos/source.mp4".to_string(),
+            output_format: "mp4".to_string(),
+            video_codec: "libx265".to_string(),
+            audio_codec: "aac".to_string(),
+            quality: Some(26),
+            preset: Some("slow".to_string()),
+            hardware_accel: Some(false),
+            audio_bitrate: Some("160k".to_string()),
+            audio_sample_rate: Some(48000),
+            video_bitrate: Some("4M".to_string()),
+            two_pass: Some(true),
+            color_range: Some("tv".to_string()),
+            pixel_format: Some("yuv420p10le".to_string()),
+            preserve_metadata: Some(false),
+            deinterlace: Some(true),
+            auto_rotate: Some(false),
+            streaming: Some(true),
+            gop_size: Some(48),
+            audio_stream_indexes: Some(vec![1, 0]),
+            keep_all_streams: Some(true),
+            extra_args: Some(vec!["-tag:v".to_string(), "hvc1".to_string()]),
+        };
+        let original = serde_json::to_value(&params).unwrap();
+
+        // 保存预设：规整后去掉路径
+        let mut saved = normalize_preset_params("convert", &original).unwrap();
+        strip_preset_paths(&mut saved);
+        for key in PRESET_PATH_KEYS {
+            assert!(saved.get(*key).is_none(), "{} should not be saved", key);
+        }
+
+        // 应用预设：合并新的输入/输出路径后再规整（与 apply_preset 相同）
+        let mut applied = saved.clone();
+        let target = applied.as_object_mut().unwrap();
+        target.insert("inputPath".to_string(), json!("/other/clip.mkv"));
+        target.insert("outputPath".to_string(), json!("/other/clip.mp4"));
+        let applied = normalize_preset_params("convert", &applied).unwrap();
+
+        let mut expected = original;
+        expected["inputPath"] = json!("/other/clip.mkv");
+        expected["outputPath"] = json!("/other/clip.mp4");
+        assert_eq!(applied, expected);
+    }
+}
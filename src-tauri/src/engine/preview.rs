@@ -0,0 +1,117 @@
+/// 预览文件注册表
+///
+/// 预览类 command 生成的临时文件不再把路径直接交给前端，
+/// 而是在此登记并换取一个令牌，前端通过 get_preview(令牌) 读取文件内容。
+/// 这样 webview 无需获得临时目录的文件系统访问权限，临时文件的清理也集中在这里：
+/// - 同一功能生成新预览时，旧预览的文件立即删除
+/// - 超过 PREVIEW_TTL 未被替换的预览在下次访问注册表时删除
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+use crate::utils::path::cleanup_temp_file;
+
+/// 预览文件的最长保留时间
+const PREVIEW_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 全局预览注册表单例
+static PREVIEWS: once_cell::sync::Lazy<Mutex<PreviewRegistry>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(PreviewRegistry::default()));
+
+/// 已登记的预览文件
+struct PreviewEntry {
+    /// 所属功能（如 "scrub"），同一功能的新预览会替换旧预览
+    feature: String,
+    /// 临时文件路径
+    path: String,
+    /// 登记时间
+    created_at: Instant,
+}
+
+/// 预览注册表（令牌 -> 预览文件）
+#[derive(Default)]
+struct PreviewRegistry {
+    entries: HashMap<String, PreviewEntry>,
+}
+
+impl PreviewRegistry {
+    /// 删除过期的预览及其文件
+    fn purge_expired(&mut self) {
+        self.entries.retain(|_, entry| {
+            let alive = entry.created_at.elapsed() < PREVIEW_TTL;
+            if !alive {
+                cleanup_temp_file(&entry.path);
+            }
+            alive
+        });
+    }
+
+    /// 删除指定功能的全部预览及其文件
+    fn remove_feature(&mut self, feature: &str) {
+        self.entries.retain(|_, entry| {
+            let keep = entry.feature != feature;
+            if !keep {
+                cleanup_temp_file(&entry.path);
+            }
+            keep
+        });
+    }
+}
+
+/// 登记一组预览文件，替换同一功能之前的全部预览
+///
+/// 一次请求生成多张预览（如时间轴缩略图）时应一起登记，
+/// 避免后登记的文件把同一批前面的文件替换掉
+///
+/// # 参数
+/// - `feature` - 所属功能标识
+/// - `paths` - 预览临时文件路径列表
+///
+/// # 返回
+/// 与 paths 顺序一一对应的令牌
+pub async fn register_previews(feature: &str, paths: Vec<String>) -> Vec<String> {
+    let mut registry = PREVIEWS.lock().await;
+    registry.purge_expired();
+    registry.remove_feature(feature);
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let token = uuid::Uuid::new_v4().to_string();
+            registry.entries.insert(
+                token.clone(),
+                PreviewEntry {
+                    feature: feature.to_string(),
+                    path,
+                    created_at: Instant::now(),
+                },
+            );
+            token
+        })
+        .collect()
+}
+
+/// 读取令牌对应的预览文件内容
+///
+/// # 参数
+/// - `token` - register_previews 返回的令牌
+///
+/// # 返回
+/// - `Ok(Vec<u8>)` - 文件内容
+/// - `Err(String)` - 令牌不存在、已过期或文件读取失败
+pub async fn read_preview(token: &str) -> Result<Vec<u8>, String> {
+    let path = {
+        let mut registry = PREVIEWS.lock().await;
+        registry.purge_expired();
+        registry
+            .entries
+            .get(token)
+            .map(|entry| entry.path.clone())
+            .ok_or_else(|| "Preview not found or expired".to_string())?
+    };
+    tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read preview: {}", e))
+}
@@ -3,15 +3,21 @@
 /// 负责通过 Tauri Sidecar 机制启动和管理 ffmpeg/ffprobe 子进程。
 /// 使用 spawn 模式获取异步事件流，实时解析进度并推送给前端
 
-use std::time::Instant;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use tauri::ipc::Channel;
 use tauri_plugin_shell::ShellExt;
 use tauri_plugin_shell::process::CommandEvent;
 
-use crate::engine::progress::ProgressParser;
+use crate::engine::builder::build_ffprobe_info_args;
+use crate::engine::progress::{extract_error_message, ProgressParser};
+use crate::engine::verify::{verify_file, ExpectedOutput};
+use crate::models::media::{FfprobeOutput, MediaInfo};
 use crate::models::task::{TaskEvent, TaskResult, TaskStatus};
-use crate::utils::path::get_file_size;
+use crate::utils::path::{
+    atomic_temp_path, cleanup_temp_file, commit_temp_output, get_file_size,
+};
 
 /// 执行 ffmpeg sidecar 命令并通过 Channel 推送进度
 ///
@@ -38,7 +44,304 @@ pub async fn run_ffmpeg(
     output_path: &str,
     on_progress: &Channel<TaskEvent>,
 ) -> Result<TaskResult, String> {
+    let options = RunOptions::default();
+    run_ffmpeg_inner(app, task_id, args, total_duration, output_path, on_progress, options).await
+}
+
+/// 执行 ffmpeg 命令，进度更新附带阶段标签
+///
+/// 与 run_ffmpeg 相同，用于多步骤任务中由单个 ffmpeg 进程完成的步骤（如合并），
+/// 每条 Progress 事件的 phase 字段为 `phase`
+///
+/// # 参数
+/// - `phase` - 阶段标签，其余参数同 run_ffmpeg
+pub async fn run_ffmpeg_with_phase(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    args: Vec<String>,
+    total_duration: f64,
+    output_path: &str,
+    on_progress: &Channel<TaskEvent>,
+    phase: &str,
+) -> Result<TaskResult, String> {
+    let options = RunOptions {
+        phase: Some(phase),
+        ..Default::default()
+    };
+    run_ffmpeg_inner(app, task_id, args, total_duration, output_path, on_progress, options).await
+}
+
+/// 执行 ffmpeg 命令，完成后核对输出文件的编码属性
+///
+/// 与 run_ffmpeg 相同，成功退出后额外探测输出文件并与 `expected` 比对，
+/// 核对结果附加在 Completed 事件和 TaskResult 中；
+/// 不一致的项以 Notice 提示，不会使任务失败
+///
+/// # 参数
+/// - `expected` - 参数要求的输出属性，其余参数同 run_ffmpeg
+///
+/// # 返回
+/// - `Ok(TaskResult)` - 任务执行结果（含核对结果）
+/// - `Err(String)` - 启动失败的错误描述
+pub async fn run_ffmpeg_verified(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    args: Vec<String>,
+    total_duration: f64,
+    output_path: &str,
+    on_progress: &Channel<TaskEvent>,
+    expected: &ExpectedOutput,
+) -> Result<TaskResult, String> {
+    let options = RunOptions {
+        expected: Some(expected),
+        ..Default::default()
+    };
+    run_ffmpeg_inner(app, task_id, args, total_duration, output_path, on_progress, options).await
+}
+
+/// 两遍编码中第一遍占总进度的百分比（第一遍只做分析，通常比第二遍快）
+const FIRST_PASS_PERCENT: f64 = 45.0;
+/// 两遍编码第一遍的阶段标签
+const FIRST_PASS_PHASE: &str = "Pass 1/2: analyzing";
+/// 两遍编码第二遍的阶段标签
+const SECOND_PASS_PHASE: &str = "Pass 2/2: encoding";
+
+/// 执行两遍编码并通过 Channel 推送进度
+///
+/// 第一遍分析（统计写入 passlog）的进度映射到 0–45%，第二遍编码映射到 45–100%，
+/// 前端看到的是一个连续的任务：只发送一次 Started 和最终的 Completed/Failed/Cancelled。
+/// 第一遍失败或被取消时不再执行第二遍。passlog 文件由调用方负责清理
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `task_id` - 任务唯一标识
+/// - `pass1_args` - 第一遍的 ffmpeg 参数（需包含 -progress pipe:1）
+/// - `pass2_args` - 第二遍的 ffmpeg 参数（需包含 -progress pipe:1）
+/// - `total_duration` - 视频总时长（秒），两遍都以此计算进度
+/// - `output_path` - 最终输出文件路径
+/// - `on_progress` - Tauri Channel，用于向前端推送 TaskEvent
+///
+/// # 返回
+/// - `Ok(TaskResult)` - 任务执行结果
+/// - `Err(String)` - 启动失败的错误描述
+pub async fn run_ffmpeg_two_pass(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    pass1_args: Vec<String>,
+    pass2_args: Vec<String>,
+    total_duration: f64,
+    output_path: &str,
+    on_progress: &Channel<TaskEvent>,
+) -> Result<TaskResult, String> {
+    let args = (pass1_args, pass2_args);
+    run_two_pass_inner(app, task_id, args, total_duration, output_path, on_progress, None).await
+}
+
+/// 执行两遍编码，完成后核对输出文件的编码属性
+///
+/// 与 run_ffmpeg_two_pass 相同，第二遍成功后按 `expected` 核对输出（见 run_ffmpeg_verified）
+///
+/// # 参数
+/// - `passes` - (第一遍参数, 第二遍参数)
+/// - `expected` - 参数要求的输出属性，其余参数同 run_ffmpeg_two_pass
+pub async fn run_ffmpeg_two_pass_verified(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    passes: (Vec<String>, Vec<String>),
+    total_duration: f64,
+    output_path: &str,
+    on_progress: &Channel<TaskEvent>,
+    expected: &ExpectedOutput,
+) -> Result<TaskResult, String> {
+    let expected = Some(expected);
+    run_two_pass_inner(app, task_id, passes, total_duration, output_path, on_progress, expected).await
+}
+
+/// run_ffmpeg_two_pass / run_ffmpeg_two_pass_verified 的共同实现
+async fn run_two_pass_inner(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    (pass1_args, pass2_args): (Vec<String>, Vec<String>),
+    total_duration: f64,
+    output_path: &str,
+    on_progress: &Channel<TaskEvent>,
+    expected: Option<&ExpectedOutput>,
+) -> Result<TaskResult, String> {
+    track_paths(task_id, &pass1_args, Some(output_path)).await;
+    // 两遍共用一个并发名额
+    if !wait_for_slot(task_id, on_progress).await {
+        let start_time = Instant::now();
+        let outcome = PassOutcome::Cancelled;
+        return finish_task(app, task_id, outcome, start_time, output_path, on_progress, None).await;
+    }
+    let start_time = Instant::now();
+    // 第一遍只分析、不写输出文件，仅第二遍需要改写到临时文件
+    let (pass2_args, temp_output) = stage_output(app, pass2_args, output_path).await;
+
+    let _ = on_progress.send(TaskEvent::Started {
+        task_id: task_id.to_string(),
+        total_duration,
+    });
+
+    let first_span = PassSpan::new(0.0, FIRST_PASS_PERCENT, Some(FIRST_PASS_PHASE));
+    let first_pass =
+        run_pass(app, task_id, pass1_args, total_duration, on_progress, first_span, None).await;
+    let outcome = match first_pass {
+        Ok(PassOutcome::Success) => {
+            let span = PassSpan::new(FIRST_PASS_PERCENT, 100.0, Some(SECOND_PASS_PHASE));
+            run_pass(app, task_id, pass2_args, total_duration, on_progress, span, None).await
+        }
+        other => other,
+    };
+    release_slot(task_id).await;
+    let outcome = settle_output(outcome, temp_output.as_deref(), output_path);
+
+    finish_task(app, task_id, outcome?, start_time, output_path, on_progress, expected).await
+}
+
+/// 复用缓存统计的两遍编码参数
+pub struct CachedTwoPass {
+    /// 使用缓存统计的第二遍参数
+    pub cached_pass: Vec<String>,
+    /// 缓存不可用时重新执行的第一遍参数
+    pub pass1: Vec<String>,
+    /// 缓存不可用时重新执行的第二遍参数
+    pub pass2: Vec<String>,
+}
+
+/// 使用缓存的第一遍统计直接执行第二遍，失败时退回完整的两遍编码
+///
+/// 缓存统计可能已损坏或与当前编码器版本不兼容：第二遍失败（非取消）时调用
+/// `on_cache_failed` 让调用方删除缓存条目，再在同一并发名额内完整执行两遍。
+/// 前端看到的仍是一个连续的任务：只发送一次 Started 和最终的 Completed/Failed/Cancelled
+///
+/// # 参数
+/// - `passes` - 缓存第二遍和退回时使用的两遍参数
+/// - `on_cache_failed` - 缓存第二遍失败时的回调（参数为错误信息）
+/// - 其余参数同 run_ffmpeg_two_pass
+pub async fn run_ffmpeg_two_pass_cached(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    passes: CachedTwoPass,
+    total_duration: f64,
+    output_path: &str,
+    on_progress: &Channel<TaskEvent>,
+    on_cache_failed: impl FnOnce(&str),
+) -> Result<TaskResult, String> {
+    let CachedTwoPass {
+        cached_pass: cached_args,
+        pass1: pass1_args,
+        pass2: pass2_args,
+    } = passes;
+    track_paths(task_id, &cached_args, Some(output_path)).await;
+    if !wait_for_slot(task_id, on_progress).await {
+        let start_time = Instant::now();
+        let outcome = PassOutcome::Cancelled;
+        return finish_task(app, task_id, outcome, start_time, output_path, on_progress, None).await;
+    }
+    let start_time = Instant::now();
+    let (cached_args, temp_output) = stage_output(app, cached_args, output_path).await;
+
+    let _ = on_progress.send(TaskEvent::Started {
+        task_id: task_id.to_string(),
+        total_duration,
+    });
+
+    let span = PassSpan::full(Some(SECOND_PASS_PHASE));
+    let cached = run_pass(app, task_id, cached_args, total_duration, on_progress, span, None).await;
+    let outcome = match settle_output(cached, temp_output.as_deref(), output_path) {
+        Ok(PassOutcome::Failed(error)) => {
+            log::warn!("任务 {} 使用缓存统计的第二遍失败，重新执行两遍编码: {}", task_id, error);
+            on_cache_failed(&error);
+            let _ = on_progress.send(TaskEvent::Notice {
+                task_id: task_id.to_string(),
+                message: "Cached first-pass statistics could not be used; \
+                          running both passes again"
+                    .to_string(),
+            });
+            let (pass2_args, temp_output) = stage_output(app, pass2_args, output_path).await;
+            let first_span = PassSpan::new(0.0, FIRST_PASS_PERCENT, Some(FIRST_PASS_PHASE));
+            let first_pass =
+                run_pass(app, task_id, pass1_args, total_duration, on_progress, first_span, None)
+                    .await;
+            let outcome = match first_pass {
+                Ok(PassOutcome::Success) => {
+                    let span = PassSpan::new(FIRST_PASS_PERCENT, 100.0, Some(SECOND_PASS_PHASE));
+                    run_pass(app, task_id, pass2_args, total_duration, on_progress, span, None)
+                        .await
+                }
+                other => other,
+            };
+            settle_output(outcome, temp_output.as_deref(), output_path)
+        }
+        other => other,
+    };
+    release_slot(task_id).await;
+
+    finish_task(app, task_id, outcome?, start_time, output_path, on_progress, None).await
+}
+
+/// 单次 ffmpeg 进程的结束方式
+enum PassOutcome {
+    /// 正常退出
+    Success,
+    /// 被用户取消
+    Cancelled,
+    /// 异常退出（附带从 stderr 提取的错误信息）
+    Failed(String),
+}
+
+/// 单个 ffmpeg 进程在整个任务中的进度区间（百分比）和阶段标签
+#[derive(Clone, Copy)]
+struct PassSpan<'a> {
+    /// 区间起点
+    start: f64,
+    /// 区间终点
+    end: f64,
+    /// 阶段标签，单步任务为 None
+    phase: Option<&'a str>,
+}
+
+impl<'a> PassSpan<'a> {
+    fn new(start: f64, end: f64, phase: Option<&'a str>) -> Self {
+        Self { start, end, phase }
+    }
+
+    /// 占满 0–100% 的单遍任务
+    fn full(phase: Option<&'a str>) -> Self {
+        Self::new(0.0, 100.0, phase)
+    }
+}
+
+/// run_ffmpeg_inner 的可选行为
+#[derive(Default, Clone, Copy)]
+struct RunOptions<'a> {
+    /// 成功后需核对的输出属性
+    expected: Option<&'a ExpectedOutput>,
+    /// 附加到进度更新的阶段标签
+    phase: Option<&'a str>,
+}
+
+/// run_ffmpeg / run_ffmpeg_verified / run_ffmpeg_with_phase 的共同实现
+async fn run_ffmpeg_inner(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    args: Vec<String>,
+    total_duration: f64,
+    output_path: &str,
+    on_progress: &Channel<TaskEvent>,
+    options: RunOptions<'_>,
+) -> Result<TaskResult, String> {
+    let RunOptions { expected, phase } = options;
+    track_paths(task_id, &args, Some(output_path)).await;
+    // 等待并发名额（受 max_concurrent 限制），排队期间被取消则直接结束
+    if !wait_for_slot(task_id, on_progress).await {
+        let start_time = Instant::now();
+        let outcome = PassOutcome::Cancelled;
+        return finish_task(app, task_id, outcome, start_time, output_path, on_progress, None).await;
+    }
     let start_time = Instant::now();
+    let (args, temp_output) = stage_output(app, args, output_path).await;
 
     // 通知前端任务开始
     let _ = on_progress.send(TaskEvent::Started {
@@ -46,6 +349,170 @@ pub async fn run_ffmpeg(
         total_duration,
     });
 
+    let span = PassSpan::full(phase);
+    let outcome = run_pass(app, task_id, args, total_duration, on_progress, span, None).await;
+    // 进程已结束（或启动失败），先释放名额再做输出核对
+    release_slot(task_id).await;
+    let outcome = settle_output(outcome, temp_output.as_deref(), output_path);
+    finish_task(app, task_id, outcome?, start_time, output_path, on_progress, expected).await
+}
+
+/// 执行分析类 ffmpeg 命令（输出到 null），推送进度并收集分析滤镜的日志
+///
+/// 与 run_ffmpeg 相同地排队、推送 Started/Progress/终态事件，
+/// 另外完整保留 stderr 中 blackdetect、freezedetect 等分析滤镜（`*detect @`）的日志行，
+/// 不受普通 stderr 缓冲区截断的影响
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `task_id` - 任务唯一标识
+/// - `args` - ffmpeg 命令行参数数组
+/// - `total_duration` - 分析时长（秒），用于进度百分比计算
+/// - `on_progress` - 进度推送 Channel
+///
+/// # 返回
+/// - `Ok((TaskResult, String))` - 任务执行结果和收集到的分析日志
+/// - `Err(String)` - 启动失败的错误描述
+pub async fn run_ffmpeg_analysis(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    args: Vec<String>,
+    total_duration: f64,
+    on_progress: &Channel<TaskEvent>,
+) -> Result<(TaskResult, String), String> {
+    let mut log = String::new();
+    track_paths(task_id, &args, None).await;
+    if !wait_for_slot(task_id, on_progress).await {
+        let start_time = Instant::now();
+        let outcome = PassOutcome::Cancelled;
+        let result = finish_task(app, task_id, outcome, start_time, "", on_progress, None).await?;
+        return Ok((result, log));
+    }
+    let start_time = Instant::now();
+
+    let _ = on_progress.send(TaskEvent::Started {
+        task_id: task_id.to_string(),
+        total_duration,
+    });
+
+    let span = PassSpan::full(None);
+    let outcome =
+        run_pass(app, task_id, args, total_duration, on_progress, span, Some(&mut log)).await;
+    release_slot(task_id).await;
+    let result = finish_task(app, task_id, outcome?, start_time, "", on_progress, None).await?;
+    Ok((result, log))
+}
+
+/// 等待并发名额，需要排队时（及排队位置变化时）向前端推送 Queued 事件
+///
+/// # 返回
+/// 是否获得名额（false 表示排队期间被取消）
+async fn wait_for_slot(task_id: &str, on_progress: &Channel<TaskEvent>) -> bool {
+    crate::engine::queue::acquire_slot(task_id, |position| {
+        let _ = on_progress.send(TaskEvent::Queued {
+            task_id: task_id.to_string(),
+            position,
+        });
+    })
+    .await
+}
+
+/// 记录任务读取的输入文件和将要写入的输出文件
+///
+/// 排队前登记，排队中的任务也能按文件取消（cancel_by_path）；
+/// 输出文件在任务结束前不允许拖出导出
+async fn track_paths(task_id: &str, args: &[String], output_path: Option<&str>) {
+    let input_paths = args
+        .windows(2)
+        .filter(|pair| pair[0] == "-i")
+        .map(|pair| pair[1].clone())
+        .collect();
+    let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+    queue.register_inputs(task_id, input_paths);
+    if let Some(output_path) = output_path {
+        queue.register_output(task_id, output_path);
+    }
+}
+
+/// 释放任务占用的并发名额
+async fn release_slot(task_id: &str) {
+    let mut queue = crate::engine::queue::TASK_QUEUE.lock().await;
+    queue.release_slot(task_id);
+}
+
+/// 启用原子输出时，把参数中的输出路径改写为同目录下的临时文件
+///
+/// ffmpeg 写入临时文件，成功后由 settle_output 移动到最终路径，
+/// 失败、取消或崩溃时最终路径上不会留下写了一半的文件，已有的同名文件也保持不变
+///
+/// # 返回
+/// (改写后的参数, 临时文件路径)，未启用或输出不适用时临时路径为 None
+async fn stage_output(
+    app: &tauri::AppHandle,
+    mut args: Vec<String>,
+    output_path: &str,
+) -> (Vec<String>, Option<String>) {
+    if !crate::commands::settings::load_settings(app).await.atomic_output {
+        return (args, None);
+    }
+    let Some(temp_path) = atomic_temp_path(output_path) else {
+        return (args, None);
+    };
+    // 输出路径位于参数末尾，从后往前匹配，避免误改同名的输入路径
+    match args.iter().rposition(|arg| arg == output_path) {
+        Some(index) => {
+            args[index] = temp_path.clone();
+            (args, Some(temp_path))
+        }
+        None => (args, None),
+    }
+}
+
+/// 根据进程结束方式处理临时输出：成功时移动到最终路径，否则删除临时文件
+///
+/// 移动失败时任务按失败处理
+fn settle_output(
+    outcome: Result<PassOutcome, String>,
+    temp_path: Option<&str>,
+    output_path: &str,
+) -> Result<PassOutcome, String> {
+    let Some(temp_path) = temp_path else {
+        return outcome;
+    };
+    match outcome {
+        Ok(PassOutcome::Success) => match commit_temp_output(temp_path, output_path) {
+            Ok(()) => Ok(PassOutcome::Success),
+            Err(e) => Ok(PassOutcome::Failed(e)),
+        },
+        other => {
+            cleanup_temp_file(temp_path);
+            other
+        }
+    }
+}
+
+/// 启动一个 ffmpeg 进程并等待其结束，期间推送进度
+///
+/// 进度百分比线性映射到 `span` 区间（多遍任务中每一遍占总进度的一段），并附带其阶段标签。
+/// 不发送 Started/Completed 等生命周期事件，由调用方根据返回的结束方式处理。
+/// 提供 `analysis_log` 时，分析滤镜的日志行会被完整追加到其中
+async fn run_pass(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    args: Vec<String>,
+    total_duration: f64,
+    on_progress: &Channel<TaskEvent>,
+    span: PassSpan<'_>,
+    mut analysis_log: Option<&mut String>,
+) -> Result<PassOutcome, String> {
+    // 上一遍结束后、本遍启动前被取消时不再启动
+    {
+        let queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        if queue.is_cancelled(task_id) {
+            return Ok(PassOutcome::Cancelled);
+        }
+    }
+
     // 使用 Tauri shell 插件以 sidecar 模式启动 ffmpeg
     let (mut rx, child) = app
         .shell()
@@ -62,7 +529,9 @@ pub async fn run_ffmpeg(
     }
 
     // 创建进度解析器
-    let mut parser = ProgressParser::new(total_duration, task_id);
+    let mut parser = ProgressParser::new(total_duration, task_id)
+        .with_percent_range(span.start, span.end)
+        .with_phase(span.phase);
 
     // stderr 中收集的错误信息
     let mut stderr_buffer = String::new();
@@ -80,6 +549,12 @@ pub async fn run_ffmpeg(
             }
             CommandEvent::Stderr(line) => {
                 let line_str = String::from_utf8_lossy(&line);
+                if let Some(log) = analysis_log.as_deref_mut() {
+                    for single_line in line_str.lines().filter(|l| l.contains("detect @ ")) {
+                        log.push_str(single_line);
+                        log.push('\n');
+                    }
+                }
                 // 收集 stderr 输出（包含错误信息和日志）
                 stderr_buffer.push_str(&line_str);
                 if stderr_buffer.len() > 10000 {
@@ -89,62 +564,25 @@ pub async fn run_ffmpeg(
                 }
             }
             CommandEvent::Terminated(payload) => {
-                let elapsed = start_time.elapsed().as_secs_f64();
-
                 // 检查是否被取消
                 let is_cancelled = {
                     let queue = crate::engine::queue::TASK_QUEUE.lock().await;
                     queue.is_cancelled(task_id)
                 };
-
                 if is_cancelled {
-                    let _ = on_progress.send(TaskEvent::Cancelled {
-                        task_id: task_id.to_string(),
-                    });
-                    return Ok(TaskResult {
-                        task_id: task_id.to_string(),
-                        status: TaskStatus::Cancelled,
-                        output_path: None,
-                        output_size: None,
-                        elapsed: Some(elapsed),
-                        error: None,
-                    });
+                    return Ok(PassOutcome::Cancelled);
                 }
 
                 // 检查退出码判断成功或失败
                 let exit_code = payload.code.unwrap_or(-1);
                 if exit_code == 0 {
-                    let output_size = get_file_size(output_path);
-                    let _ = on_progress.send(TaskEvent::Completed {
-                        task_id: task_id.to_string(),
-                        output_path: output_path.to_string(),
-                        output_size,
-                        elapsed,
-                    });
-                    return Ok(TaskResult {
-                        task_id: task_id.to_string(),
-                        status: TaskStatus::Completed,
-                        output_path: Some(output_path.to_string()),
-                        output_size: Some(output_size),
-                        elapsed: Some(elapsed),
-                        error: None,
-                    });
-                } else {
-                    // 从 stderr 中提取最后一行有意义的错误信息
-                    let error_msg = extract_error_message(&stderr_buffer, exit_code);
-                    let _ = on_progress.send(TaskEvent::Failed {
-                        task_id: task_id.to_string(),
-                        error: error_msg.clone(),
-                    });
-                    return Ok(TaskResult {
-                        task_id: task_id.to_string(),
-                        status: TaskStatus::Failed,
-                        output_path: None,
-                        output_size: None,
-                        elapsed: Some(elapsed),
-                        error: Some(error_msg),
-                    });
+                    return Ok(PassOutcome::Success);
                 }
+                // 从 stderr 中提取最后一行有意义的错误信息
+                return Ok(PassOutcome::Failed(extract_error_message(
+                    &stderr_buffer,
+                    exit_code,
+                )));
             }
             _ => {}
         }
@@ -154,6 +592,94 @@ pub async fn run_ffmpeg(
     Err("ffmpeg 进程事件流意外关闭".to_string())
 }
 
+/// 根据最后一个进程的结束方式推送终态事件并生成 TaskResult
+///
+/// 成功时若提供了 `expected`，先核对输出文件的编码属性，不一致的项以 Notice 提示
+async fn finish_task(
+    app: &tauri::AppHandle,
+    task_id: &str,
+    outcome: PassOutcome,
+    start_time: Instant,
+    output_path: &str,
+    on_progress: &Channel<TaskEvent>,
+    expected: Option<&ExpectedOutput>,
+) -> Result<TaskResult, String> {
+    let elapsed = start_time.elapsed().as_secs_f64();
+
+    match outcome {
+        PassOutcome::Cancelled => {
+            let _ = on_progress.send(TaskEvent::Cancelled {
+                task_id: task_id.to_string(),
+            });
+            Ok(TaskResult {
+                task_id: task_id.to_string(),
+                status: TaskStatus::Cancelled,
+                output_path: None,
+                output_size: None,
+                elapsed: Some(elapsed),
+                error: None,
+                verification: Vec::new(),
+            })
+        }
+        PassOutcome::Success => {
+            crate::engine::queue::TASK_QUEUE
+                .lock()
+                .await
+                .record_completed_output(task_id, output_path);
+            let output_size = get_file_size(output_path);
+            let verification = match expected {
+                Some(expected) => {
+                    let settings = crate::commands::settings::load_settings(app).await;
+                    verify_file(app, output_path, expected, settings.probe_timeout_ms).await
+                }
+                None => Vec::new(),
+            };
+            for check in verification.iter().filter(|c| !c.ok) {
+                let _ = on_progress.send(TaskEvent::Notice {
+                    task_id: task_id.to_string(),
+                    message: format!(
+                        "Output {} is {} (expected {})",
+                        check.name, check.actual, check.expected
+                    ),
+                });
+            }
+            let _ = on_progress.send(TaskEvent::Completed {
+                task_id: task_id.to_string(),
+                output_path: output_path.to_string(),
+                output_size,
+                elapsed,
+                verification: verification.clone(),
+                note: None,
+                container_change: None,
+            });
+            Ok(TaskResult {
+                task_id: task_id.to_string(),
+                status: TaskStatus::Completed,
+                output_path: Some(output_path.to_string()),
+                output_size: Some(output_size),
+                elapsed: Some(elapsed),
+                error: None,
+                verification,
+            })
+        }
+        PassOutcome::Failed(error_msg) => {
+            let _ = on_progress.send(TaskEvent::Failed {
+                task_id: task_id.to_string(),
+                error: error_msg.clone(),
+            });
+            Ok(TaskResult {
+                task_id: task_id.to_string(),
+                status: TaskStatus::Failed,
+                output_path: None,
+                output_size: None,
+                elapsed: Some(elapsed),
+                error: Some(error_msg),
+                verification: Vec::new(),
+            })
+        }
+    }
+}
+
 /// 执行 ffmpeg 命令（静默模式，不推送进度事件）
 ///
 /// 用于多步骤任务中的中间步骤（如多片段裁剪的每段切割），
@@ -189,6 +715,80 @@ pub async fn run_ffmpeg_quiet(
     }
 }
 
+/// 执行分析类 ffmpeg 命令并返回 stderr 输出
+///
+/// blackdetect、silencedetect 等分析滤镜把结果写入 stderr 日志，
+/// 此函数静默执行命令并返回完整 stderr 供调用方解析
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `args` - ffmpeg 命令行参数数组
+///
+/// # 返回
+/// - `Ok(String)` - ffmpeg 的 stderr 输出
+/// - `Err(String)` - 执行失败的错误描述
+pub async fn run_ffmpeg_capture(
+    app: &tauri::AppHandle,
+    args: Vec<String>,
+) -> Result<String, String> {
+    let output = app
+        .shell()
+        .sidecar("ffmpeg")
+        .map_err(|e| format!("创建 ffmpeg sidecar 失败: {}", e))?
+        .args(&args)
+        .output()
+        .await
+        .map_err(|e| format!("执行 ffmpeg 失败: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if output.status.success() {
+        Ok(stderr)
+    } else {
+        let exit_code = output.status.code().unwrap_or(-1);
+        Err(extract_error_message(&stderr, exit_code))
+    }
+}
+
+/// ffprobe 超时的错误标识，前端据此提示检查外接硬盘或网络存储
+pub const PROBE_TIMEOUT: &str = "ProbeTimeout";
+
+/// 探测输入文件前推送的阶段描述
+pub const ANALYZING_INPUT_PHASE: &str = "Analyzing input…";
+
+/// 推送任务阶段事件
+///
+/// 用于 ffmpeg 启动前耗时较长的准备工作（如探测大文件），避免界面长时间无反馈
+///
+/// # 参数
+/// - `on_progress` - 进度推送 Channel
+/// - `task_id` - 任务唯一标识
+/// - `description` - 阶段描述
+pub fn send_phase(on_progress: &Channel<TaskEvent>, task_id: &str, description: &str) {
+    let _ = on_progress.send(TaskEvent::Phase {
+        task_id: task_id.to_string(),
+        description: description.to_string(),
+    });
+}
+
+/// 使用自定义参数执行 ffprobe（同步等待结果）
+///
+/// 用于 run_ffprobe 固定参数之外的查询（如列出关键帧时间）
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `args` - ffprobe 命令行参数数组（包含输入文件路径）
+/// - `timeout_ms` - 探测超时（毫秒，设置中的 probe_timeout_ms），0 为不限时
+///
+/// # 返回
+/// ffprobe 的 stdout 输出
+pub async fn run_ffprobe_with_args(
+    app: &tauri::AppHandle,
+    args: Vec<String>,
+    timeout_ms: u64,
+) -> Result<String, String> {
+    run_ffprobe_watched(app, args, timeout_ms).await
+}
+
 /// 使用 ffprobe 获取媒体文件信息（同步等待结果）
 ///
 /// 调用 ffprobe sidecar 获取 JSON 格式的媒体信息，
@@ -197,55 +797,243 @@ pub async fn run_ffmpeg_quiet(
 /// # 参数
 /// - `app` - Tauri AppHandle
 /// - `file_path` - 媒体文件路径
+/// - `timeout_ms` - 探测超时（毫秒，设置中的 probe_timeout_ms），0 为不限时
 ///
 /// # 返回
 /// ffprobe JSON 输出字符串
 pub async fn run_ffprobe(
     app: &tauri::AppHandle,
     file_path: &str,
+    timeout_ms: u64,
 ) -> Result<String, String> {
-    let output = app
+    run_ffprobe_watched(app, build_ffprobe_info_args(file_path), timeout_ms).await
+}
+
+/// 以子进程方式启动 ffprobe 并收集输出，超过 timeout_ms 时终止进程
+///
+/// 外接硬盘或网络存储上的超大文件探测可能长时间无响应，超时后以 PROBE_TIMEOUT 开头的错误返回，
+/// 而不是让 command 一直挂起。timeout_ms 为 0 时不限时
+async fn run_ffprobe_watched(
+    app: &tauri::AppHandle,
+    args: Vec<String>,
+    timeout_ms: u64,
+) -> Result<String, String> {
+    let (mut rx, child) = app
         .shell()
         .sidecar("ffprobe")
         .map_err(|e| format!("创建 ffprobe sidecar 失败: {}", e))?
-        .args(&[
-            "-v", "quiet",
-            "-print_format", "json",
-            "-show_format",
-            "-show_streams",
-            file_path,
-        ])
-        .output()
-        .await
+        .args(&args)
+        .spawn()
         .map_err(|e| format!("执行 ffprobe 失败: {}", e))?;
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    let collect = async {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => append_line(&mut stdout, &line),
+                CommandEvent::Stderr(line) => append_line(&mut stderr, &line),
+                CommandEvent::Terminated(payload) => {
+                    return Ok((payload.code == Some(0), stdout, stderr));
+                }
+                _ => {}
+            }
+        }
+        Err("ffprobe 进程事件流意外关闭".to_string())
+    };
+
+    let finished = if timeout_ms == 0 {
+        collect.await
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("ffprobe 执行失败: {}", stderr))
-    }
-}
-
-/// 从 ffmpeg stderr 输出中提取有意义的错误信息
-///
-/// ffmpeg 的 stderr 输出包含大量日志信息，
-/// 此函数尝试从中提取最后一个有意义的错误描述
-fn extract_error_message(stderr: &str, exit_code: i32) -> String {
-    // 从 stderr 的最后几行中找包含错误关键词的行
-    let lines: Vec<&str> = stderr.lines().rev().take(20).collect();
-    for line in &lines {
-        let lower = line.to_lowercase();
-        if lower.contains("error")
-            || lower.contains("invalid")
-            || lower.contains("no such")
-            || lower.contains("permission denied")
-            || lower.contains("not found")
-        {
-            return line.trim().to_string();
+        match tokio::time::timeout(Duration::from_millis(timeout_ms), collect).await {
+            Ok(finished) => finished,
+            Err(_) => {
+                let _ = child.kill();
+                return Err(probe_timeout_message(timeout_ms));
+            }
         }
+    };
+
+    let (success, stdout, stderr) = finished?;
+    if success {
+        Ok(String::from_utf8_lossy(&stdout).to_string())
+    } else {
+        Err(format!("ffprobe 执行失败: {}", String::from_utf8_lossy(&stderr)))
     }
+}
+
+/// 生成探测超时的错误信息，超时按秒显示（不足整秒时保留小数）
+fn probe_timeout_message(timeout_ms: u64) -> String {
+    format!(
+        "{}: analyzing the input took longer than {} seconds",
+        PROBE_TIMEOUT,
+        timeout_ms as f64 / 1000.0
+    )
+}
+
+/// 追加一行子进程输出，保证行尾有换行符
+fn append_line(buffer: &mut Vec<u8>, line: &[u8]) {
+    buffer.extend_from_slice(line);
+    if !line.ends_with(b"\n") {
+        buffer.push(b'\n');
+    }
+}
 
-    // 未找到特定错误信息，返回通用错误
-    format!("ffmpeg 进程退出，退出码: {}", exit_code)
+/// 使用 ffprobe 获取媒体时长（秒）
+///
+/// 容器没有记录时长时退回最长的流时长（见 FfprobeOutput::duration），
+/// 仍无法获取时返回 0.0
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `file_path` - 媒体文件路径
+/// - `timeout_ms` - 探测超时（毫秒），0 为不限时
+pub async fn probe_duration(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<f64, String> {
+    Ok(probe_output(app, file_path, timeout_ms).await?.duration())
+}
+
+/// 运行 ffprobe 并解析为原始输出结构
+///
+/// 需要直接访问 format 标签或原始流字段时使用，其余场景优先用 `probe_media`
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `file_path` - 媒体文件路径
+/// - `timeout_ms` - 探测超时（毫秒），0 为不限时
+pub async fn probe_output(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<FfprobeOutput, String> {
+    parse_ffprobe_output(&run_ffprobe(app, file_path, timeout_ms).await?)
+}
+
+/// 解析 ffprobe 的 JSON 输出
+///
+/// # 参数
+/// - `json_str` - ffprobe `-print_format json` 的输出
+pub fn parse_ffprobe_output(json_str: &str) -> Result<FfprobeOutput, String> {
+    serde_json::from_str(json_str).map_err(|e| format!("解析 ffprobe 输出失败: {}", e))
+}
+
+/// 运行 ffprobe 并转换为应用内部的 MediaInfo
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `file_path` - 媒体文件路径
+/// - `timeout_ms` - 探测超时（毫秒），0 为不限时
+pub async fn probe_media(
+    app: &tauri::AppHandle,
+    file_path: &str,
+    timeout_ms: u64,
+) -> Result<MediaInfo, String> {
+    Ok(probe_output(app, file_path, timeout_ms).await?.to_media_info(file_path))
+}
+
+/// 硬件编码器可用性缓存（编码器名 → 是否可用），应用运行期间硬件不会变化
+static HARDWARE_ENCODERS: once_cell::sync::Lazy<tokio::sync::Mutex<HashMap<String, bool>>> =
+    once_cell::sync::Lazy::new(|| tokio::sync::Mutex::new(HashMap::new()));
+
+/// 检查硬件编码器在本机是否可用
+///
+/// 用 lavfi 生成的极短黑场实际编码一次：编码器未编译进 ffmpeg，
+/// 或硬件不支持（如部分 Intel Mac 没有 HEVC 编码单元）时都会失败。结果按编码器缓存
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `encoder` - 编码器名称（如 "hevc_videotoolbox"）
+///
+/// # 返回
+/// 编码器是否可用
+pub async fn hardware_encoder_available(app: &tauri::AppHandle, encoder: &str) -> bool {
+    let mut cache = HARDWARE_ENCODERS.lock().await;
+    if let Some(&available) = cache.get(encoder) {
+        return available;
+    }
+
+    let args = [
+        "-hide_banner", "-loglevel", "error",
+        "-f", "lavfi", "-i", "color=c=black:s=256x256:d=0.1",
+        "-c:v", encoder,
+        "-f", "null", "-",
+    ];
+    let available = match app.shell().sidecar("ffmpeg") {
+        Ok(command) => command
+            .args(args)
+            .output()
+            .await
+            .map(|output| output.status.success())
+            .unwrap_or(false),
+        Err(_) => false,
+    };
+    cache.insert(encoder.to_string(), available);
+    available
+}
+
+/// 检查 sidecar ffmpeg 是否编译了指定滤镜
+///
+/// 解析 `ffmpeg -filters` 的列表（每行为 "标志 名称 输入->输出 说明"），
+/// 用于 vidstab 等依赖可选外部库、精简构建中可能缺失的滤镜
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `filter` - 滤镜名称（如 "vidstabdetect"）
+///
+/// # 返回
+/// 滤镜是否可用；ffmpeg 无法执行时视为不可用
+pub async fn filter_available(app: &tauri::AppHandle, filter: &str) -> bool {
+    let output = match app.shell().sidecar("ffmpeg") {
+        Ok(command) => command.args(["-hide_banner", "-filters"]).output().await,
+        Err(_) => return false,
+    };
+    let Ok(output) = output else {
+        return false;
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| line.split_whitespace().nth(1) == Some(filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsed_probe_output_reports_duration() {
+        let json = r#"{
+            "streams": [
+                { "index": 0, "codec_type": "video", "codec_name": "h264",
+                  "width": 1280, "height": 720, "duration": "12.5" },
+                { "index": 1, "codec_type": "audio", "codec_name": "aac", "duration": "12.48" }
+            ],
+            "format": { "duration": "12.512" }
+        }"#;
+        let output = parse_ffprobe_output(json).unwrap();
+        assert_eq!(output.duration(), 12.512);
+
+        let without_format = r#"{ "streams": [{ "index": 0, "duration": "7.0" }] }"#;
+        assert_eq!(parse_ffprobe_output(without_format).unwrap().duration(), 7.0);
+    }
+
+    #[test]
+    fn probe_timeout_message_keeps_fractional_seconds() {
+        assert_eq!(
+            probe_timeout_message(30_000),
+            "ProbeTimeout: analyzing the input took longer than 30 seconds"
+        );
+        assert_eq!(
+            probe_timeout_message(500),
+            "ProbeTimeout: analyzing the input took longer than 0.5 seconds"
+        );
+    }
+
+    #[test]
+    fn malformed_probe_output_is_an_error() {
+        let err = parse_ffprobe_output("Invalid data found when processing input").unwrap_err();
+        assert!(err.starts_with("解析 ffprobe 输出失败"), "{}", err);
+    }
 }
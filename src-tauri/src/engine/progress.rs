@@ -27,6 +27,13 @@ use crate::utils::time::microseconds_to_seconds;
 /// 避免过于频繁地推送进度更新导致前端渲染压力过大
 const MIN_EMIT_INTERVAL_MS: u128 = 200;
 
+/// 处理速度指数移动平均的平滑系数（新样本权重）
+///
+/// ffmpeg 每 0.5 秒左右报告一次瞬时速度，启动阶段常报告远高于实际的速度，
+/// 遇到复杂画面或磁盘抖动时也波动很大，直接用瞬时速度算 ETA 会忽长忽短；
+/// 每个新样本占 0.3（smoothed = 0.7 × 旧值 + 0.3 × 新样本），启动尖峰在几秒内衰减
+const SPEED_EMA_ALPHA: f64 = 0.3;
+
 /// ffmpeg 进度解析器
 ///
 /// 维护解析状态和时间控制，每当收到一组完整的 progress 输出
@@ -41,6 +48,14 @@ pub struct ProgressParser {
     last_emit_time: Instant,
     /// 当前正在解析的 key=value 对缓存
     current_values: HashMap<String, String>,
+    /// 进度百分比映射区间（多遍任务中每一遍只占总进度的一段），默认 0–100
+    percent_range: (f64, f64),
+    /// 处理速度的指数移动平均，收到第一个有效速度前为 None
+    smoothed_speed: Option<f64>,
+    /// 已推送的最大百分比，保证进度不回退
+    max_percent: f64,
+    /// 附加到每次更新的阶段标签（多步骤任务中标识当前步骤）
+    phase: Option<String>,
 }
 
 impl ProgressParser {
@@ -57,9 +72,32 @@ impl ProgressParser {
                 .checked_sub(std::time::Duration::from_millis(MIN_EMIT_INTERVAL_MS as u64))
                 .unwrap_or_else(Instant::now),
             current_values: HashMap::new(),
+            percent_range: (0.0, 100.0),
+            smoothed_speed: None,
+            max_percent: 0.0,
+            phase: None,
         }
     }
 
+    /// 把本进程的 0–100% 进度映射到 [start, end] 区间
+    ///
+    /// 用于两遍编码等多进程任务：如第一遍映射到 0–45%，第二遍映射到 45–100%
+    ///
+    /// # 参数
+    /// - `start` - 区间起点（百分比）
+    /// - `end` - 区间终点（百分比）
+    pub fn with_percent_range(mut self, start: f64, end: f64) -> Self {
+        self.percent_range = (start, end);
+        self.max_percent = start;
+        self
+    }
+
+    /// 为每次进度更新附加阶段标签（如两遍编码的 "Pass 1/2: analyzing"）
+    pub fn with_phase(mut self, phase: Option<&str>) -> Self {
+        self.phase = phase.map(str::to_string);
+        self
+    }
+
     /// 解析一行 ffmpeg progress 输出
     ///
     /// 收集 key=value 对，当遇到 `progress=continue` 或 `progress=end` 时
@@ -84,18 +122,23 @@ impl ProgressParser {
 
             // 当遇到 progress=continue/end 时，表示一组完整的进度数据
             if key == "progress" {
-                let result = self.build_progress_update();
+                // 每组数据都计入速度均值和最大进度，包括被节流丢弃的那些
+                self.update_smoothed_speed();
+                let result = self.build_progress_update().map(|update| self.clamp_percent(update));
                 self.current_values.clear();
 
+                // progress=end 不受节流限制，总是推送区间终点（100%）的最后一次更新；
+                // 最后一组的 out_time 常略小于总时长，不能直接用换算出的百分比
+                if value == "end" {
+                    self.last_emit_time = Instant::now();
+                    return result.map(|update| self.finish_update(update));
+                }
+
                 // 节流控制：避免推送过于频繁
                 if self.last_emit_time.elapsed().as_millis() >= MIN_EMIT_INTERVAL_MS {
                     self.last_emit_time = Instant::now();
                     return result;
                 }
-                // 如果是 progress=end，强制推送最后一次更新
-                if value == "end" {
-                    return result;
-                }
                 return None;
             }
 
@@ -115,9 +158,47 @@ impl ProgressParser {
         line.trim() == "progress=end"
     }
 
+    /// 用当前这组数据中的速度更新指数移动平均
+    ///
+    /// 启动阶段 ffmpeg 报告 "N/A" 或 0，这类无效样本不计入
+    fn update_smoothed_speed(&mut self) {
+        let Some(speed) = self.current_speed().filter(|s| *s > 0.0) else {
+            return;
+        };
+        self.smoothed_speed = Some(match self.smoothed_speed {
+            Some(previous) => previous + SPEED_EMA_ALPHA * (speed - previous),
+            None => speed,
+        });
+    }
+
+    /// 保证推送的百分比单调不减
+    ///
+    /// 部分滤镜（如 concat、多段 trim）会让 out_time 回到较早的时间点，
+    /// 直接换算会让进度条倒退
+    fn clamp_percent(&mut self, mut update: ProgressUpdate) -> ProgressUpdate {
+        self.max_percent = self.max_percent.max(update.percent);
+        update.percent = self.max_percent;
+        update
+    }
+
+    /// 把最后一次更新的进度设为区间终点，剩余时间归零
+    fn finish_update(&mut self, mut update: ProgressUpdate) -> ProgressUpdate {
+        self.max_percent = self.percent_range.1;
+        update.percent = self.max_percent;
+        update.eta = 0.0;
+        update
+    }
+
+    /// 当前这组数据中的瞬时处理速度（如 "1.5x" → 1.5）
+    fn current_speed(&self) -> Option<f64> {
+        self.current_values
+            .get("speed")
+            .and_then(|v| v.trim_end_matches('x').parse::<f64>().ok())
+    }
+
     /// 从缓存的 key=value 对构建 ProgressUpdate
     ///
-    /// 从 out_time_us 计算进度百分比，从 speed 计算预估剩余时间
+    /// 从 out_time_us 计算进度百分比，从平滑后的速度计算预估剩余时间
     fn build_progress_update(&self) -> Option<ProgressUpdate> {
         // 提取 out_time_us（已处理的时间，微秒）
         let out_time_us = self
@@ -126,30 +207,27 @@ impl ProgressParser {
             .and_then(|v| v.parse::<i64>().ok())
             .unwrap_or(0);
 
-        // 计算进度百分比
-        let percent = if self.total_duration_us > 0 {
+        // 计算进度百分比（映射到 percent_range 区间）
+        let raw_percent = if self.total_duration_us > 0 {
             (out_time_us as f64 / self.total_duration_us as f64 * 100.0).min(100.0)
         } else {
             0.0
         };
+        let (range_start, range_end) = self.percent_range;
+        let percent = range_start + raw_percent / 100.0 * (range_end - range_start);
 
         // 提取已处理的时间（秒）
         let current_time = microseconds_to_seconds(out_time_us);
 
         // 提取处理速度（如 "1.5x" → 1.5）
-        let speed = self
-            .current_values
-            .get("speed")
-            .and_then(|v| v.trim_end_matches('x').parse::<f64>().ok())
-            .unwrap_or(0.0);
+        let speed = self.current_speed().unwrap_or(0.0);
 
-        // 计算预估剩余时间：remaining_time / speed
+        // 计算预估剩余时间：remaining_time / 平滑速度
         let total_duration_secs = microseconds_to_seconds(self.total_duration_us);
         let remaining_time = total_duration_secs - current_time;
-        let eta = if speed > 0.0 {
-            (remaining_time / speed).max(0.0)
-        } else {
-            0.0
+        let eta = match self.smoothed_speed {
+            Some(smoothed) if smoothed > 0.0 => (remaining_time / smoothed).max(0.0),
+            _ => 0.0,
         };
 
         // 提取输出文件当前大小（字节）
@@ -182,6 +260,117 @@ impl ProgressParser {
             output_size,
             frame,
             fps,
+            phase: self.phase.clone(),
         })
     }
 }
+
+/// 从 ffmpeg stderr 输出中提取有意义的错误信息
+///
+/// ffmpeg 的 stderr 输出包含大量日志信息，
+/// 此函数尝试从中提取最后一个有意义的错误描述
+pub fn extract_error_message(stderr: &str, exit_code: i32) -> String {
+    // 从 stderr 的最后几行中找包含错误关键词的行
+    let lines: Vec<&str> = stderr.lines().rev().take(20).collect();
+    for line in &lines {
+        let lower = line.to_lowercase();
+        if lower.contains("error")
+            || lower.contains("invalid")
+            || lower.contains("no such")
+            || lower.contains("permission denied")
+            || lower.contains("not found")
+        {
+            return line.trim().to_string();
+        }
+    }
+
+    // 未找到特定错误信息，返回通用错误
+    format!("ffmpeg 进程退出，退出码: {}", exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// 输入一组 progress 输出（out_time_us + speed + 结尾标记）
+    fn feed_group(
+        parser: &mut ProgressParser,
+        out_time_secs: f64,
+        speed: &str,
+        progress: &str,
+    ) -> Option<ProgressUpdate> {
+        parser.parse_line(&format!("out_time_us={}", (out_time_secs * 1_000_000.0) as i64));
+        parser.parse_line(&format!("speed={}", speed));
+        parser.parse_line(&format!("progress={}", progress))
+    }
+
+    /// 把上次推送时间拨回一个节流间隔之前，使下一组数据一定会被推送
+    fn expire_throttle(parser: &mut ProgressParser) {
+        parser.last_emit_time =
+            Instant::now() - Duration::from_millis(MIN_EMIT_INTERVAL_MS as u64);
+    }
+
+    #[test]
+    fn spiky_speed_keeps_eta_bounded_and_percent_monotonic() {
+        let mut parser = ProgressParser::new(100.0, "task");
+        // 速度在 10x 和 0.5x 之间来回跳动，中途 out_time 回退一次
+        let samples = [
+            (5.0, "10x"),
+            (10.0, "0.5x"),
+            (15.0, "10x"),
+            (12.0, "0.5x"),
+            (20.0, "10x"),
+            (25.0, "0.5x"),
+            (30.0, "10x"),
+            (35.0, "0.5x"),
+        ];
+        let mut last_percent = 0.0;
+        for (out_time, speed) in samples {
+            expire_throttle(&mut parser);
+            let update = feed_group(&mut parser, out_time, speed, "continue").unwrap();
+            assert!(update.percent >= last_percent, "{} < {}", update.percent, last_percent);
+            last_percent = update.percent;
+
+            // 平滑速度稳定在 4x–10x 之间，ETA 不会随 0.5x 的样本跳到 20 倍
+            let remaining = 100.0 - out_time;
+            assert!(update.eta >= remaining / 10.0 - 1e-9, "eta {}", update.eta);
+            assert!(update.eta <= remaining / 4.0, "eta {}", update.eta);
+        }
+        assert_eq!(last_percent, 35.0);
+    }
+
+    #[test]
+    fn speed_ema_follows_fixed_sequence() {
+        let mut parser = ProgressParser::new(100.0, "task");
+        // 首个有效样本直接作为均值，N/A 和 0x 不计入，其余按 0.3 的权重平滑
+        let samples = [
+            (10.0, "2x", 2.0),
+            (20.0, "N/A", 2.0),
+            (30.0, "4x", 2.6),
+            (40.0, "0x", 2.6),
+            (50.0, "1x", 2.12),
+        ];
+        for (out_time, speed, smoothed) in samples {
+            expire_throttle(&mut parser);
+            let update = feed_group(&mut parser, out_time, speed, "continue").unwrap();
+            let actual = parser.smoothed_speed.unwrap();
+            assert!((actual - smoothed).abs() < 1e-9, "{} at {}: {}", speed, out_time, actual);
+            assert!((update.eta - (100.0 - out_time) / smoothed).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn end_update_bypasses_throttle() {
+        let mut parser = ProgressParser::new(10.0, "task").with_percent_range(45.0, 100.0);
+        assert!(feed_group(&mut parser, 8.0, "2x", "continue").is_some());
+        let previous_emit = Instant::now();
+
+        // 紧接上一次推送（远小于 50 ms）：continue 被节流，end 仍然推送区间终点
+        assert!(feed_group(&mut parser, 9.0, "2x", "continue").is_none());
+        let last = feed_group(&mut parser, 9.96, "2x", "end").unwrap();
+        assert!(previous_emit.elapsed() < Duration::from_millis(50));
+        assert_eq!(last.percent, 100.0);
+        assert_eq!(last.eta, 0.0);
+    }
+}
@@ -1,11 +1,17 @@
 /// 任务队列管理器
 ///
 /// 管理 ffmpeg 任务的生命周期：注册子进程、取消任务、
-/// 跟踪运行状态。使用全局单例模式通过 Mutex 保证线程安全
+/// 跟踪运行状态，并按 max_concurrent 限制同时运行的任务数。
+/// 使用全局单例模式通过 Mutex 保证线程安全
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
 use tauri_plugin_shell::process::CommandChild;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
+
+/// 默认最大并发任务数（与 AppSettings 默认值一致，设置加载后更新）
+const DEFAULT_MAX_CONCURRENT: usize = 1;
 
 /// 全局任务队列单例
 ///
@@ -28,6 +34,26 @@ pub struct TaskQueue {
     /// 取消操作先杀进程，再在此集合中标记，
     /// 进程 Terminated 事件回调中检查此标记以区分正常退出和取消
     cancelled: HashSet<String>,
+    /// 占用并发名额的任务 ID（从获得名额到最后一个 ffmpeg 进程结束，多遍任务只占一个名额）
+    active: HashSet<String>,
+    /// 等待并发名额的任务 ID（按到达顺序）
+    waiting: VecDeque<String>,
+    /// 最大并发任务数
+    max_concurrent: usize,
+    /// 名额释放或上限调整时唤醒等待中的任务
+    slot_freed: Arc<Notify>,
+    /// 正在写入的输出文件（task_id -> 输出路径），任务清理时移除
+    writing: HashMap<String, String>,
+    /// 任务读取的输入文件（task_id -> 输入路径列表），任务清理时移除
+    inputs: HashMap<String, Vec<String>>,
+    /// 本次运行中成功完成的任务输出文件路径
+    completed_outputs: HashSet<String>,
+    /// 可以暂停的任务 ID（可续传的下载），任务清理时移除
+    pausable: HashSet<String>,
+    /// 已标记暂停的任务 ID
+    ///
+    /// 与取消相同，先杀进程再标记，进程 Terminated 事件回调中据此区分暂停和取消
+    paused: HashSet<String>,
 }
 
 impl TaskQueue {
@@ -36,9 +62,85 @@ impl TaskQueue {
         Self {
             running: HashMap::new(),
             cancelled: HashSet::new(),
+            active: HashSet::new(),
+            waiting: VecDeque::new(),
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            slot_freed: Arc::new(Notify::new()),
+            writing: HashMap::new(),
+            inputs: HashMap::new(),
+            completed_outputs: HashSet::new(),
+            pausable: HashSet::new(),
+            paused: HashSet::new(),
         }
     }
 
+    /// 设置最大并发任务数（至少为 1）
+    ///
+    /// 调高上限时立即唤醒等待中的任务；调低时已运行的任务不受影响，
+    /// 新任务等到运行数降到上限以下才启动
+    ///
+    /// # 参数
+    /// - `max_concurrent` - 最大并发任务数
+    pub fn set_max_concurrent(&mut self, max_concurrent: usize) {
+        self.max_concurrent = max_concurrent.max(1);
+        self.slot_freed.notify_waiters();
+    }
+
+    /// 尝试为任务获取并发名额
+    ///
+    /// 有空闲名额且前面没有更早等待的任务时获得名额；
+    /// 否则把任务加入等待队列（已在队列中则保持原位置）
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    ///
+    /// # 返回
+    /// 是否获得名额
+    pub fn try_acquire_slot(&mut self, task_id: &str) -> bool {
+        if self.active.contains(task_id) {
+            return true;
+        }
+        let first_in_line = self.waiting.front().is_none_or(|id| id == task_id);
+        if self.active.len() < self.max_concurrent && first_in_line {
+            self.waiting.retain(|id| id != task_id);
+            self.active.insert(task_id.to_string());
+            // 下一个等待者可能也有空闲名额
+            self.slot_freed.notify_waiters();
+            return true;
+        }
+        if !self.waiting.iter().any(|id| id == task_id) {
+            self.waiting.push_back(task_id.to_string());
+        }
+        false
+    }
+
+    /// 释放任务占用的并发名额并唤醒等待中的任务
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    pub fn release_slot(&mut self, task_id: &str) {
+        let was_active = self.active.remove(task_id);
+        let was_waiting = self.remove_waiting(task_id);
+        if was_active || was_waiting {
+            self.slot_freed.notify_waiters();
+        }
+    }
+
+    /// 任务在等待队列中的位置（前面排队的任务数），不在队列中时返回 None
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    pub fn queue_position(&self, task_id: &str) -> Option<usize> {
+        self.waiting.iter().position(|id| id == task_id)
+    }
+
+    /// 从等待队列中移除任务，返回任务是否在队列中
+    fn remove_waiting(&mut self, task_id: &str) -> bool {
+        let before = self.waiting.len();
+        self.waiting.retain(|id| id != task_id);
+        self.waiting.len() != before
+    }
+
     /// 注册一个运行中的 ffmpeg 子进程
     ///
     /// 在 ffmpeg spawn 成功后调用，将 CommandChild 存入映射表，
@@ -70,11 +172,142 @@ impl TaskQueue {
             // 向进程发送 kill 信号
             let _ = child.kill();
             Ok(())
+        } else if self.remove_waiting(task_id) {
+            // 仍在等待名额：标记取消后唤醒，等待方据此放弃启动
+            self.cancelled.insert(task_id.to_string());
+            self.slot_freed.notify_waiters();
+            Ok(())
         } else {
             Err(format!("任务 {} 不存在或已完成", task_id))
         }
     }
 
+    /// 记录任务可以暂停（进程被终止后能从已下载的部分继续）
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    pub fn register_pausable(&mut self, task_id: &str) {
+        self.pausable.insert(task_id.to_string());
+    }
+
+    /// 暂停指定任务
+    ///
+    /// 向子进程发送 kill 信号并标记为已暂停，进程终止后由任务自身保存续传状态并推送 Paused 事件
+    ///
+    /// # 参数
+    /// - `task_id` - 要暂停的任务 ID
+    ///
+    /// # 返回
+    /// - `Ok(())` - 成功发送暂停信号
+    /// - `Err(String)` - 任务不存在、已结束或不支持暂停
+    pub fn pause_task(&mut self, task_id: &str) -> Result<(), String> {
+        if !self.pausable.contains(task_id) {
+            return Err(format!(
+                "Task {} cannot be paused; only resumable downloads support pausing",
+                task_id
+            ));
+        }
+        let Some(child) = self.running.remove(task_id) else {
+            return Err(format!("任务 {} 不存在或已完成", task_id));
+        };
+        self.paused.insert(task_id.to_string());
+        let _ = child.kill();
+        Ok(())
+    }
+
+    /// 检查任务是否已被标记为暂停
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    pub fn is_paused(&self, task_id: &str) -> bool {
+        self.paused.contains(task_id)
+    }
+
+    /// 记录任务正在写入的输出文件
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    /// - `output_path` - 输出文件路径
+    pub fn register_output(&mut self, task_id: &str, output_path: &str) {
+        self.writing.insert(task_id.to_string(), output_path.to_string());
+    }
+
+    /// 记录任务读取的输入文件
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    /// - `input_paths` - 输入文件路径列表
+    pub fn register_inputs(&mut self, task_id: &str, input_paths: Vec<String>) {
+        self.inputs.insert(task_id.to_string(), input_paths);
+    }
+
+    /// 取消所有读取或写入指定文件的任务
+    ///
+    /// 前端丢失 task_id（如页面重新加载）时按文件取消。
+    /// 路径按组件比较，忽略多余的分隔符和末尾斜杠
+    ///
+    /// # 参数
+    /// - `path` - 输入或输出文件路径
+    ///
+    /// # 返回
+    /// - `Ok(Vec<String>)` - 已取消的任务 ID
+    /// - `Err(String)` - 没有使用该文件的任务
+    pub fn cancel_by_path(&mut self, path: &str) -> Result<Vec<String>, String> {
+        let target = Path::new(path);
+        let mut task_ids: Vec<String> = self
+            .writing
+            .iter()
+            .filter(|(_, output)| Path::new(output) == target)
+            .map(|(task_id, _)| task_id.clone())
+            .chain(
+                self.inputs
+                    .iter()
+                    .filter(|(_, inputs)| inputs.iter().any(|input| Path::new(input) == target))
+                    .map(|(task_id, _)| task_id.clone()),
+            )
+            .collect();
+        task_ids.sort();
+        task_ids.dedup();
+
+        let cancelled: Vec<String> = task_ids
+            .into_iter()
+            .filter(|task_id| self.cancel_task(task_id).is_ok())
+            .collect();
+        if cancelled.is_empty() {
+            return Err(format!("No running task uses {}", path));
+        }
+        Ok(cancelled)
+    }
+
+    /// 记录任务成功完成的输出文件（不再视为正在写入）
+    ///
+    /// # 参数
+    /// - `task_id` - 任务 ID
+    /// - `output_path` - 输出文件路径
+    pub fn record_completed_output(&mut self, task_id: &str, output_path: &str) {
+        self.writing.remove(task_id);
+        self.completed_outputs.insert(output_path.to_string());
+    }
+
+    /// 文件是否正被运行中的任务写入
+    pub fn is_output_in_progress(&self, path: &str) -> bool {
+        self.writing.values().any(|p| p == path)
+    }
+
+    /// 文件是否为已完成任务的输出
+    pub fn is_completed_output(&self, path: &str) -> bool {
+        self.completed_outputs.contains(path)
+    }
+
+    /// 已占用或正在等待并发名额的任务数量
+    ///
+    /// 定时调度器据此判断是否还有空闲并发名额（max_concurrent）。
+    /// 等待中的任务排在调度器提交的任务之前，同样计入；
+    /// 只统计 ffmpeg 进程会漏掉探测阶段和两遍编码间隙的任务
+    pub fn occupied_slots(&self) -> usize {
+        self.active.len() + self.waiting.len()
+    }
+
     /// 检查任务是否已被标记为取消
     ///
     /// # 参数
@@ -85,12 +318,56 @@ impl TaskQueue {
 
     /// 清理已完成任务的记录
     ///
-    /// 从 running 映射和 cancelled 集合中移除指定任务
+    /// 从 running 映射、cancelled/paused 集合和正在读写的文件记录中移除指定任务
     ///
     /// # 参数
     /// - `task_id` - 要清理的任务 ID
     pub fn cleanup(&mut self, task_id: &str) {
         self.running.remove(task_id);
         self.cancelled.remove(task_id);
+        self.paused.remove(task_id);
+        self.pausable.remove(task_id);
+        self.writing.remove(task_id);
+        self.inputs.remove(task_id);
+        self.release_slot(task_id);
+    }
+}
+
+/// 等待并获取并发名额
+///
+/// 没有空闲名额时以排队位置（前面等待的任务数）调用 `on_wait`，
+/// 之后每当位置变化再次调用（用于通知前端任务在排队），
+/// 然后等待其他任务释放名额；等待期间任务被取消则放弃。
+/// 获得的名额需在任务的 ffmpeg 进程全部结束后通过 release_slot 释放
+///
+/// # 参数
+/// - `task_id` - 任务 ID
+/// - `on_wait` - 需要排队或排队位置变化时的回调
+///
+/// # 返回
+/// - `true` - 已获得名额
+/// - `false` - 等待期间任务被取消
+pub async fn acquire_slot(task_id: &str, mut on_wait: impl FnMut(usize)) -> bool {
+    let slot_freed = TASK_QUEUE.lock().await.slot_freed.clone();
+    let mut last_position = None;
+    loop {
+        // 先创建等待 future 再检查，检查与等待之间的 notify_waiters 不会丢失
+        let notified = slot_freed.notified();
+        let position = {
+            let mut queue = TASK_QUEUE.lock().await;
+            if queue.is_cancelled(task_id) {
+                queue.remove_waiting(task_id);
+                return false;
+            }
+            if queue.try_acquire_slot(task_id) {
+                return true;
+            }
+            queue.queue_position(task_id)
+        };
+        if let Some(position) = position.filter(|p| last_position != Some(*p)) {
+            on_wait(position);
+            last_position = Some(position);
+        }
+        notified.await;
     }
 }
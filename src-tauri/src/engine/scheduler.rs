@@ -0,0 +1,503 @@
+/// 定时任务调度器
+///
+/// 将需要延后执行的任务（指定时间 / 系统空闲时）持久化到
+/// $APPDATA/com.clipforge.app/scheduled.json，后台循环定期检查，
+/// 条件满足且并发名额（max_concurrent）允许时提交执行。
+/// 任务提交后标记为执行中，执行结束才从文件删除；
+/// 应用重启后从文件恢复，未执行完的任务不会丢失
+
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use tauri::ipc::Channel;
+use tauri::Manager;
+use tokio::sync::Mutex;
+
+use crate::commands;
+use crate::models::task::{ScheduledTask, TaskEvent};
+
+/// 定时任务文件名
+const SCHEDULE_FILE: &str = "scheduled.json";
+
+/// 调度检查间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 空闲判定阈值：每个 CPU 核心的 1 分钟平均负载低于此值
+const IDLE_LOAD_PER_CORE: f64 = 0.25;
+
+/// 空闲判定时长：负载持续低于阈值达到此时长才视为空闲
+const IDLE_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// 支持定时执行的任务类型
+pub const SCHEDULABLE_KINDS: &[&str] = &[
+    "convert",
+    "compress",
+    "trim",
+    "merge",
+    "audio",
+    "watermark",
+    "resize",
+    "gif",
+    "subtitle",
+    "export",
+];
+
+/// scheduled.json 读写锁，避免调度循环与 command 同时改写文件
+static SCHEDULE_LOCK: once_cell::sync::Lazy<Mutex<()>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(()));
+
+/// 前端当前订阅的事件 Channel
+///
+/// 定时任务触发时前端可能已重启，因此不保存创建时的 Channel，
+/// 而是推送到前端最近一次通过 subscribe_scheduled_events 注册的 Channel
+static EVENT_CHANNEL: once_cell::sync::Lazy<Mutex<Option<Channel<TaskEvent>>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+/// 启动后台调度循环
+///
+/// 在应用 setup 阶段调用一次
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+pub fn start(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = requeue_interrupted(&app).await {
+            log::warn!("Failed to requeue interrupted scheduled tasks: {}", e);
+        }
+        let mut idle_since: Option<Instant> = None;
+        loop {
+            if let Err(e) = tick(&app, &mut idle_since).await {
+                log::warn!("Scheduler tick failed: {}", e);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// 将上次运行时已提交但未执行完的任务（应用在执行中退出）重新排队
+async fn requeue_interrupted(app: &tauri::AppHandle) -> Result<(), String> {
+    let _guard = SCHEDULE_LOCK.lock().await;
+    let mut tasks = read_tasks(app)?;
+    if !tasks.iter().any(|t| t.running) {
+        return Ok(());
+    }
+    for task in tasks.iter_mut() {
+        task.running = false;
+    }
+    write_tasks(app, &tasks)
+}
+
+/// 解析定时任务的计划执行时间（RFC 3339）
+///
+/// # 返回
+/// - `Ok(DateTime)` - 解析后的时间
+/// - `Err(String)` - 时间格式错误
+pub fn parse_schedule_time(at: &str) -> Result<chrono::DateTime<chrono::FixedOffset>, String> {
+    chrono::DateTime::parse_from_rfc3339(at)
+        .map_err(|e| format!("Invalid scheduleAt time '{}': {}", at, e))
+}
+
+/// 注册接收定时任务事件的 Channel（替换之前注册的 Channel）
+pub async fn set_event_channel(channel: Channel<TaskEvent>) {
+    *EVENT_CHANNEL.lock().await = Some(channel);
+}
+
+/// 添加定时任务
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `task` - 已校验的定时任务
+pub async fn add_task(app: &tauri::AppHandle, task: ScheduledTask) -> Result<(), String> {
+    if let Some(at) = task.schedule_at.as_deref() {
+        parse_schedule_time(at)?;
+    }
+    let _guard = SCHEDULE_LOCK.lock().await;
+    let mut tasks = read_tasks(app)?;
+    tasks.push(task);
+    write_tasks(app, &tasks)
+}
+
+/// 列出所有未执行完的定时任务（running 标记正在执行的）
+pub async fn list_tasks(app: &tauri::AppHandle) -> Result<Vec<ScheduledTask>, String> {
+    let _guard = SCHEDULE_LOCK.lock().await;
+    read_tasks(app)
+}
+
+/// 移除尚未执行的定时任务
+///
+/// # 返回
+/// - `Ok(())` - 已移除
+/// - `Err(String)` - 任务不存在或已开始执行
+pub async fn remove_task(app: &tauri::AppHandle, id: &str) -> Result<(), String> {
+    let _guard = SCHEDULE_LOCK.lock().await;
+    let mut tasks = read_tasks(app)?;
+    match tasks.iter().find(|t| t.id == id) {
+        None => return Err(format!("Scheduled task {} not found", id)),
+        Some(task) if task.running => {
+            return Err(format!(
+                "Scheduled task {} has already started; cancel the running task instead",
+                id
+            ));
+        }
+        Some(_) => {}
+    }
+    tasks.retain(|t| t.id != id);
+    write_tasks(app, &tasks)
+}
+
+/// 执行结束后从文件中删除定时任务
+async fn finish_task(app: &tauri::AppHandle, id: &str) -> Result<(), String> {
+    let _guard = SCHEDULE_LOCK.lock().await;
+    let mut tasks = read_tasks(app)?;
+    tasks.retain(|t| t.id != id);
+    write_tasks(app, &tasks)
+}
+
+/// 校验任务参数能否解析为对应 command 的参数结构
+///
+/// 在创建定时任务时调用，避免参数错误到执行时才暴露
+///
+/// # 参数
+/// - `kind` - 任务类型
+/// - `params` - 原始参数
+pub fn validate_params(kind: &str, params: &serde_json::Value) -> Result<(), String> {
+    use crate::models::preset::*;
+    match kind {
+        "convert" => parse_params::<ConvertParams>(params).map(|_| ()),
+        "compress" => parse_params::<CompressParams>(params).map(|_| ()),
+        "trim" => parse_params::<TrimParams>(params).map(|_| ()),
+        "merge" => parse_params::<MergeParams>(params).map(|_| ()),
+        "audio" => parse_params::<AudioParams>(params).map(|_| ()),
+        "watermark" => parse_params::<WatermarkParams>(params).map(|_| ()),
+        "resize" => parse_params::<ResizeParams>(params).map(|_| ()),
+        "gif" => parse_params::<GifParams>(params).map(|_| ()),
+        "subtitle" => parse_params::<SubtitleParams>(params).map(|_| ()),
+        "export" => parse_params::<PlatformExportParams>(params).map(|_| ()),
+        _ => Err(format!(
+            "Unsupported scheduled task kind: {} (supported: {})",
+            kind,
+            SCHEDULABLE_KINDS.join(", ")
+        )),
+    }
+}
+
+/// 单轮调度检查
+///
+/// 更新空闲状态，按剩余并发名额取出到期任务并提交执行
+async fn tick(app: &tauri::AppHandle, idle_since: &mut Option<Instant>) -> Result<(), String> {
+    // 更新系统空闲状态
+    match system_load_per_core() {
+        Some(load) if load < IDLE_LOAD_PER_CORE => {
+            idle_since.get_or_insert_with(Instant::now);
+        }
+        _ => *idle_since = None,
+    }
+    let is_idle = idle_since.is_some_and(|since| since.elapsed() >= IDLE_DURATION);
+
+    // 计算剩余并发名额
+    let max_concurrent = commands::settings::load_settings(app)
+        .await
+        .max_concurrent
+        .max(1) as usize;
+    let occupied = {
+        let queue = crate::engine::queue::TASK_QUEUE.lock().await;
+        queue.occupied_slots()
+    };
+    let slots = free_slots(max_concurrent, occupied);
+    if slots == 0 {
+        return Ok(());
+    }
+
+    // 取出到期任务（按创建顺序），先在文件中标记为执行中再执行，避免重复触发；
+    // 执行结束后才删除，应用中途退出时下次启动会重新执行
+    let now = chrono::Utc::now();
+    let due = {
+        let _guard = SCHEDULE_LOCK.lock().await;
+        let mut tasks = read_tasks(app)?;
+        let mut due = Vec::new();
+        for task in tasks.iter_mut() {
+            if due.len() < slots && !task.running && is_due(task, now, is_idle) {
+                task.running = true;
+                due.push(task.clone());
+            }
+        }
+        if !due.is_empty() {
+            write_tasks(app, &tasks)?;
+        }
+        due
+    };
+
+    for task in due {
+        fire(app.clone(), task);
+    }
+    Ok(())
+}
+
+/// 剩余可提交的并发名额
+///
+/// # 参数
+/// - `max_concurrent` - 设置中的最大并发数
+/// - `occupied` - TASK_QUEUE 中已占用或正在等待名额的任务数
+///
+/// 已提交的定时任务进入 TASK_QUEUE 后同样计入 `occupied`，不再单独统计；
+/// 提交后到入队前（探测输入期间）的任务未计入，下一轮超额提交的任务会在队列中等待，
+/// 并发数仍由 TASK_QUEUE 保证不超过 max_concurrent
+fn free_slots(max_concurrent: usize, occupied: usize) -> usize {
+    max_concurrent.saturating_sub(occupied)
+}
+
+/// 判断定时任务是否满足执行条件
+///
+/// - 设置了 schedule_at：当前时间已到达（创建时已校验格式，
+///   文件被手动改成无法解析的时间时不执行）
+/// - 设置了 run_when_idle：系统已持续空闲
+/// - 两者都设置时需同时满足
+fn is_due(task: &ScheduledTask, now: chrono::DateTime<chrono::Utc>, is_idle: bool) -> bool {
+    let time_ok = match task.schedule_at.as_deref() {
+        Some(at) => match parse_schedule_time(at) {
+            Ok(at) => at <= now,
+            Err(e) => {
+                log::warn!("Skipping scheduled task {}: {}", task.id, e);
+                false
+            }
+        },
+        None => true,
+    };
+    time_ok && (!task.run_when_idle || is_idle)
+}
+
+/// 在后台执行到期的定时任务
+///
+/// 事件推送到前端当前订阅的 Channel；执行前的失败（如参数非法、探测失败）
+/// 以定时任务 ID 推送 Failed 事件。无论成功与否，结束后从文件中删除该任务
+fn fire(app: tauri::AppHandle, task: ScheduledTask) {
+    tauri::async_runtime::spawn(async move {
+        log::info!("Starting scheduled task {} ({})", task.id, task.kind);
+        let channel = current_channel().await;
+        if let Err(error) = dispatch(app.clone(), &task, channel.clone()).await {
+            log::warn!("Scheduled task {} failed: {}", task.id, error);
+            let _ = channel.send(TaskEvent::Failed {
+                task_id: task.id.clone(),
+                error,
+            });
+        }
+        if let Err(e) = finish_task(&app, &task.id).await {
+            log::warn!("Failed to remove finished scheduled task {}: {}", task.id, e);
+        }
+    });
+}
+
+/// 获取前端当前订阅的 Channel，未订阅时返回丢弃所有事件的 Channel
+async fn current_channel() -> Channel<TaskEvent> {
+    EVENT_CHANNEL
+        .lock()
+        .await
+        .clone()
+        .unwrap_or_else(|| Channel::new(|_| Ok(())))
+}
+
+/// 按任务类型调用对应 command
+async fn dispatch(
+    app: tauri::AppHandle,
+    task: &ScheduledTask,
+    channel: Channel<TaskEvent>,
+) -> Result<(), String> {
+    let params = &task.params;
+    match task.kind.as_str() {
+        "convert" => commands::convert::convert_video(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ()),
+        "compress" => commands::compress::compress_video(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ()),
+        "trim" => commands::trim::trim_video(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        "merge" => commands::merge::merge_videos(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ()),
+        "audio" => commands::audio::process_audio(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ()),
+        "watermark" => commands::watermark::add_watermark(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ()),
+        "resize" => commands::resize::resize_video(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ()),
+        "gif" => commands::gif::create_gif(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        "subtitle" => commands::subtitle::process_subtitle(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ()),
+        "export" => commands::export::export_for_platform(app, parse_params(params)?, channel)
+            .await
+            .map(|_| ()),
+        other => Err(format!("Unsupported scheduled task kind: {}", other)),
+    }
+}
+
+/// 将原始参数解析为 command 参数结构
+fn parse_params<T: DeserializeOwned>(params: &serde_json::Value) -> Result<T, String> {
+    serde_json::from_value(params.clone())
+        .map_err(|e| format!("Invalid scheduled task params: {}", e))
+}
+
+/// 获取每个 CPU 核心的 1 分钟平均负载
+///
+/// macOS 通过 `sysctl -n vm.loadavg`（输出形如 "{ 1.23 1.10 1.05 }"），
+/// Linux 读取 /proc/loadavg。获取失败返回 None（视为非空闲）
+fn system_load_per_core() -> Option<f64> {
+    let raw = if cfg!(target_os = "macos") {
+        let output = std::process::Command::new("sysctl")
+            .args(["-n", "vm.loadavg"])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout).to_string()
+    } else {
+        std::fs::read_to_string("/proc/loadavg").ok()?
+    };
+    let load: f64 = raw
+        .trim_matches(|c: char| c == '{' || c == '}' || c.is_whitespace())
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1) as f64;
+    Some(load / cores)
+}
+
+/// 读取定时任务列表（文件不存在时为空）
+fn read_tasks(app: &tauri::AppHandle) -> Result<Vec<ScheduledTask>, String> {
+    let path = get_schedule_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("读取定时任务文件失败: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析定时任务文件失败: {}", e))
+}
+
+/// 写入定时任务列表
+fn write_tasks(app: &tauri::AppHandle, tasks: &[ScheduledTask]) -> Result<(), String> {
+    let path = get_schedule_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建定时任务目录失败: {}", e))?;
+    }
+    let json =
+        serde_json::to_string_pretty(tasks).map_err(|e| format!("序列化定时任务失败: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("写入定时任务文件失败: {}", e))
+}
+
+/// 获取定时任务文件的完整路径
+fn get_schedule_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
+    Ok(app_data.join(SCHEDULE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造定时任务
+    fn task(schedule_at: Option<&str>, run_when_idle: bool) -> ScheduledTask {
+        ScheduledTask {
+            id: "t1".to_string(),
+            kind: "convert".to_string(),
+            params: serde_json::json!({}),
+            schedule_at: schedule_at.map(str::to_string),
+            run_when_idle,
+            created_at: "2026-10-16T00:00:00Z".to_string(),
+            running: false,
+        }
+    }
+
+    fn now() -> chrono::DateTime<chrono::Utc> {
+        "2026-10-16T12:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn free_slots_count_each_queued_task_once() {
+        // 最大并发 4、两个定时任务正在运行：还能再提交两个
+        assert_eq!(free_slots(4, 2), 2);
+        assert_eq!(free_slots(2, 2), 0);
+        assert_eq!(free_slots(1, 3), 0);
+        assert_eq!(free_slots(4, 0), 4);
+    }
+
+    #[test]
+    fn free_slots_include_tasks_waiting_in_queue() {
+        let mut queue = crate::engine::queue::TaskQueue::new();
+        queue.set_max_concurrent(2);
+        assert!(queue.try_acquire_slot("a"));
+        assert!(queue.try_acquire_slot("b"));
+        assert!(!queue.try_acquire_slot("c"));
+        // 两个任务占用名额、一个排队，尚未启动任何 ffmpeg 进程
+        assert_eq!(queue.occupied_slots(), 3);
+        assert_eq!(free_slots(4, queue.occupied_slots()), 1);
+        assert_eq!(free_slots(3, queue.occupied_slots()), 0);
+
+        queue.release_slot("a");
+        assert!(queue.try_acquire_slot("c"));
+        assert_eq!(queue.occupied_slots(), 2);
+        assert_eq!(free_slots(3, queue.occupied_slots()), 1);
+    }
+
+    #[test]
+    fn rejects_non_rfc3339_schedule_times() {
+        assert!(parse_schedule_time("2026-10-17T02:00:00+08:00").is_ok());
+        assert!(parse_schedule_time("2026-10-17 02:00").is_err());
+        assert!(parse_schedule_time("tomorrow").is_err());
+    }
+
+    #[test]
+    fn due_when_time_reached_and_idle_condition_met() {
+        assert!(is_due(&task(Some("2026-10-16T11:59:00Z"), false), now(), false));
+        assert!(!is_due(&task(Some("2026-10-16T20:30:00+08:00"), false), now(), false));
+        assert!(is_due(&task(Some("2026-10-16T19:00:00+08:00"), false), now(), false));
+        assert!(!is_due(&task(None, true), now(), false));
+        assert!(is_due(&task(None, true), now(), true));
+        assert!(!is_due(&task(Some("2026-10-16T11:59:00Z"), true), now(), false));
+    }
+
+    #[test]
+    fn invalid_stored_time_is_never_due() {
+        assert!(!is_due(&task(Some("not a time"), false), now(), true));
+    }
+
+    #[test]
+    fn running_flag_defaults_to_false_for_old_files() {
+        let stored: ScheduledTask = serde_json::from_value(serde_json::json!({
+            "id": "t1",
+            "kind": "convert",
+            "params": {},
+            "scheduleAt": null,
+            "runWhenIdle": true,
+            "createdAt": "2026-10-16T00:00:00Z",
+        }))
+        .unwrap();
+        assert!(!stored.running);
+    }
+
+    #[test]
+    fn validates_params_against_command_structs() {
+        assert!(validate_params("gif", &serde_json::json!({})).is_err());
+        assert!(validate_params("unknown", &serde_json::json!({})).is_err());
+        let resize = serde_json::json!({
+            "inputPath": "in.mp4",
+            "outputPath": "out.mp4",
+            "width": 1280,
+            "height": 720,
+            "keepAspectRatio": true,
+        });
+        assert!(validate_params("resize", &resize).is_ok());
+    }
+}
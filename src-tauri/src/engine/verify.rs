@@ -0,0 +1,490 @@
+/// 输出文件编码属性核对
+///
+/// 任务完成后探测输出文件，把编码器、分辨率、帧率、像素格式、profile/level
+/// 以及 faststart（moov 是否位于 mdat 之前）与参数要求的值逐项比对。
+/// 比对逻辑是纯函数，只依赖 ffprobe 输出和 moov 位置，探测与读文件在外层完成
+
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::engine::process::run_ffprobe;
+use crate::models::media::{parse_frame_rate, FfprobeOutput, FfprobeStream};
+use crate::models::preset::ConvertParams;
+use crate::models::task::VerificationCheck;
+
+/// 帧率比较允许的误差
+const FPS_TOLERANCE: f64 = 0.01;
+/// 查找 moov/mdat 时最多检查的顶层 box 数量
+const MAX_TOP_LEVEL_BOXES: usize = 64;
+/// 无法探测到的实际值
+const UNKNOWN: &str = "unknown";
+
+/// 参数要求的输出属性
+///
+/// 为 None 的字段不参与核对
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExpectedOutput {
+    /// ffprobe 报告的编解码器名称（如 "h264", "hevc"）
+    pub codec: Option<String>,
+    /// 分辨率（宽, 高）
+    pub resolution: Option<(u32, u32)>,
+    /// 帧率
+    pub fps: Option<f64>,
+    /// 像素格式（如 "yuv420p"）
+    pub pixel_format: Option<String>,
+    /// 编码 profile（如 "high"，比较时忽略大小写和空格）
+    pub profile: Option<String>,
+    /// 编码 level（ffprobe 的整数形式，H.264 的 4.1 为 41）
+    pub level: Option<i32>,
+    /// 是否要求 faststart
+    pub faststart: Option<bool>,
+    /// 章节数量（要求保留源文件章节时）
+    pub chapters: Option<usize>,
+}
+
+impl ExpectedOutput {
+    /// 根据格式转换参数推导输出属性
+    ///
+    /// 转换不缩放也不改帧率，分辨率和帧率沿用源视频；
+    /// extra_args 中显式指定的 -pix_fmt / -profile:v / -level / -r 优先。
+    /// 保留元数据时核对章节数量与源文件一致。视频流复制时只核对 faststart 和章节
+    ///
+    /// # 参数
+    /// - `params` - 格式转换参数
+    /// - `source` - 源文件的主视频流（探测失败时为 None）
+    /// - `source_chapters` - 源文件的章节数量
+    pub fn from_convert(
+        params: &ConvertParams,
+        source: Option<&FfprobeStream>,
+        source_chapters: usize,
+    ) -> Self {
+        let format = params.output_format.to_lowercase();
+        let preserve_chapters = params.preserve_metadata.unwrap_or(true) && source_chapters > 0;
+        let mut expected = ExpectedOutput {
+            faststart: matches!(format.as_str(), "mp4" | "mov").then_some(true),
+            chapters: preserve_chapters.then_some(source_chapters),
+            ..Default::default()
+        };
+
+        let encoder = params.video_codec.as_str();
+        if encoder == "copy" {
+            return expected;
+        }
+        // WebM 不兼容的编码器在构建命令时会被替换为 VP9；
+        // 硬件加速时 libx265 使用 HEVC VideoToolbox，其余编码器使用 H.264 VideoToolbox
+        let hardware = params.hardware_accel.unwrap_or(false) && format != "webm";
+        expected.codec = if format == "webm"
+            && !matches!(encoder, "libvpx" | "libvpx-vp9" | "libaom-av1")
+        {
+            Some("vp9".to_string())
+        } else if hardware {
+            Some(if encoder == "libx265" { "hevc" } else { "h264" }.to_string())
+        } else {
+            codec_name_for_encoder(encoder).map(str::to_string)
+        };
+
+        if let Some(stream) = source {
+            if let (Some(w), Some(h)) = (stream.width, stream.height) {
+                // 自动转正 90°/270° 旋转的源视频时，输出宽高互换
+                let transposed = params.auto_rotate.unwrap_or(true)
+                    && matches!(stream.rotation(), 90 | 270);
+                expected.resolution = Some(if transposed { (h, w) } else { (w, h) });
+            }
+            expected.fps = stream
+                .r_frame_rate
+                .as_deref()
+                .map(parse_frame_rate)
+                .filter(|fps| *fps > 0.0);
+        }
+
+        expected.pixel_format = params.pixel_format.clone();
+        let extra = params.extra_args.as_deref().unwrap_or(&[]);
+        if let Some(pix_fmt) = extra_arg_value(extra, &["-pix_fmt"]) {
+            expected.pixel_format = Some(pix_fmt.to_string());
+        }
+        if let Some(profile) = extra_arg_value(extra, &["-profile:v", "-profile"]) {
+            expected.profile = Some(profile.to_string());
+        }
+        if let Some(level) = extra_arg_value(extra, &["-level", "-level:v"]) {
+            expected.level = parse_level(level, expected.codec.as_deref());
+        }
+        if let Some(rate) = extra_arg_value(extra, &["-r", "-r:v"]) {
+            expected.fps = Some(parse_frame_rate(rate)).filter(|fps| *fps > 0.0);
+        }
+        expected
+    }
+
+    /// 纯音频输出只核对章节，视频属性和 faststart 不适用
+    pub fn audio_only(self) -> Self {
+        ExpectedOutput {
+            chapters: self.chapters,
+            ..Default::default()
+        }
+    }
+}
+
+/// 比对输出文件的属性与参数要求
+///
+/// # 参数
+/// - `expected` - 参数要求的输出属性
+/// - `probe` - 输出文件的 ffprobe 结果
+/// - `moov_first` - moov 是否位于 mdat 之前（非 MP4 族或无法读取时为 None）
+///
+/// # 返回
+/// 每个要求的属性对应一项核对结果
+pub fn verify_output(
+    expected: &ExpectedOutput,
+    probe: &FfprobeOutput,
+    moov_first: Option<bool>,
+) -> Vec<VerificationCheck> {
+    let video = probe.main_video_stream();
+
+    let mut checks = Vec::new();
+
+    if let Some(codec) = &expected.codec {
+        let actual = video.and_then(|v| v.codec_name.clone());
+        let ok = actual.as_deref() == Some(codec.as_str());
+        checks.push(check("codec", codec.clone(), actual, ok));
+    }
+
+    if let Some((width, height)) = expected.resolution {
+        let actual = video.and_then(|v| Some((v.width?, v.height?)));
+        checks.push(check(
+            "resolution",
+            format!("{}x{}", width, height),
+            actual.map(|(w, h)| format!("{}x{}", w, h)),
+            actual == Some((width, height)),
+        ));
+    }
+
+    if let Some(fps) = expected.fps {
+        let actual = video
+            .and_then(|v| v.r_frame_rate.as_deref())
+            .map(parse_frame_rate)
+            .filter(|fps| *fps > 0.0);
+        checks.push(check(
+            "fps",
+            format_fps(fps),
+            actual.map(format_fps),
+            actual.is_some_and(|a| (a - fps).abs() < FPS_TOLERANCE),
+        ));
+    }
+
+    if let Some(pix_fmt) = &expected.pixel_format {
+        let actual = video.and_then(|v| v.pix_fmt.clone());
+        let ok = actual.as_deref() == Some(pix_fmt.as_str());
+        checks.push(check("pixelFormat", pix_fmt.clone(), actual, ok));
+    }
+
+    if let Some(profile) = &expected.profile {
+        let actual = video.and_then(|v| v.profile.clone());
+        let ok = actual
+            .as_deref()
+            .is_some_and(|a| normalize_profile(a) == normalize_profile(profile));
+        checks.push(check("profile", profile.clone(), actual, ok));
+    }
+
+    if let Some(level) = expected.level {
+        let actual = video.and_then(|v| v.level).filter(|l| *l > 0);
+        checks.push(check(
+            "level",
+            level.to_string(),
+            actual.map(|l| l.to_string()),
+            actual == Some(level),
+        ));
+    }
+
+    if let Some(chapters) = expected.chapters {
+        let actual = probe.chapters.len();
+        checks.push(check(
+            "chapters",
+            chapters.to_string(),
+            Some(actual.to_string()),
+            actual == chapters,
+        ));
+    }
+
+    if let Some(faststart) = expected.faststart {
+        checks.push(check(
+            "faststart",
+            faststart.to_string(),
+            moov_first.map(|m| m.to_string()),
+            moov_first == Some(faststart),
+        ));
+    }
+
+    checks
+}
+
+/// 文件是否已 faststart（moov 位于 mdat 之前）
+///
+/// 非 MP4 族或无法读取的文件视为否。注意流复制重封装时 ffmpeg 的 mov 封装器总是把 moov
+/// 写在 mdat 之后，源文件已 faststart 并不代表输出也是，输出仍需 `+faststart`
+pub fn is_faststart(path: &str) -> bool {
+    moov_before_mdat(path) == Some(true)
+}
+
+/// 检查 MP4/MOV 文件的 moov box 是否位于 mdat 之前（即是否已 faststart）
+///
+/// 只遍历顶层 box 的头部，不读取内容
+///
+/// # 参数
+/// - `path` - 文件路径
+///
+/// # 返回
+/// - `Some(true)` - moov 在前
+/// - `Some(false)` - mdat 在前
+/// - `None` - 文件无法读取或不是 ISO BMFF 结构
+pub fn moov_before_mdat(path: &str) -> Option<bool> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+    let mut offset: u64 = 0;
+
+    for _ in 0..MAX_TOP_LEVEL_BOXES {
+        if offset + 8 > file_len {
+            return None;
+        }
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header).ok()?;
+        let size32 = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let box_type = &header[4..8];
+
+        match box_type {
+            b"moov" => return Some(true),
+            b"mdat" => return Some(false),
+            _ => {}
+        }
+
+        let size = match size32 {
+            // box 延伸到文件末尾，之后没有其他 box
+            0 => return None,
+            // 64 位扩展大小
+            1 => {
+                let mut large = [0u8; 8];
+                file.read_exact(&mut large).ok()?;
+                u64::from_be_bytes(large)
+            }
+            n => n,
+        };
+        if size < 8 {
+            return None;
+        }
+        offset += size;
+    }
+    None
+}
+
+/// 探测输出文件并与参数要求比对
+///
+/// 探测失败时所有项的实际值记为 unknown（不一致）
+///
+/// # 参数
+/// - `app` - Tauri AppHandle
+/// - `output_path` - 输出文件路径
+/// - `expected` - 参数要求的输出属性
+/// - `timeout_ms` - 探测超时（毫秒），0 为不限时
+pub async fn verify_file(
+    app: &tauri::AppHandle,
+    output_path: &str,
+    expected: &ExpectedOutput,
+    timeout_ms: u64,
+) -> Vec<VerificationCheck> {
+    let probe = run_ffprobe(app, output_path, timeout_ms)
+        .await
+        .ok()
+        .and_then(|json| serde_json::from_str::<FfprobeOutput>(&json).ok())
+        .unwrap_or(FfprobeOutput {
+            streams: None,
+            format: None,
+            chapters: Vec::new(),
+        });
+    let moov_first = if expected.faststart.is_some() {
+        moov_before_mdat(output_path)
+    } else {
+        None
+    };
+    verify_output(expected, &probe, moov_first)
+}
+
+/// 构造一项核对结果
+fn check(name: &str, expected: String, actual: Option<String>, ok: bool) -> VerificationCheck {
+    VerificationCheck {
+        name: name.to_string(),
+        expected,
+        actual: actual.unwrap_or_else(|| UNKNOWN.to_string()),
+        ok,
+    }
+}
+
+/// 编码器名称到 ffprobe 编解码器名称的映射
+fn codec_name_for_encoder(encoder: &str) -> Option<&'static str> {
+    match encoder {
+        "libx264" | "h264_videotoolbox" => Some("h264"),
+        "libx265" | "hevc_videotoolbox" => Some("hevc"),
+        "libvpx-vp9" => Some("vp9"),
+        "libvpx" => Some("vp8"),
+        "libaom-av1" | "libsvtav1" => Some("av1"),
+        "prores" | "prores_ks" | "prores_videotoolbox" => Some("prores"),
+        _ => None,
+    }
+}
+
+/// 取 extra_args 中某个选项的值（多次出现时以最后一次为准，与 ffmpeg 一致）
+fn extra_arg_value<'a>(args: &'a [String], names: &[&str]) -> Option<&'a str> {
+    args.windows(2)
+        .rev()
+        .find(|pair| names.contains(&pair[0].as_str()))
+        .map(|pair| pair[1].as_str())
+}
+
+/// 把 -level 参数转换为 ffprobe 的整数形式
+///
+/// H.264 的 "4.1" / "41" 都对应 41；HEVC 的 level 按 general_level_idc 报告（4.1 为 123）
+fn parse_level(level: &str, codec: Option<&str>) -> Option<i32> {
+    let value = level.parse::<f64>().ok()?;
+    // "41" 这类整数写法与 "4.1" 等价
+    let level = if value >= 10.0 { value / 10.0 } else { value };
+    let scale = if codec == Some("hevc") { 30.0 } else { 10.0 };
+    Some((level * scale).round() as i32)
+}
+
+/// profile 比较时统一大小写并去掉空格（"High 10" 与 "high10" 相同）
+fn normalize_profile(profile: &str) -> String {
+    profile
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// 帧率显示为最多三位小数
+fn format_fps(fps: f64) -> String {
+    let text = format!("{:.3}", fps);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 1080p30 H.264 High@4.1 输出的 ffprobe 结果（带一张封面图和两个章节）
+    fn probe_fixture() -> FfprobeOutput {
+        serde_json::from_value(json!({
+            "streams": [
+                { "index": 0, "codec_type": "video", "codec_name": "h264", "profile": "High",
+                  "level": 41, "width": 1920, "height": 1080, "pix_fmt": "yuv420p",
+                  "r_frame_rate": "30/1" },
+                { "index": 1, "codec_type": "audio", "codec_name": "aac", "channels": 2 },
+                { "index": 2, "codec_type": "video", "codec_name": "mjpeg", "width": 600,
+                  "height": 600, "disposition": { "attached_pic": 1 } },
+            ],
+            "format": { "duration": "12.500000", "bit_rate": "5000000" },
+            "chapters": [
+                { "start_time": "0.000000", "end_time": "6.000000", "tags": { "title": "A" } },
+                { "start_time": "6.000000", "end_time": "12.500000", "tags": { "title": "B" } },
+            ],
+        }))
+        .unwrap()
+    }
+
+    fn expected_fixture() -> ExpectedOutput {
+        ExpectedOutput {
+            codec: Some("h264".to_string()),
+            resolution: Some((1920, 1080)),
+            fps: Some(30.0),
+            pixel_format: Some("yuv420p".to_string()),
+            profile: Some("high".to_string()),
+            level: Some(41),
+            faststart: Some(true),
+            chapters: Some(2),
+        }
+    }
+
+    #[test]
+    fn matching_output_passes_every_check() {
+        let checks = verify_output(&expected_fixture(), &probe_fixture(), Some(true));
+        let names: Vec<&str> = checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "codec",
+                "resolution",
+                "fps",
+                "pixelFormat",
+                "profile",
+                "level",
+                "chapters",
+                "faststart",
+            ]
+        );
+        assert!(checks.iter().all(|c| c.ok), "{:?}", checks);
+    }
+
+    #[test]
+    fn mismatching_output_reports_actual_values() {
+        let expected = ExpectedOutput {
+            codec: Some("hevc".to_string()),
+            resolution: Some((1280, 720)),
+            fps: Some(25.0),
+            level: Some(40),
+            chapters: Some(3),
+            ..expected_fixture()
+        };
+        let checks = verify_output(&expected, &probe_fixture(), Some(false));
+        let failed: Vec<(&str, &str)> = checks
+            .iter()
+            .filter(|c| !c.ok)
+            .map(|c| (c.name.as_str(), c.actual.as_str()))
+            .collect();
+        assert_eq!(
+            failed,
+            [
+                ("codec", "h264"),
+                ("resolution", "1920x1080"),
+                ("fps", "30"),
+                ("level", "41"),
+                ("chapters", "2"),
+                ("faststart", "false"),
+            ]
+        );
+
+        // 探测不到 moov 位置时实际值为 unknown
+        let checks = verify_output(&expected_fixture(), &probe_fixture(), None);
+        let faststart = checks.iter().find(|c| c.name == "faststart").unwrap();
+        assert!(!faststart.ok);
+        assert_eq!(faststart.actual, UNKNOWN);
+    }
+
+
+    /// 由 (类型, 内容长度) 依次拼出顶层 box 的最小 MP4 文件
+    fn write_boxes(name: &str, boxes: &[(&[u8; 4], usize)]) -> String {
+        let mut bytes = Vec::new();
+        for (box_type, payload) in boxes {
+            bytes.extend_from_slice(&((8 + payload) as u32).to_be_bytes());
+            bytes.extend_from_slice(*box_type);
+            bytes.extend(std::iter::repeat(0u8).take(*payload));
+        }
+        let path = crate::utils::path::temp_file_path(name, "mp4").unwrap();
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn faststart_follows_moov_position() {
+        let moov_first = write_boxes("moov_first", &[(b"ftyp", 8), (b"moov", 16), (b"mdat", 32)]);
+        let moov_last = write_boxes("moov_last", &[(b"ftyp", 8), (b"mdat", 32), (b"moov", 16)]);
+        let not_mp4 = crate::utils::path::temp_file_path("not_mp4", "mp4").unwrap();
+        std::fs::write(&not_mp4, b"not an mp4").unwrap();
+
+        assert!(is_faststart(&moov_first));
+        assert!(!is_faststart(&moov_last));
+        assert_eq!(moov_before_mdat(&moov_last), Some(false));
+        assert!(!is_faststart(&not_mp4));
+        assert_eq!(moov_before_mdat(&not_mp4), None);
+
+        for path in [moov_first, moov_last, not_mp4] {
+            crate::utils::path::cleanup_temp_file(&path);
+        }
+    }
+}
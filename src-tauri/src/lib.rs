@@ -4,12 +4,17 @@
 /// and shared entry point for desktop and mobile builds.
 
 // Module declarations
+// commands and the Tauri entry point need the `desktop` feature;
+// engine / models / utils build without it for clipforge-cli
+#[cfg(feature = "desktop")]
 pub mod commands;
 pub mod engine;
 pub mod models;
 pub mod utils;
 
+#[cfg(feature = "desktop")]
 use tauri::image::Image;
+#[cfg(feature = "desktop")]
 use tauri::menu::{AboutMetadata, MenuBuilder, SubmenuBuilder};
 
 /// Build macOS application menu with custom About dialog icon
@@ -21,6 +26,7 @@ use tauri::menu::{AboutMetadata, MenuBuilder, SubmenuBuilder};
 ///
 /// The app icon is embedded at compile time via `include_bytes!`
 /// so it displays correctly in both dev and production modes.
+#[cfg(feature = "desktop")]
 fn build_app_menu(handle: &tauri::AppHandle) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
     /* Decode embedded PNG icon into raw RGBA for Tauri Image */
     let icon_img = image::load_from_memory(include_bytes!("../icons/icon.png"))
@@ -69,7 +75,9 @@ fn build_app_menu(handle: &tauri::AppHandle) -> tauri::Result<tauri::menu::Menu<
 /// Run the Tauri application
 ///
 /// Registers all plugins and commands, configures the custom
-/// macOS menu with app icon, then starts the application main loop.
+/// macOS menu with app icon, starts the task scheduler,
+/// then starts the application main loop.
+#[cfg(feature = "desktop")]
 pub fn run() {
     tauri::Builder::default()
         // Register Tauri plugins
@@ -79,24 +87,87 @@ pub fn run() {
         .plugin(tauri_plugin_notification::init())
         // Custom macOS menu with app icon in About dialog
         .menu(|handle| build_app_menu(handle))
+        // Start the background scheduler for deferred (timed / idle) tasks
+        .setup(|app| {
+            // 按已保存的设置初始化任务并发上限
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let settings = commands::settings::load_settings(&handle).await;
+                let mut queue = engine::queue::TASK_QUEUE.lock().await;
+                queue.set_max_concurrent(settings.max_concurrent as usize);
+            });
+            engine::scheduler::start(app.handle().clone());
+            Ok(())
+        })
         // Register frontend-callable commands
         .invoke_handler(tauri::generate_handler![
             commands::media_info::get_media_info,
             commands::convert::convert_video,
+            commands::batch::run_batch,
+            commands::convert::multi_resolution,
             commands::compress::compress_video,
             commands::trim::trim_video,
+            commands::trim::trim_leading_black,
+            commands::analyze::analyze_defects,
+            commands::streaming::generate_adaptive_stream,
             commands::merge::merge_videos,
+            commands::compose::add_intro_outro,
+            commands::slideshow::create_slideshow,
             commands::audio::process_audio,
+            commands::audio::extract_multi_format,
             commands::watermark::add_watermark,
+            commands::watermark::remove_logo,
             commands::resize::resize_video,
+            commands::speed::speed_video,
+            commands::speed::change_speed,
+            commands::reverse::reverse_video,
             commands::gif::create_gif,
+            commands::gif::estimate_gif_output_size,
             commands::subtitle::process_subtitle,
+            commands::subtitle::strip_subtitles,
+            commands::export::export_for_platform,
+            commands::thumbnail::scrub_thumbnails,
+            commands::thumbnail::extract_thumbnail,
+            commands::thumbnail::extract_thumbnails,
+            commands::thumbnail::preview_clip,
+            commands::attachment::extract_attachments,
+            commands::chapters::export_cue_sheet,
+            commands::chapters::embed_chapters,
+            commands::metadata::set_metadata,
+            commands::preview::get_preview,
+            commands::transform::auto_orient,
+            commands::transform::rotate_video,
+            commands::transform::transform_video,
+            commands::lut::apply_lut,
+            commands::stabilize::stabilize_video,
+            commands::chromakey::apply_chromakey,
+            commands::crop::crop_video,
+            commands::zoom_pan::zoom_pan,
+            commands::zoom_pan::zoom_pan_preview,
+            commands::deinterlace::deinterlace_video,
+            commands::denoise::denoise_video,
+            commands::hdr::hdr_to_sdr,
+            commands::dry_run::preview_command,
+            commands::estimate::estimate_output_size,
             commands::download::parse_video_url,
             commands::download::download_video,
+            commands::download::pause_download,
+            commands::download::resume_download,
             commands::task::cancel_task,
+            commands::task::cancel_by_path,
+            commands::task::schedule_task,
+            commands::task::list_scheduled_tasks,
+            commands::task::cancel_scheduled_task,
+            commands::task::subscribe_scheduled_events,
             commands::settings::get_settings,
             commands::settings::save_settings,
+            commands::settings::list_presets,
+            commands::settings::save_preset,
+            commands::settings::delete_preset,
+            commands::settings::save_params_as_preset,
+            commands::settings::apply_preset,
             commands::finder::reveal_in_finder,
+            commands::finder::start_drag,
         ])
         .run(tauri::generate_context!())
         .expect("Failed to start ClipForge application");
@@ -0,0 +1,77 @@
+/// command 错误类型
+///
+/// 大多数 command 直接返回字符串错误；需要让前端定位具体问题的 command
+/// 返回 CommandError，结构化错误序列化为带 code 的对象，其余错误仍序列化为字符串
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::utils::segment::SegmentError;
+
+/// 返回给前端的 command 错误
+///
+/// - `Segment` 序列化为 `{ "code": "overlappingSegments", "i": 0, "j": 1, "message": "..." }`，
+///   前端按 code 和片段下标标出出错的行
+/// - `Message` 序列化为普通字符串，与返回 `Result<_, String>` 的 command 一致
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum CommandError {
+    /// 时间片段校验失败
+    Segment {
+        #[serde(flatten)]
+        error: SegmentError,
+        /// 面向用户的错误描述
+        message: String,
+    },
+    /// 其他错误
+    Message(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Segment { message, .. } | CommandError::Message(message) => {
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+impl From<SegmentError> for CommandError {
+    fn from(error: SegmentError) -> Self {
+        let message = error.to_string();
+        CommandError::Segment { error, message }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Message(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_error_serializes_as_structured_object() {
+        let error = CommandError::from(SegmentError::EmptySegment { i: 2 });
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "emptySegment",
+                "i": 2,
+                "message": "Segment 3 ends before it starts",
+            })
+        );
+        assert_eq!(error.to_string(), "Segment 3 ends before it starts");
+    }
+
+    #[test]
+    fn other_errors_serialize_as_plain_strings() {
+        let error = CommandError::from("Input file not found".to_string());
+        assert_eq!(serde_json::to_value(&error).unwrap(), "Input file not found");
+    }
+}
@@ -24,12 +24,33 @@ pub struct MediaInfo {
     pub duration: f64,
     /// 总码率（bps）
     pub bitrate: u64,
-    /// 视频流信息列表
+    /// 视频流信息列表（不含作为封面图的 attached_pic 流）
     pub video_streams: Vec<VideoStream>,
     /// 音频流信息列表
     pub audio_streams: Vec<AudioStream>,
     /// 字幕流信息列表
     pub subtitle_streams: Vec<SubtitleStream>,
+    /// 附件流信息列表（MKV 内嵌字体、封面图等）
+    pub attachment_streams: Vec<AttachmentStream>,
+    /// 是否带有以视频流形式存放的封面图（MP3/MP4 的 attached_pic）
+    pub has_cover_art: bool,
+    /// 章节列表（按开始时间排序，无章节时为空）
+    pub chapters: Vec<Chapter>,
+}
+
+/// 章节信息
+///
+/// 用于前端展示和编辑章节，也是写回文件（embed_chapters）时的输入格式
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    /// 开始时间（秒）
+    pub start: f64,
+    /// 结束时间（秒）
+    pub end: f64,
+    /// 章节标题（未设置时为空字符串）
+    #[serde(default)]
+    pub title: String,
 }
 
 /// 视频流信息
@@ -49,14 +70,90 @@ pub struct VideoStream {
     pub height: u32,
     /// 帧率（浮点数，从 r_frame_rate 分数计算得出）
     pub frame_rate: f64,
-    /// 码率（bps），部分流可能不包含此信息
+    /// 码率（bps），流未标注时从 MKV 统计标签读取或按文件大小估算
     pub bitrate: Option<u64>,
+    /// 码率是否为估算值（流和标签都没有码率信息时按文件大小减去音频估算）
+    pub bitrate_estimated: bool,
     /// 像素格式（如 "yuv420p", "yuv444p"）
     pub pix_fmt: String,
     /// 总帧数（部分容器格式可能不提供）
     pub nb_frames: Option<u64>,
     /// 流时长（秒，可能与容器时长略有差异）
     pub duration: Option<f64>,
+    /// 编码 profile（如 "High", "Main"），部分编码器不提供
+    pub profile: Option<String>,
+    /// 编码 level（ffprobe 以整数表示，如 41 代表 H.264 Level 4.1）
+    pub level: Option<i32>,
+    /// 像素宽高比（SAR，如 "1:1", "4:3"），变形（anamorphic）视频不为 1:1
+    pub sample_aspect_ratio: Option<String>,
+    /// 色彩范围（"tv" 有限范围 / "pc" 全范围），未标记时为 None
+    pub color_range: Option<String>,
+    /// 色彩矩阵（如 "bt709", "bt2020nc"），未标记时为 None
+    pub color_space: Option<String>,
+    /// 传输特性（如 "bt709"，HDR10 为 "smpte2084"，HLG 为 "arib-std-b67"），未标记时为 None
+    pub color_transfer: Option<String>,
+    /// 色域原色（如 "bt709", "bt2020"），未标记时为 None
+    pub color_primaries: Option<String>,
+    /// 播放时需顺时针旋转的角度（0/90/180/270），来自 rotate 标签或显示矩阵
+    pub rotation: u32,
+    /// 场序（"progressive" 逐行，"tt"/"bb"/"tb"/"bt" 隔行），未标记时为 None
+    pub field_order: Option<String>,
+    /// 是否为隔行扫描视频（按场序判断，见 is_interlaced_field_order）
+    pub is_interlaced: bool,
+}
+
+impl VideoStream {
+    /// 像素宽高比的浮点值
+    ///
+    /// 缺失或无法解析（如 "0:1"）时视为方形像素，返回 1.0
+    pub fn sar(&self) -> f64 {
+        self.sample_aspect_ratio
+            .as_deref()
+            .and_then(parse_sample_aspect_ratio)
+            .unwrap_or(1.0)
+    }
+
+    /// 播放时显示的宽高（像素）
+    ///
+    /// 旋转 90°/270° 的视频（如手机竖拍）编码宽高与显示宽高相反，这里交换回来；
+    /// ffmpeg 解码时默认自动旋转，滤镜看到的也是显示宽高
+    pub fn display_dimensions(&self) -> (u32, u32) {
+        if self.rotation % 180 == 90 {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// 显示宽高比（考虑旋转和非方形像素），宽高未知时返回 None
+    pub fn display_aspect(&self) -> Option<f64> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let storage = self.width as f64 * self.sar() / self.height as f64;
+        Some(if self.rotation % 180 == 90 { 1.0 / storage } else { storage })
+    }
+
+    /// 是否为竖屏视频（显示宽度小于高度）
+    pub fn is_vertical(&self) -> bool {
+        self.display_aspect().is_some_and(|a| a < 1.0)
+    }
+
+    /// 是否为 HDR 视频（传输特性为 PQ 或 HLG）
+    pub fn is_hdr(&self) -> bool {
+        matches!(
+            self.color_transfer.as_deref(),
+            Some("smpte2084" | "arib-std-b67")
+        )
+    }
+}
+
+/// 按 ffprobe 的 field_order 判断是否为隔行扫描
+///
+/// tt/bb 为顶场/底场优先，tb/bt 为场序与显示顺序相反的隔行；
+/// progressive、unknown 或缺失均视为逐行
+pub fn is_interlaced_field_order(field_order: Option<&str>) -> bool {
+    matches!(field_order, Some("tt" | "bb" | "tb" | "bt"))
 }
 
 /// 音频流信息
@@ -74,8 +171,10 @@ pub struct AudioStream {
     pub sample_rate: u32,
     /// 声道数（1=单声道, 2=立体声, 6=5.1 环绕声）
     pub channels: u32,
-    /// 码率（bps），部分流可能不包含此信息
+    /// 码率（bps），流未标注时从 MKV 统计标签读取或按编码格式估算
     pub bitrate: Option<u64>,
+    /// 码率是否为估算值（按编码格式的常见码率推测）
+    pub bitrate_estimated: bool,
 }
 
 /// 字幕流信息
@@ -93,6 +192,41 @@ pub struct SubtitleStream {
     pub language: Option<String>,
 }
 
+/// 附件流信息
+///
+/// 对应 ffprobe 输出中 codec_type == "attachment" 的流，
+/// 常见于 MKV 内嵌的字体（ASS 字幕渲染所需）和封面图
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentStream {
+    /// 流在容器中的索引位置
+    pub index: u32,
+    /// 编解码器名称（如 "ttf", "otf"，未知类型可能为空）
+    pub codec_name: String,
+    /// 附件原始文件名（存储在 tags 对象中）
+    pub filename: Option<String>,
+    /// 附件 MIME 类型（如 "application/x-truetype-font", "image/jpeg"）
+    pub mimetype: Option<String>,
+}
+
+impl AttachmentStream {
+    /// 是否为字体附件
+    ///
+    /// 依次根据 MIME 类型、编解码器名称和文件扩展名判断
+    pub fn is_font(&self) -> bool {
+        if self.mimetype.as_deref().is_some_and(|m| m.contains("font") || m.contains("opentype")) {
+            return true;
+        }
+        if matches!(self.codec_name.as_str(), "ttf" | "otf") {
+            return true;
+        }
+        self.filename
+            .as_deref()
+            .map(|f| f.to_lowercase())
+            .is_some_and(|f| f.ends_with(".ttf") || f.ends_with(".otf") || f.ends_with(".ttc"))
+    }
+}
+
 /// ffprobe 原始 JSON 输出的根结构
 ///
 /// 仅用于内部反序列化，不暴露给前端。
@@ -103,6 +237,27 @@ pub struct FfprobeOutput {
     pub streams: Option<Vec<FfprobeStream>>,
     /// 容器格式信息
     pub format: Option<FfprobeFormat>,
+    /// 章节列表（需 -show_chapters）
+    #[serde(default)]
+    pub chapters: Vec<FfprobeChapter>,
+}
+
+/// ffprobe 章节的原始 JSON 结构
+#[derive(Deserialize, Debug)]
+pub struct FfprobeChapter {
+    /// 开始时间（字符串形式的秒数）
+    pub start_time: Option<String>,
+    /// 结束时间（字符串形式的秒数）
+    pub end_time: Option<String>,
+    /// 章节标签（标题存放在 title 中）
+    pub tags: Option<FfprobeChapterTags>,
+}
+
+/// ffprobe 章节标签
+#[derive(Deserialize, Debug)]
+pub struct FfprobeChapterTags {
+    /// 章节标题
+    pub title: Option<String>,
 }
 
 /// ffprobe 流的原始 JSON 结构
@@ -114,7 +269,7 @@ pub struct FfprobeStream {
     pub index: Option<u32>,
     /// 编解码器名称
     pub codec_name: Option<String>,
-    /// 流类型标识（"video" / "audio" / "subtitle"）
+    /// 流类型标识（"video" / "audio" / "subtitle" / "attachment"）
     pub codec_type: Option<String>,
     /// 视频宽度
     pub width: Option<u32>,
@@ -134,8 +289,99 @@ pub struct FfprobeStream {
     pub sample_rate: Option<String>,
     /// 声道数
     pub channels: Option<u32>,
+    /// 编码 profile（如 "High", "Main"）
+    pub profile: Option<String>,
+    /// 编码 level（整数形式，如 41）
+    pub level: Option<i32>,
+    /// 像素宽高比（如 "1:1", "32:27"）
+    pub sample_aspect_ratio: Option<String>,
+    /// 色彩范围（"tv" / "pc" / "unknown"）
+    pub color_range: Option<String>,
+    /// 色彩矩阵（如 "bt709" / "bt2020nc" / "unknown"）
+    pub color_space: Option<String>,
+    /// 传输特性（如 "bt709" / "smpte2084" / "arib-std-b67" / "unknown"）
+    pub color_transfer: Option<String>,
+    /// 色域原色（如 "bt709" / "bt2020" / "unknown"）
+    pub color_primaries: Option<String>,
+    /// 场序（"progressive" / "tt" / "bb" / "tb" / "bt" / "unknown"）
+    pub field_order: Option<String>,
     /// 语言标签（存储在 tags 对象中）
     pub tags: Option<FfprobeStreamTags>,
+    /// 流附加数据（如显示矩阵）
+    pub side_data_list: Option<Vec<FfprobeSideData>>,
+    /// 流处置标记（默认流、封面图等）
+    pub disposition: Option<FfprobeDisposition>,
+}
+
+impl FfprobeStream {
+    /// 是否为封面图流（attached_pic）
+    ///
+    /// MP3 和部分 MP4 把封面图存为一条单帧"视频"流，
+    /// 不能当作真正的视频参与滤镜或映射
+    pub fn is_attached_pic(&self) -> bool {
+        self.disposition
+            .as_ref()
+            .is_some_and(|d| d.attached_pic == 1)
+    }
+
+    /// 流自身声明的码率（bps）
+    ///
+    /// 依次取流的 bit_rate、MKV 统计标签 BPS、NUMBER_OF_BYTES 除以时长
+    /// （流时长缺失时用容器时长），都没有时返回 None
+    pub fn declared_bitrate(&self, container_duration: f64) -> Option<u64> {
+        let parse = |v: &Option<String>| v.as_deref().and_then(|b| b.trim().parse::<u64>().ok());
+        let tags = self.tags.as_ref();
+        parse(&self.bit_rate)
+            .or_else(|| tags.and_then(|t| parse(&t.bps)))
+            .or_else(|| {
+                let bytes = tags.and_then(|t| parse(&t.number_of_bytes))?;
+                let duration = self
+                    .duration
+                    .as_deref()
+                    .and_then(|d| d.parse::<f64>().ok())
+                    .unwrap_or(container_duration);
+                (duration > 0.0).then(|| (bytes as f64 * 8.0 / duration) as u64)
+            })
+            .filter(|b| *b > 0)
+    }
+
+    /// 播放时需顺时针旋转的角度，归一化为 0/90/180/270
+    ///
+    /// 旧版 ffmpeg 写入 rotate 标签（顺时针角度），
+    /// 新版改用显示矩阵（rotation 为逆时针角度，如 -90 表示顺时针 90°）
+    pub fn rotation(&self) -> u32 {
+        let from_tag = self
+            .tags
+            .as_ref()
+            .and_then(|t| t.rotate.as_ref())
+            .and_then(|r| r.trim().parse::<f64>().ok());
+        let from_matrix = self.side_data_list.as_ref().and_then(|list| {
+            list.iter()
+                .filter_map(|d| d.rotation)
+                .next()
+                .map(|r| -r)
+        });
+        let degrees = from_tag.or(from_matrix).unwrap_or(0.0);
+        // 四舍五入到最近的 90° 倍数
+        (((degrees / 90.0).round() as i64).rem_euclid(4) * 90) as u32
+    }
+}
+
+/// ffprobe 流处置标记
+#[derive(Deserialize, Debug)]
+pub struct FfprobeDisposition {
+    /// 是否为封面图（1 是 / 0 否）
+    #[serde(default)]
+    pub attached_pic: u8,
+}
+
+/// ffprobe 流附加数据
+#[derive(Deserialize, Debug)]
+pub struct FfprobeSideData {
+    /// 附加数据类型（如 "Display Matrix"）
+    pub side_data_type: Option<String>,
+    /// 显示矩阵的旋转角度（逆时针，度）
+    pub rotation: Option<f64>,
 }
 
 /// ffprobe 流标签
@@ -145,6 +391,18 @@ pub struct FfprobeStream {
 pub struct FfprobeStreamTags {
     /// 语言标签
     pub language: Option<String>,
+    /// 附件文件名（仅附件流）
+    pub filename: Option<String>,
+    /// 附件 MIME 类型（仅附件流）
+    pub mimetype: Option<String>,
+    /// 旋转角度（旧版容器的 rotate 标签，顺时针度数）
+    pub rotate: Option<String>,
+    /// 平均码率（mkvmerge 写入的统计标签，bps）
+    #[serde(rename = "BPS", alias = "bps")]
+    pub bps: Option<String>,
+    /// 流数据总字节数（mkvmerge 写入的统计标签）
+    #[serde(rename = "NUMBER_OF_BYTES")]
+    pub number_of_bytes: Option<String>,
 }
 
 /// ffprobe 容器格式信息
@@ -162,9 +420,68 @@ pub struct FfprobeFormat {
     pub size: Option<String>,
     /// 总码率（字符串形式的 bps 值）
     pub bit_rate: Option<String>,
+    /// 容器级元数据标签（键名大小写随容器而定，如 MKV 为 "TITLE"，MP4 为 "title"）
+    pub tags: Option<std::collections::HashMap<String, String>>,
 }
 
+/// 描述性元数据标签（标题、艺术家等），提取音频时从源文件复制
+pub const DESCRIPTIVE_METADATA_KEYS: &[&str] = &[
+    "title", "artist", "album_artist", "album", "composer", "genre", "date", "track", "comment",
+];
+
 impl FfprobeOutput {
+    /// 媒体时长（秒）
+    ///
+    /// 优先取容器层的 format.duration；部分 MP3/FLAC 只在流层记录时长，
+    /// 此时退回最长的流时长。都没有时返回 0.0
+    pub fn duration(&self) -> f64 {
+        let parse = |d: Option<&String>| d.and_then(|d| d.parse::<f64>().ok()).filter(|d| *d > 0.0);
+        parse(self.format.as_ref().and_then(|f| f.duration.as_ref()))
+            .or_else(|| {
+                self.streams
+                    .iter()
+                    .flatten()
+                    .filter_map(|s| parse(s.duration.as_ref()))
+                    .reduce(f64::max)
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// 容器中的描述性元数据标签（键名统一为小写，按 DESCRIPTIVE_METADATA_KEYS 的顺序）
+    ///
+    /// 编码器、创建时间、MP4 品牌等容器内部标签不包含在内
+    pub fn descriptive_tags(&self) -> Vec<(String, String)> {
+        let Some(tags) = self.format.as_ref().and_then(|f| f.tags.as_ref()) else {
+            return Vec::new();
+        };
+        DESCRIPTIVE_METADATA_KEYS
+            .iter()
+            .filter_map(|&key| {
+                tags.iter()
+                    .find(|(k, v)| k.eq_ignore_ascii_case(key) && !v.trim().is_empty())
+                    .map(|(_, value)| (key.to_string(), value.clone()))
+            })
+            .collect()
+    }
+
+    /// 主视频流：第一条不是封面图的视频流
+    pub fn main_video_stream(&self) -> Option<&FfprobeStream> {
+        self.streams
+            .iter()
+            .flatten()
+            .find(|s| s.codec_type.as_deref() == Some("video") && !s.is_attached_pic())
+    }
+
+    /// 是否为纯音频文件：有音频流、没有主视频流（封面图不算视频）
+    pub fn is_audio_only(&self) -> bool {
+        self.main_video_stream().is_none()
+            && self
+                .streams
+                .iter()
+                .flatten()
+                .any(|s| s.codec_type.as_deref() == Some("audio"))
+    }
+
     /// 将 ffprobe 原始输出转换为应用内部使用的 MediaInfo
     ///
     /// 解析所有流信息，按类型分类为视频/音频/字幕流，
@@ -184,28 +501,38 @@ impl FfprobeOutput {
         let format_name = format
             .and_then(|f| f.format_name.clone())
             .unwrap_or_default();
-        let duration = format
-            .and_then(|f| f.duration.as_ref())
-            .and_then(|d| d.parse::<f64>().ok())
-            .unwrap_or(0.0);
+        let duration = self.duration();
         let file_size = format
             .and_then(|f| f.size.as_ref())
             .and_then(|s| s.parse::<u64>().ok())
             .unwrap_or(0);
+        // 容器未标注总码率时按文件大小和时长计算平均码率
         let bitrate = format
             .and_then(|f| f.bit_rate.as_ref())
             .and_then(|b| b.parse::<u64>().ok())
-            .unwrap_or(0);
+            .filter(|b| *b > 0)
+            .unwrap_or_else(|| {
+                if duration > 0.0 {
+                    (file_size as f64 * 8.0 / duration) as u64
+                } else {
+                    0
+                }
+            });
 
-        // 按 codec_type 将流分类为视频/音频/字幕
+        // 按 codec_type 将流分类为视频/音频/字幕/附件
         let mut video_streams = Vec::new();
         let mut audio_streams = Vec::new();
         let mut subtitle_streams = Vec::new();
+        let mut attachment_streams = Vec::new();
+        let mut has_cover_art = false;
 
         if let Some(streams) = &self.streams {
             for stream in streams {
                 let codec_type = stream.codec_type.as_deref().unwrap_or("");
                 match codec_type {
+                    "video" if stream.is_attached_pic() => {
+                        has_cover_art = true;
+                    }
                     "video" => {
                         video_streams.push(VideoStream {
                             index: stream.index.unwrap_or(0),
@@ -215,13 +542,41 @@ impl FfprobeOutput {
                             frame_rate: parse_frame_rate(
                                 stream.r_frame_rate.as_deref().unwrap_or("0/1"),
                             ),
-                            bitrate: stream.bit_rate.as_ref().and_then(|b| b.parse().ok()),
+                            bitrate: stream.declared_bitrate(duration),
+                            bitrate_estimated: false,
                             pix_fmt: stream.pix_fmt.clone().unwrap_or_default(),
                             nb_frames: stream
                                 .nb_frames
                                 .as_ref()
                                 .and_then(|n| n.parse().ok()),
                             duration: stream.duration.as_ref().and_then(|d| d.parse().ok()),
+                            profile: stream.profile.clone(),
+                            level: stream.level,
+                            sample_aspect_ratio: stream.sample_aspect_ratio.clone(),
+                            color_range: stream
+                                .color_range
+                                .clone()
+                                .filter(|r| r != "unknown"),
+                            color_space: stream
+                                .color_space
+                                .clone()
+                                .filter(|c| c != "unknown"),
+                            color_transfer: stream
+                                .color_transfer
+                                .clone()
+                                .filter(|c| c != "unknown"),
+                            color_primaries: stream
+                                .color_primaries
+                                .clone()
+                                .filter(|c| c != "unknown"),
+                            rotation: stream.rotation(),
+                            field_order: stream
+                                .field_order
+                                .clone()
+                                .filter(|f| f != "unknown"),
+                            is_interlaced: is_interlaced_field_order(
+                                stream.field_order.as_deref(),
+                            ),
                         });
                     }
                     "audio" => {
@@ -234,7 +589,8 @@ impl FfprobeOutput {
                                 .and_then(|s| s.parse().ok())
                                 .unwrap_or(0),
                             channels: stream.channels.unwrap_or(0),
-                            bitrate: stream.bit_rate.as_ref().and_then(|b| b.parse().ok()),
+                            bitrate: stream.declared_bitrate(duration),
+                            bitrate_estimated: false,
                         });
                     }
                     "subtitle" => {
@@ -244,11 +600,40 @@ impl FfprobeOutput {
                             language: stream.tags.as_ref().and_then(|t| t.language.clone()),
                         });
                     }
+                    "attachment" => {
+                        attachment_streams.push(AttachmentStream {
+                            index: stream.index.unwrap_or(0),
+                            codec_name: stream.codec_name.clone().unwrap_or_default(),
+                            filename: stream.tags.as_ref().and_then(|t| t.filename.clone()),
+                            mimetype: stream.tags.as_ref().and_then(|t| t.mimetype.clone()),
+                        });
+                    }
                     _ => {}
                 }
             }
         }
 
+        estimate_missing_bitrates(&mut video_streams, &mut audio_streams, bitrate);
+
+        let mut chapters: Vec<Chapter> = self
+            .chapters
+            .iter()
+            .filter_map(|chapter| {
+                let start = chapter.start_time.as_ref()?.parse::<f64>().ok()?;
+                let end = chapter.end_time.as_ref()?.parse::<f64>().ok()?;
+                Some(Chapter {
+                    start,
+                    end,
+                    title: chapter
+                        .tags
+                        .as_ref()
+                        .and_then(|t| t.title.clone())
+                        .unwrap_or_default(),
+                })
+            })
+            .collect();
+        chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+
         MediaInfo {
             file_path: file_path.to_string(),
             file_name,
@@ -259,10 +644,55 @@ impl FfprobeOutput {
             video_streams,
             audio_streams,
             subtitle_streams,
+            attachment_streams,
+            has_cover_art,
+            chapters,
         }
     }
 }
 
+/// 为没有码率信息的流估算码率
+///
+/// 音频按编码格式的常见码率推测（PCM 按采样率 × 声道 × 位深精确计算）；
+/// 只有一条视频流且码率未知时，用容器总码率减去全部音频码率作为视频码率
+fn estimate_missing_bitrates(
+    video_streams: &mut [VideoStream],
+    audio_streams: &mut [AudioStream],
+    total_bitrate: u64,
+) {
+    for audio in audio_streams.iter_mut().filter(|a| a.bitrate.is_none()) {
+        audio.bitrate = typical_audio_bitrate(&audio.codec_name, audio.sample_rate, audio.channels);
+        audio.bitrate_estimated = audio.bitrate.is_some();
+    }
+
+    if let [video] = video_streams {
+        if video.bitrate.is_none() && total_bitrate > 0 {
+            let audio_total: u64 = audio_streams.iter().filter_map(|a| a.bitrate).sum();
+            video.bitrate = Some(total_bitrate.saturating_sub(audio_total));
+            video.bitrate_estimated = true;
+        }
+    }
+}
+
+/// 按编码格式推测音频码率（bps），未知编码返回 None
+fn typical_audio_bitrate(codec: &str, sample_rate: u32, channels: u32) -> Option<u64> {
+    let channels = u64::from(channels.max(1));
+    let pcm = |bits: u64| u64::from(sample_rate) * channels * bits;
+    match codec {
+        "pcm_s16le" | "pcm_s16be" => Some(pcm(16)),
+        "pcm_s24le" | "pcm_s24be" => Some(pcm(24)),
+        "pcm_s32le" | "pcm_f32le" => Some(pcm(32)),
+        // 无损压缩通常约为 PCM 的 60%
+        "flac" | "alac" => Some(pcm(16) * 6 / 10),
+        "aac" | "vorbis" => Some(64_000 * channels),
+        "opus" => Some(48_000 * channels),
+        "mp3" | "mp2" => Some(if channels > 1 { 192_000 } else { 96_000 }),
+        "ac3" | "eac3" => Some(if channels > 2 { 448_000 } else { 192_000 }),
+        "dts" => Some(1_536_000),
+        _ => None,
+    }
+}
+
 /// 解析 ffprobe 帧率分数字符串为浮点数
 ///
 /// ffprobe 输出帧率为分数形式（如 "30/1", "30000/1001"），
@@ -273,7 +703,7 @@ impl FfprobeOutput {
 ///
 /// # 返回
 /// 帧率浮点数值，解析失败返回 0.0
-fn parse_frame_rate(rate_str: &str) -> f64 {
+pub(crate) fn parse_frame_rate(rate_str: &str) -> f64 {
     let parts: Vec<&str> = rate_str.split('/').collect();
     if parts.len() == 2 {
         let num = parts[0].parse::<f64>().unwrap_or(0.0);
@@ -285,3 +715,209 @@ fn parse_frame_rate(rate_str: &str) -> f64 {
     // 如果不是分数形式，尝试直接解析为浮点数
     rate_str.parse::<f64>().unwrap_or(0.0)
 }
+
+/// 解析 ffprobe 像素宽高比字符串为浮点数
+///
+/// ffprobe 输出 SAR 为 "num:den" 形式（如 "32:27"），
+/// 未知时输出 "0:1"，此时返回 None
+///
+/// # 参数
+/// - `sar_str` - 像素宽高比字符串
+///
+/// # 返回
+/// 像素宽高比浮点值，无效时返回 None
+pub fn parse_sample_aspect_ratio(sar_str: &str) -> Option<f64> {
+    let (num, den) = sar_str.split_once(':')?;
+    let num = num.trim().parse::<f64>().ok()?;
+    let den = den.trim().parse::<f64>().ok()?;
+    if num > 0.0 && den > 0.0 {
+        Some(num / den)
+    } else {
+        None
+    }
+}
+
+/// 构造测试用的源视频流（未列出的字段取常见 1080p H.264 值）
+///
+/// # 参数
+/// - `overrides` - 覆盖默认值的字段（camelCase 键）
+#[cfg(test)]
+pub(crate) fn video_stream(overrides: serde_json::Value) -> VideoStream {
+    let mut value = serde_json::json!({
+        "index": 0,
+        "codecName": "h264",
+        "width": 1920,
+        "height": 1080,
+        "frameRate": 30.0,
+        "bitrateEstimated": false,
+        "pixFmt": "yuv420p",
+        "profile": "High",
+        "level": 40,
+        "rotation": 0,
+        "isInterlaced": false,
+    });
+    for (key, field) in overrides.as_object().unwrap() {
+        value[key] = field.clone();
+    }
+    serde_json::from_value(value).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sample_aspect_ratio() {
+        assert_eq!(parse_sample_aspect_ratio("1:1"), Some(1.0));
+        assert_eq!(parse_sample_aspect_ratio("3:2"), Some(1.5));
+        assert_eq!(parse_sample_aspect_ratio(" 4 : 3 "), Some(4.0 / 3.0));
+        assert_eq!(parse_sample_aspect_ratio("0:1"), None);
+        assert_eq!(parse_sample_aspect_ratio("16/9"), None);
+        assert_eq!(parse_sample_aspect_ratio("n/a"), None);
+    }
+
+    /// 构造指定编码宽高、旋转角度和 SAR 的视频流
+    fn stream(width: u32, height: u32, rotation: u32, sar: Option<&str>) -> VideoStream {
+        video_stream(serde_json::json!({
+            "width": width,
+            "height": height,
+            "sampleAspectRatio": sar,
+            "rotation": rotation,
+        }))
+    }
+
+    #[test]
+    fn probe_color_range_drops_unknown() {
+        let probe: FfprobeOutput = serde_json::from_value(serde_json::json!({
+            "streams": [
+                { "index": 0, "codec_type": "video", "codec_name": "h264",
+                  "width": 1920, "height": 1080, "color_range": "pc" },
+                { "index": 1, "codec_type": "video", "codec_name": "h264",
+                  "width": 1920, "height": 1080, "color_range": "unknown" },
+            ],
+            "format": { "duration": "10.0" },
+        }))
+        .unwrap();
+        let info = probe.to_media_info("/tmp/in.mp4");
+        assert_eq!(info.video_streams[0].color_range.as_deref(), Some("pc"));
+        assert_eq!(info.video_streams[1].color_range, None);
+    }
+
+    /// 解析单条 ffprobe 流
+    fn probe_stream(value: serde_json::Value) -> FfprobeStream {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn rotation_from_display_matrix_is_clockwise() {
+        let matrix = |rotation: f64| {
+            probe_stream(serde_json::json!({
+                "side_data_list": [
+                    { "side_data_type": "Display Matrix", "rotation": rotation },
+                ],
+            }))
+        };
+        assert_eq!(matrix(-90.0).rotation(), 90);
+        assert_eq!(matrix(90.0).rotation(), 270);
+        assert_eq!(matrix(180.0).rotation(), 180);
+        assert_eq!(matrix(-89.6).rotation(), 90);
+    }
+
+    #[test]
+    fn rotation_tag_takes_precedence_over_display_matrix() {
+        let stream = probe_stream(serde_json::json!({
+            "tags": { "rotate": "270" },
+            "side_data_list": [{ "rotation": -90.0 }],
+        }));
+        assert_eq!(stream.rotation(), 270);
+        assert_eq!(probe_stream(serde_json::json!({})).rotation(), 0);
+    }
+
+    #[test]
+    fn cover_art_is_not_listed_as_video_stream() {
+        let probe: FfprobeOutput = serde_json::from_value(serde_json::json!({
+            "streams": [
+                { "index": 0, "codec_type": "audio", "codec_name": "mp3",
+                  "sample_rate": "44100", "channels": 2 },
+                { "index": 1, "codec_type": "video", "codec_name": "mjpeg",
+                  "width": 600, "height": 600, "disposition": { "attached_pic": 1 } },
+            ],
+            "format": { "duration": "180.0" },
+        }))
+        .unwrap();
+        let info = probe.to_media_info("/tmp/song.mp3");
+        assert!(info.has_cover_art);
+        assert!(info.video_streams.is_empty());
+        assert_eq!(info.audio_streams.len(), 1);
+    }
+
+    #[test]
+    fn rotated_phone_video_is_vertical() {
+        for rotation in [90, 270] {
+            let video = stream(1920, 1080, rotation, None);
+            assert_eq!(video.display_dimensions(), (1080, 1920));
+            assert!(video.is_vertical(), "rotation {}", rotation);
+        }
+        for rotation in [0, 180] {
+            let video = stream(1920, 1080, rotation, None);
+            assert_eq!(video.display_dimensions(), (1920, 1080));
+            assert!(!video.is_vertical(), "rotation {}", rotation);
+        }
+    }
+
+    #[test]
+    fn display_aspect_accounts_for_sar_and_rotation() {
+        let anamorphic = stream(1440, 1080, 0, Some("4:3"));
+        assert_eq!(anamorphic.display_aspect(), Some(1440.0 * 4.0 / 3.0 / 1080.0));
+        let rotated = stream(1440, 1080, 90, Some("4:3"));
+        assert_eq!(rotated.display_aspect(), Some(1080.0 / (1440.0 * 4.0 / 3.0)));
+        assert_eq!(stream(0, 0, 0, None).display_aspect(), None);
+        assert!(!stream(0, 0, 90, None).is_vertical());
+    }
+
+
+    #[test]
+    fn interlaced_fixture_is_detected_from_field_order() {
+        let probe: FfprobeOutput = serde_json::from_value(serde_json::json!({
+            "streams": [
+                { "index": 0, "codec_type": "video", "codec_name": "mpeg2video",
+                  "width": 720, "height": 576, "field_order": "tt" },
+                { "index": 1, "codec_type": "video", "codec_name": "h264",
+                  "width": 1920, "height": 1080, "field_order": "progressive" },
+                { "index": 2, "codec_type": "video", "codec_name": "h264",
+                  "width": 1920, "height": 1080, "field_order": "unknown" },
+            ],
+            "format": { "duration": "10.0" },
+        }))
+        .unwrap();
+        let info = probe.to_media_info("/tmp/dv.mpg");
+        let interlaced = &info.video_streams[0];
+        assert!(interlaced.is_interlaced);
+        assert_eq!(interlaced.field_order.as_deref(), Some("tt"));
+        assert!(!info.video_streams[1].is_interlaced);
+        assert!(!info.video_streams[2].is_interlaced);
+        assert_eq!(info.video_streams[2].field_order, None);
+    }
+
+
+    #[test]
+    fn duration_falls_back_to_longest_stream_without_format_duration() {
+        let probe: FfprobeOutput = serde_json::from_value(serde_json::json!({
+            "streams": [
+                { "index": 0, "codec_type": "audio", "codec_name": "flac",
+                  "sample_rate": "44100", "channels": 2, "duration": "183.25" },
+                { "index": 1, "codec_type": "video", "codec_name": "mjpeg",
+                  "duration": "0.04", "disposition": { "attached_pic": 1 } },
+                { "index": 2, "codec_type": "data", "duration": "N/A" },
+            ],
+            "format": { "format_name": "flac" },
+        }))
+        .unwrap();
+        assert_eq!(probe.duration(), 183.25);
+        assert_eq!(probe.to_media_info("/tmp/song.flac").duration, 183.25);
+
+        let empty: FfprobeOutput =
+            serde_json::from_value(serde_json::json!({ "format": {} })).unwrap();
+        assert_eq!(empty.duration(), 0.0);
+    }
+}
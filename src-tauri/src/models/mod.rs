@@ -3,6 +3,8 @@
 /// 导出所有数据结构定义，包括媒体信息、任务状态、
 /// 功能参数预设和应用设置
 
+/// command 错误类型（结构化错误）
+pub mod error;
 /// 媒体文件信息（ffprobe 解析结果）
 pub mod media;
 /// 预设参数结构体（各功能的 command 参数类型）
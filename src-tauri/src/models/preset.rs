@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// 定义格式转换所需的全部参数，包括输入/输出路径、
 /// 目标格式、编码器选择和质量控制等
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ConvertParams {
     /// 输入文件完整路径
@@ -30,12 +30,99 @@ pub struct ConvertParams {
     pub quality: Option<u32>,
     /// 编码速度预设（如 "medium", "slow", "fast"）
     pub preset: Option<String>,
-    /// 是否启用 VideoToolbox 硬件加速
+    /// 是否启用 VideoToolbox 硬件加速（libx265 映射为 hevc_videotoolbox，其余为 h264_videotoolbox），
+    /// 本机不支持对应硬件编码器时回退到软件编码
     pub hardware_accel: Option<bool>,
+    /// 音频码率（如 "192k"），未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+    /// 音频采样率（Hz，如 44100 / 48000），None 时沿用源音频，音频流复制时忽略
+    pub audio_sample_rate: Option<u32>,
+    /// 视频目标码率（如 "4M"），软件编码时代替 CRF，硬件编码时未指定则为 5M
+    pub video_bitrate: Option<String>,
+    /// 是否两遍编码（需指定 video_bitrate，仅 libx264 / libx265 / libvpx-vp9）
+    pub two_pass: Option<bool>,
+    /// 输出色彩范围标记（"tv" 有限范围 16-235 / "pc" 全范围 0-255），None 时沿用源视频
+    pub color_range: Option<String>,
+    /// 输出像素格式（"yuv420p" / "yuv420p10le" / "yuv422p10le" / "yuva420p"），
+    /// None 时由编码器决定（10-bit 源使用 H.264 VideoToolbox 时自动降为 yuv420p）
+    pub pixel_format: Option<String>,
+    /// 是否保留全局元数据（标题、创建时间等）、章节和流语言标签，默认 true
+    pub preserve_metadata: Option<bool>,
+    /// 是否去隔行（yadif），None 时按源视频场序自动判断，重编码时生效
+    pub deinterlace: Option<bool>,
+    /// 是否按源视频旋转元数据校正画面方向（默认 true）：重编码时用 transpose 转正并清除旋转标记，
+    /// 关闭时保留原始画面和旋转标记；流复制时始终保留旋转标记
+    pub auto_rotate: Option<bool>,
+    /// 是否按流媒体分发要求编码（见 presets::STREAMING_KEYFRAME_SECONDS）：固定 GOP、closed GOP、
+    /// 禁用场景切换关键帧、yuv420p、faststart，仅支持重编码输出 MP4/MOV
+    pub streaming: Option<bool>,
+    /// 固定关键帧间隔（帧数，写入 -g / -keyint_min），streaming 时未指定则按源帧率每 2 秒一个关键帧
+    pub gop_size: Option<u32>,
+    /// 要保留的音轨（在 media_info 的 audio_streams 中的位置，对应 `0:a:N`，按列表顺序输出），
+    /// None 时由 ffmpeg 默认只选一路音频
+    #[serde(default)]
+    pub audio_stream_indexes: Option<Vec<u32>>,
+    /// 是否保留全部音轨和字幕轨（未指定 audio_stream_indexes 时输出全部音轨）
+    #[serde(default)]
+    pub keep_all_streams: Option<bool>,
     /// 额外的 ffmpeg 命令行参数
     pub extra_args: Option<Vec<String>>,
 }
 
+/// 多分辨率输出中的一档
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionVariant {
+    /// 输出高度（像素），宽度按源宽高比计算
+    pub height: u32,
+    /// 输出文件名后缀（如 "_720p"），为空时使用 "_<高度>p"
+    pub suffix: Option<String>,
+}
+
+/// 多分辨率输出参数
+///
+/// 一次解码、缩放为多个分辨率并分别输出，输出文件名为 `<源文件名><后缀>.<格式>`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiResolutionParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出目录
+    pub output_dir: String,
+    /// 输出分辨率列表
+    pub variants: Vec<ResolutionVariant>,
+    /// 输出格式（"mp4" / "mov" / "mkv"，默认 "mp4"）
+    pub output_format: Option<String>,
+    /// 视频质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 编码速度预设，未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 音频码率，未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+}
+
+/// 多分辨率输出中单个文件的结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantOutput {
+    /// 输出高度（像素）
+    pub height: u32,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 输出文件大小（字节）
+    pub output_size: u64,
+}
+
+/// 多分辨率输出结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiResolutionResult {
+    /// 任务 ID
+    pub task_id: String,
+    /// 各档输出（与请求中的 variants 顺序一致）
+    pub outputs: Vec<VariantOutput>,
+}
+
 // ============================================================
 // 视频压缩参数
 // ============================================================
@@ -43,7 +130,7 @@ pub struct ConvertParams {
 /// 压缩模式枚举
 ///
 /// 三种压缩策略：按目标大小、按压缩比例、按质量等级
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum CompressMode {
     /// 按目标文件大小压缩（自动反算码率）
@@ -57,7 +144,7 @@ pub enum CompressMode {
 /// 视频压缩参数
 ///
 /// 支持三种压缩模式，根据 mode 字段选择使用不同的参数组合
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CompressParams {
     /// 输入文件路径
@@ -72,10 +159,56 @@ pub struct CompressParams {
     pub compress_ratio: Option<f64>,
     /// 质量等级（1-10，10 最高），仅 ByQuality 模式使用
     pub quality_level: Option<u32>,
+    /// 视频编码器（"libx264" / "libx265"，默认 "libx264"），
+    /// 启用硬件加速时映射为对应的 VideoToolbox 编码器
+    pub video_codec: Option<String>,
     /// 编码速度预设
     pub preset: Option<String>,
     /// 是否启用硬件加速
     pub hardware_accel: Option<bool>,
+    /// 目标发布平台（"social" / "wechat" / "bilibili"），
+    /// 设置后按平台约束限制帧率、分辨率、profile/level 和像素格式
+    pub platform: Option<String>,
+    /// 音频码率（如 "192k"），未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+    /// BySize 模式下 -maxrate 相对目标码率的倍数（默认 1.5，不小于 1.0），仅软件编码
+    pub maxrate_multiplier: Option<f64>,
+    /// BySize 模式下 -bufsize 相对目标码率的倍数（默认 2.0，严格 CBR 时默认 1.0），仅软件编码
+    pub bufsize_multiplier: Option<f64>,
+    /// BySize 模式下是否严格恒定码率（minrate = maxrate = 目标码率），
+    /// 用于有硬性码率上限的推流场景，仅软件编码
+    pub strict_cbr: Option<bool>,
+    /// 是否去隔行（yadif），None 时按源视频场序自动判断
+    pub deinterlace: Option<bool>,
+    /// 画面内容类型提示，决定编码器 tune 和关键帧间隔，未指定时使用编码器默认值
+    pub content_type: Option<ContentType>,
+    /// 两遍编码选项（与其他字段平铺在同一层）
+    #[serde(flatten)]
+    pub two_pass_options: TwoPassParams,
+}
+
+/// 压缩时的画面内容类型提示
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentType {
+    /// 实拍影片（tune film）
+    Film,
+    /// 动画（tune animation：大面积平涂色块、锐利边缘）
+    Animation,
+    /// 录屏/教程（tune stillimage + 长关键帧间隔：画面大部分时间静止）
+    Screencast,
+}
+
+/// 两遍编码选项
+///
+/// 第一遍分析视频复杂度并写入统计文件，第二遍按统计分配码率，
+/// 输出大小比单遍按码率编码准确得多。仅 BySize 模式的软件编码生效
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoPassParams {
+    /// 是否两遍编码
+    #[serde(default)]
+    pub two_pass: bool,
 }
 
 // ============================================================
@@ -85,7 +218,7 @@ pub struct CompressParams {
 /// 时间片段
 ///
 /// 定义一个裁剪片段的起止时间点（以秒为单位）
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TimeSegment {
     /// 起始时间（秒）
@@ -97,7 +230,7 @@ pub struct TimeSegment {
 /// 视频裁剪参数
 ///
 /// 支持单片段和多片段裁剪，可选精确切割（重编码）或快速切割（copy）
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TrimParams {
     /// 输入文件路径
@@ -110,6 +243,71 @@ pub struct TrimParams {
     pub precise_cut: bool,
     /// 多片段时是否合并为一个输出文件
     pub merge_segments: bool,
+    /// 音频码率（如 "192k"），仅精确切割时生效，未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+    /// 精确切割时的质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 精确切割时的速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 切点精度模式，未指定时为 Fast
+    pub accuracy: Option<TrimAccuracy>,
+}
+
+impl TrimParams {
+    /// 是否需要重新编码（精确切割或帧级精度模式）
+    pub fn reencodes(&self) -> bool {
+        self.precise_cut || matches!(self.accuracy, Some(TrimAccuracy::Frame))
+    }
+}
+
+/// 裁剪切点精度模式
+///
+/// Fast 把 -ss 放在 -i 之前快速定位并用 -t 限定时长，速度快，
+/// 但定位依赖关键帧索引，GOP 边界附近的结束点可能偏差几帧；
+/// Frame 把 -ss 放在 -i 之后并用 -to 指定绝对结束时间，从头解码到起点，
+/// 起止点都精确到帧，代价是起点越靠后越慢（始终重新编码）
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TrimAccuracy {
+    /// 输入端定位 + 时长（默认）
+    #[default]
+    Fast,
+    /// 输出端定位 + 绝对结束时间，帧级精度
+    Frame,
+}
+
+/// 去除片头黑场参数
+///
+/// 先用 blackdetect 找到片头黑场的结束位置，再从该位置裁剪到视频末尾
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimLeadingBlackParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 是否使用精确切割（false 时从黑场结束后的第一个关键帧开始流复制）
+    pub precise_cut: bool,
+    /// 像素亮度阈值（0.0-1.0，默认 0.10），低于此值的像素视为黑色
+    pub black_threshold: Option<f64>,
+    /// 最短黑场时长（秒，默认 0.5），更短的暗场不视为黑场
+    pub min_black_duration: Option<f64>,
+    /// 音频码率，仅精确切割时生效，未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+    /// 精确切割时的质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 精确切割时的速度预设，未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+/// 去除片头黑场结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimLeadingBlackResult {
+    /// 任务 ID
+    pub task_id: String,
+    /// 实际去除的时长（秒）
+    pub trimmed_seconds: f64,
 }
 
 // ============================================================
@@ -119,7 +317,7 @@ pub struct TrimParams {
 /// 转场效果配置
 ///
 /// 定义两个视频片段之间的过渡效果
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TransitionConfig {
     /// 转场类型（如 "fade", "wipeleft", "dissolve", "slideright"）
@@ -131,7 +329,7 @@ pub struct TransitionConfig {
 /// 视频合并参数
 ///
 /// 将多个视频文件合并为一个，支持可选的转场效果和参数归一化
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MergeParams {
     /// 待合并的输入文件路径列表（按照期望的播放顺序排列）
@@ -146,6 +344,84 @@ pub struct MergeParams {
     pub target_resolution: Option<String>,
     /// 目标帧率，仅在 normalize=true 时使用
     pub target_fps: Option<f64>,
+    /// 音频码率（如 "192k"），重编码合并时生效，未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 仅音频交叉淡化时长（秒）：视频硬切，前一片段尾部音频在下一片段开始时淡出。
+    /// 设置了 transition 时忽略（转场本身已包含音频交叉淡化）
+    pub audio_only_crossfade: Option<f64>,
+    /// 输入片段的最短时长（秒，默认 0.5），更短的片段会在合并前被拒绝
+    pub min_clip_duration: Option<f64>,
+    /// 是否对合并后的音频做 EBU R128 响度标准化（直接拼接模式下音频改为重编码）
+    pub normalize_loudness: Option<bool>,
+    /// 响度标准化的综合响度目标（LUFS，-70 ~ -5，默认 -16）
+    pub loudness_target_lufs: Option<f64>,
+    /// 响度标准化的响度范围目标（LU，1 ~ 50，默认 11）
+    pub loudness_range: Option<f64>,
+    /// 响度标准化的真峰值上限（dBTP，-9 ~ 0，默认 -1.5）
+    pub true_peak: Option<f64>,
+    /// 归一化合并时输出的音频声道数（1-8），None 时取各输入的最大声道数（至少为立体声），
+    /// 避免 5.1 等环绕声输入被缩混为立体声
+    pub target_channels: Option<u32>,
+}
+
+/// 片头片尾拼接参数
+///
+/// 片头、片尾通常在多个视频间复用，统一缩放到正片的分辨率和帧率后按顺序拼接
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IntroOutroParams {
+    /// 片头视频路径
+    pub intro: Option<String>,
+    /// 正片视频路径（决定输出的分辨率和帧率）
+    pub main: String,
+    /// 片尾视频路径
+    pub outro: Option<String>,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 音频码率（如 "192k"），未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// 幻灯片参数
+// ============================================================
+
+/// 图片幻灯片参数
+///
+/// 把一组图片按顺序拼成视频，每张图片显示固定时长，可选转场和背景音乐
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SlideshowParams {
+    /// 图片路径列表（按播放顺序排列）
+    pub image_paths: Vec<String>,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 每张图片的显示时长（秒）
+    pub frame_duration: f64,
+    /// 输出帧率
+    pub fps: u32,
+    /// 输出宽度（像素，偶数），图片按比例缩放后补黑边
+    pub width: u32,
+    /// 输出高度（像素，偶数）
+    pub height: u32,
+    /// 图片之间的转场效果（None 表示硬切）
+    pub transition: Option<TransitionConfig>,
+    /// 背景音乐路径，视频和音乐以较短者为准
+    pub audio_path: Option<String>,
+    /// 音频码率（如 "192k"），未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+    /// 编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
 }
 
 // ============================================================
@@ -155,7 +431,7 @@ pub struct MergeParams {
 /// 音频处理模式枚举
 ///
 /// 四种音频处理策略
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum AudioMode {
     /// 从视频中提取音频（输出纯音频文件）
@@ -172,7 +448,7 @@ pub enum AudioMode {
 ///
 /// 根据 mode 字段选择不同的处理逻辑，
 /// 不同模式使用不同的参数子集
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioParams {
     /// 输入文件路径
@@ -185,24 +461,76 @@ pub struct AudioParams {
     pub output_format: Option<String>,
     /// 替换用的音频文件路径，仅 Replace 模式
     pub replace_audio_path: Option<String>,
+    /// 新音频与视频时长不一致时的处理方式，仅 Replace 模式：
+    /// "shortest"（取较短者）/ "pad_silence"（补静音或截断到视频时长，默认）/ "loop"（循环音频）
+    pub fit_mode: Option<String>,
+    /// 音频码率（如 "192k"），Replace/Adjust 模式生效，未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
     /// 音量倍数（如 2.0 为加倍, 0.5 为减半），仅 Adjust 模式
     pub volume: Option<f64>,
     /// 音量调整（dB 值，如 3.0 为 +3dB），仅 Adjust 模式
     pub volume_db: Option<f64>,
     /// 是否启用 EBU R128 响度标准化，仅 Adjust 模式
     pub normalize: Option<bool>,
+    /// 响度标准化的综合响度目标（LUFS，-70 ~ -5，默认 -16）
+    pub loudness_target_lufs: Option<f64>,
+    /// 响度标准化的响度范围目标（LU，1 ~ 50，默认 11）
+    pub loudness_range: Option<f64>,
+    /// 响度标准化的真峰值上限（dBTP，-9 ~ 0，默认 -1.5）
+    pub true_peak: Option<f64>,
     /// 淡入时长（秒），仅 Adjust 模式
     pub fade_in: Option<f64>,
     /// 淡出时长（秒），仅 Adjust 模式
     pub fade_out: Option<f64>,
 }
 
+/// 多格式音频提取参数
+///
+/// 一次解码同一条音轨，同时编码输出为多个格式，
+/// 输出文件名为 `<源文件名><后缀>.<格式>`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiFormatAudioParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出目录（空字符串表示与输入同目录）
+    pub output_dir: String,
+    /// 输出格式列表（"mp3" / "aac" / "m4a" / "wav" / "flac"），不可重复
+    pub formats: Vec<String>,
+    /// 音轨序号（第几条音频流，从 0 开始，默认 0）
+    pub audio_track: Option<u32>,
+    /// 输出文件名后缀（默认 "_audio"）
+    pub suffix: Option<String>,
+}
+
+/// 多格式音频提取中单个文件的结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioFormatOutput {
+    /// 输出格式
+    pub format: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 输出文件大小（字节）
+    pub output_size: u64,
+}
+
+/// 多格式音频提取结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiFormatAudioResult {
+    /// 任务 ID
+    pub task_id: String,
+    /// 各格式输出（与请求中的 formats 顺序一致）
+    pub outputs: Vec<AudioFormatOutput>,
+}
+
 // ============================================================
 // 水印参数
 // ============================================================
 
 /// 水印类型枚举
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum WatermarkType {
     /// 图片水印（PNG/JPG 等图片叠加到视频上）
@@ -214,7 +542,7 @@ pub enum WatermarkType {
 /// 水印位置枚举（九宫格预设位置）
 ///
 /// 对应 ffmpeg overlay 滤镜的坐标计算公式
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum WatermarkPosition {
     /// 左上角
@@ -241,7 +569,7 @@ pub enum WatermarkPosition {
 ///
 /// 支持图片水印和文字水印两种类型，通过 watermark_type 区分。
 /// 图片水印使用 overlay 滤镜，文字水印使用 drawtext 滤镜
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct WatermarkParams {
     /// 输入视频文件路径
@@ -277,6 +605,276 @@ pub struct WatermarkParams {
     pub offset_x: Option<i32>,
     /// Y 方向额外偏移（像素，正值向下）
     pub offset_y: Option<i32>,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 是否去隔行（yadif），None 时按源视频场序自动判断
+    pub deinterlace: Option<bool>,
+}
+
+/// 去除台标参数
+///
+/// 用 delogo 滤镜以周围像素插值覆盖指定矩形区域（坐标基于显示方向的画面）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveLogoParams {
+    /// 输入视频文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 区域左上角 X 坐标（像素）
+    pub x: u32,
+    /// 区域左上角 Y 坐标（像素）
+    pub y: u32,
+    /// 区域宽度（像素）
+    pub width: u32,
+    /// 区域高度（像素）
+    pub height: u32,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// 画面裁剪参数
+// ============================================================
+
+/// 画面裁剪参数
+///
+/// 用 crop 滤镜截取任意矩形区域（坐标基于显示方向的画面），如去除黑边或截取画面一角
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CropParams {
+    /// 输入视频文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 裁剪区域左上角 X 坐标（像素）
+    pub x: u32,
+    /// 裁剪区域左上角 Y 坐标（像素）
+    pub y: u32,
+    /// 裁剪区域宽度（像素，奇数时向下取偶）
+    pub width: u32,
+    /// 裁剪区域高度（像素，奇数时向下取偶）
+    pub height: u32,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// 缩放平移（Ken Burns）参数
+// ============================================================
+
+/// 画面中的矩形区域（各值为占画面宽/高的比例，0.0-1.0）
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoomRect {
+    /// 左上角 X（占画面宽度的比例）
+    pub x: f64,
+    /// 左上角 Y（占画面高度的比例）
+    pub y: f64,
+    /// 宽度（占画面宽度的比例）
+    pub width: f64,
+    /// 高度（占画面高度的比例）
+    pub height: f64,
+}
+
+/// 缩放平移的缓动曲线
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoomEasing {
+    /// 匀速
+    Linear,
+    /// 慢进慢出（余弦曲线）
+    EaseInOut,
+}
+
+/// 缩放平移（Ken Burns）参数
+///
+/// 在片段时长内从起始区域平滑过渡到结束区域，区域内容缩放到固定的输出分辨率
+/// （源画面尺寸）。图片输入用 zoompan 滤镜生成视频，只支持等比缩放（按区域宽度计算）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoomPanParams {
+    /// 输入视频或图片路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 起始区域
+    pub start_rect: ZoomRect,
+    /// 结束区域
+    pub end_rect: ZoomRect,
+    /// 缓动曲线
+    pub easing: ZoomEasing,
+    /// 视频片段起点（秒），默认 0；图片输入时忽略
+    pub start_time: Option<f64>,
+    /// 片段时长（秒），视频默认到结尾，图片默认 5 秒
+    pub duration: Option<f64>,
+    /// 图片输入的输出帧率，默认 30
+    pub fps: Option<u32>,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// 去隔行参数
+// ============================================================
+
+/// 去隔行滤镜
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DeinterlaceMethod {
+    /// yadif：速度快，通用
+    Yadif,
+    /// bwdif：基于 w3fdif 和 yadif 的改进算法，细节和运动边缘更好，稍慢
+    Bwdif,
+}
+
+impl DeinterlaceMethod {
+    /// 对应的 ffmpeg 滤镜名
+    pub fn filter_name(&self) -> &'static str {
+        match self {
+            DeinterlaceMethod::Yadif => "yadif",
+            DeinterlaceMethod::Bwdif => "bwdif",
+        }
+    }
+}
+
+/// 去隔行输出模式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DeinterlaceMode {
+    /// 每帧输出一帧（保持原帧率）
+    SendFrame,
+    /// 每场输出一帧（帧率翻倍，运动更流畅）
+    SendField,
+}
+
+impl DeinterlaceMode {
+    /// 对应的滤镜 mode 参数值
+    pub fn filter_value(&self) -> &'static str {
+        match self {
+            DeinterlaceMode::SendFrame => "send_frame",
+            DeinterlaceMode::SendField => "send_field",
+        }
+    }
+}
+
+/// 去隔行参数
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeinterlaceParams {
+    /// 输入视频文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 去隔行滤镜
+    pub method: DeinterlaceMethod,
+    /// 输出模式
+    pub mode: DeinterlaceMode,
+    /// 场序（-1 自动检测，0 顶场优先，1 底场优先），None 时自动检测
+    pub parity: Option<i32>,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// 降噪参数
+// ============================================================
+
+/// 降噪滤镜
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DenoiseMethod {
+    /// hqdn3d：3D 时空降噪，速度快，适合轻中度噪点
+    Hqdn3d,
+    /// nlmeans：非局部均值降噪，细节保留更好，适合严重噪点和压缩块效应，速度慢
+    Nlmeans,
+}
+
+/// 降噪参数
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DenoiseParams {
+    /// 输入视频文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 降噪滤镜
+    pub method: DenoiseMethod,
+    /// 降噪强度（0.0-1.0），映射到滤镜的亮度/色度、空间/时间参数
+    pub strength: f64,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// HDR 转 SDR 参数
+// ============================================================
+
+/// 色调映射算法
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TonemapAlgorithm {
+    /// Hable（Uncharted 2 曲线）：保留高光和暗部细节，整体偏暗，最常用
+    Hable,
+    /// Mobius：亮度范围内基本线性，只压缩超出部分，色彩更接近原片
+    Mobius,
+    /// Reinhard：简单的全局压缩，画面偏灰
+    Reinhard,
+    /// Clip：直接截断超出范围的亮度，高光细节丢失
+    Clip,
+}
+
+impl TonemapAlgorithm {
+    /// 对应的 tonemap 滤镜算法名
+    pub fn filter_value(&self) -> &'static str {
+        match self {
+            TonemapAlgorithm::Hable => "hable",
+            TonemapAlgorithm::Mobius => "mobius",
+            TonemapAlgorithm::Reinhard => "reinhard",
+            TonemapAlgorithm::Clip => "clip",
+        }
+    }
+}
+
+/// HDR 转 SDR 参数
+///
+/// 把 HDR10（PQ）/ HLG 视频色调映射为 SDR，便于在不支持 HDR 的设备和平台上正常显示
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HdrToSdrParams {
+    /// 输入视频文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 色调映射算法
+    pub tonemap_algorithm: TonemapAlgorithm,
+    /// 目标色域原色（"bt709" / "smpte170m" / "bt470bg"，默认 "bt709"），同时决定输出色彩矩阵
+    #[serde(default = "default_sdr_color")]
+    pub target_primaries: String,
+    /// 目标传输特性（"bt709" / "smpte170m" / "iec61966-2-1"，默认 "bt709"）
+    #[serde(default = "default_sdr_color")]
+    pub target_trc: String,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+/// 默认 SDR 色域和传输特性：BT.709
+fn default_sdr_color() -> String {
+    "bt709".to_string()
 }
 
 // ============================================================
@@ -286,7 +884,7 @@ pub struct WatermarkParams {
 /// 分辨率/帧率调整参数
 ///
 /// 调整视频的分辨率和/或帧率，支持多种缩放算法和宽高比处理模式
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ResizeParams {
     /// 输入文件路径
@@ -303,8 +901,115 @@ pub struct ResizeParams {
     pub scale_algorithm: Option<String>,
     /// 目标帧率（如 24.0, 30.0, 60.0），None 时保持原帧率
     pub fps: Option<f64>,
-    /// 宽高比不匹配时的处理模式（"crop" 裁切 / "pad" 加黑边 / "stretch" 拉伸）
+    /// 宽高比不匹配时的处理模式（"crop" 裁切 / "pad" 加黑边 / "stretch" 拉伸 /
+    /// "fit_downscale_only" 等比缩小到目标范围内、小于目标的源视频不放大）
     pub aspect_mode: Option<String>,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 是否去隔行（yadif），None 时按源视频场序自动判断
+    pub deinterlace: Option<bool>,
+}
+
+// ============================================================
+// 变速参数
+// ============================================================
+
+/// 视频变速参数
+///
+/// 视频用 setpts 改变时间戳，音频用 atempo 变速（保持音高）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 视频播放速度倍率（0.25-8.0，小于 1 为慢放，大于 1 为快放）
+    pub video_speed: f64,
+    /// 音频播放速度倍率（通常与 video_speed 相同，不同时音画会逐渐错开）
+    pub audio_speed: f64,
+    /// 是否保留音频（false 时输出无音轨）
+    pub keep_audio: bool,
+    /// 音频变速时是否保持音高（false 时音高随速度升降，类似磁带快放），默认保持
+    #[serde(default = "default_keep_audio_pitch")]
+    pub keep_audio_pitch: bool,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 音频码率（如 "192k"），未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+}
+
+/// 默认保持音高
+fn default_keep_audio_pitch() -> bool {
+    true
+}
+
+/// 统一倍率变速参数
+///
+/// 视频和音频按同一倍率变速、音画保持同步，是 SpeedParams 的简化形式
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSpeedParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 播放速度倍率（0.25-8.0，小于 1 为慢放，大于 1 为快放）
+    pub factor: f64,
+    /// 是否保持音高（false 时音高随速度升降）
+    pub keep_audio_pitch: bool,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 音频码率（如 "192k"），未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
+}
+
+impl From<ChangeSpeedParams> for SpeedParams {
+    fn from(params: ChangeSpeedParams) -> Self {
+        Self {
+            input_path: params.input_path,
+            output_path: params.output_path,
+            video_speed: params.factor,
+            audio_speed: params.factor,
+            keep_audio: true,
+            keep_audio_pitch: params.keep_audio_pitch,
+            quality: params.quality,
+            preset: params.preset,
+            audio_bitrate: params.audio_bitrate,
+        }
+    }
+}
+
+// ============================================================
+// 倒放参数
+// ============================================================
+
+/// 倒放参数
+///
+/// 视频和音频可分别选择是否倒放，不倒放的流直接复制
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReverseParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 是否倒放视频
+    pub reverse_video: bool,
+    /// 是否倒放音频
+    pub reverse_audio: bool,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 音频码率（如 "192k"），未指定时使用设置中的默认音频码率
+    pub audio_bitrate: Option<String>,
 }
 
 // ============================================================
@@ -314,13 +1019,14 @@ pub struct ResizeParams {
 /// GIF 制作参数
 ///
 /// 使用 ffmpeg 高质量两步法（palettegen + paletteuse）生成 GIF，
-/// 支持自定义帧率、尺寸、调色板和抖动算法
-#[derive(Deserialize, Clone, Debug)]
+/// 支持自定义帧率、尺寸、调色板和抖动算法。
+/// 输出路径扩展名为 .webp 时生成动画 WebP（不使用调色板参数）
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GifParams {
     /// 输入视频文件路径
     pub input_path: String,
-    /// 输出 GIF 文件路径
+    /// 输出文件路径（.gif 或 .webp）
     pub output_path: String,
     /// 截取起始时间（秒）
     pub start_time: f64,
@@ -330,7 +1036,10 @@ pub struct GifParams {
     pub width: u32,
     /// 输出帧率（推荐 10-15fps）
     pub fps: u32,
-    /// 循环次数（0=无限循环, -1=不循环, n=循环 n 次）
+    /// 播放次数（0=无限循环, -1 或 1=只播放一次, n=共播放 n 次）
+    ///
+    /// 与各容器的 -loop 约定不同（GIF 的 n 表示额外重复 n 次，WebP 的 n 表示共播放 n 次），
+    /// 由 build_gif_command 按输出格式换算
     pub loop_count: i32,
     /// 调色板最大颜色数（2-256，默认 256）
     pub max_colors: Option<u32>,
@@ -338,6 +1047,20 @@ pub struct GifParams {
     pub dither: Option<String>,
     /// 质量预设（"low"=小体积, "medium"=平衡, "high"=高质量）
     pub quality: Option<String>,
+    /// 输出为无声循环 MP4（H.264 yuv420p）代替 GIF，体积通常小一个数量级
+    pub output_as_mp4: Option<bool>,
+    /// output_as_mp4 时同时生成 GIF，并报告两者的体积对比
+    pub also_gif: Option<bool>,
+    /// 输出为动画 WebP 代替 GIF（扩展名自动改为 .webp，output_as_mp4 时不生效）
+    pub output_as_webp: Option<bool>,
+    /// 估算体积超过设置中的上限时仍然生成（帧数硬上限不受影响）
+    pub force: Option<bool>,
+    /// 叠加图片路径（如 logo），在生成调色板之前叠加，一次完成水印和 GIF
+    pub overlay_image: Option<String>,
+    /// 叠加图片位置（默认右下角）
+    pub overlay_position: Option<WatermarkPosition>,
+    /// 叠加图片宽度占输出宽度的比例（0-1，默认 0.2）
+    pub overlay_scale: Option<f64>,
 }
 
 // ============================================================
@@ -345,7 +1068,7 @@ pub struct GifParams {
 // ============================================================
 
 /// 字幕处理模式枚举
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum SubtitleMode {
     /// 添加软字幕（字幕作为独立流嵌入容器，播放器可切换显示）
@@ -360,7 +1083,7 @@ pub enum SubtitleMode {
 ///
 /// 支持三种模式：嵌入、提取、烧录。
 /// 烧录模式额外支持自定义字幕样式（字体、颜色、描边等）
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SubtitleParams {
     /// 输入视频文件路径
@@ -386,6 +1109,517 @@ pub struct SubtitleParams {
     pub outline_width: Option<u32>,
     /// 垂直边距（距底部距离）
     pub margin_v: Option<u32>,
+    /// 额外字体目录（libass fontsdir），ASS 字幕引用的字体可放在此处
+    pub fonts_dir: Option<String>,
+    /// 烧录重编码质量（CRF 值），仅 BurnIn 模式，未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 烧录重编码速度预设，仅 BurnIn 模式，未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// 短视频平台一键导出参数
+// ============================================================
+
+/// 短视频平台一键导出参数
+///
+/// 一次完成竖屏重构图、帧率/码率限制、像素格式和响度标准化，
+/// 输出符合 Instagram Reels / TikTok / YouTube Shorts 要求的视频
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformExportParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径（MP4）
+    pub output_path: String,
+    /// 目标平台（"instagram_reels" / "tiktok" / "youtube_shorts"）
+    pub platform: String,
+    /// 横屏素材的竖屏重构图方式（"crop" 居中裁切填满，默认 / "pad" 上下加黑边）
+    pub reframe_mode: Option<String>,
+    /// 编码速度预设，未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+    /// 覆盖平台规格的综合响度目标（LUFS，-70 ~ -5）
+    pub loudness_target_lufs: Option<f64>,
+    /// 覆盖平台规格的响度范围目标（LU，1 ~ 50）
+    pub loudness_range: Option<f64>,
+    /// 覆盖平台规格的真峰值上限（dBTP，-9 ~ 0）
+    pub true_peak: Option<f64>,
+}
+
+/// 平台导出实际采用的参数
+///
+/// 随任务 ID 一起返回前端，用于展示本次导出的具体设置
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformExportSettings {
+    /// 目标平台
+    pub platform: String,
+    /// 输出宽度（像素）
+    pub width: u32,
+    /// 输出高度（像素）
+    pub height: u32,
+    /// 输出帧率（源帧率未超上限时为源帧率）
+    pub fps: f64,
+    /// 重构图方式（"none" / "crop" / "pad"）
+    pub reframe: String,
+    /// 视频编码器
+    pub video_codec: String,
+    /// 目标视频码率（如 "8M"）
+    pub video_bitrate: String,
+    /// 最大视频码率
+    pub max_bitrate: String,
+    /// 音频编码器
+    pub audio_codec: String,
+    /// 音频码率
+    pub audio_bitrate: String,
+    /// 音频采样率（Hz）
+    pub sample_rate: u32,
+    /// 响度目标（LUFS）
+    pub loudness_lufs: f64,
+    /// 像素格式
+    pub pix_fmt: String,
+    /// 是否启用 faststart
+    pub faststart: bool,
+}
+
+/// 平台导出结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformExportResult {
+    /// 任务 ID
+    pub task_id: String,
+    /// 实际采用的导出参数
+    pub settings: PlatformExportSettings,
+}
+
+// ============================================================
+// 自适应流（HLS / DASH）参数
+// ============================================================
+
+/// 码率阶梯中的一档清晰度
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamRendition {
+    /// 输出高度（像素），宽度按源宽高比计算
+    pub height: u32,
+    /// 视频目标码率（kbps）
+    pub video_bitrate_kbps: u32,
+    /// 音频码率（kbps），未指定时为 128
+    pub audio_bitrate_kbps: Option<u32>,
+}
+
+impl StreamRendition {
+    /// 清晰度名称（如 "720p"），用作子目录名和播放列表中的流名
+    pub fn name(&self) -> String {
+        format!("{}p", self.height)
+    }
+}
+
+/// 自适应流打包参数
+///
+/// 单次 ffmpeg 调用生成多档清晰度和 master 播放列表（HLS）或 MPD 清单（DASH）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveStreamParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出目录（HLS 每档清晰度一个子目录、master 播放列表位于根目录；DASH 全部文件位于根目录）
+    pub output_dir: String,
+    /// 打包格式（"hls" / "dash"，默认 "hls"）
+    pub format: Option<String>,
+    /// 清晰度列表，未指定时按源分辨率自动生成码率阶梯
+    pub renditions: Option<Vec<StreamRendition>>,
+    /// 分片时长（秒，默认 6）
+    pub segment_duration: Option<f64>,
+    /// 输出目录非空时是否覆盖
+    pub overwrite: Option<bool>,
+    /// 编码速度预设，未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+/// 单档清晰度的输出信息
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenditionOutput {
+    /// 清晰度名称（如 "720p"）
+    pub name: String,
+    /// 该档播放列表路径（DASH 各档共用同一个清单）
+    pub playlist_path: String,
+    /// 该档全部文件的总大小（字节）
+    pub size: u64,
+}
+
+/// 自适应流打包结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveStreamResult {
+    /// 任务 ID
+    pub task_id: String,
+    /// master 播放列表（HLS）或 MPD 清单（DASH）路径
+    pub master_playlist_path: String,
+    /// 各档清晰度的输出信息（与阶梯顺序一致）
+    pub renditions: Vec<RenditionOutput>,
+}
+
+// ============================================================
+// 视频方向校正参数
+// ============================================================
+
+/// 自动方向校正参数
+///
+/// 按源文件的旋转元数据把画面实际旋转过来，并清除旋转标记
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoOrientParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+/// 旋转/翻转方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum RotateAngle {
+    /// 顺时针旋转 90°
+    Rotate90CW,
+    /// 逆时针旋转 90°
+    Rotate90CCW,
+    /// 旋转 180°
+    Rotate180,
+    /// 水平翻转（左右镜像）
+    FlipHorizontal,
+    /// 垂直翻转（上下镜像）
+    FlipVertical,
+}
+
+/// 手动旋转/翻转参数
+///
+/// 在播放器显示的画面基础上旋转或翻转（源文件的旋转元数据先由 ffmpeg 自动应用）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 旋转/翻转方式
+    pub rotate: RotateAngle,
+    /// 是否保留音频（false 时输出无音轨）
+    pub keep_audio: bool,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+/// 旋转+翻转组合变换参数
+///
+/// 先按 rotate 顺时针旋转，再在旋转后的画面上水平/垂直翻转
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 顺时针旋转角度（90 的倍数，负数表示逆时针），不填不旋转
+    pub rotate: Option<i32>,
+    /// 是否水平翻转（左右镜像）
+    #[serde(default)]
+    pub flip_h: bool,
+    /// 是否垂直翻转（上下镜像）
+    #[serde(default)]
+    pub flip_v: bool,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// LUT 调色参数
+// ============================================================
+
+/// LUT 文件格式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum LutFormat {
+    /// Adobe / Resolve 的 .cube 文件
+    Cube,
+    /// Autodesk / Lustre 的 .3dl 文件
+    #[serde(rename = "3dl")]
+    ThreeDl,
+}
+
+impl LutFormat {
+    /// 对应的文件扩展名（不含点）
+    pub fn extension(&self) -> &'static str {
+        match self {
+            LutFormat::Cube => "cube",
+            LutFormat::ThreeDl => "3dl",
+        }
+    }
+}
+
+/// LUT 调色参数
+///
+/// 用 lut3d 滤镜套用 3D LUT；强度小于 1 时把调色结果与原画面按比例混合
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LutParams {
+    /// 输入视频文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// LUT 文件路径
+    pub lut_path: String,
+    /// LUT 文件格式（需与文件扩展名一致）
+    pub lut_format: LutFormat,
+    /// 调色强度（0.0-1.0，1.0 为完全套用 LUT）
+    pub intensity: f64,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// 视频防抖参数
+// ============================================================
+
+/// 视频防抖参数
+///
+/// 两遍处理：vidstabdetect 分析抖动写入 .trf 文件，vidstabtransform 按其补偿画面
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StabilizeParams {
+    /// 输入视频文件路径
+    pub input_path: String,
+    /// 输出文件路径
+    pub output_path: String,
+    /// 抖动程度（1-10，越大表示源视频越晃）
+    pub shakiness: u32,
+    /// 平滑帧数（1-100，越大镜头运动越平滑）
+    pub smoothing: u32,
+    /// 补偿后露出的边缘填黑（false 时沿用上一帧的画面）
+    pub crop_black: bool,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+// ============================================================
+// 绿幕抠像参数
+// ============================================================
+
+/// 绿幕抠像参数
+///
+/// 用 chromakey 滤镜去除指定颜色：指定背景时合成到背景上输出普通视频，
+/// 否则输出带透明通道的 WebM（VP9）或 PNG 序列
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChromakeyParams {
+    /// 输入视频文件路径（绿幕素材）
+    pub input_path: String,
+    /// 背景视频或图片路径，None 时输出透明背景
+    pub background_path: Option<String>,
+    /// 输出文件路径（无背景时为 .webm，或含帧序号占位符的 .png，如 "frame_%04d.png"）
+    pub output_path: String,
+    /// 抠除的颜色（"#RRGGBB"，默认 "#00FF00"）
+    #[serde(default = "default_key_color")]
+    pub key_color: String,
+    /// 颜色相似度（0.01-1.0，越大抠除的颜色范围越宽）
+    pub similarity: f64,
+    /// 边缘混合程度（0.0-1.0，0 为硬边缘）
+    pub blend: f64,
+    /// 重编码质量（CRF 值），未指定时使用设置中的默认质量
+    pub quality: Option<u32>,
+    /// 重编码速度预设（如 "medium"），未指定时使用设置中的默认预设
+    pub preset: Option<String>,
+}
+
+/// 默认抠除颜色：标准绿幕
+fn default_key_color() -> String {
+    "#00FF00".to_string()
+}
+
+// ============================================================
+// 画面缺陷检查参数
+// ============================================================
+
+/// 画面缺陷检查参数
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DefectAnalysisParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 黑场像素亮度阈值（0.0-1.0，默认 0.10）
+    pub black_threshold: Option<f64>,
+    /// 静止画面噪声容差（0.0-1.0，默认 0.001，约 -60dB）
+    pub freeze_noise: Option<f64>,
+    /// 最短上报时长（秒，默认 2.0），更短的黑场/静止不上报
+    pub min_duration: Option<f64>,
+    /// 是否为每段缺陷截取起始帧（通过 get_preview 按令牌读取）
+    #[serde(default)]
+    pub screenshots: bool,
+}
+
+/// 缺陷类型
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DefectKind {
+    /// 黑场
+    Black,
+    /// 静止画面（冻帧）
+    Freeze,
+}
+
+/// 一段检测到的缺陷
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DefectRegion {
+    /// 开始时间（秒）
+    pub start: f64,
+    /// 结束时间（秒）
+    pub end: f64,
+    /// 缺陷类型
+    #[serde(rename = "type")]
+    pub kind: DefectKind,
+    /// 起始帧截图的预览令牌（未要求截图时为 None）
+    pub screenshot: Option<String>,
+}
+
+/// 画面缺陷检查报告
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DefectReport {
+    /// 任务 ID
+    pub task_id: String,
+    /// 按开始时间排序的缺陷列表
+    pub defects: Vec<DefectRegion>,
+}
+
+// ============================================================
+// 缩略图参数
+// ============================================================
+
+/// 缩略图提取参数
+///
+/// 指定 count 时生成等间隔的缩略图拼图（contact sheet），否则截取 timestamp 处的单帧
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出图片路径
+    pub output_path: String,
+    /// 单帧截取的时间点（秒），默认 0
+    pub timestamp: Option<f64>,
+    /// 拼图中的缩略图数量（按时长等间隔取帧）
+    pub count: Option<u32>,
+    /// 输出宽度（像素，拼图中为单张缩略图宽度），高度按比例计算；
+    /// 单帧未指定时保持原尺寸，拼图默认 320
+    pub width: Option<u32>,
+    /// 图片格式（"jpg" 或 "png"）
+    pub format: String,
+}
+
+/// 批量缩略图的取帧方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ThumbnailMode {
+    /// 按数量：在全片范围内等间隔取 count 帧
+    ByCount,
+    /// 按间隔：每隔 interval_sec 秒取一帧
+    ByInterval,
+}
+
+/// 批量缩略图导出参数
+///
+/// 导出一组独立的 JPG 文件（thumb_0001.jpg、thumb_0002.jpg …），用于制作故事板预览
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailSeriesParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出目录（不存在时自动创建）
+    pub output_dir: String,
+    /// 取帧方式
+    pub mode: ThumbnailMode,
+    /// 缩略图数量（ByCount 模式必填）
+    pub count: Option<u32>,
+    /// 取帧间隔（秒，ByInterval 模式必填）
+    pub interval_sec: Option<f64>,
+    /// 输出宽度（像素），高度按比例计算；未指定时保持原尺寸
+    pub width: Option<u32>,
+}
+
+/// 悬停预览短片参数
+///
+/// 从全片等间隔截取若干小片段，拼成一段无声、低分辨率的短预览（类似视频网站的悬停预览）
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewClipParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径（mp4 / m4v / mov / mkv）
+    pub output_path: String,
+    /// 片段数量，默认 6
+    pub snippet_count: Option<u32>,
+    /// 预览总时长（秒），默认 6，平均分给各片段
+    pub total_duration: Option<f64>,
+    /// 输出宽度（像素），高度按比例计算，默认 480
+    pub width: Option<u32>,
+}
+
+// ============================================================
+// 元数据编辑参数
+// ============================================================
+
+/// 容器级元数据标签
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataTag {
+    /// 标签名（如 "title"、"artist"、"date"、"comment"）
+    pub key: String,
+    /// 标签值，空字符串表示删除该标签
+    pub value: String,
+}
+
+/// 流级元数据标签
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamMetadataTag {
+    /// 流说明符（如 "v:0"、"a:1"、"s"）
+    pub stream: String,
+    /// 标签名（如 "language"、"title"）
+    pub key: String,
+    /// 标签值，空字符串表示删除该标签
+    pub value: String,
+}
+
+/// 元数据编辑参数
+///
+/// 流复制重封装写入标签，不重新编码
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataParams {
+    /// 输入文件路径
+    pub input_path: String,
+    /// 输出文件路径，None 时修改原文件
+    pub output_path: Option<String>,
+    /// 容器级标签
+    #[serde(default)]
+    pub tags: Vec<MetadataTag>,
+    /// 流级标签
+    #[serde(default)]
+    pub stream_tags: Vec<StreamMetadataTag>,
+    /// 是否先清除原有的全部标签（默认 false，保留未修改的标签）
+    pub clear_existing: Option<bool>,
 }
 
 // ============================================================
@@ -395,7 +1629,7 @@ pub struct SubtitleParams {
 /// 视频下载参数
 ///
 /// 前端解析完成后用户选择格式，提交此参数执行下载
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadParams {
     /// 视频页面 URL（YouTube/X/Instagram 等）
@@ -404,6 +1638,23 @@ pub struct DownloadParams {
     pub format_id: String,
     /// 输出文件路径
     pub output_path: String,
+    /// 是否保留原始下载不做容器修正（默认会把标成 .mp4 的 MPEG-TS 重封装为真正的 MP4）
+    #[serde(default)]
+    pub keep_raw_container: bool,
+    /// 下载完成后转换到的容器格式（"mp4" / "mkv" / "mov" / "webm" / "avi" / "flv"），
+    /// None 时保持站点提供的格式
+    pub remux_to: Option<String>,
+    /// 源编码无法直接封装进 remux_to 容器时是否允许重新编码（默认 false，只做流复制）
+    #[serde(default)]
+    pub allow_recode: bool,
+    /// 所选格式的 yt-dlp 编码名（FormatInfo 的 vcodec / acodec，如 "avc1.640028"、"opus"），
+    /// 用于判断能否流复制到 remux_to 容器
+    #[serde(default)]
+    pub source_codecs: Vec<String>,
+    /// 是否以可续传模式下载（默认 false）：保留 .part 临时文件并使用 --continue，
+    /// 任务可以暂停后继续
+    #[serde(default)]
+    pub resumable: bool,
 }
 
 /// yt-dlp 解析出的视频格式信息
@@ -463,7 +1714,9 @@ pub struct VideoInfo {
 
 /// 预设方案信息
 ///
-/// 描述一个内置预设的基本信息，用于前端展示预设选择列表
+/// 描述一个预设的基本信息，用于前端展示预设选择列表。
+/// 内置预设的参数大多由前端维护（params 为 None），需要后端保证参数组合的内置预设
+/// （如流媒体转换）直接携带参数；用户预设保存在 presets.json 中，params 为对应功能的参数对象
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PresetInfo {
@@ -472,7 +1725,228 @@ pub struct PresetInfo {
     /// 预设显示名称（如 "社交媒体优化", "高质量归档"）
     pub name: String,
     /// 预设描述（简要说明适用场景）
+    #[serde(default)]
     pub description: String,
     /// 所属功能模块（如 "convert", "compress", "gif"）
     pub category: String,
+    /// 预设参数（camelCase 键，与对应 command 的参数结构一致）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 反序列化后再序列化，结果应与输入 JSON 完全一致（字段名、枚举取值和嵌套结构都不丢失）
+    fn assert_round_trip<T: Serialize + serde::de::DeserializeOwned>(value: serde_json::Value) {
+        let typed: T = serde_json::from_value(value.clone())
+            .unwrap_or_else(|e| panic!("{}: {}", std::any::type_name::<T>(), e));
+        assert_eq!(serde_json::to_value(&typed).unwrap(), value, "{}", std::any::type_name::<T>());
+    }
+
+    #[test]
+    fn params_round_trip_through_json() {
+        assert_round_trip::<ConvertParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "outputFormat": "x", "videoCodec": "x",
+            "audioCodec": "x", "quality": 1, "preset": "medium", "hardwareAccel": true,
+            "audioBitrate": "192k", "audioSampleRate": 1, "videoBitrate": "x", "twoPass": true,
+            "colorRange": "x", "pixelFormat": "x", "preserveMetadata": true, "deinterlace": true,
+            "autoRotate": true, "streaming": true, "gopSize": 1, "audioStreamIndexes": [1],
+            "keepAllStreams": true, "extraArgs": ["x"],
+        }));
+        assert_round_trip::<ResolutionVariant>(json!({ "height": 1, "suffix": "x" }));
+        assert_round_trip::<MultiResolutionParams>(json!({
+            "inputPath": "in.mp4", "outputDir": "out", "variants": [{"height": 1, "suffix": "x"}],
+            "outputFormat": "x", "quality": 1, "preset": "medium", "audioBitrate": "192k",
+        }));
+        assert_round_trip::<CompressParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "mode": "bySize", "targetSizeMb": 1.5,
+            "compressRatio": 1.5, "qualityLevel": 1, "videoCodec": "x", "preset": "medium",
+            "hardwareAccel": true, "platform": "x", "audioBitrate": "192k",
+            "maxrateMultiplier": 1.5, "bufsizeMultiplier": 1.5, "strictCbr": true,
+            "deinterlace": true, "contentType": "film", "twoPass": true,
+        }));
+        assert_round_trip::<TwoPassParams>(json!({ "twoPass": true }));
+        assert_round_trip::<TimeSegment>(json!({ "start": 1.5, "end": 1.5 }));
+        assert_round_trip::<TrimParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4",
+            "segments": [{"start": 1.5, "end": 1.5}], "preciseCut": true, "mergeSegments": true,
+            "audioBitrate": "192k", "quality": 1, "preset": "medium", "accuracy": "fast",
+        }));
+        assert_round_trip::<TrimLeadingBlackParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "preciseCut": true,
+            "blackThreshold": 1.5, "minBlackDuration": 1.5, "audioBitrate": "192k", "quality": 1,
+            "preset": "medium",
+        }));
+        assert_round_trip::<TransitionConfig>(json!({ "transitionType": "x", "duration": 1.5 }));
+        assert_round_trip::<MergeParams>(json!({
+            "inputPaths": ["x"], "outputPath": "out.mp4",
+            "transition": {"transitionType": "x", "duration": 1.5}, "normalize": true,
+            "targetResolution": "x", "targetFps": 1.5, "audioBitrate": "192k", "quality": 1,
+            "preset": "medium", "audioOnlyCrossfade": 1.5, "minClipDuration": 1.5,
+            "normalizeLoudness": true, "loudnessTargetLufs": 1.5, "loudnessRange": 1.5,
+            "truePeak": 1.5, "targetChannels": 1,
+        }));
+        assert_round_trip::<IntroOutroParams>(json!({
+            "intro": "x", "main": "x", "outro": "x", "outputPath": "out.mp4",
+            "audioBitrate": "192k", "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<SlideshowParams>(json!({
+            "imagePaths": ["x"], "outputPath": "out.mp4", "frameDuration": 1.5, "fps": 1,
+            "width": 1, "height": 1, "transition": {"transitionType": "x", "duration": 1.5},
+            "audioPath": "x", "audioBitrate": "192k", "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<AudioParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "mode": "extract", "outputFormat": "x",
+            "replaceAudioPath": "x", "fitMode": "x", "audioBitrate": "192k", "volume": 1.5,
+            "volumeDb": 1.5, "normalize": true, "loudnessTargetLufs": 1.5, "loudnessRange": 1.5,
+            "truePeak": 1.5, "fadeIn": 1.5, "fadeOut": 1.5,
+        }));
+        assert_round_trip::<MultiFormatAudioParams>(json!({
+            "inputPath": "in.mp4", "outputDir": "out", "formats": ["x"], "audioTrack": 1,
+            "suffix": "x",
+        }));
+        assert_round_trip::<WatermarkParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "watermarkType": "image",
+            "imagePath": "x", "imageScale": 1.5, "opacity": 1.5, "text": "x", "fontPath": "x",
+            "fontSize": 1, "fontColor": "x", "borderWidth": 1, "borderColor": "x",
+            "position": "topLeft", "offsetX": 1, "offsetY": 1, "quality": 1, "preset": "medium",
+            "deinterlace": true,
+        }));
+        assert_round_trip::<RemoveLogoParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "x": 1, "y": 1, "width": 1,
+            "height": 1, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<CropParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "x": 1, "y": 1, "width": 1,
+            "height": 1, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<ZoomRect>(json!({ "x": 1.5, "y": 1.5, "width": 1.5, "height": 1.5 }));
+        assert_round_trip::<ZoomPanParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4",
+            "startRect": {"x": 1.5, "y": 1.5, "width": 1.5, "height": 1.5},
+            "endRect": {"x": 1.5, "y": 1.5, "width": 1.5, "height": 1.5}, "easing": "linear",
+            "startTime": 1.5, "duration": 1.5, "fps": 1, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<DeinterlaceParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "method": "yadif", "mode": "sendFrame",
+            "parity": 1, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<DenoiseParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "method": "hqdn3d", "strength": 1.5,
+            "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<HdrToSdrParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "tonemapAlgorithm": "hable",
+            "targetPrimaries": "x", "targetTrc": "x", "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<ResizeParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "width": 1, "height": 1,
+            "keepAspectRatio": true, "scaleAlgorithm": "x", "fps": 1.5, "aspectMode": "x",
+            "quality": 1, "preset": "medium", "deinterlace": true,
+        }));
+        assert_round_trip::<SpeedParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "videoSpeed": 1.5, "audioSpeed": 1.5,
+            "keepAudio": true, "keepAudioPitch": true, "quality": 1, "preset": "medium",
+            "audioBitrate": "192k",
+        }));
+        assert_round_trip::<ChangeSpeedParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "factor": 1.5, "keepAudioPitch": true,
+            "quality": 1, "preset": "medium", "audioBitrate": "192k",
+        }));
+        assert_round_trip::<ReverseParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "reverseVideo": true,
+            "reverseAudio": true, "quality": 1, "preset": "medium", "audioBitrate": "192k",
+        }));
+        assert_round_trip::<GifParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "startTime": 1.5, "duration": 1.5,
+            "width": 1, "fps": 1, "loopCount": 1, "maxColors": 1, "dither": "x", "quality": "x",
+            "outputAsMp4": true, "alsoGif": true, "outputAsWebp": true, "force": true,
+            "overlayImage": "x", "overlayPosition": "topLeft", "overlayScale": 1.5,
+        }));
+        assert_round_trip::<SubtitleParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "mode": "embed", "subtitlePath": "x",
+            "subtitleIndex": 1, "outputFormat": "x", "fontName": "x", "fontSize": 1,
+            "primaryColor": "x", "outlineWidth": 1, "marginV": 1, "fontsDir": "x", "quality": 1,
+            "preset": "medium",
+        }));
+        assert_round_trip::<PlatformExportParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "platform": "x", "reframeMode": "x",
+            "preset": "medium", "loudnessTargetLufs": 1.5, "loudnessRange": 1.5, "truePeak": 1.5,
+        }));
+        assert_round_trip::<StreamRendition>(json!({
+            "height": 1, "videoBitrateKbps": 1, "audioBitrateKbps": 1,
+        }));
+        assert_round_trip::<AdaptiveStreamParams>(json!({
+            "inputPath": "in.mp4", "outputDir": "out", "format": "x",
+            "renditions": [{"height": 1, "videoBitrateKbps": 1, "audioBitrateKbps": 1}],
+            "segmentDuration": 1.5, "overwrite": true, "preset": "medium",
+        }));
+        assert_round_trip::<AutoOrientParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<RotateParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "rotate": "Rotate90CW",
+            "keepAudio": true, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<TransformParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "rotate": 1, "flipH": true,
+            "flipV": true, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<LutParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "lutPath": "x", "lutFormat": "Cube",
+            "intensity": 1.5, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<StabilizeParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "shakiness": 1, "smoothing": 1,
+            "cropBlack": true, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<ChromakeyParams>(json!({
+            "inputPath": "in.mp4", "backgroundPath": "x", "outputPath": "out.mp4", "keyColor": "x",
+            "similarity": 1.5, "blend": 1.5, "quality": 1, "preset": "medium",
+        }));
+        assert_round_trip::<DefectAnalysisParams>(json!({
+            "inputPath": "in.mp4", "blackThreshold": 1.5, "freezeNoise": 1.5, "minDuration": 1.5,
+            "screenshots": true,
+        }));
+        assert_round_trip::<ThumbnailParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "timestamp": 1.5, "count": 1,
+            "width": 1, "format": "x",
+        }));
+        assert_round_trip::<ThumbnailSeriesParams>(json!({
+            "inputPath": "in.mp4", "outputDir": "out", "mode": "byCount", "count": 1,
+            "intervalSec": 1.5, "width": 1,
+        }));
+        assert_round_trip::<PreviewClipParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "snippetCount": 1,
+            "totalDuration": 1.5, "width": 1,
+        }));
+        assert_round_trip::<MetadataTag>(json!({ "key": "x", "value": "x" }));
+        assert_round_trip::<StreamMetadataTag>(json!({ "stream": "x", "key": "x", "value": "x" }));
+        assert_round_trip::<MetadataParams>(json!({
+            "inputPath": "in.mp4", "outputPath": "out.mp4", "tags": [{"key": "x", "value": "x"}],
+            "streamTags": [{"stream": "x", "key": "x", "value": "x"}], "clearExisting": true,
+        }));
+        assert_round_trip::<DownloadParams>(json!({
+            "url": "x", "formatId": "x", "outputPath": "out.mp4", "keepRawContainer": true,
+            "remuxTo": "x", "allowRecode": true, "sourceCodecs": ["x"], "resumable": true,
+        }));
+        assert_round_trip::<FormatInfo>(json!({
+            "formatId": "x", "formatNote": "x", "ext": "x", "width": 1, "height": 1, "filesize": 1,
+            "hasVideo": true, "hasAudio": true, "vcodec": "x", "acodec": "x", "protocol": "x",
+        }));
+        assert_round_trip::<VideoInfo>(json!({
+            "title": "x", "duration": 1.5, "thumbnail": "x", "uploader": "x", "platform": "x",
+            "formats": [{
+                "formatId": "x", "formatNote": "x", "ext": "x", "width": 1, "height": 1,
+                "filesize": 1, "hasVideo": true, "hasAudio": true, "vcodec": "x", "acodec": "x",
+                "protocol": "x",
+            }],
+        }));
+        assert_round_trip::<PresetInfo>(json!({
+            "id": "x", "name": "x", "description": "x", "category": "x", "params": {"k": 1},
+        }));
+    }
 }
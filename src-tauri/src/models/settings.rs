@@ -30,6 +30,22 @@ pub struct AppSettings {
     pub overwrite_existing: bool,
     /// 界面语言（"en" = 英文，"zh" = 中文），默认英文
     pub language: String,
+    /// 默认音频码率（如 "128k", "192k", "320k"），参数未指定音频码率时使用
+    pub default_audio_bitrate: String,
+    /// 默认视频质量（CRF 值，越小质量越高），参数未指定质量时使用
+    pub default_quality: u32,
+    /// 默认编码速度预设（如 "medium", "slow"），参数未指定预设时使用
+    pub default_preset: String,
+    /// 输入文件仍在写入（如正在录制）时最多等待的秒数，0 表示立即报错
+    pub growing_file_wait_seconds: u32,
+    /// GIF 估算体积上限（MB），超出时需确认（force）才生成
+    pub max_gif_size_mb: u32,
+    /// 是否先写入同目录临时文件、成功后再移动到输出路径（失败或取消时不留下残缺文件）
+    pub atomic_output: bool,
+    /// ffprobe 探测输入文件的超时时间（毫秒），0 表示不限时
+    pub probe_timeout_ms: u64,
+    /// ffmpeg 使用的线程数上限（-threads），None 表示由 ffmpeg 自动决定
+    pub ffmpeg_threads: Option<u32>,
 }
 
 impl Default for AppSettings {
@@ -41,6 +57,12 @@ impl Default for AppSettings {
     /// - 完成后通知但不自动打开
     /// - 默认后缀 "_output"
     /// - 不自动覆盖
+    /// - 音频码率 128k，CRF 18，preset medium
+    /// - 输入文件仍在写入时最多等待 10 秒
+    /// - GIF 估算体积上限 100 MB
+    /// - 启用原子输出
+    /// - 探测输入文件最多 30 秒
+    /// - 不限制 ffmpeg 线程数
     fn default() -> Self {
         Self {
             output_directory: String::new(),
@@ -51,6 +73,40 @@ impl Default for AppSettings {
             output_suffix: "_output".to_string(),
             overwrite_existing: false,
             language: "en".to_string(),
+            default_audio_bitrate: "128k".to_string(),
+            default_quality: 18,
+            default_preset: "medium".to_string(),
+            growing_file_wait_seconds: 10,
+            max_gif_size_mb: 100,
+            atomic_output: true,
+            probe_timeout_ms: 30_000,
+            ffmpeg_threads: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_encoding_defaults_are_filled_in() {
+        let settings: AppSettings = serde_json::from_str(r#"{"language":"zh"}"#).unwrap();
+        assert_eq!(settings.language, "zh");
+        assert_eq!(settings.default_audio_bitrate, "128k");
+        assert_eq!(settings.default_quality, 18);
+        assert_eq!(settings.default_preset, "medium");
+    }
+
+    #[test]
+    fn audio_bitrate_round_trips_in_camel_case() {
+        let settings = AppSettings {
+            default_audio_bitrate: "320k".to_string(),
+            ..AppSettings::default()
+        };
+        let value = serde_json::to_value(&settings).unwrap();
+        assert_eq!(value["defaultAudioBitrate"], "320k");
+        let back: AppSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(back.default_audio_bitrate, "320k");
+    }
+}
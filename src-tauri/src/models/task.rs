@@ -19,6 +19,8 @@ pub enum TaskStatus {
     Completed,
     /// 已取消（用户主动取消或队列清空）
     Cancelled,
+    /// 已暂停（可续传的下载任务，保留已下载的部分）
+    Paused,
     /// 执行失败（ffmpeg 进程出错退出）
     Failed,
 }
@@ -32,6 +34,18 @@ pub enum TaskStatus {
 #[derive(Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase", tag = "event", content = "data")]
 pub enum TaskEvent {
+    /// 任务排队事件
+    ///
+    /// 并发任务数已达上限（max_concurrent）时发送，排队位置变化时再次发送，
+    /// 任务在获得名额后才发送 Started
+    #[serde(rename_all = "camelCase")]
+    Queued {
+        /// 任务唯一标识（UUID v4）
+        task_id: String,
+        /// 前面排队的任务数（0 表示下一个启动）
+        position: usize,
+    },
+
     /// 任务开始执行事件
     ///
     /// ffmpeg 进程成功启动后发送，前端据此初始化进度条
@@ -61,6 +75,15 @@ pub enum TaskEvent {
         output_size: u64,
         /// 任务执行耗时（秒）
         elapsed: f64,
+        /// 输出文件编码属性核对结果（未要求核对时为空）
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        verification: Vec<VerificationCheck>,
+        /// 完成后对输出做的自动修正说明（如下载文件的容器修正），没有修正时省略
+        #[serde(skip_serializing_if = "Option::is_none")]
+        note: Option<String>,
+        /// 下载后转换容器时的原始格式和最终格式（仅指定 remux_to 的下载任务）
+        #[serde(skip_serializing_if = "Option::is_none")]
+        container_change: Option<ContainerChange>,
     },
 
     /// 任务失败事件
@@ -82,6 +105,65 @@ pub enum TaskEvent {
         /// 任务唯一标识
         task_id: String,
     },
+
+    /// 任务暂停事件
+    ///
+    /// 可续传的下载任务被暂停后发送，已下载的部分保留，可按同一 task_id 继续
+    #[serde(rename_all = "camelCase")]
+    Paused {
+        /// 任务唯一标识
+        task_id: String,
+        /// 暂停时的进度百分比
+        percent: f64,
+    },
+
+    /// 任务提示事件
+    ///
+    /// 命令自动调整了用户参数（如按平台约束降帧）时发送，不影响任务执行
+    #[serde(rename_all = "camelCase")]
+    Notice {
+        /// 任务唯一标识
+        task_id: String,
+        /// 提示内容
+        message: String,
+    },
+
+    /// 任务阶段事件
+    ///
+    /// ffmpeg 启动前耗时较长的准备阶段（如探测输入文件）开始时发送，前端展示阶段描述
+    #[serde(rename_all = "camelCase")]
+    Phase {
+        /// 任务唯一标识
+        task_id: String,
+        /// 阶段描述（如 "Analyzing input…"）
+        description: String,
+    },
+
+    /// 批量进度事件
+    ///
+    /// 批量处理中每个子任务结束（完成、失败或取消）时发送，
+    /// 子任务自身的事件照常按各自的 task_id 推送
+    #[serde(rename_all = "camelCase")]
+    BatchProgress {
+        /// 批量处理标识
+        batch_id: String,
+        /// 已结束的子任务数
+        completed: usize,
+        /// 子任务总数
+        total: usize,
+    },
+}
+
+/// 下载任务的容器转换结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerChange {
+    /// yt-dlp 下载得到的容器格式（扩展名，如 "webm"）
+    pub downloaded: String,
+    /// 转换后经 ffprobe 确认的容器格式（扩展名，如 "mp4"）
+    pub final_format: String,
+    /// 是否重新编码（false 表示流复制重封装）
+    pub recoded: bool,
 }
 
 /// 进度更新数据
@@ -99,7 +181,7 @@ pub struct ProgressUpdate {
     pub speed: f64,
     /// 已处理的时间位置（秒）
     pub current_time: f64,
-    /// 预估剩余时间（秒）
+    /// 预估剩余时间（秒），按处理速度的指数移动平均计算，避免随瞬时速度跳动
     pub eta: f64,
     /// 当前输出文件大小（字节）
     pub output_size: u64,
@@ -107,6 +189,33 @@ pub struct ProgressUpdate {
     pub frame: u64,
     /// 当前处理帧率（fps）
     pub fps: f64,
+    /// 多步骤任务的当前阶段（如 "Cutting segment 2/5"、"Merging"），单步任务不序列化该字段
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+}
+
+/// 定时任务
+///
+/// 持久化在 $APPDATA/com.clipforge.app/scheduled.json 中，应用重启后仍然有效。
+/// 到达 schedule_at 时间或系统持续空闲时由调度器提交执行
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTask {
+    /// 定时任务唯一标识（UUID v4，与执行时生成的任务 ID 不同）
+    pub id: String,
+    /// 任务类型（对应 command，如 "convert", "compress", "trim"）
+    pub kind: String,
+    /// 原始任务参数（与对应 command 的 params 结构一致）
+    pub params: serde_json::Value,
+    /// 计划执行时间（ISO 8601 / RFC 3339，如 "2026-10-17T02:00:00+08:00"）
+    pub schedule_at: Option<String>,
+    /// 是否在系统空闲时执行（与 schedule_at 同时设置时两者都需满足）
+    pub run_when_idle: bool,
+    /// 创建时间（RFC 3339）
+    pub created_at: String,
+    /// 是否已提交执行（执行结束后从文件中删除；应用在执行中退出时，下次启动重新排队）
+    #[serde(default)]
+    pub running: bool,
 }
 
 /// 任务执行结果
@@ -128,4 +237,133 @@ pub struct TaskResult {
     pub elapsed: Option<f64>,
     /// 错误信息（仅在失败时有值）
     pub error: Option<String>,
+    /// 输出文件编码属性核对结果（未要求核对时为空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verification: Vec<VerificationCheck>,
+}
+
+/// 输出文件的一项编码属性核对
+///
+/// 任务完成后探测输出文件，与参数要求的值逐项比对。
+/// 不一致不会使任务失败，只作为提示展示
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationCheck {
+    /// 核对项名称（如 "codec", "resolution", "faststart"）
+    pub name: String,
+    /// 参数要求的值
+    pub expected: String,
+    /// 输出文件中的实际值（无法探测时为 "unknown"）
+    pub actual: String,
+    /// 是否一致
+    pub ok: bool,
+}
+
+/// 拖出导出的实际行为
+///
+/// macOS 上发起原生拖拽会话；其他平台或会话启动失败时
+/// 回退为把文件路径复制到剪贴板，前端据此提示用户粘贴
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DragBehavior {
+    /// 原生拖拽会话已开始
+    NativeDrag,
+    /// 文件路径已复制到剪贴板
+    CopiedPath,
+}
+
+/// ffmpeg 命令预览（不执行）
+///
+/// 多遍编码时每一遍对应一条预览
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPreview {
+    /// 传给 ffmpeg 的参数列表（不含 ffmpeg 本身）
+    pub args: Vec<String>,
+    /// 可直接粘贴到终端执行的单行命令（参数已按 shell 规则加引号）
+    pub command_line: String,
+    /// 实际执行时会推送的参数自动调整提示
+    pub notices: Vec<String>,
+}
+
+/// 输出体积估算的可信度
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EstimateConfidence {
+    /// 按码率计算（指定码率、按目标大小压缩或流复制）
+    Exact,
+    /// 按 CRF 经验表估算，实际体积随画面复杂度浮动
+    Approximate,
+}
+
+/// 开始转换/压缩前的输出体积估算
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeEstimate {
+    /// 估算的输出体积（字节）
+    pub estimated_bytes: u64,
+    /// 估算可信度
+    pub confidence: EstimateConfidence,
+    /// 参与计算的时长（秒）
+    pub duration: f64,
+    /// 参与计算的视频码率（bps），纯音频输出为 0
+    pub video_bitrate: u64,
+    /// 参与计算的音频码率之和（bps）
+    pub audio_bitrate: u64,
+    /// 视频编码器（流复制为 "copy"）
+    pub video_encoder: String,
+    /// CRF 模式使用的 CRF 值，码率模式为 None
+    pub crf: Option<u32>,
+    /// 实际执行时会推送的参数自动调整提示
+    pub notices: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造指定阶段标签的进度更新
+    fn progress(phase: Option<&str>) -> ProgressUpdate {
+        ProgressUpdate {
+            task_id: "task".to_string(),
+            percent: 50.0,
+            speed: 1.5,
+            current_time: 5.0,
+            eta: 3.3,
+            output_size: 1024,
+            frame: 150,
+            fps: 30.0,
+            phase: phase.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn progress_phase_is_omitted_when_none() {
+        let value = serde_json::to_value(progress(None)).unwrap();
+        assert!(value.get("phase").is_none(), "{}", value);
+        assert_eq!(value["taskId"], "task");
+        assert_eq!(value["outputSize"], 1024);
+    }
+
+    #[test]
+    fn progress_phase_is_serialized_when_set() {
+        let value = serde_json::to_value(progress(Some("Merging"))).unwrap();
+        assert_eq!(value["phase"], "Merging");
+    }
+
+
+    #[test]
+    fn queued_event_serializes_with_event_tag_and_position() {
+        let event = TaskEvent::Queued {
+            task_id: "task".to_string(),
+            position: 2,
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "event": "queued",
+                "data": { "taskId": "task", "position": 2 },
+            })
+        );
+    }
 }
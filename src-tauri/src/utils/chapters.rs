@@ -0,0 +1,210 @@
+/// 章节格式工具
+///
+/// 章节列表的校验，以及与 CUE 表单、ffmetadata 文件之间的文本转换
+
+use crate::models::media::Chapter;
+
+/// CUE 时间戳每秒的帧数（CD 扇区，固定 75）
+const CUE_FRAMES_PER_SECOND: f64 = 75.0;
+/// 章节结束时间允许超出媒体时长的误差（秒），容器时长通常取整到毫秒
+const DURATION_TOLERANCE: f64 = 0.01;
+
+/// 校验章节列表
+///
+/// 每个章节需满足 0 <= start < end，章节按开始时间排列且互不重叠，
+/// 已知媒体时长时结束时间不能超出时长
+///
+/// # 参数
+/// - `chapters` - 章节列表
+/// - `duration` - 媒体时长（秒），未知时为 0
+pub fn validate_chapters(chapters: &[Chapter], duration: f64) -> Result<(), String> {
+    for (i, chapter) in chapters.iter().enumerate() {
+        if !chapter.start.is_finite() || !chapter.end.is_finite() || chapter.start < 0.0 {
+            return Err(format!("Chapter {} has an invalid time range", i + 1));
+        }
+        if chapter.end <= chapter.start {
+            return Err(format!("Chapter {} must end after it starts", i + 1));
+        }
+        if duration > 0.0 && chapter.end > duration + DURATION_TOLERANCE {
+            return Err(format!(
+                "Chapter {} ends at {:.3}s, past the end of the file ({:.3}s)",
+                i + 1,
+                chapter.end,
+                duration
+            ));
+        }
+    }
+    if let Some(i) = chapters
+        .windows(2)
+        .position(|pair| pair[1].start < pair[0].end - 0.0005)
+    {
+        return Err(format!(
+            "Chapter {} overlaps the previous chapter; chapters must be in order",
+            i + 2
+        ));
+    }
+    Ok(())
+}
+
+/// 生成 CUE 表单
+///
+/// 每个章节对应一个 TRACK，INDEX 01 为章节开始时间（mm:ss:ff，ff 为 1/75 秒）
+///
+/// # 参数
+/// - `media_file_name` - CUE 引用的媒体文件名（与 CUE 文件同目录）
+/// - `album_title` - 专辑标题（文件标题，可为空）
+/// - `chapters` - 章节列表
+///
+/// # 返回
+/// CUE 文件内容
+pub fn build_cue_sheet(media_file_name: &str, album_title: &str, chapters: &[Chapter]) -> String {
+    let file_type = if media_file_name.to_lowercase().ends_with(".mp3") {
+        "MP3"
+    } else {
+        "WAVE"
+    };
+
+    let mut cue = String::new();
+    if !album_title.is_empty() {
+        cue.push_str(&format!("TITLE \"{}\"\n", cue_text(album_title)));
+    }
+    cue.push_str(&format!("FILE \"{}\" {}\n", cue_text(media_file_name), file_type));
+    for (i, chapter) in chapters.iter().enumerate() {
+        cue.push_str(&format!("  TRACK {:02} AUDIO\n", i + 1));
+        let title = if chapter.title.is_empty() {
+            format!("Chapter {}", i + 1)
+        } else {
+            chapter.title.clone()
+        };
+        cue.push_str(&format!("    TITLE \"{}\"\n", cue_text(&title)));
+        cue.push_str(&format!("    INDEX 01 {}\n", cue_timestamp(chapter.start)));
+    }
+    cue
+}
+
+/// 生成 ffmetadata 章节文件
+///
+/// 时间基使用 1/1000（毫秒），标题中的特殊字符按 ffmetadata 规则转义
+///
+/// # 参数
+/// - `chapters` - 章节列表
+///
+/// # 返回
+/// ffmetadata 文件内容
+pub fn build_ffmetadata(chapters: &[Chapter]) -> String {
+    let mut metadata = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        metadata.push_str("\n[CHAPTER]\nTIMEBASE=1/1000\n");
+        metadata.push_str(&format!("START={}\n", (chapter.start * 1000.0).round() as u64));
+        metadata.push_str(&format!("END={}\n", (chapter.end * 1000.0).round() as u64));
+        if !chapter.title.is_empty() {
+            metadata.push_str(&format!("title={}\n", escape_ffmetadata(&chapter.title)));
+        }
+    }
+    metadata
+}
+
+/// 秒数转换为 CUE 时间戳（mm:ss:ff，分钟可超过 99）
+fn cue_timestamp(seconds: f64) -> String {
+    let total_frames = (seconds.max(0.0) * CUE_FRAMES_PER_SECOND).round() as u64;
+    let frames = total_frames % 75;
+    let total_seconds = total_frames / 75;
+    format!("{:02}:{:02}:{:02}", total_seconds / 60, total_seconds % 60, frames)
+}
+
+/// CUE 字符串不支持转义：双引号替换为单引号，换行替换为空格
+fn cue_text(text: &str) -> String {
+    text.replace('"', "'").replace(['\r', '\n'], " ")
+}
+
+/// ffmetadata 值中的 `=`、`;`、`#`、`\` 和换行需要用反斜杠转义
+fn escape_ffmetadata(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::media::FfprobeOutput;
+
+    fn chapter(start: f64, end: f64, title: &str) -> Chapter {
+        Chapter {
+            start,
+            end,
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn ffmetadata_round_trips_chapters() {
+        let chapters = vec![
+            chapter(0.0, 61.5, "Intro"),
+            chapter(61.5, 125.25, "Q&A = answers; #1 \\ more\nsecond line"),
+            chapter(125.25, 180.0, ""),
+        ];
+        let metadata = build_ffmetadata(&chapters);
+        assert_eq!(
+            metadata,
+            concat!(
+                ";FFMETADATA1\n",
+                "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=61500\ntitle=Intro\n",
+                "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=61500\nEND=125250\n",
+                "title=Q&A \\= answers\\; \\#1 \\\\ more\\\nsecond line\n",
+                "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=125250\nEND=180000\n",
+            )
+        );
+        assert!(validate_chapters(&chapters, 180.0).is_ok());
+
+        // ffprobe 读回写入后的文件：时间按容器时间基换算，标题已去掉转义
+        let probe: FfprobeOutput = serde_json::from_value(serde_json::json!({
+            "format": { "duration": "180.000000" },
+            "chapters": [
+                { "id": 0, "time_base": "1/1000", "start": 0, "start_time": "0.000000",
+                  "end": 61500, "end_time": "61.500000", "tags": { "title": "Intro" } },
+                { "id": 1, "time_base": "1/44100", "start": 2712149,
+                  "start_time": "61.499977", "end": 5523525, "end_time": "125.250000",
+                  "tags": { "title": "Q&A = answers; #1 \\ more\nsecond line" } },
+                { "id": 2, "time_base": "1/1000000000", "start": 125250000000i64,
+                  "start_time": "125.250000", "end": 180000000000i64,
+                  "end_time": "180.000000" },
+            ],
+        }))
+        .unwrap();
+        let read_back = probe.to_media_info("/tmp/chapters.m4a").chapters;
+
+        assert_eq!(read_back.len(), chapters.len());
+        for (read, written) in read_back.iter().zip(&chapters) {
+            assert!((read.start - written.start).abs() < 0.001, "{:?}", read);
+            assert!((read.end - written.end).abs() < 0.001, "{:?}", read);
+            assert_eq!(read.title, written.title);
+        }
+        // 按毫秒取整后重新生成的 ffmetadata 与原文件相同
+        assert_eq!(build_ffmetadata(&read_back), metadata);
+    }
+
+    #[test]
+    fn cue_sheet_indexes_chapter_starts() {
+        let chapters = vec![chapter(0.0, 61.5, "Intro \"live\""), chapter(61.5, 90.0, "")];
+        let cue = build_cue_sheet("album.mp3", "Album", &chapters);
+        assert_eq!(
+            cue,
+            concat!(
+                "TITLE \"Album\"\n",
+                "FILE \"album.mp3\" MP3\n",
+                "  TRACK 01 AUDIO\n",
+                "    TITLE \"Intro 'live'\"\n",
+                "    INDEX 01 00:00:00\n",
+                "  TRACK 02 AUDIO\n",
+                "    TITLE \"Chapter 2\"\n",
+                "    INDEX 01 01:01:38\n",
+            )
+        );
+    }
+}
@@ -2,9 +2,13 @@
 ///
 /// 导出路径处理和时间格式转换等通用工具函数
 
+/// 章节格式工具（CUE 表单、ffmetadata 生成与章节校验）
+pub mod chapters;
 /// 路径处理工具（输出路径生成、临时文件管理）
 pub mod path;
 /// 时间格式工具（秒数与时间戳互转）
 pub mod time;
+/// 时间片段校验工具（排序、重叠、时长范围检查）
+pub mod segment;
 /// 文字图片渲染工具（将文本渲染为透明 PNG，替代 ffmpeg drawtext 滤镜）
 pub mod text_image;
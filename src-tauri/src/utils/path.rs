@@ -4,6 +4,12 @@
 /// 用于在 ffmpeg 命令构建前确定输出路径
 
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// 输入文件仍在写入时的错误标识，前端据此提示"请先停止录制"
+pub const FILE_STILL_BEING_WRITTEN: &str = "FileStillBeingWritten";
+/// 判断文件是否在增长时两次检查的间隔
+const GROWTH_CHECK_INTERVAL: Duration = Duration::from_millis(500);
 
 /// 根据输入路径和参数生成输出文件路径
 ///
@@ -89,6 +95,17 @@ pub fn file_extension(path: &str) -> String {
         .unwrap_or_default()
 }
 
+/// 静态图片文件扩展名（作为视频输入时只有一帧，需要循环或 zoompan 生成时长）
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "bmp", "webp", "tif", "tiff"];
+
+/// 按扩展名判断是否为静态图片文件
+///
+/// # 参数
+/// - `path` - 文件路径
+pub fn is_image_file(path: &str) -> bool {
+    IMAGE_EXTENSIONS.contains(&file_extension(path).as_str())
+}
+
 /// 获取文件大小（字节）
 ///
 /// # 参数
@@ -102,6 +119,52 @@ pub fn get_file_size(path: &str) -> u64 {
         .unwrap_or(0)
 }
 
+/// 文件的大小和修改时间，用于判断文件是否仍在写入
+fn file_snapshot(path: &str) -> Option<(u64, SystemTime)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()?))
+}
+
+/// 检查文件在间隔时间内是否发生变化（大小或修改时间改变）
+///
+/// 文件不存在或无法读取时视为未变化，由后续处理报告具体错误
+///
+/// # 参数
+/// - `path` - 文件路径
+/// - `interval` - 两次检查的间隔
+pub async fn is_file_growing(path: &str, interval: Duration) -> bool {
+    let Some(before) = file_snapshot(path) else {
+        return false;
+    };
+    tokio::time::sleep(interval).await;
+    file_snapshot(path).is_some_and(|after| after != before)
+}
+
+/// 确认输入文件已写入完毕
+///
+/// 录屏软件（如 OBS）仍在写入的文件没有完整的索引，处理会得到损坏的输出或卡住。
+/// 文件在增长时每隔一段时间重新检查，直到稳定或超过等待时长
+///
+/// # 参数
+/// - `path` - 输入文件路径
+/// - `max_wait` - 最长等待时长（为 0 时检测到增长立即报错）
+///
+/// # 返回
+/// - `Ok(())` - 文件未在写入
+/// - `Err(String)` - 以 FILE_STILL_BEING_WRITTEN 开头的错误描述
+pub async fn ensure_file_settled(path: &str, max_wait: Duration) -> Result<(), String> {
+    let start = Instant::now();
+    while is_file_growing(path, GROWTH_CHECK_INTERVAL).await {
+        if start.elapsed() >= max_wait {
+            return Err(format!(
+                "{}: {} is still being written; stop the recording first",
+                FILE_STILL_BEING_WRITTEN, path
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// 创建临时目录用于存放中间文件
 ///
 /// 使用系统临时目录下的 clipforge 子目录，
@@ -142,3 +205,137 @@ pub fn temp_file_path(prefix: &str, extension: &str) -> Result<String, String> {
 pub fn cleanup_temp_file(path: &str) {
     let _ = std::fs::remove_file(path);
 }
+
+/// 清理两遍编码的统计文件
+///
+/// ffmpeg 以 `<passlogfile>-<流序号>.log` 命名统计文件，x264 还会生成 `.log.mbtree`，
+/// 删除与前缀同目录下所有以 `<前缀文件名>-` 开头的文件
+///
+/// # 参数
+/// - `passlog_prefix` - 传给 -passlogfile 的前缀路径
+pub fn cleanup_passlog_files(passlog_prefix: &str) {
+    let prefix = Path::new(passlog_prefix);
+    let (Some(dir), Some(name)) = (prefix.parent(), prefix.file_name()) else {
+        return;
+    };
+    let pattern = format!("{}-", name.to_string_lossy());
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&pattern) {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// 清理临时目录
+///
+/// 递归删除指定的临时目录及其内容，忽略删除失败的错误
+///
+/// # 参数
+/// - `path` - 要删除的临时目录路径
+pub fn cleanup_temp_dir(path: &str) {
+    let _ = std::fs::remove_dir_all(path);
+}
+
+/// 写入多个文件的输出格式（播放列表 + 分片），无法整体原子替换
+const MULTI_FILE_OUTPUT_EXTENSIONS: &[&str] = &["m3u8", "mpd"];
+
+/// 生成与输出文件同目录的临时写入路径，输出不适合原子替换时返回 None
+///
+/// 临时文件名为 `.<文件名>.<唯一标识>.part.<扩展名>`：保留扩展名让 ffmpeg 推断输出格式，
+/// 以点开头在 Finder 中隐藏。图片序列（含 `%`）和 HLS/DASH 等多文件输出不适用
+///
+/// # 参数
+/// - `output_path` - 最终输出文件路径
+pub fn atomic_temp_path(output_path: &str) -> Option<String> {
+    let extension = file_extension(output_path);
+    if output_path.is_empty()
+        || output_path.contains('%')
+        || MULTI_FILE_OUTPUT_EXTENSIONS.contains(&extension.as_str())
+    {
+        return None;
+    }
+    let path = Path::new(output_path);
+    let name = path.file_name()?.to_string_lossy();
+    let unique_id = uuid::Uuid::new_v4().to_string();
+    let temp_name = format!(".{}.{}.part.{}", name, &unique_id[..8], extension);
+    Some(path.with_file_name(temp_name).to_string_lossy().to_string())
+}
+
+/// 把临时写入的文件移动到最终输出路径
+///
+/// 同一文件系统上 rename 是原子操作，已存在的输出文件被整体替换；
+/// 跨文件系统（rename 失败）时回退为复制后删除临时文件
+///
+/// # 参数
+/// - `temp_path` - 临时写入路径
+/// - `output_path` - 最终输出文件路径
+///
+/// # 返回
+/// - `Ok(())` - 移动成功
+/// - `Err(String)` - 移动失败（临时文件已清理）
+pub fn commit_temp_output(temp_path: &str, output_path: &str) -> Result<(), String> {
+    if std::fs::rename(temp_path, output_path).is_ok() {
+        return Ok(());
+    }
+    let copied = std::fs::copy(temp_path, output_path);
+    cleanup_temp_file(temp_path);
+    copied
+        .map(|_| ())
+        .map_err(|e| format!("Failed to move output into place at {}: {}", output_path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// 在后台每 50 ms 向文件追加一次数据，持续 `duration`
+    fn spawn_writer(path: String, duration: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let start = Instant::now();
+            let mut file = std::fs::File::options().append(true).open(&path).unwrap();
+            while start.elapsed() < duration {
+                file.write_all(&[0u8; 1024]).unwrap();
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn settled_file_passes_immediately() {
+        let path = temp_file_path("settled", "mp4").unwrap();
+        std::fs::write(&path, [0u8; 1024]).unwrap();
+        assert!(ensure_file_settled(&path, Duration::ZERO).await.is_ok());
+        cleanup_temp_file(&path);
+    }
+
+    #[tokio::test]
+    async fn growing_file_is_refused_without_wait() {
+        let path = temp_file_path("growing", "mp4").unwrap();
+        std::fs::write(&path, []).unwrap();
+        let writer = spawn_writer(path.clone(), Duration::from_secs(2));
+
+        let error = ensure_file_settled(&path, Duration::ZERO).await.unwrap_err();
+        assert!(error.starts_with(FILE_STILL_BEING_WRITTEN), "{}", error);
+
+        writer.abort();
+        cleanup_temp_file(&path);
+    }
+
+    #[tokio::test]
+    async fn waits_until_file_stops_growing() {
+        let path = temp_file_path("recording", "mp4").unwrap();
+        std::fs::write(&path, []).unwrap();
+        let writer = spawn_writer(path.clone(), Duration::from_millis(800));
+
+        let start = Instant::now();
+        assert!(ensure_file_settled(&path, Duration::from_secs(10)).await.is_ok());
+        // 至少经历一次检测到增长后的重新检查
+        assert!(start.elapsed() >= GROWTH_CHECK_INTERVAL * 2);
+
+        writer.await.unwrap();
+        cleanup_temp_file(&path);
+    }
+}
@@ -0,0 +1,194 @@
+/// 时间片段校验工具
+///
+/// 裁剪、GIF 等按时间片段处理的功能共用同一套校验规则：
+/// 起点非负、终点大于起点、按时间顺序排列、互不重叠、不超出媒体时长
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::models::preset::TimeSegment;
+
+/// 允许的浮点误差（秒），避免前端取整导致的误报
+const EPSILON: f64 = 0.001;
+
+/// 超出媒体时长时的处理策略
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SegmentPolicy {
+    /// 超出范围直接报错
+    Strict,
+    /// 把起点截到 0、终点截到媒体时长；截断后为空的片段仍然报错
+    Clamp,
+}
+
+/// 时间片段校验错误
+///
+/// 索引均为片段在原列表中的下标（从 0 开始），前端据此定位出错的行。
+/// 序列化为带 code 的对象（如 `{ "code": "emptySegment", "i": 2 }`）
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "code", rename_all = "camelCase")]
+pub enum SegmentError {
+    /// 片段列表为空
+    NoSegments,
+    /// 终点不大于起点
+    EmptySegment { i: usize },
+    /// 起点为负或超出媒体时长
+    SegmentOutOfRange { i: usize },
+    /// 片段 j 与其前一个片段 i 重叠
+    OverlappingSegments { i: usize, j: usize },
+    /// 片段 i 的起点早于前一个片段
+    UnsortedSegments { i: usize },
+}
+
+impl fmt::Display for SegmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SegmentError::NoSegments => write!(f, "At least one segment is required"),
+            SegmentError::EmptySegment { i } => {
+                write!(f, "Segment {} ends before it starts", i + 1)
+            }
+            SegmentError::SegmentOutOfRange { i } => {
+                write!(f, "Segment {} is outside the media duration", i + 1)
+            }
+            SegmentError::OverlappingSegments { i, j } => {
+                write!(f, "Segments {} and {} overlap", i + 1, j + 1)
+            }
+            SegmentError::UnsortedSegments { i } => {
+                write!(f, "Segment {} starts before the previous segment", i + 1)
+            }
+        }
+    }
+}
+
+/// 校验并规整时间片段列表
+///
+/// 按列表顺序逐个检查，遇到第一个问题即返回对应错误。
+/// `duration` 为 0（时长未知）时跳过时长范围检查
+///
+/// # 参数
+/// - `segments` - 待校验的片段列表
+/// - `duration` - 媒体总时长（秒）
+/// - `policy` - 超出时长时的处理策略
+///
+/// # 返回
+/// - `Ok(Vec<TimeSegment>)` - 校验通过（Clamp 策略下可能已截断）的片段列表
+/// - `Err(SegmentError)` - 第一个不合法的片段
+pub fn validate_segments(
+    segments: &[TimeSegment],
+    duration: f64,
+    policy: SegmentPolicy,
+) -> Result<Vec<TimeSegment>, SegmentError> {
+    if segments.is_empty() {
+        return Err(SegmentError::NoSegments);
+    }
+
+    let mut result: Vec<TimeSegment> = Vec::with_capacity(segments.len());
+    for (i, segment) in segments.iter().enumerate() {
+        if !segment.start.is_finite() || !segment.end.is_finite() || segment.end <= segment.start {
+            return Err(SegmentError::EmptySegment { i });
+        }
+
+        let mut start = segment.start;
+        let mut end = segment.end;
+        let below = start < 0.0;
+        let beyond = duration > 0.0 && end > duration + EPSILON;
+        if below || beyond {
+            if policy == SegmentPolicy::Strict {
+                return Err(SegmentError::SegmentOutOfRange { i });
+            }
+            start = start.max(0.0);
+            if duration > 0.0 {
+                end = end.min(duration);
+            }
+            if end - start < EPSILON {
+                return Err(SegmentError::SegmentOutOfRange { i });
+            }
+        }
+
+        if let Some(prev) = result.last() {
+            if start < prev.start {
+                return Err(SegmentError::UnsortedSegments { i });
+            }
+            if start < prev.end - EPSILON {
+                return Err(SegmentError::OverlappingSegments { i: i - 1, j: i });
+            }
+        }
+
+        result.push(TimeSegment { start, end });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(ranges: &[(f64, f64)]) -> Vec<TimeSegment> {
+        ranges
+            .iter()
+            .map(|&(start, end)| TimeSegment { start, end })
+            .collect()
+    }
+
+    #[test]
+    fn accepts_sorted_disjoint_segments() {
+        let input = segments(&[(0.0, 2.0), (2.0, 4.0), (6.0, 9.5)]);
+        assert_eq!(validate_segments(&input, 10.0, SegmentPolicy::Strict), Ok(input));
+    }
+
+    #[test]
+    fn reports_first_invalid_segment_index() {
+        assert_eq!(
+            validate_segments(&[], 10.0, SegmentPolicy::Strict),
+            Err(SegmentError::NoSegments)
+        );
+        assert_eq!(
+            validate_segments(&segments(&[(0.0, 1.0), (3.0, 3.0)]), 10.0, SegmentPolicy::Strict),
+            Err(SegmentError::EmptySegment { i: 1 })
+        );
+        assert_eq!(
+            validate_segments(&segments(&[(0.0, 3.0), (2.0, 4.0)]), 10.0, SegmentPolicy::Strict),
+            Err(SegmentError::OverlappingSegments { i: 0, j: 1 })
+        );
+        assert_eq!(
+            validate_segments(&segments(&[(5.0, 6.0), (1.0, 2.0)]), 10.0, SegmentPolicy::Strict),
+            Err(SegmentError::UnsortedSegments { i: 1 })
+        );
+        assert_eq!(
+            validate_segments(&segments(&[(8.0, 12.0)]), 10.0, SegmentPolicy::Strict),
+            Err(SegmentError::SegmentOutOfRange { i: 0 })
+        );
+    }
+
+    #[test]
+    fn clamp_policy_trims_to_media_duration() {
+        let input = segments(&[(-1.0, 2.0), (8.0, 12.0)]);
+        assert_eq!(
+            validate_segments(&input, 10.0, SegmentPolicy::Clamp),
+            Ok(segments(&[(0.0, 2.0), (8.0, 10.0)]))
+        );
+        assert_eq!(
+            validate_segments(&segments(&[(10.0, 12.0)]), 10.0, SegmentPolicy::Clamp),
+            Err(SegmentError::SegmentOutOfRange { i: 0 })
+        );
+    }
+
+    #[test]
+    fn unknown_duration_skips_range_check() {
+        let input = segments(&[(100.0, 200.0)]);
+        assert_eq!(validate_segments(&input, 0.0, SegmentPolicy::Strict), Ok(input));
+    }
+
+    #[test]
+    fn serializes_with_code_and_indices() {
+        assert_eq!(
+            serde_json::to_value(SegmentError::OverlappingSegments { i: 0, j: 1 }).unwrap(),
+            serde_json::json!({ "code": "overlappingSegments", "i": 0, "j": 1 })
+        );
+        assert_eq!(
+            serde_json::to_value(SegmentError::NoSegments).unwrap(),
+            serde_json::json!({ "code": "noSegments" })
+        );
+    }
+}
@@ -16,6 +16,7 @@
 ///
 /// # 示例
 /// ```
+/// # use clipforge_lib::utils::time::seconds_to_timestamp;
 /// assert_eq!(seconds_to_timestamp(5405.5), "01:30:05.500");
 /// assert_eq!(seconds_to_timestamp(0.0), "00:00:00.000");
 /// ```
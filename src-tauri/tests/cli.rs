@@ -0,0 +1,97 @@
+// clipforge-cli 命令行入口的集成测试
+//
+// 直接运行编译好的 clipforge-cli 可执行文件；需要 ffmpeg/ffprobe 的用例
+// 用 lavfi testsrc 现场生成测试片段，PATH 中没有 ffmpeg/ffprobe 时跳过
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// 以给定参数运行 clipforge-cli
+fn run_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_clipforge-cli"))
+        .args(args)
+        .output()
+        .expect("failed to run clipforge-cli")
+}
+
+/// 创建本用例独占的临时目录
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("clipforge_cli_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn help_prints_usage_and_exits_with_code_2() {
+    let output = run_cli(&["--help"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Usage: clipforge-cli <operation>"), "{}", stderr);
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn missing_explicit_ffmpeg_is_reported() {
+    let output = run_cli(&["convert", "params.json", "--ffmpeg", "/nonexistent/ffmpeg"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("ffmpeg not found at /nonexistent/ffmpeg"), "{}", stderr);
+}
+
+/// 在 PATH 中查找工具，找不到时返回 None
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os("PATH")
+        .iter()
+        .flat_map(std::env::split_paths)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+#[test]
+fn convert_generated_clip_streams_json_events() {
+    let (Some(ffmpeg), Some(_)) = (find_in_path("ffmpeg"), find_in_path("ffprobe")) else {
+        eprintln!("skipping: ffmpeg/ffprobe not found in PATH");
+        return;
+    };
+    let dir = temp_dir();
+    let input_path = dir.join("in.mp4");
+    let generated = Command::new(&ffmpeg)
+        .args(["-y", "-hide_banner", "-loglevel", "error"])
+        .args(["-f", "lavfi", "-i", "testsrc=duration=2:size=320x240:rate=25"])
+        .args(["-pix_fmt", "yuv420p"])
+        .arg(&input_path)
+        .status()
+        .expect("failed to run ffmpeg");
+    assert!(generated.success(), "failed to generate the test clip");
+
+    let output_path = dir.join("out.mkv");
+    let params = dir.join("params.json");
+    let params_json = serde_json::json!({
+        "inputPath": input_path,
+        "outputPath": output_path,
+        "outputFormat": "mkv",
+        "videoCodec": "mpeg4",
+        "audioCodec": "aac",
+    });
+    std::fs::write(&params, params_json.to_string()).unwrap();
+
+    let output = run_cli(&["convert", params.to_str().unwrap()]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let names: Vec<&str> = events.iter().map(|e| e["event"].as_str().unwrap()).collect();
+    assert_eq!(names.first(), Some(&"started"), "{}", stdout);
+    assert_eq!(names.last(), Some(&"completed"), "{}", stdout);
+    let total_duration = events[0]["data"]["totalDuration"].as_f64().unwrap();
+    assert!((total_duration - 2.0).abs() < 0.1, "{}", total_duration);
+
+    let output_size = std::fs::metadata(&output_path).unwrap().len();
+    assert!(output_size > 0);
+    assert_eq!(events.last().unwrap()["data"]["outputSize"], output_size);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
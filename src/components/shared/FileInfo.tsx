@@ -68,7 +68,11 @@ export function FileInfo({ mediaInfo, className }: FileInfoProps) {
       { label: t('file.videoCodec'), value: video.codecName.toUpperCase() },
     );
     if (video.bitrate) {
-      items.push({ label: t('file.videoBitrate'), value: formatBitrate(video.bitrate) });
+      const bitrate = formatBitrate(video.bitrate);
+      items.push({
+        label: t('file.videoBitrate'),
+        value: video.bitrateEstimated ? `≈ ${bitrate}` : bitrate,
+      });
     }
   }
 
@@ -5,6 +5,7 @@
 import { useState, useCallback, useRef } from 'react';
 import type { TaskStatus, ProgressUpdate, TaskEvent } from '@/types/task';
 import { cancelTask } from '@/services/ffmpeg';
+import { formatCommandError } from '@/lib/format';
 
 /** 单个批量任务项 */
 export interface BatchItem {
@@ -162,7 +163,7 @@ export function useBatchTask(): UseBatchTaskReturn {
           }).catch(reject);
         });
       } catch (err) {
-        const message = err instanceof Error ? err.message : String(err);
+        const message = formatCommandError(err);
         setItems((prev) =>
           prev.map((item) =>
             item.id === task.id ? { ...item, status: 'failed', error: message } : item
@@ -8,6 +8,7 @@ import { useState, useCallback, useRef } from 'react';
 import type { TaskStatus, ProgressUpdate, TaskEvent, TaskResult } from '@/types/task';
 import { cancelTask } from '@/services/ffmpeg';
 import { revealInFinder } from '@/services/files';
+import { formatCommandError } from '@/lib/format';
 import { useSettingsStore } from '@/stores/useSettingsStore';
 import { useTaskStore } from '@/stores/useTaskStore';
 
@@ -94,9 +95,16 @@ export function useTask(taskType: string = 'unknown'): UseTaskReturn {
     try {
       const taskId = await serviceFn(params, (event: TaskEvent) => {
         switch (event.event) {
+          case 'queued':
+            /* 并发名额已满：记录任务 ID 以便排队期间也能取消 */
+            taskIdRef.current = event.data.taskId;
+            setStatus('pending');
+            updateStatus(tempId, 'pending');
+            break;
           case 'started':
             /* Store task ID immediately so cancel() can use it while task is running */
             taskIdRef.current = event.data.taskId;
+            setStatus('running');
             /* Replace temp ID with real task ID in global store */
             removeTask(tempId);
             addTask({
@@ -145,12 +153,17 @@ export function useTask(taskType: string = 'unknown'): UseTaskReturn {
             setStatus('cancelled');
             updateStatus(taskIdRef.current || tempId, 'cancelled');
             break;
+          case 'paused':
+            /* 暂停不是失败：保留任务记录，可按同一 taskId 继续 */
+            setStatus('paused');
+            updateStatus(event.data.taskId, 'paused');
+            break;
         }
       });
       taskIdRef.current = taskId;
     } catch (err) {
       setStatus('failed');
-      const message = err instanceof Error ? err.message : String(err);
+      const message = formatCommandError(err);
       setError(message);
       /* Update global store on execution error */
       updateStatus(tempId, 'failed', message);
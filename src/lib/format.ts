@@ -161,3 +161,19 @@ export function generateOutputName(inputName: string, suffix: string, newExtensi
   const ext = newExtension || (lastDot > 0 ? inputName.slice(lastDot + 1) : 'mp4');
   return `${baseName}${suffix}.${ext}`;
 }
+
+/**
+ * 提取 command 错误的描述文本
+ *
+ * command 错误可能是字符串、Error 或带 message 字段的结构化错误（如片段校验错误）
+ *
+ * @param err - invoke 抛出的错误
+ * @returns 面向用户的错误描述
+ */
+export function formatCommandError(err: unknown): string {
+  if (err instanceof Error) return err.message;
+  if (typeof err === 'object' && err !== null && 'message' in err) {
+    return String((err as { message: unknown }).message);
+  }
+  return String(err);
+}
@@ -59,8 +59,21 @@ export interface DownloadParams {
   formatId: string;
   /** 输出文件路径 */
   outputPath: string;
+  /** 保留原始容器，不修正扩展名为 mp4 的 MPEG-TS 文件（默认 false） */
+  keepRawContainer?: boolean;
+  /** 下载后转换到的容器格式，输出扩展名随之改变 */
+  remuxTo?: 'mp4' | 'mkv' | 'mov' | 'webm' | 'avi' | 'flv';
+  /** 源编码无法直接封装进 remuxTo 容器时允许重新编码（默认 false） */
+  allowRecode?: boolean;
+  /** 所选格式的 vcodec / acodec，用于判断能否流复制 */
+  sourceCodecs?: string[];
+  /** 以可续传模式下载（保留 .part 文件），任务可暂停后继续（默认 false） */
+  resumable?: boolean;
 }
 
+/** 容器转换失败（文件已下载并以原格式保留）时错误信息的前缀 */
+export const REMUX_FAILED = 'RemuxFailed';
+
 /**
  * 解析视频 URL，获取视频信息和可用格式
  *
@@ -86,3 +99,28 @@ export async function downloadVideo(
   channel.onmessage = onEvent;
   return invoke<string>('download_video', { params, onProgress: channel });
 }
+
+/**
+ * 暂停可续传的下载（保留已下载的部分）
+ *
+ * @param taskId - 下载任务 ID
+ */
+export async function pauseDownload(taskId: string): Promise<void> {
+  return invoke<void>('pause_download', { taskId });
+}
+
+/**
+ * 继续已暂停的下载（沿用原 taskId）
+ *
+ * @param taskId - 已暂停的下载任务 ID
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function resumeDownload(
+  taskId: string,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = new Channel<TaskEvent>();
+  channel.onmessage = onEvent;
+  return invoke<string>('resume_download', { taskId, onProgress: channel });
+}
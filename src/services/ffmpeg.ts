@@ -5,7 +5,8 @@
  * 所有操作函数接收参数对象和事件回调，通过 Tauri Channel 接收进度推送
  */
 import { invoke, Channel } from '@tauri-apps/api/core';
-import type { TaskEvent } from '@/types/task';
+import type { CommandPreview, ScheduledTask, SizeEstimate, TaskEvent } from '@/types/task';
+import type { WatermarkPosition } from '@/types/presets';
 
 /**
  * 创建 Tauri Channel 并绑定事件回调
@@ -30,6 +31,28 @@ export interface ConvertParams {
   preset?: string;
   hardwareAccel?: boolean;
   extraArgs?: string[];
+  audioBitrate?: string;
+  /** 音频采样率（Hz），不填沿用源音频 */
+  audioSampleRate?: number;
+  colorRange?: string;
+  /** 输出像素格式（yuv420p / yuv420p10le / yuv422p10le / yuva420p），不填由编码器决定 */
+  pixelFormat?: string;
+  videoBitrate?: string;
+  twoPass?: boolean;
+  /** 保留全局元数据、章节和流语言标签（默认 true） */
+  preserveMetadata?: boolean;
+  /** 是否去隔行，不填时按源视频场序自动判断 */
+  deinterlace?: boolean;
+  /** 是否按旋转元数据转正画面（默认 true），关闭时保留原始画面和旋转标记 */
+  autoRotate?: boolean;
+  /** 流媒体分发输出（2 秒固定 GOP、closed GOP、yuv420p、faststart），仅 MP4/MOV 重编码 */
+  streaming?: boolean;
+  /** 固定关键帧间隔（帧数），streaming 时不填按源帧率计算 */
+  gopSize?: number;
+  /** 要保留的音轨（在 MediaInfo.audioStreams 中的位置，按顺序输出），不填时只输出默认音轨 */
+  audioStreamIndexes?: number[];
+  /** 保留全部音轨和字幕轨 */
+  keepAllStreams?: boolean;
 }
 
 /**
@@ -47,6 +70,68 @@ export async function convertVideo(
   return invoke<string>('convert_video', { params, onProgress: channel });
 }
 
+/**
+ * 批量执行格式转换
+ *
+ * 子任务按设置中的最大并发数执行，各自的事件按 taskId 区分，
+ * 另有汇总的 batchProgress 事件
+ *
+ * @param jobs - 每个文件的转换参数
+ * @param onEvent - 进度事件回调（全部子任务共用）
+ * @returns 与 jobs 顺序一一对应的子任务 ID
+ */
+export async function runBatch(
+  jobs: ConvertParams[],
+  onEvent: (event: TaskEvent) => void,
+): Promise<string[]> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string[]>('run_batch', { jobs, onProgress: channel });
+}
+
+/** 多分辨率输出中的一档 */
+export interface ResolutionVariant {
+  /** 输出高度（像素，不能高于源视频） */
+  height: number;
+  /** 输出文件名后缀，缺省为 "_<高度>p" */
+  suffix?: string;
+}
+
+/** 多分辨率输出参数 */
+export interface MultiResolutionParams {
+  inputPath: string;
+  outputDir: string;
+  variants: ResolutionVariant[];
+  /** 输出格式（mp4 / mov / mkv，默认 mp4） */
+  outputFormat?: string;
+  quality?: number;
+  preset?: string;
+  audioBitrate?: string;
+}
+
+/** 多分辨率输出结果 */
+export interface MultiResolutionResult {
+  taskId: string;
+  outputs: Array<{ height: number; outputPath: string; outputSize: number }>;
+}
+
+/**
+ * 一次解码输出多个分辨率
+ *
+ * @param params - 多分辨率输出参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID 和各档输出
+ */
+export async function multiResolution(
+  params: MultiResolutionParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<MultiResolutionResult> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<MultiResolutionResult>('multi_resolution', { params, onProgress: channel });
+}
+
+/** 压缩时的画面内容类型（决定编码器 tune 和关键帧间隔） */
+export type ContentType = 'film' | 'animation' | 'screencast';
+
 /** 视频压缩参数 */
 export interface CompressParams {
   inputPath: string;
@@ -55,8 +140,20 @@ export interface CompressParams {
   targetSizeMb?: number;
   compressRatio?: number;
   qualityLevel?: number;
+  /** 视频编码器（libx264 / libx265，默认 libx264） */
+  videoCodec?: string;
   preset?: string;
   hardwareAccel?: boolean;
+  platform?: string;
+  audioBitrate?: string;
+  maxrateMultiplier?: number;
+  bufsizeMultiplier?: number;
+  strictCbr?: boolean;
+  twoPass?: boolean;
+  /** 是否去隔行，不填时按源视频场序自动判断 */
+  deinterlace?: boolean;
+  /** 画面内容类型，录屏选 screencast 可显著减小体积 */
+  contentType?: ContentType;
 }
 
 /**
@@ -81,6 +178,11 @@ export interface TrimParams {
   segments: Array<{ start: number; end: number }>;
   preciseCut: boolean;
   mergeSegments: boolean;
+  audioBitrate?: string;
+  quality?: number;
+  preset?: string;
+  /** 切点精度：fast（默认，快）/ frame（帧级精确，始终重编码，较慢） */
+  accuracy?: 'fast' | 'frame';
 }
 
 /**
@@ -89,6 +191,7 @@ export interface TrimParams {
  * @param params - 裁剪参数
  * @param onEvent - 进度事件回调
  * @returns 任务 ID
+ * @throws {CommandError} 片段不合法时为带 code 和片段下标的 SegmentError
  */
 export async function trimVideo(
   params: TrimParams,
@@ -98,6 +201,42 @@ export async function trimVideo(
   return invoke<string>('trim_video', { params, onProgress: channel });
 }
 
+/** 去除片头黑场参数 */
+export interface TrimLeadingBlackParams {
+  inputPath: string;
+  outputPath: string;
+  preciseCut: boolean;
+  /** 像素亮度阈值（0-1，默认 0.10） */
+  blackThreshold?: number;
+  /** 最短黑场时长（秒，默认 0.5） */
+  minBlackDuration?: number;
+  audioBitrate?: string;
+  quality?: number;
+  preset?: string;
+}
+
+/** 去除片头黑场结果 */
+export interface TrimLeadingBlackResult {
+  taskId: string;
+  /** 实际去除的时长（秒） */
+  trimmedSeconds: number;
+}
+
+/**
+ * 检测并去除片头黑场
+ *
+ * @param params - 去除片头黑场参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID 和实际去除的时长
+ */
+export async function trimLeadingBlack(
+  params: TrimLeadingBlackParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<TrimLeadingBlackResult> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<TrimLeadingBlackResult>('trim_leading_black', { params, onProgress: channel });
+}
+
 /** 视频合并参数 */
 export interface MergeParams {
   inputPaths: string[];
@@ -106,6 +245,17 @@ export interface MergeParams {
   normalize: boolean;
   targetResolution?: string;
   targetFps?: number;
+  audioBitrate?: string;
+  quality?: number;
+  preset?: string;
+  audioOnlyCrossfade?: number;
+  minClipDuration?: number;
+  normalizeLoudness?: boolean;
+  loudnessTargetLufs?: number;
+  loudnessRange?: number;
+  truePeak?: number;
+  /** 归一化合并的输出声道数（1-8），不填时取输入中最多的声道数（至少立体声） */
+  targetChannels?: number;
 }
 
 /**
@@ -123,6 +273,65 @@ export async function mergeVideos(
   return invoke<string>('merge_videos', { params, onProgress: channel });
 }
 
+/** 片头片尾拼接参数 */
+export interface IntroOutroParams {
+  intro?: string;
+  /** 正片（决定输出分辨率和帧率） */
+  main: string;
+  outro?: string;
+  outputPath: string;
+  audioBitrate?: string;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 在正片前后拼接片头/片尾（统一到正片的分辨率和帧率）
+ *
+ * @param params - 片头片尾拼接参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function addIntroOutro(
+  params: IntroOutroParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('add_intro_outro', { params, onProgress: channel });
+}
+
+/** 图片幻灯片参数 */
+export interface SlideshowParams {
+  imagePaths: string[];
+  outputPath: string;
+  /** 每张图片的显示时长（秒） */
+  frameDuration: number;
+  fps: number;
+  width: number;
+  height: number;
+  transition?: TransitionConfig;
+  /** 背景音乐（视频与音乐以较短者为准） */
+  audioPath?: string;
+  audioBitrate?: string;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 把图片序列拼成幻灯片视频
+ *
+ * @param params - 幻灯片参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function createSlideshow(
+  params: SlideshowParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('create_slideshow', { params, onProgress: channel });
+}
+
 /** 音频处理参数 */
 export interface AudioParams {
   inputPath: string;
@@ -130,9 +339,14 @@ export interface AudioParams {
   mode: string;
   outputFormat?: string;
   replaceAudioPath?: string;
+  fitMode?: string;
+  audioBitrate?: string;
   volume?: number;
   volumeDb?: number;
   normalize?: boolean;
+  loudnessTargetLufs?: number;
+  loudnessRange?: number;
+  truePeak?: number;
   fadeIn?: number;
   fadeOut?: number;
 }
@@ -152,6 +366,40 @@ export async function processAudio(
   return invoke<string>('process_audio', { params, onProgress: channel });
 }
 
+/** 多格式音频提取参数 */
+export interface MultiFormatAudioParams {
+  inputPath: string;
+  /** 输出目录，空字符串表示与输入同目录 */
+  outputDir: string;
+  /** 输出格式（mp3 / aac / m4a / wav / flac），不可重复 */
+  formats: string[];
+  /** 音轨序号（从 0 开始，默认 0） */
+  audioTrack?: number;
+  /** 输出文件名后缀（默认 _audio） */
+  suffix?: string;
+}
+
+/** 多格式音频提取结果 */
+export interface MultiFormatAudioResult {
+  taskId: string;
+  outputs: Array<{ format: string; outputPath: string; outputSize: number }>;
+}
+
+/**
+ * 从同一条音轨一次提取多个格式（只解码一次）
+ *
+ * @param params - 多格式音频提取参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID 和各格式输出
+ */
+export async function extractMultiFormat(
+  params: MultiFormatAudioParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<MultiFormatAudioResult> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<MultiFormatAudioResult>('extract_multi_format', { params, onProgress: channel });
+}
+
 /** 水印参数 */
 export interface WatermarkParams {
   inputPath: string;
@@ -169,6 +417,10 @@ export interface WatermarkParams {
   position: string;
   offsetX?: number;
   offsetY?: number;
+  quality?: number;
+  preset?: string;
+  /** 是否去隔行，不填时按源视频场序自动判断 */
+  deinterlace?: boolean;
 }
 
 /**
@@ -186,6 +438,33 @@ export async function addWatermark(
   return invoke<string>('add_watermark', { params, onProgress: channel });
 }
 
+/** 去除台标参数（区域坐标基于显示方向的画面） */
+export interface RemoveLogoParams {
+  inputPath: string;
+  outputPath: string;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 去除台标（delogo 滤镜覆盖指定区域）
+ *
+ * @param params - 区域和编码参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function removeLogo(
+  params: RemoveLogoParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('remove_logo', { params, onProgress: channel });
+}
+
 /** 分辨率/帧率调整参数 */
 export interface ResizeParams {
   inputPath: string;
@@ -196,6 +475,10 @@ export interface ResizeParams {
   scaleAlgorithm?: string;
   fps?: number;
   aspectMode?: string;
+  quality?: number;
+  preset?: string;
+  /** 是否去隔行，不填时按源视频场序自动判断 */
+  deinterlace?: boolean;
 }
 
 /**
@@ -213,6 +496,91 @@ export async function resizeVideo(
   return invoke<string>('resize_video', { params, onProgress: channel });
 }
 
+/** 视频变速参数 */
+export interface SpeedParams {
+  inputPath: string;
+  outputPath: string;
+  /** 视频倍率（0.25-8.0） */
+  videoSpeed: number;
+  /** 音频倍率（通常与 videoSpeed 相同） */
+  audioSpeed: number;
+  keepAudio: boolean;
+  /** 是否保持音高（默认 true，false 时音高随速度升降） */
+  keepAudioPitch?: boolean;
+  quality?: number;
+  preset?: string;
+  audioBitrate?: string;
+}
+
+/**
+ * 执行视频变速（慢放/快放）
+ *
+ * @param params - 变速参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function speedVideo(
+  params: SpeedParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('speed_video', { params, onProgress: channel });
+}
+
+/** 统一倍率变速参数（视频和音频同一倍率） */
+export interface ChangeSpeedParams {
+  inputPath: string;
+  outputPath: string;
+  /** 倍率（0.25-8.0，小于 1 为慢动作） */
+  factor: number;
+  /** 是否保持音高（false 时音高随速度升降） */
+  keepAudioPitch: boolean;
+  quality?: number;
+  preset?: string;
+  audioBitrate?: string;
+}
+
+/**
+ * 按统一倍率变速（慢动作/快进）
+ *
+ * @param params - 变速参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function changeSpeed(
+  params: ChangeSpeedParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('change_speed', { params, onProgress: channel });
+}
+
+/** 倒放参数（不倒放的流直接复制） */
+export interface ReverseParams {
+  inputPath: string;
+  outputPath: string;
+  reverseVideo: boolean;
+  reverseAudio: boolean;
+  quality?: number;
+  preset?: string;
+  audioBitrate?: string;
+}
+
+/**
+ * 倒放视频和/或音频（整条流缓存在内存中，长视频占用较大）
+ *
+ * @param params - 倒放参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function reverseVideo(
+  params: ReverseParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('reverse_video', { params, onProgress: channel });
+}
+
 /** GIF 制作参数 */
 export interface GifParams {
   inputPath: string;
@@ -225,6 +593,17 @@ export interface GifParams {
   maxColors?: number;
   dither?: string;
   quality?: string;
+  outputAsMp4?: boolean;
+  alsoGif?: boolean;
+  /** 输出为动画 WebP（扩展名自动改为 .webp，loopCount 语义不变） */
+  outputAsWebp?: boolean;
+  /** 估算体积超过设置上限时仍然生成 */
+  force?: boolean;
+  /** 叠加图片（如 logo），在生成调色板前叠加 */
+  overlayImage?: string;
+  overlayPosition?: WatermarkPosition;
+  /** 叠加图片宽度占输出宽度的比例（0-1，默认 0.2） */
+  overlayScale?: number;
 }
 
 /**
@@ -233,6 +612,7 @@ export interface GifParams {
  * @param params - GIF 参数
  * @param onEvent - 进度事件回调
  * @returns 任务 ID
+ * @throws {CommandError} 片段不合法时为带 code 和片段下标的 SegmentError
  */
 export async function createGif(
   params: GifParams,
@@ -242,6 +622,22 @@ export async function createGif(
   return invoke<string>('create_gif', { params, onProgress: channel });
 }
 
+/**
+ * 估算 GIF 输出体积
+ *
+ * @param width - 输出宽度
+ * @param fps - 输出帧率
+ * @param duration - 片段时长（秒）
+ * @returns 估算体积（字节）
+ */
+export async function estimateGifOutputSize(
+  width: number,
+  fps: number,
+  duration: number,
+): Promise<number> {
+  return invoke<number>('estimate_gif_output_size', { width, fps, duration });
+}
+
 /** 字幕处理参数 */
 export interface SubtitleParams {
   inputPath: string;
@@ -255,6 +651,9 @@ export interface SubtitleParams {
   primaryColor?: string;
   outlineWidth?: number;
   marginV?: number;
+  fontsDir?: string;
+  quality?: number;
+  preset?: string;
 }
 
 /**
@@ -273,10 +672,839 @@ export async function processSubtitle(
 }
 
 /**
- * 取消指定任务
+ * 移除全部字幕流（其余流直接复制）
  *
- * @param taskId - 要取消的任务 ID
+ * @param inputPath - 输入文件路径
+ * @param outputPath - 输出文件路径
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
  */
-export async function cancelTask(taskId: string): Promise<void> {
-  return invoke('cancel_task', { taskId });
+export async function stripSubtitles(
+  inputPath: string,
+  outputPath: string,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('strip_subtitles', { inputPath, outputPath, onProgress: channel });
+}
+
+/** 短视频平台一键导出参数 */
+export interface PlatformExportParams {
+  inputPath: string;
+  outputPath: string;
+  platform: string;
+  reframeMode?: string;
+  preset?: string;
+  loudnessTargetLufs?: number;
+  loudnessRange?: number;
+  truePeak?: number;
+}
+
+/** 平台导出实际采用的参数 */
+export interface PlatformExportSettings {
+  platform: string;
+  width: number;
+  height: number;
+  fps: number;
+  reframe: string;
+  videoCodec: string;
+  videoBitrate: string;
+  maxBitrate: string;
+  audioCodec: string;
+  audioBitrate: string;
+  sampleRate: number;
+  loudnessLufs: number;
+  pixFmt: string;
+  faststart: boolean;
+}
+
+/** 平台导出结果 */
+export interface PlatformExportResult {
+  taskId: string;
+  settings: PlatformExportSettings;
+}
+
+/**
+ * 按短视频平台规格一键导出（Instagram Reels / TikTok / YouTube Shorts）
+ *
+ * @param params - 平台导出参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID 和实际采用的导出参数
+ */
+export async function exportForPlatform(
+  params: PlatformExportParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<PlatformExportResult> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<PlatformExportResult>('export_for_platform', { params, onProgress: channel });
+}
+
+/** 自动方向校正参数 */
+export interface AutoOrientParams {
+  inputPath: string;
+  outputPath: string;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 按旋转元数据自动校正视频方向（无旋转时直接流复制）
+ *
+ * @param params - 方向校正参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function autoOrient(
+  params: AutoOrientParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('auto_orient', { params, onProgress: channel });
+}
+
+/** 手动旋转/翻转方式 */
+export type RotateAngle =
+  | 'Rotate90CW'
+  | 'Rotate90CCW'
+  | 'Rotate180'
+  | 'FlipHorizontal'
+  | 'FlipVertical';
+
+/** 手动旋转/翻转参数 */
+export interface RotateParams {
+  inputPath: string;
+  outputPath: string;
+  rotate: RotateAngle;
+  keepAudio: boolean;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 手动旋转或翻转视频（重新编码，输出不带旋转标记）
+ *
+ * @param params - 旋转/翻转参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function rotateVideo(
+  params: RotateParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('rotate_video', { params, onProgress: channel });
+}
+
+/** 旋转+翻转组合变换参数 */
+export interface TransformParams {
+  inputPath: string;
+  outputPath: string;
+  /** 顺时针旋转角度（90 的倍数，负数表示逆时针） */
+  rotate?: number;
+  /** 旋转后水平翻转 */
+  flipH?: boolean;
+  /** 旋转后垂直翻转 */
+  flipV?: boolean;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 旋转并翻转视频（一次重新编码，输出不带旋转标记）
+ *
+ * @param params - 变换参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function transformVideo(
+  params: TransformParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('transform_video', { params, onProgress: channel });
+}
+
+/** LUT 文件格式 */
+export type LutFormat = 'Cube' | '3dl';
+
+/** LUT 调色参数 */
+export interface LutParams {
+  inputPath: string;
+  outputPath: string;
+  lutPath: string;
+  /** 需与 LUT 文件扩展名一致 */
+  lutFormat: LutFormat;
+  /** 调色强度（0-1，1 为完全套用） */
+  intensity: number;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 套用 3D LUT 调色
+ *
+ * @param params - LUT 调色参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function applyLut(
+  params: LutParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('apply_lut', { params, onProgress: channel });
+}
+
+/** 视频防抖参数（对应 Rust StabilizeParams） */
+export interface StabilizeParams {
+  inputPath: string;
+  outputPath: string;
+  /** 抖动程度（1-10） */
+  shakiness: number;
+  /** 平滑帧数（1-100） */
+  smoothing: number;
+  /** 补偿后露出的边缘填黑，否则沿用上一帧画面 */
+  cropBlack: boolean;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 视频防抖（vidstabdetect 分析 + vidstabtransform 补偿）
+ *
+ * @param params - 防抖参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function stabilizeVideo(
+  params: StabilizeParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('stabilize_video', { params, onProgress: channel });
+}
+
+/** 绿幕抠像参数（对应 Rust ChromakeyParams） */
+export interface ChromakeyParams {
+  inputPath: string;
+  /** 背景视频或图片，不填时输出透明背景 */
+  backgroundPath?: string;
+  /** 无背景时为 .webm，或含帧序号占位符的 .png（如 frame_%04d.png） */
+  outputPath: string;
+  /** 抠除的颜色（#RRGGBB，默认 #00FF00） */
+  keyColor?: string;
+  /** 颜色相似度（0.01-1） */
+  similarity: number;
+  /** 边缘混合程度（0-1） */
+  blend: number;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 绿幕抠像（合成到背景上或输出透明视频）
+ *
+ * @param params - 抠像参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function applyChromakey(
+  params: ChromakeyParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('apply_chromakey', { params, onProgress: channel });
+}
+
+/** 画面裁剪参数（坐标基于显示方向的画面） */
+export interface CropParams {
+  inputPath: string;
+  outputPath: string;
+  x: number;
+  y: number;
+  /** 裁剪宽度，奇数时向下取偶 */
+  width: number;
+  /** 裁剪高度，奇数时向下取偶 */
+  height: number;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 裁剪画面中的任意矩形区域
+ *
+ * @param params - 裁剪参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function cropVideo(
+  params: CropParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('crop_video', { params, onProgress: channel });
+}
+
+/** 画面区域（各值为占画面宽/高的比例，0-1） */
+export interface ZoomRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+/** 缩放平移缓动曲线 */
+export type ZoomEasing = 'linear' | 'ease_in_out';
+
+/** 缩放平移（Ken Burns）参数 */
+export interface ZoomPanParams {
+  inputPath: string;
+  outputPath: string;
+  startRect: ZoomRect;
+  endRect: ZoomRect;
+  easing: ZoomEasing;
+  /** 视频片段起点（秒），图片输入时忽略 */
+  startTime?: number;
+  /** 片段时长（秒），视频默认到结尾，图片默认 5 秒 */
+  duration?: number;
+  /** 图片输入的输出帧率，默认 30 */
+  fps?: number;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 缩放平移（Ken Burns）：从起始区域平滑过渡到结束区域
+ *
+ * @param params - 缩放平移参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function zoomPan(
+  params: ZoomPanParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('zoom_pan', { params, onProgress: channel });
+}
+
+/**
+ * 预览缩放平移的起始或结束画面
+ *
+ * @param params - 缩放平移参数
+ * @param atEnd - true 时预览结束区域
+ * @returns 预览令牌（通过 get_preview 读取 PNG）
+ */
+export async function zoomPanPreview(params: ZoomPanParams, atEnd: boolean): Promise<string> {
+  return invoke<string>('zoom_pan_preview', { params, atEnd });
+}
+
+/** 去隔行滤镜 */
+export type DeinterlaceMethod = 'yadif' | 'bwdif';
+
+/** 去隔行输出模式（sendField 每场输出一帧，帧率翻倍） */
+export type DeinterlaceMode = 'sendFrame' | 'sendField';
+
+/** 去隔行参数 */
+export interface DeinterlaceParams {
+  inputPath: string;
+  outputPath: string;
+  method: DeinterlaceMethod;
+  mode: DeinterlaceMode;
+  /** 场序（-1 自动，0 顶场优先，1 底场优先），不填自动检测 */
+  parity?: number;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 去隔行（yadif / bwdif）
+ *
+ * @param params - 去隔行参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function deinterlaceVideo(
+  params: DeinterlaceParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('deinterlace_video', { params, onProgress: channel });
+}
+
+/** 降噪滤镜（nlmeans 细节保留更好但慢得多） */
+export type DenoiseMethod = 'hqdn3d' | 'nlmeans';
+
+/** 降噪参数 */
+export interface DenoiseParams {
+  inputPath: string;
+  outputPath: string;
+  method: DenoiseMethod;
+  /** 降噪强度（0.0-1.0） */
+  strength: number;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * 降噪（hqdn3d / nlmeans）
+ *
+ * @param params - 降噪参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function denoiseVideo(
+  params: DenoiseParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('denoise_video', { params, onProgress: channel });
+}
+
+/** 色调映射算法（hable 最常用） */
+export type TonemapAlgorithm = 'hable' | 'mobius' | 'reinhard' | 'clip';
+
+/** HDR 转 SDR 参数 */
+export interface HdrToSdrParams {
+  inputPath: string;
+  outputPath: string;
+  tonemapAlgorithm: TonemapAlgorithm;
+  /** 目标色域原色（bt709 / smpte170m / bt470bg），默认 bt709 */
+  targetPrimaries?: string;
+  /** 目标传输特性（bt709 / smpte170m / iec61966-2-1），默认 bt709 */
+  targetTrc?: string;
+  quality?: number;
+  preset?: string;
+}
+
+/**
+ * HDR 转 SDR（zscale + tonemap 色调映射）
+ *
+ * @param params - HDR 转 SDR 参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function hdrToSdr(
+  params: HdrToSdrParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('hdr_to_sdr', { params, onProgress: channel });
+}
+
+/** 支持命令预览的功能 */
+export type PreviewFeature =
+  | 'convert'
+  | 'compress'
+  | 'gif'
+  | 'resize'
+  | 'multiResolution'
+  | 'trim'
+  | 'merge'
+  | 'introOutro'
+  | 'slideshow'
+  | 'audio'
+  | 'extractMultiFormat'
+  | 'watermark'
+  | 'removeLogo'
+  | 'crop'
+  | 'zoomPan'
+  | 'deinterlace'
+  | 'denoise'
+  | 'hdrToSdr'
+  | 'speed'
+  | 'changeSpeed'
+  | 'reverse'
+  | 'subtitle'
+  | 'stripSubtitles'
+  | 'thumbnail'
+  | 'thumbnails'
+  | 'extractAttachments'
+  | 'platformExport'
+  | 'autoOrient'
+  | 'rotate'
+  | 'transform'
+  | 'lut'
+  | 'stabilize'
+  | 'chromakey'
+  | 'metadata'
+  | 'embedChapters'
+  | 'analyzeDefects'
+  | 'streaming';
+
+/**
+ * 预览 ffmpeg 命令（不执行）
+ *
+ * 与实际执行共用参数准备和 ffprobe 探测，返回的命令与实际执行一致
+ *
+ * @param feature - 功能名
+ * @param params - 对应功能的参数（与实际调用的参数相同）
+ * @returns 每一遍编码的命令预览
+ */
+export async function previewCommand(
+  feature: PreviewFeature,
+  params: unknown,
+): Promise<CommandPreview[]> {
+  return invoke<CommandPreview[]>('preview_command', { feature, params });
+}
+
+/** 支持体积估算的功能 */
+export type EstimateFeature = 'convert' | 'compress';
+
+/**
+ * 估算输出体积（开始转换/压缩前）
+ *
+ * 与实际执行共用参数准备和 ffprobe 探测，按码率或 CRF 经验表估算
+ *
+ * @param feature - 功能名
+ * @param params - 对应功能的参数（与实际调用的参数相同）
+ * @returns 估算体积、可信度和参与计算的码率/CRF
+ */
+export async function estimateOutputSize(
+  feature: EstimateFeature,
+  params: unknown,
+): Promise<SizeEstimate> {
+  return invoke<SizeEstimate>('estimate_output_size', { feature, params });
+}
+
+/**
+ * 生成时间轴预览缩略图
+ *
+ * @param inputPath - 输入视频路径
+ * @param timestamps - 时间点列表（秒）
+ * @param width - 缩略图宽度
+ * @returns 与 timestamps 一一对应的预览令牌（通过 getPreview 读取 PNG）
+ */
+export async function scrubThumbnails(
+  inputPath: string,
+  timestamps: number[],
+  width: number,
+): Promise<string[]> {
+  return invoke<string[]>('scrub_thumbnails', { inputPath, timestamps, width });
+}
+
+/** 画面缺陷检查参数 */
+export interface DefectAnalysisParams {
+  inputPath: string;
+  /** 黑场像素亮度阈值（0-1，默认 0.10） */
+  blackThreshold?: number;
+  /** 静止画面噪声容差（0-1，默认 0.001） */
+  freezeNoise?: number;
+  /** 最短上报时长（秒，默认 2） */
+  minDuration?: number;
+  /** 是否截取每段缺陷的起始帧 */
+  screenshots?: boolean;
+}
+
+/** 一段检测到的缺陷 */
+export interface DefectRegion {
+  start: number;
+  end: number;
+  type: 'black' | 'freeze';
+  /** 起始帧截图的预览令牌（通过 getPreview 读取 PNG） */
+  screenshot: string | null;
+}
+
+/** 画面缺陷检查报告 */
+export interface DefectReport {
+  taskId: string;
+  defects: DefectRegion[];
+}
+
+/**
+ * 检查视频中的黑场和静止画面
+ *
+ * @param params - 检查参数
+ * @param onEvent - 进度事件回调
+ * @returns 缺陷报告
+ */
+export async function analyzeDefects(
+  params: DefectAnalysisParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<DefectReport> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<DefectReport>('analyze_defects', { params, onProgress: channel });
+}
+
+/** 缩略图提取参数（指定 count 时生成拼图，否则截取单帧） */
+export interface ThumbnailParams {
+  inputPath: string;
+  outputPath: string;
+  /** 单帧时间点（秒），默认 0 */
+  timestamp?: number;
+  /** 拼图中的缩略图数量 */
+  count?: number;
+  /** 输出宽度（拼图中为单张宽度） */
+  width?: number;
+  format: 'jpg' | 'png';
+}
+
+/**
+ * 提取单帧截图或等间隔缩略图拼图
+ *
+ * @param params - 缩略图参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function extractThumbnail(
+  params: ThumbnailParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('extract_thumbnail', { params, onProgress: channel });
+}
+
+/** 批量缩略图取帧方式 */
+export type ThumbnailMode = 'byCount' | 'byInterval';
+
+/** 批量缩略图导出参数（输出 thumb_0001.jpg 起编号的 JPG 文件） */
+export interface ThumbnailSeriesParams {
+  inputPath: string;
+  /** 输出目录（不存在时自动创建） */
+  outputDir: string;
+  mode: ThumbnailMode;
+  /** 缩略图数量（byCount 必填） */
+  count?: number;
+  /** 取帧间隔（秒，byInterval 必填） */
+  intervalSec?: number;
+  /** 输出宽度，未指定时保持原尺寸 */
+  width?: number;
+}
+
+/**
+ * 批量导出缩略图（故事板）
+ *
+ * @param params - 批量缩略图参数
+ * @param onEvent - 进度事件回调
+ * @returns 按时间顺序排列的缩略图文件路径
+ */
+export async function extractThumbnails(
+  params: ThumbnailSeriesParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string[]> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string[]>('extract_thumbnails', { params, onProgress: channel });
+}
+
+/** 悬停预览短片参数 */
+export interface PreviewClipParams {
+  inputPath: string;
+  /** 输出路径（mp4 / m4v / mov / mkv） */
+  outputPath: string;
+  /** 片段数量，默认 6 */
+  snippetCount?: number;
+  /** 预览总时长（秒），默认 6 */
+  totalDuration?: number;
+  /** 输出宽度，默认 480 */
+  width?: number;
+}
+
+/**
+ * 生成悬停预览短片（等间隔截取若干片段拼成无声短片）
+ *
+ * @param params - 悬停预览参数
+ * @param onEvent - 进度事件回调
+ * @returns 任务 ID
+ */
+export async function previewClip(
+  params: PreviewClipParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<string> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<string>('preview_clip', { params, onProgress: channel });
+}
+
+/**
+ * 按令牌读取预览文件内容
+ *
+ * 令牌在同一功能生成新预览或超时（10 分钟）后失效
+ *
+ * @param token - 预览类操作返回的令牌
+ * @returns 文件原始字节
+ */
+export async function getPreview(token: string): Promise<ArrayBuffer> {
+  return invoke<ArrayBuffer>('get_preview', { token });
+}
+
+/**
+ * 读取预览并生成可直接用于 <img> 的 Object URL（使用后需调用 URL.revokeObjectURL）
+ *
+ * @param token - 预览令牌
+ * @param mimeType - 文件 MIME 类型
+ * @returns blob: URL
+ */
+export async function getPreviewUrl(token: string, mimeType = 'image/png'): Promise<string> {
+  const bytes = await getPreview(token);
+  return URL.createObjectURL(new Blob([bytes], { type: mimeType }));
+}
+
+/**
+ * 导出内嵌附件（字体、封面图）
+ *
+ * @param inputPath - 输入文件路径
+ * @param outputDir - 导出目录
+ * @param indices - 要导出的附件流索引，省略则导出全部
+ * @returns 导出的文件路径列表
+ */
+export async function extractAttachments(
+  inputPath: string,
+  outputDir: string,
+  indices?: number[],
+): Promise<string[]> {
+  return invoke<string[]>('extract_attachments', { inputPath, outputDir, indices });
+}
+
+/** 元数据标签，value 为空字符串表示删除该标签 */
+export interface MetadataTag {
+  key: string;
+  value: string;
+}
+
+/** 流级元数据标签 */
+export interface StreamMetadataTag extends MetadataTag {
+  /** 流说明符（如 "v:0"、"a:1"、"s"） */
+  stream: string;
+}
+
+/** 元数据编辑参数 */
+export interface MetadataParams {
+  inputPath: string;
+  /** 输出路径，不填时修改原文件 */
+  outputPath?: string;
+  tags?: MetadataTag[];
+  streamTags?: StreamMetadataTag[];
+  /** 先清除原有的全部标签 */
+  clearExisting?: boolean;
+}
+
+/**
+ * 写入元数据标签（流复制重封装，不重新编码）
+ *
+ * @param params - 元数据编辑参数
+ * @returns 写入后的文件路径
+ */
+export async function setMetadata(params: MetadataParams): Promise<string> {
+  return invoke<string>('set_metadata', { params });
+}
+
+/**
+ * 取消指定任务
+ *
+ * @param taskId - 要取消的任务 ID
+ */
+export async function cancelTask(taskId: string): Promise<void> {
+  return invoke('cancel_task', { taskId });
+}
+
+/**
+ * 按文件路径取消任务（重新加载后丢失 taskId 时使用）
+ *
+ * @param path - 任务的输入或输出文件路径
+ * @returns 已取消的任务 ID
+ */
+export async function cancelByPath(path: string): Promise<string[]> {
+  return invoke<string[]>('cancel_by_path', { path });
+}
+
+/**
+ * 创建定时任务
+ *
+ * @param kind - 任务类型（如 'convert', 'compress'）
+ * @param params - 对应操作的参数
+ * @param schedule - 执行时间（ISO 8601）和/或空闲时执行
+ * @returns 已创建的定时任务
+ */
+export async function scheduleTask(
+  kind: string,
+  params: object,
+  schedule: { scheduleAt?: string; runWhenIdle?: boolean },
+): Promise<ScheduledTask> {
+  return invoke<ScheduledTask>('schedule_task', { kind, params, ...schedule });
+}
+
+/**
+ * 列出尚未执行的定时任务
+ *
+ * @returns 定时任务列表
+ */
+export async function listScheduledTasks(): Promise<ScheduledTask[]> {
+  return invoke<ScheduledTask[]>('list_scheduled_tasks');
+}
+
+/**
+ * 取消尚未执行的定时任务
+ *
+ * @param id - 定时任务 ID
+ */
+export async function cancelScheduledTask(id: string): Promise<void> {
+  return invoke('cancel_scheduled_task', { id });
+}
+
+/**
+ * 订阅定时任务执行事件（前端启动后调用，重复调用会替换之前的订阅）
+ *
+ * @param onEvent - 任务事件回调
+ */
+export async function subscribeScheduledEvents(
+  onEvent: (event: TaskEvent) => void,
+): Promise<void> {
+  const channel = createProgressChannel(onEvent);
+  return invoke('subscribe_scheduled_events', { onEvent: channel });
+}
+
+/** 码率阶梯中的一档清晰度 */
+export interface StreamRendition {
+  /** 输出高度（像素） */
+  height: number;
+  /** 视频目标码率（kbps） */
+  videoBitrateKbps: number;
+  /** 音频码率（kbps，默认 128） */
+  audioBitrateKbps?: number;
+}
+
+/** 自适应流打包参数 */
+export interface AdaptiveStreamParams {
+  inputPath: string;
+  outputDir: string;
+  /** 打包格式（默认 hls） */
+  format?: 'hls' | 'dash';
+  /** 清晰度列表，缺省时按源分辨率自动生成码率阶梯 */
+  renditions?: StreamRendition[];
+  /** 分片时长（秒，默认 6） */
+  segmentDuration?: number;
+  /** 输出目录非空时是否覆盖 */
+  overwrite?: boolean;
+  preset?: string;
+}
+
+/** 单档清晰度的输出信息 */
+export interface RenditionOutput {
+  name: string;
+  playlistPath: string;
+  size: number;
+}
+
+/** 自适应流打包结果（DASH 时 masterPlaylistPath 为 MPD 清单路径） */
+export interface AdaptiveStreamResult {
+  taskId: string;
+  masterPlaylistPath: string;
+  renditions: RenditionOutput[];
+}
+
+/**
+ * 生成 HLS / DASH 码率阶梯（多档清晰度 + master 播放列表或 MPD 清单）
+ *
+ * @param params - 自适应流打包参数
+ * @param onEvent - 进度事件回调
+ * @returns master 播放列表路径和各档输出大小
+ */
+export async function generateAdaptiveStream(
+  params: AdaptiveStreamParams,
+  onEvent: (event: TaskEvent) => void,
+): Promise<AdaptiveStreamResult> {
+  const channel = createProgressChannel(onEvent);
+  return invoke<AdaptiveStreamResult>('generate_adaptive_stream', {
+    params,
+    onProgress: channel,
+  });
 }
@@ -3,7 +3,7 @@
  * @description 封装 Tauri invoke 调用后端 ffprobe 命令，获取媒体文件信息
  */
 import { invoke } from '@tauri-apps/api/core';
-import type { MediaInfo } from '@/types/media';
+import type { Chapter, MediaInfo } from '@/types/media';
 
 /**
  * 获取视频文件的媒体信息
@@ -17,3 +17,23 @@ import type { MediaInfo } from '@/types/media';
 export async function getMediaInfo(filePath: string): Promise<MediaInfo> {
   return invoke<MediaInfo>('get_media_info', { filePath });
 }
+
+/**
+ * 把文件中的章节导出为 CUE 表单（与媒体文件同目录同名）
+ *
+ * @param path - 媒体文件路径
+ * @returns CUE 文件路径
+ */
+export async function exportCueSheet(path: string): Promise<string> {
+  return invoke<string>('export_cue_sheet', { path });
+}
+
+/**
+ * 把章节列表写回媒体文件（MP4/M4A/M4B/MOV/MKV，流复制重封装）
+ *
+ * @param path - 媒体文件路径
+ * @param chapters - 新的章节列表，空数组表示清除章节
+ */
+export async function embedChapters(path: string, chapters: Chapter[]): Promise<void> {
+  return invoke<void>('embed_chapters', { path, chapters });
+}
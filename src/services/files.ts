@@ -136,3 +136,19 @@ export async function selectDirectory(): Promise<string | null> {
 export async function revealInFinder(path: string): Promise<void> {
   return invoke('reveal_in_finder', { path });
 }
+
+/** 拖出导出的实际行为（nativeDrag：原生拖拽会话已开始；copiedPath：路径已复制到剪贴板） */
+export type DragBehavior = 'nativeDrag' | 'copiedPath';
+
+/**
+ * 拖出已完成任务的输出文件
+ *
+ * 仍在写入或不是已完成任务输出的文件会被拒绝；
+ * 无法发起原生拖拽时后端把路径复制到剪贴板
+ *
+ * @param path - 输出文件的完整路径
+ * @returns 实际采用的行为
+ */
+export async function startDrag(path: string): Promise<DragBehavior> {
+  return invoke<DragBehavior>('start_drag', { path });
+}
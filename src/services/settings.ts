@@ -3,6 +3,7 @@
  * @description 封装应用设置的读写操作，通过 Tauri invoke 与后端交互
  */
 import { invoke } from '@tauri-apps/api/core';
+import type { PresetInfo } from '@/types/presets';
 import type { AppSettings } from '@/types/settings';
 
 /**
@@ -24,3 +25,62 @@ export async function getSettings(): Promise<AppSettings> {
 export async function saveSettings(settings: AppSettings): Promise<void> {
   return invoke('save_settings', { settings });
 }
+
+/**
+ * 获取全部预设（内置预设 + presets.json 中的用户预设）
+ *
+ * @returns 预设列表，内置预设在前
+ */
+export async function listPresets(): Promise<PresetInfo[]> {
+  return invoke<PresetInfo[]>('list_presets');
+}
+
+/**
+ * 保存用户预设（同 id 覆盖）
+ *
+ * @param preset - 预设信息，params 为对应操作的参数对象
+ */
+export async function savePreset(preset: PresetInfo): Promise<void> {
+  return invoke('save_preset', { preset });
+}
+
+/**
+ * 删除用户预设
+ *
+ * @param id - 预设 ID
+ */
+export async function deletePreset(id: string): Promise<void> {
+  return invoke('delete_preset', { id });
+}
+
+/**
+ * 将当前操作参数保存为用户预设（输入/输出路径不会被保存）
+ *
+ * @param name - 预设名称
+ * @param category - 功能模块（如 "convert", "compress"）
+ * @param params - 当前操作的完整参数
+ * @param description - 可选的预设描述
+ * @returns 新建的预设
+ */
+export async function saveParamsAsPreset(
+  name: string,
+  category: string,
+  params: object,
+  description?: string,
+): Promise<PresetInfo> {
+  return invoke<PresetInfo>('save_params_as_preset', { name, category, params, description });
+}
+
+/**
+ * 应用用户预设，返回可直接传给对应操作的完整参数
+ *
+ * @param id - 用户预设 ID
+ * @param overrides - 覆盖到预设参数上的字段（通常为 inputPath / outputPath）
+ * @returns 完整的操作参数
+ */
+export async function applyPreset<T = Record<string, unknown>>(
+  id: string,
+  overrides: object,
+): Promise<T> {
+  return invoke<T>('apply_preset', { id, overrides });
+}
@@ -19,8 +19,24 @@ export interface VideoStream {
   frameRate: number;
   /** 视频码率（bps），可能不存在 */
   bitrate: number | null;
+  /** 码率是否为估算值（界面显示为 "≈ 4.2 Mbps"） */
+  bitrateEstimated: boolean;
   /** 像素格式（如 yuv420p, yuv444p） */
   pixFmt: string;
+  /** 色彩范围（tv = 有限范围, pc = 全范围），未标记时为 null */
+  colorRange: string | null;
+  /** 色彩矩阵（如 bt709, bt2020nc），未标记时为 null */
+  colorSpace: string | null;
+  /** 传输特性（HDR10 为 smpte2084，HLG 为 arib-std-b67），未标记时为 null */
+  colorTransfer: string | null;
+  /** 色域原色（如 bt709, bt2020），未标记时为 null */
+  colorPrimaries: string | null;
+  /** 播放时需顺时针旋转的角度（0/90/180/270） */
+  rotation: number;
+  /** 场序（progressive 逐行，tt/bb/tb/bt 隔行），未标记时为 null */
+  fieldOrder: string | null;
+  /** 是否为隔行扫描视频（场序为 tt/bb/tb/bt） */
+  isInterlaced: boolean;
 }
 
 /** 音频流信息 */
@@ -35,6 +51,8 @@ export interface AudioStream {
   channels: number;
   /** 音频码率（bps），可能不存在 */
   bitrate: number | null;
+  /** 码率是否为估算值（界面显示为 "≈ 4.2 Mbps"） */
+  bitrateEstimated: boolean;
 }
 
 /** 字幕流信息 */
@@ -47,6 +65,18 @@ export interface SubtitleStream {
   language: string | null;
 }
 
+/** 附件流信息（MKV 内嵌字体、封面图等） */
+export interface AttachmentStream {
+  /** 流索引 */
+  index: number;
+  /** 编解码器名称（如 ttf, otf） */
+  codecName: string;
+  /** 附件原始文件名，可能不存在 */
+  filename: string | null;
+  /** MIME 类型，可能不存在 */
+  mimetype: string | null;
+}
+
 /**
  * 媒体文件完整信息
  * @description 从 ffprobe 解析获得的媒体元数据，包含文件信息和所有流信息
@@ -70,6 +100,25 @@ export interface MediaInfo {
   audioStreams: AudioStream[];
   /** 字幕流信息列表 */
   subtitleStreams: SubtitleStream[];
+  /** 附件流信息列表 */
+  attachmentStreams: AttachmentStream[];
+  /** 是否带有封面图（attached_pic，不计入 videoStreams） */
+  hasCoverArt: boolean;
+  /** 章节列表（按开始时间排序） */
+  chapters: Chapter[];
+}
+
+/**
+ * 章节信息
+ * @description 读取自文件的章节，也是 embedChapters 写回时的输入格式
+ */
+export interface Chapter {
+  /** 开始时间（秒） */
+  start: number;
+  /** 结束时间（秒） */
+  end: number;
+  /** 章节标题 */
+  title: string;
 }
 
 /**
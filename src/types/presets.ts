@@ -101,3 +101,21 @@ export interface FrameRatePreset {
   /** 帧率值 */
   value: number;
 }
+
+/**
+ * 预设信息（后端 list_presets 返回）
+ * @description 内置预设的参数大多由前端常量维护（params 为空），流媒体转换等内置预设
+ * 和用户预设携带参数对象，可通过 apply_preset 合并输入输出路径
+ */
+export interface PresetInfo {
+  /** 预设唯一标识 */
+  id: string;
+  /** 预设名称 */
+  name: string;
+  /** 预设描述 */
+  description: string;
+  /** 所属功能模块（如 "convert", "compress", "gif"） */
+  category: string;
+  /** 预设参数（键名与对应操作的参数一致） */
+  params?: Record<string, unknown>;
+}
@@ -25,6 +25,22 @@ export interface AppSettings {
   overwriteExisting: boolean;
   /** 界面语言（'en' = 英文，'zh' = 中文），默认英文 */
   language: string;
+  /** 默认音频码率（如 '128k', '192k'），操作参数未指定时使用 */
+  defaultAudioBitrate: string;
+  /** 默认视频质量（CRF 值，越小质量越高），操作参数未指定时使用 */
+  defaultQuality: number;
+  /** 默认编码速度预设（如 'medium', 'slow'），操作参数未指定时使用 */
+  defaultPreset: string;
+  /** 输入文件仍在写入（如正在录制）时最多等待的秒数，0 表示立即报错 */
+  growingFileWaitSeconds: number;
+  /** GIF 估算体积上限（MB），超出时需确认才生成 */
+  maxGifSizeMb: number;
+  /** 是否先写入同目录临时文件、成功后再移动到输出路径（失败或取消时不留下残缺文件） */
+  atomicOutput: boolean;
+  /** ffprobe 探测输入文件的超时时间（毫秒），0 表示不限时 */
+  probeTimeoutMs: number;
+  /** ffmpeg 使用的线程数上限，null 表示由 ffmpeg 自动决定 */
+  ffmpegThreads: number | null;
 }
 
 /**
@@ -40,4 +56,12 @@ export const DEFAULT_SETTINGS: AppSettings = {
   outputSuffix: '_output',
   overwriteExisting: false,
   language: 'en',
+  defaultAudioBitrate: '128k',
+  defaultQuality: 18,
+  defaultPreset: 'medium',
+  growingFileWaitSeconds: 10,
+  maxGifSizeMb: 100,
+  atomicOutput: true,
+  probeTimeoutMs: 30000,
+  ffmpegThreads: null,
 };
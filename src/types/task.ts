@@ -13,8 +13,19 @@ export type TaskStatus =
   | 'running'    // 正在执行
   | 'completed'  // 执行完成
   | 'cancelled'  // 已取消
+  | 'paused'     // 已暂停（可续传的下载）
   | 'failed';    // 执行失败
 
+/** 下载任务的容器转换结果 */
+export interface ContainerChange {
+  /** yt-dlp 下载得到的容器格式 */
+  downloaded: string;
+  /** 转换后经 ffprobe 确认的容器格式 */
+  finalFormat: string;
+  /** 是否重新编码 */
+  recoded: boolean;
+}
+
 /**
  * 进度更新数据
  * @description 通过 Tauri Channel 从后端推送的实时进度信息
@@ -34,6 +45,8 @@ export interface ProgressUpdate {
   frame: number;
   /** 当前处理帧率 */
   fps: number;
+  /** 多步骤任务的当前阶段（如 "Cutting segment 2/5"、"Merging segments"），单步任务不存在 */
+  phase?: string;
 }
 
 /**
@@ -43,11 +56,29 @@ export interface ProgressUpdate {
  * 使用 tagged union 模式，event 字段区分不同事件类型
  */
 export type TaskEvent =
+  | { event: 'queued'; data: { taskId: string; position: number } }
   | { event: 'started'; data: { taskId: string; totalDuration: number } }
   | { event: 'progress'; data: ProgressUpdate }
-  | { event: 'completed'; data: { taskId: string; outputPath: string; outputSize: number; elapsed: number } }
+  | {
+      event: 'completed';
+      data: {
+        taskId: string;
+        outputPath: string;
+        outputSize: number;
+        elapsed: number;
+        verification?: VerificationCheck[];
+        /** 完成后自动处理的说明（如下载容器修正） */
+        note?: string;
+        /** 下载后转换容器的结果（仅指定 remuxTo 的下载） */
+        containerChange?: ContainerChange;
+      };
+    }
   | { event: 'failed'; data: { taskId: string; error: string } }
-  | { event: 'cancelled'; data: { taskId: string } };
+  | { event: 'cancelled'; data: { taskId: string } }
+  | { event: 'paused'; data: { taskId: string; percent: number } }
+  | { event: 'notice'; data: { taskId: string; message: string } }
+  | { event: 'phase'; data: { taskId: string; description: string } }
+  | { event: 'batchProgress'; data: { batchId: string; completed: number; total: number } };
 
 /**
  * 任务执行结果
@@ -66,6 +97,59 @@ export interface TaskResult {
   elapsed: number | null;
   /** 错误信息（失败时） */
   error: string | null;
+  /** 输出文件编码属性核对结果（未要求核对时缺省） */
+  verification?: VerificationCheck[];
+}
+
+/**
+ * 输出文件编码属性核对项
+ * @description 任务完成后探测输出文件与参数要求比对，不一致只作提示不影响任务结果
+ */
+export interface VerificationCheck {
+  /** 核对项名称（codec / resolution / fps / pixelFormat / profile / level / faststart） */
+  name: string;
+  /** 参数要求的值 */
+  expected: string;
+  /** 输出文件中的实际值（无法探测时为 "unknown"） */
+  actual: string;
+  /** 是否一致 */
+  ok: boolean;
+}
+
+/**
+ * ffmpeg 命令预览
+ * @description 构建但不执行的命令，多遍编码时每一遍一条
+ */
+export interface CommandPreview {
+  /** 传给 ffmpeg 的参数列表（不含 ffmpeg 本身） */
+  args: string[];
+  /** 可直接粘贴到终端执行的单行命令 */
+  commandLine: string;
+  /** 实际执行时会推送的参数自动调整提示 */
+  notices: string[];
+}
+
+/**
+ * 输出体积估算
+ * @description exact 按码率计算；approximate 按 CRF 经验表估算，实际体积随画面复杂度浮动
+ */
+export interface SizeEstimate {
+  /** 估算的输出体积（字节） */
+  estimatedBytes: number;
+  /** 估算可信度 */
+  confidence: 'exact' | 'approximate';
+  /** 参与计算的时长（秒） */
+  duration: number;
+  /** 参与计算的视频码率（bps），纯音频输出为 0 */
+  videoBitrate: number;
+  /** 参与计算的音频码率之和（bps） */
+  audioBitrate: number;
+  /** 视频编码器（流复制为 "copy"） */
+  videoEncoder: string;
+  /** CRF 模式使用的 CRF 值，码率模式为 null */
+  crf: number | null;
+  /** 实际执行时会推送的参数自动调整提示 */
+  notices: string[];
 }
 
 /**
@@ -90,3 +174,37 @@ export interface TaskInfo {
   /** 创建时间戳 */
   createdAt: number;
 }
+
+/**
+ * 定时任务
+ * @description 持久化在后端 scheduled.json 中，到时间或系统空闲时自动执行
+ */
+export interface ScheduledTask {
+  /** 定时任务 ID（与执行时生成的任务 ID 不同） */
+  id: string;
+  /** 任务类型（如 convert, compress, trim 等） */
+  kind: string;
+  /** 对应操作的参数对象 */
+  params: Record<string, unknown>;
+  /** 计划执行时间（ISO 8601） */
+  scheduleAt: string | null;
+  /** 是否在系统空闲时执行 */
+  runWhenIdle: boolean;
+  /** 创建时间（ISO 8601） */
+  createdAt: string;
+  /** 是否已提交执行（执行结束后自动移除） */
+  running: boolean;
+}
+
+/**
+ * 时间片段校验错误
+ * @description trim_video / create_gif 在片段不合法时返回的结构化错误，
+ * i/j 为出错片段的下标（从 0 开始），可据此标出出错的行
+ */
+export type SegmentError =
+  | { code: 'noSegments'; message: string }
+  | { code: 'emptySegment' | 'segmentOutOfRange' | 'unsortedSegments'; i: number; message: string }
+  | { code: 'overlappingSegments'; i: number; j: number; message: string };
+
+/** command 返回的错误：结构化片段错误或错误描述字符串 */
+export type CommandError = SegmentError | string;